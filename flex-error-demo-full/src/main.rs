@@ -80,6 +80,30 @@ pub mod bar {
     }
 }
 
+pub mod relay {
+    use super::bar;
+    use flex_error::*;
+
+    define_error! {
+      @display(chain)
+      RelayError {
+        Timeout
+          { height: u64 }
+          @transient
+          | err | { format_args!("timed out at height {}", err.height) },
+        Config
+          { key: String }
+          @exit_code(78)
+          @help("set the key in the configuration file")
+          | err | { format_args!("missing configuration key {}", err.key) },
+        Bar
+          [ bar::BarError ]
+          @from
+          | _ | { "bar error" },
+      }
+    }
+}
+
 fn main() -> Result<(), bar::BarError> {
     color_eyre::install().unwrap();
 
@@ -89,6 +113,16 @@ fn main() -> Result<(), bar::BarError> {
 
     println!("error: {:?}", err3);
 
-    // Err(err3)
+    let err4 = relay::RelayError::from(err3).add_trace(&"while relaying");
+    println!("error: {}", err4);
+
+    let err5 = relay::RelayError::config("rpc_addr".into());
+    println!("{}", err5.render(flex_error::Theme::Ansi));
+    println!("exit code: {}", err5.exit_code());
+
+    let err6 = relay::RelayError::timeout(10);
+    println!("transient: {}", err6.is_transient());
+
+    // Err(err4)
     Ok(())
 }
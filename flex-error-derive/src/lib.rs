@@ -0,0 +1,494 @@
+/*!
+  A derive macro alternative to the `define_error!` macro of `flex-error`.
+
+  `define_error!` is a declarative macro, which IDEs cannot see through and
+  which reports malformed definitions with hard to read compile errors.
+  `#[derive(FlexError)]` instead defines the error type from a plain enum,
+  which is checked by the compiler and IDEs like any other enum:
+
+  ```
+  use flex_error::{FlexError, TraceError};
+
+  #[derive(FlexError)]
+  #[flex_error(name = FooError)]
+  pub enum FooErrorDef {
+    /// The file could not be read.
+    #[error("failed to read {path}")]
+    Read {
+      path: String,
+      #[source(TraceError<std::io::Error>)]
+      source: std::io::Error,
+    },
+
+    #[error("invalid size {}, expected at most {}", size, max)]
+    InvalidSize { size: u64, max: u64 },
+
+    #[error("nested foo error")]
+    Nested {
+      #[source(Self)]
+      source: FooError,
+    },
+  }
+  # let err = FooError::invalid_size(100, 64);
+  # assert_eq!(err.detail().to_string(), "invalid size 100, expected at most 64");
+  ```
+
+  The derive expands to the equivalent `define_error!` definition, so the
+  error type `FooError`, its detail and sub-details, and its constructors
+  such as `FooError::read(path, source)` are the same as those generated
+  by the macro:
+
+  ```ignore
+  define_error! {
+    FooError {
+      /// The file could not be read.
+      Read
+        { path: String }
+        [ TraceError<std::io::Error> ]
+        | e | { format_args!("failed to read {}", e.path) },
+      ...
+    }
+  }
+  ```
+
+  In addition, `From<FooErrorDef>` is implemented for `FooError`, which
+  calls the constructor of the sub-error with the fields of the variant:
+
+  ```
+  # use flex_error::FlexError;
+  # #[derive(FlexError)]
+  # #[flex_error(name = FooError)]
+  # pub enum FooErrorDef {
+  #   #[error("invalid size {}, expected at most {}", size, max)]
+  #   InvalidSize { size: u64, max: u64 },
+  # }
+  # fn check_size(size: u64) -> Result<(), FooError> {
+  #   if size > 64 {
+  return Err(FooErrorDef::InvalidSize { size, max: 64 }.into());
+  #   }
+  #   Ok(())
+  # }
+  # assert!(check_size(100).is_err());
+  ```
+
+  The error message is given with `#[error(...)]` in the form of the
+  `format!` arguments, which refer to the fields of the variant by their
+  names. The source of a sub-error is the field annotated with
+  `#[source(...)]`, which gives the error source type in the same form as
+  `[ ... ]` in `define_error!`, with the type of the field being the type
+  of the source error. The detail of the source error is referred to as
+  `source` in the error message, or as `source1` and `source2` if two
+  fields are annotated with `#[source(...)]`. The `#[detail(...)]`
+  attributes of the fields are forwarded to the sub-detail fields.
+
+  The enum takes the following options with `#[flex_error(...)]`:
+
+    - `name = FooError` - The name of the error type, which is required.
+
+    - `tracer = MyTracer` - The error tracer, instead of the default tracer.
+
+  The doc comments of the enum and its variants are forwarded to the error
+  detail and the sub-details. Other options of `define_error!` are not
+  supported by the derive, and require the macro to be used instead.
+*/
+
+extern crate proc_macro;
+
+use std::collections::BTreeSet;
+
+use proc_macro::TokenStream;
+use proc_macro2::{TokenStream as TokenStream2, TokenTree};
+use quote::{format_ident, quote};
+use syn::parse::ParseStream;
+use syn::spanned::Spanned;
+use syn::{Attribute, Data, DeriveInput, Error, Fields, Ident, LitStr, Result, Token, Type};
+
+/// Derives a `flex-error` error type from an enum. See the
+/// [crate documentation](crate).
+#[proc_macro_derive(FlexError, attributes(flex_error, error, source, detail))]
+pub fn derive_flex_error(input: TokenStream) -> TokenStream {
+    let input = syn::parse_macro_input!(input as DeriveInput);
+    expand(&input)
+        .unwrap_or_else(|err| err.to_compile_error())
+        .into()
+}
+
+/// The options given to the enum with `#[flex_error(...)]`.
+struct ErrorOptions {
+    name: Ident,
+    tracer: Option<Type>,
+}
+
+/// A sub-error, parsed from a variant of the enum.
+struct Suberror {
+    docs: Vec<Attribute>,
+    name: Ident,
+    fields: Vec<Field>,
+    sources: Vec<SourceField>,
+    message: Message,
+}
+
+/// A field of a sub-error other than the source.
+struct Field {
+    attrs: Vec<Attribute>,
+    name: Ident,
+    ty: Type,
+}
+
+/// A source field of a sub-error, annotated with `#[source(...)]`.
+struct SourceField {
+    name: Ident,
+    source: Type,
+}
+
+/// The error message given with `#[error(...)]`.
+struct Message {
+    format: LitStr,
+    args: TokenStream2,
+}
+
+fn expand(input: &DeriveInput) -> Result<TokenStream2> {
+    let variants = match &input.data {
+        Data::Enum(data) => &data.variants,
+        _ => {
+            return Err(Error::new(
+                input.ident.span(),
+                "`FlexError` can only be derived for enums",
+            ))
+        }
+    };
+
+    if !input.generics.params.is_empty() {
+        return Err(Error::new(
+            input.generics.span(),
+            "`FlexError` cannot be derived for generic enums",
+        ));
+    }
+
+    let options = parse_options(input)?;
+    let suberrors = variants
+        .iter()
+        .map(parse_suberror)
+        .collect::<Result<Vec<_>>>()?;
+
+    let definition = define_error(input, &options, &suberrors);
+    let from_impl = from_impl(input, &options, &suberrors);
+
+    Ok(quote! {
+        #definition
+        #from_impl
+    })
+}
+
+fn parse_options(input: &DeriveInput) -> Result<ErrorOptions> {
+    let mut name = None;
+    let mut tracer = None;
+
+    for attr in input
+        .attrs
+        .iter()
+        .filter(|attr| attr.path().is_ident("flex_error"))
+    {
+        attr.parse_nested_meta(|meta| {
+            if meta.path.is_ident("name") {
+                name = Some(meta.value()?.parse()?);
+                Ok(())
+            } else if meta.path.is_ident("tracer") {
+                tracer = Some(meta.value()?.parse()?);
+                Ok(())
+            } else {
+                Err(meta.error("unsupported `flex_error` option, expected `name` or `tracer`"))
+            }
+        })?;
+    }
+
+    let name = name.ok_or_else(|| {
+        Error::new(
+            input.ident.span(),
+            "missing the name of the error type, given with `#[flex_error(name = ...)]`",
+        )
+    })?;
+
+    Ok(ErrorOptions { name, tracer })
+}
+
+fn parse_suberror(variant: &syn::Variant) -> Result<Suberror> {
+    let mut message = None;
+    for attr in variant
+        .attrs
+        .iter()
+        .filter(|attr| attr.path().is_ident("error"))
+    {
+        if message.is_some() {
+            return Err(Error::new(
+                attr.span(),
+                "duplicate `#[error(...)]` attribute",
+            ));
+        }
+        message = Some(attr.parse_args_with(parse_message)?);
+    }
+
+    let message = message.ok_or_else(|| {
+        Error::new(
+            variant.ident.span(),
+            "missing the error message, given with `#[error(...)]`",
+        )
+    })?;
+
+    let named = match &variant.fields {
+        Fields::Named(fields) => fields.named.iter().collect(),
+        Fields::Unit => Vec::new(),
+        Fields::Unnamed(fields) => {
+            return Err(Error::new(
+                fields.span(),
+                "sub-errors must have named fields",
+            ))
+        }
+    };
+
+    let mut fields = Vec::new();
+    let mut sources = Vec::new();
+
+    for field in named {
+        let name = field.ident.clone().expect("named fields have names");
+        let source_attr = field
+            .attrs
+            .iter()
+            .find(|attr| attr.path().is_ident("source"));
+
+        match source_attr {
+            Some(attr) => {
+                if sources.len() == 2 {
+                    return Err(Error::new(
+                        attr.span(),
+                        "sub-errors can have at most two `#[source(...)]` fields",
+                    ));
+                }
+                sources.push(SourceField {
+                    name,
+                    source: attr.parse_args()?,
+                });
+            }
+            None => fields.push(Field {
+                attrs: field
+                    .attrs
+                    .iter()
+                    .filter(|attr| attr.path().is_ident("detail"))
+                    .cloned()
+                    .collect(),
+                name,
+                ty: field.ty.clone(),
+            }),
+        }
+    }
+
+    Ok(Suberror {
+        docs: doc_attrs(&variant.attrs),
+        name: variant.ident.clone(),
+        fields,
+        sources,
+        message,
+    })
+}
+
+fn parse_message(input: ParseStream) -> Result<Message> {
+    let format = input.parse()?;
+    let args = if input.is_empty() {
+        TokenStream2::new()
+    } else {
+        input.parse::<Token![,]>()?;
+        input.parse()?
+    };
+    Ok(Message { format, args })
+}
+
+fn doc_attrs(attrs: &[Attribute]) -> Vec<Attribute> {
+    attrs
+        .iter()
+        .filter(|attr| attr.path().is_ident("doc"))
+        .cloned()
+        .collect()
+}
+
+fn define_error(
+    input: &DeriveInput,
+    options: &ErrorOptions,
+    suberrors: &[Suberror],
+) -> TokenStream2 {
+    let name = &options.name;
+    let tracer = options
+        .tracer
+        .as_ref()
+        .map(|tracer| quote!(: tracer = #tracer));
+    let docs = doc_attrs(&input.attrs);
+
+    let suberrors = suberrors.iter().map(|suberror| {
+        let docs = &suberror.docs;
+        let suberror_name = &suberror.name;
+
+        let fields = if suberror.fields.is_empty() {
+            None
+        } else {
+            let fields = suberror.fields.iter().map(|field| {
+                let attrs = &field.attrs;
+                let field_name = &field.name;
+                let ty = &field.ty;
+                quote!( #( #attrs )* #field_name: #ty )
+            });
+            Some(quote!({ #( #fields ),* }))
+        };
+
+        let source = if suberror.sources.is_empty() {
+            None
+        } else {
+            let sources = suberror.sources.iter().map(|source| &source.source);
+            Some(quote!([ #( #sources ),* ]))
+        };
+
+        let formatter = formatter(suberror);
+
+        quote! {
+            #( #docs )*
+            #suberror_name
+              #fields
+              #source
+              #formatter
+        }
+    });
+
+    quote! {
+        ::flex_error::define_error! {
+            #( #docs )*
+            #[derive(Debug)]
+            #name #tracer {
+                #( #suberrors ),*
+            }
+        }
+    }
+}
+
+/// Returns the formatter of a sub-error, which binds the fields mentioned
+/// in the error message by destructuring the sub-detail.
+fn formatter(suberror: &Suberror) -> TokenStream2 {
+    let subdetail = format_ident!("{}Subdetail", suberror.name);
+    let Message { format, args } = &suberror.message;
+
+    let mut mentioned = format_arg_names(&format.value());
+    collect_idents(args.clone(), &mut mentioned);
+
+    let mut bindings: Vec<Ident> = suberror
+        .fields
+        .iter()
+        .filter(|field| mentioned.contains(&field.name.to_string()))
+        .map(|field| field.name.clone())
+        .collect();
+
+    let source_names: &[&str] = match suberror.sources.len() {
+        0 => &[],
+        1 => &["source"],
+        _ => &["source1", "source2"],
+    };
+    for name in source_names {
+        if mentioned.contains(*name) {
+            bindings.push(format_ident!("{}", name, span = format.span()));
+        }
+    }
+
+    let args = if args.is_empty() {
+        None
+    } else {
+        Some(quote!(, #args))
+    };
+
+    quote! {
+        | #subdetail { #( #bindings, )* .. } | { format_args!(#format #args) }
+    }
+}
+
+/// Returns the names of the arguments captured by a format string, e.g.
+/// `path` in `"failed to read {path}"`.
+fn format_arg_names(format: &str) -> BTreeSet<String> {
+    let mut names = BTreeSet::new();
+    let mut chars = format.chars().peekable();
+
+    while let Some(ch) = chars.next() {
+        if ch != '{' {
+            continue;
+        }
+        if chars.peek() == Some(&'{') {
+            chars.next();
+            continue;
+        }
+
+        let mut name = String::new();
+        while let Some(&ch) = chars.peek() {
+            if ch == '}' || ch == ':' {
+                break;
+            }
+            name.push(ch);
+            chars.next();
+        }
+
+        let name = name.trim();
+        if name.starts_with(|ch: char| ch.is_alphabetic() || ch == '_') {
+            names.insert(name.to_string());
+        }
+    }
+
+    names
+}
+
+fn collect_idents(tokens: TokenStream2, idents: &mut BTreeSet<String>) {
+    for token in tokens {
+        match token {
+            TokenTree::Ident(ident) => {
+                idents.insert(ident.to_string());
+            }
+            TokenTree::Group(group) => collect_idents(group.stream(), idents),
+            _ => {}
+        }
+    }
+}
+
+fn from_impl(input: &DeriveInput, options: &ErrorOptions, suberrors: &[Suberror]) -> TokenStream2 {
+    let enum_name = &input.ident;
+    let name = &options.name;
+
+    let arms = suberrors.iter().map(|suberror| {
+        let variant = &suberror.name;
+        let constructor = format_ident!("{}", snake_case(&variant.to_string()));
+
+        let mut args: Vec<&Ident> = suberror.fields.iter().map(|field| &field.name).collect();
+        args.extend(suberror.sources.iter().map(|source| &source.name));
+
+        quote! {
+            #enum_name::#variant { #( #args ),* } => #name::#constructor( #( #args ),* )
+        }
+    });
+
+    quote! {
+        impl ::core::convert::From<#enum_name> for #name {
+            #[track_caller]
+            fn from(error: #enum_name) -> Self {
+                match error {
+                    #( #arms ),*
+                }
+            }
+        }
+    }
+}
+
+/// Converts a sub-error name to the name of its constructor, in the same
+/// way as `[< $suberror:snake >]` in `define_error!`.
+fn snake_case(name: &str) -> String {
+    let mut snake = String::new();
+    let mut prev = '_';
+    for ch in name.chars() {
+        if ch.is_uppercase() && prev != '_' {
+            snake.push('_');
+        }
+        snake.push(ch);
+        prev = ch;
+    }
+    snake.to_lowercase()
+}
@@ -0,0 +1,129 @@
+/*!
+  Snapshots of the environment attached to severe errors.
+
+  Bug reports from operators often lack the version of the software or the
+  configuration in use when an error occurred. When the `attachments`
+  feature is enabled, the sub-errors with the `@attach` option capture a
+  snapshot of the data registered for the given attachment kind when they
+  are constructed, with providers registered once at startup:
+
+  ```
+  # use flex_error::define_error;
+  # fn config_digest() -> String {
+  #   "9a3b".to_string()
+  # }
+  flex_error::attach::register_provider("env", "version", || env!("CARGO_PKG_VERSION").into());
+  flex_error::attach::register_provider("env", "git_hash", || option_env!("GIT_HASH").unwrap_or("unknown").into());
+  flex_error::attach::register_provider("env", "config", || config_digest());
+
+  define_error! {
+    MyError {
+      Corrupted
+        { height: u64 }
+        @attach(env)
+        | e | { format_args!("corrupted state at height {}", e.height) },
+    }
+  }
+  # let err = MyError::corrupted(42);
+  # assert!(format!("{:#}", err).contains("config=9a3b"));
+  ```
+
+  The snapshot is added to the error trace as a message on top of the
+  message of the sub-error, in the form
+  `env: version=1.2.0, git_hash=5f2c1e9, config=9a3b`, so that it is
+  included in the rendered error traces and in the exports of the traces.
+  Several kinds can be given as `@attach(env, config)`. No message is added
+  for a kind without registered providers.
+
+  Without the `attachments` feature, the `@attach` option has no effect.
+*/
+
+use alloc::string::String;
+use alloc::vec::Vec;
+use core::fmt::{Display, Formatter};
+use std::sync::RwLock;
+
+use crate::tracer::ErrorMessageTracer;
+
+/// A function returning the current value of an attached data.
+pub type Provider = fn() -> String;
+
+static PROVIDERS: RwLock<Vec<(&'static str, &'static str, Provider)>> = RwLock::new(Vec::new());
+
+/// Registers a provider for the data with the given name, captured by the
+/// sub-errors with the `@attach` option for the given kind. A provider
+/// previously registered with the same kind and name is replaced.
+pub fn register_provider(kind: &'static str, name: &'static str, provider: Provider) {
+    let mut providers = PROVIDERS.write().unwrap_or_else(|e| e.into_inner());
+    match providers
+        .iter_mut()
+        .find(|(k, n, _)| *k == kind && *n == name)
+    {
+        Some(entry) => entry.2 = provider,
+        None => providers.push((kind, name, provider)),
+    }
+}
+
+/// Removes all providers registered for the given kind.
+pub fn clear_providers(kind: &str) {
+    PROVIDERS
+        .write()
+        .unwrap_or_else(|e| e.into_inner())
+        .retain(|(k, _, _)| *k != kind);
+}
+
+/// A snapshot of the data registered for an attachment kind.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Attachment {
+    pub kind: &'static str,
+    pub values: Vec<(&'static str, String)>,
+}
+
+impl Display for Attachment {
+    fn fmt(&self, f: &mut Formatter<'_>) -> core::fmt::Result {
+        write!(f, "{}:", self.kind)?;
+        for (index, (name, value)) in self.values.iter().enumerate() {
+            let separator = if index > 0 { "," } else { "" };
+            write!(f, "{0} {1}={2}", separator, name, value)?;
+        }
+        Ok(())
+    }
+}
+
+/// Captures the data of the providers registered for the given kind, in
+/// the order they have been registered, or `None` if there is none.
+pub fn capture(kind: &'static str) -> Option<Attachment> {
+    // The providers are copied out, so that they can themselves
+    // construct errors without deadlocking.
+    let providers: Vec<(&'static str, Provider)> = PROVIDERS
+        .read()
+        .unwrap_or_else(|e| e.into_inner())
+        .iter()
+        .filter(|(k, _, _)| *k == kind)
+        .map(|(_, name, provider)| (*name, *provider))
+        .collect();
+
+    if providers.is_empty() {
+        return None;
+    }
+
+    Some(Attachment {
+        kind,
+        values: providers
+            .into_iter()
+            .map(|(name, provider)| (name, provider()))
+            .collect(),
+    })
+}
+
+/// Adds the snapshots of the given kinds to the error trace of a newly
+/// constructed error. The kinds are added in reverse order, so that they
+/// are rendered in the order they are given to the `@attach` option.
+#[doc(hidden)]
+pub fn attach<Tracer: ErrorMessageTracer>(trace: Tracer, kinds: &[&'static str]) -> Tracer {
+    kinds
+        .iter()
+        .rev()
+        .filter_map(|kind| capture(kind))
+        .fold(trace, |trace, attachment| trace.add_message(&attachment))
+}
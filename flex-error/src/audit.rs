@@ -0,0 +1,219 @@
+/*!
+  Audit of the error types defined in a workspace, for governance reports.
+
+  When the `audit-tool` feature is enabled, each error type defined by
+  [`define_error!`](crate::define_error) registers its sub-errors with
+  [`inventory`](https://docs.rs/inventory), together with the error codes
+  and severities assigned with `@codes` and the error sources they wrap.
+  [`report`] lists the error types of all crates linked into a binary in
+  the JSON format, so that a report can be produced by a small binary
+  target depending on all the crates of the workspace, with the following
+  in its `main` function:
+
+  ```
+  println!("{}", flex_error::audit::report());
+  ```
+
+  The report has the following form, with the error types sorted by their
+  modules and names, and the sub-errors in the order they are defined:
+
+  ```text
+  {"errors":[{"module":"my_crate::errors","error":"MyError","variants":[
+    {"name":"Io","code":"E-IO","severity":"error","sources":["TraceError<std::io::Error>"]},
+    {"name":"Timeout","code":null,"severity":null,"sources":[]}]}]}
+  ```
+
+  The sources are given as they are written in the definitions, with the
+  name of the error type for a `[ Self ]` source, so that the wrap
+  relationships can be followed from one error type to another.
+*/
+
+use alloc::string::String;
+use alloc::vec::Vec;
+use core::fmt::Write;
+
+use crate::codes::ErrorCode;
+
+/// An error type registered by [`define_error!`](crate::define_error).
+#[derive(Debug, Clone, Copy)]
+pub struct AuditedError {
+    /// The path of the module defining the error type.
+    pub module: &'static str,
+
+    /// The name of the error type.
+    pub error: &'static str,
+
+    /// The sub-errors of the error type, including the variant aliases.
+    pub variants: &'static [AuditedVariant],
+}
+
+/// A sub-error of an [`AuditedError`].
+#[derive(Debug, Clone, Copy)]
+pub struct AuditedVariant {
+    /// The name of the sub-error.
+    pub name: &'static str,
+
+    /// The error code and severity assigned with `@codes`, if any.
+    pub code: Option<ErrorCode>,
+
+    /// The error sources wrapped by the sub-error, as written in the
+    /// definition of the error type.
+    pub sources: &'static [&'static str],
+}
+
+inventory::collect!(AuditedError);
+
+/// Returns the error types linked into the current binary, sorted by
+/// their modules and names.
+pub fn audited_errors() -> Vec<AuditedError> {
+    let mut errors: Vec<AuditedError> = inventory::iter::<AuditedError>
+        .into_iter()
+        .copied()
+        .collect();
+    errors.sort_by_key(|error| (error.module, error.error));
+    // The same error type may be registered more than once, such as by
+    // multiple versions of a crate.
+    errors.dedup_by_key(|error| (error.module, error.error));
+    errors
+}
+
+/// Returns the report of the error types linked into the current binary,
+/// in the JSON format. See the [module documentation](self).
+pub fn report() -> String {
+    let mut out = String::from("{\"errors\":[");
+    for (index, error) in audited_errors().into_iter().enumerate() {
+        if index > 0 {
+            out.push(',');
+        }
+        out.push_str("{\"module\":");
+        write_json_str(&mut out, error.module);
+        out.push_str(",\"error\":");
+        write_json_str(&mut out, error.error);
+        out.push_str(",\"variants\":[");
+        for (index, variant) in error.variants.iter().enumerate() {
+            if index > 0 {
+                out.push(',');
+            }
+            write_variant(&mut out, variant);
+        }
+        out.push_str("]}");
+    }
+    out.push_str("]}");
+    out
+}
+
+fn write_variant(out: &mut String, variant: &AuditedVariant) {
+    out.push_str("{\"name\":");
+    write_json_str(out, variant.name);
+    match variant.code {
+        Some(code) => {
+            out.push_str(",\"code\":");
+            write_json_str(out, code.code);
+            let _ = write!(out, ",\"severity\":\"{}\"", code.severity);
+        }
+        None => out.push_str(",\"code\":null,\"severity\":null"),
+    }
+    out.push_str(",\"sources\":[");
+    for (index, source) in variant.sources.iter().enumerate() {
+        if index > 0 {
+            out.push(',');
+        }
+        write_json_str(out, source);
+    }
+    out.push_str("]}");
+}
+
+fn write_json_str(out: &mut String, value: &str) {
+    out.push('"');
+    for c in value.chars() {
+        match c {
+            '"' => out.push_str("\\\""),
+            '\\' => out.push_str("\\\\"),
+            '\n' => out.push_str("\\n"),
+            c if (c as u32) < 0x20 => {
+                let _ = write!(out, "\\u{:04x}", c as u32);
+            }
+            c => out.push(c),
+        }
+    }
+    out.push('"');
+}
+
+/// Internal macro used to register an error type for the audit report.
+#[macro_export]
+#[doc(hidden)]
+macro_rules! register_error_audit {
+  ( @ctx $ctx:tt,
+    @variants $variants:tt,
+    @suberrors{} $(,)?
+  ) => {
+    $crate::register_error_audit! {
+      @emit
+      @ctx $ctx,
+      @variants $variants
+    }
+  };
+  ( @ctx $ctx:tt,
+    @variants[ $( $variants:tt )* ],
+    @suberrors{
+      $( #[$sub_attr:meta] )*
+      $suberror:ident $( as $alias:ident )* $( = $numeric_code:literal )?
+        $( { $( $( #[detail $field_opts:tt] )* $arg_name:ident : $( @ $modifier:ident )? $arg_type:ty ),* $(,)? } )?
+        $( [ $( $source:tt )+ ] )?
+        $( @ $option:ident $( ( $( $option_arg:tt )* ) )? )*
+        | $formatter_arg:pat | $formatter:expr
+      $( , $($tail:tt)* )?
+    }
+  ) => {
+    $crate::register_error_audit! {
+      @ctx $ctx,
+      @variants[
+        $( $variants )*
+        ( $suberror [ $( $alias ),* ] [ $( $( $source )+ )? ] )
+      ],
+      @suberrors{ $( $( $tail )* )? }
+    }
+  };
+  ( @emit
+    @ctx[ @name( $name:ident ), @codes $codes:tt ],
+    @variants[ $( ( $suberror:ident [ $( $alias:ident ),* ] $sources:tt ) )* ]
+  ) => {
+    $crate::inventory::submit! {
+      $crate::audit::AuditedError {
+        module: ::core::module_path!(),
+        error: ::core::stringify!($name),
+        variants: &[
+          $(
+            $crate::register_error_audit!( @variant $name, $suberror, $codes, $sources ),
+            $( $crate::register_error_audit!( @variant $name, $alias, $codes, $sources ), )*
+          )*
+        ],
+      }
+    }
+  };
+  ( @variant $name:ident, $variant:ident, $codes:tt, $sources:tt ) => {
+    $crate::audit::AuditedVariant {
+      name: ::core::stringify!($variant),
+      code: $crate::register_error_audit!( @code $name, $variant, $codes ),
+      sources: $crate::register_error_audit!( @sources $name, $sources ),
+    }
+  };
+  ( @code $name:ident, $variant:ident, [] ) => {
+    ::core::option::Option::None
+  };
+  ( @code $name:ident, $variant:ident, [ $codes:expr ] ) => {
+    $crate::codes::lookup( $codes, ::core::stringify!($name), ::core::stringify!($variant) )
+  };
+  ( @sources $name:ident, [] ) => {
+    &[]
+  };
+  ( @sources $name:ident, [ Self ] ) => {
+    &[ ::core::stringify!($name) ]
+  };
+  ( @sources $name:ident, [ $source:ty, $source2:ty ] ) => {
+    &[ ::core::stringify!($source), ::core::stringify!($source2) ]
+  };
+  ( @sources $name:ident, [ $source:ty ] ) => {
+    &[ ::core::stringify!($source) ]
+  };
+}
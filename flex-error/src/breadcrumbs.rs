@@ -0,0 +1,109 @@
+/*!
+  Breadcrumb-style context for long pipelines, staged as the pipeline
+  progresses and only added to the error trace if an error occurs.
+
+  Wrapping every intermediate `Result` of a pipeline with a sub-error just
+  to record which step failed is verbose, and formatting context messages
+  up front is wasted on the successful runs. A [`TraceBuilder`] instead
+  keeps a stack of small stage messages, which are pushed and popped as the
+  pipeline progresses, and folded into the trace of the error when one
+  ultimately occurs:
+
+  ```
+  # use flex_error::breadcrumbs::TraceBuilder;
+  # use flex_error::define_error;
+  # define_error! {
+  #   DecodeError {
+  #     Truncated
+  #       | _ | { "input is truncated" },
+  #   }
+  # }
+  # fn decode_header(bytes: &[u8]) -> Result<u8, DecodeError> {
+  #   bytes.first().copied().ok_or_else(DecodeError::truncated)
+  # }
+  # fn decode_body(header: &u8, bytes: &[u8]) -> Result<Vec<u8>, DecodeError> {
+  #   bytes.get(1..=usize::from(*header)).map(<[u8]>::to_vec).ok_or_else(DecodeError::truncated)
+  # }
+  # fn decode(bytes: &[u8]) -> Result<Vec<u8>, DecodeError> {
+  let mut stages = TraceBuilder::new();
+
+  stages.stage("decoding header");
+  let header = decode_header(&bytes).map_err(|e| e.map_trace(|trace| stages.fold(trace)))?;
+  stages.pop();
+
+  let body = stages.scoped("decoding body", |stages| {
+    decode_body(&header, &bytes).map_err(|e| e.map_trace(|trace| stages.fold(trace)))
+  })?;
+  # Ok(body)
+  # }
+  # let err = decode(&[4, 1, 2]).unwrap_err();
+  # assert_eq!(format!("{:#}", err), "decoding body: input is truncated");
+  ```
+
+  The failure of `decode_body` is then traced with the message `decoding body`
+  on top of the messages of the error. Successful runs only pay for pushing
+  and popping the stages, which do not allocate for `&'static str` messages.
+*/
+
+use alloc::borrow::Cow;
+use alloc::vec::Vec;
+
+use crate::tracer::ErrorMessageTracer;
+
+/// A stack of stage messages, which are added to an error trace with
+/// [`fold`](TraceBuilder::fold) if an error occurs. See the
+/// [module documentation](self).
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct TraceBuilder {
+    stages: Vec<Cow<'static, str>>,
+}
+
+impl TraceBuilder {
+    pub fn new() -> Self {
+        TraceBuilder { stages: Vec::new() }
+    }
+
+    /// Pushes a stage message, when the pipeline enters a new stage.
+    pub fn stage(&mut self, message: impl Into<Cow<'static, str>>) -> &mut Self {
+        self.stages.push(message.into());
+        self
+    }
+
+    /// Pops the innermost stage message, when the pipeline leaves the stage.
+    pub fn pop(&mut self) -> Option<Cow<'static, str>> {
+        self.stages.pop()
+    }
+
+    /// Runs `f` within a stage, which is popped after `f` returns.
+    pub fn scoped<R>(
+        &mut self,
+        message: impl Into<Cow<'static, str>>,
+        f: impl FnOnce(&mut Self) -> R,
+    ) -> R {
+        let depth = self.stages.len();
+        self.stages.push(message.into());
+        let result = f(self);
+        self.stages.truncate(depth);
+        result
+    }
+
+    /// Returns an iterator over the stage messages, from the outermost
+    /// to the innermost stage.
+    pub fn stages(&self) -> impl Iterator<Item = &str> {
+        self.stages.iter().map(|stage| stage.as_ref())
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.stages.is_empty()
+    }
+
+    /// Adds the stage messages to an error trace, from the innermost to the
+    /// outermost stage, so that the outermost stage becomes the outermost
+    /// message of the trace.
+    pub fn fold<Tracer: ErrorMessageTracer>(&self, trace: Tracer) -> Tracer {
+        self.stages
+            .iter()
+            .rev()
+            .fold(trace, |trace, stage| trace.add_message(stage))
+    }
+}
@@ -0,0 +1,201 @@
+/*!
+  Error codes and severities assigned to sub-errors from a centrally
+  governed mapping file, such as `error_codes.toml`.
+
+  The mapping file is given to [`define_error!`](crate::define_error) with
+  the `@codes` option, typically with `include_str!`, and is parsed at
+  compile time. The build fails if any sub-error of the error type has no
+  entry in the mapping file, so that the error definitions and the mapping
+  are kept in sync:
+
+  ```
+  # use flex_error::codes::Severity;
+  # use flex_error::define_error;
+  define_error! {
+    @codes(include_str!("../tests/error_codes.toml"))
+    MyError {
+      ConnectTimeout
+        { timeout: u64 }
+        | e | { format_args!("timed out after {}ms", e.timeout) },
+    }
+  }
+  # let err = MyError::connect_timeout(100);
+  # let code = err.detail().error_code();
+  # assert_eq!((code.code, code.severity), ("NET-0001", Severity::Warning));
+  ```
+
+  With each entry of the mapping file keyed by the error and sub-error names:
+
+  ```toml
+  [MyError.ConnectTimeout]
+  code = "NET-0001"
+  severity = "warning"
+  ```
+
+  The code and severity of an error detail are then returned by the generated
+  `MyErrorDetail::error_code` method.
+
+  Only the subset of TOML needed for the mapping file is supported: tables
+  with dotted names, and `code` and `severity` keys with string or bare values.
+  The severity defaults to [`Severity::Error`] if it is not given.
+*/
+
+use core::fmt::{Display, Formatter};
+
+/// The severity of an error, as assigned in the error codes mapping file.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum Severity {
+    Info,
+    Warning,
+    Error,
+    Fatal,
+}
+
+impl Display for Severity {
+    fn fmt(&self, f: &mut Formatter<'_>) -> core::fmt::Result {
+        let severity = match self {
+            Severity::Info => "info",
+            Severity::Warning => "warning",
+            Severity::Error => "error",
+            Severity::Fatal => "fatal",
+        };
+        write!(f, "{}", severity)
+    }
+}
+
+/// The error code and severity assigned to a sub-error.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct ErrorCode {
+    pub code: &'static str,
+    pub severity: Severity,
+}
+
+impl Display for ErrorCode {
+    fn fmt(&self, f: &mut Formatter<'_>) -> core::fmt::Result {
+        write!(f, "{}", self.code)
+    }
+}
+
+/// Looks up the entry for the sub-error `variant` of the error type `error`
+/// in the error codes mapping file `table`. Returns `None` if there is no
+/// entry, or if the entry has no `code` key.
+///
+/// This is a `const fn` so that missing entries are detected at compile time.
+pub const fn lookup(table: &'static str, error: &str, variant: &str) -> Option<ErrorCode> {
+    let bytes = table.as_bytes();
+    let mut in_entry = false;
+    let mut code = None;
+    let mut severity = Severity::Error;
+
+    let mut line_start = 0;
+    while line_start < bytes.len() {
+        let mut line_end = line_start;
+        while line_end < bytes.len() && bytes[line_end] != b'\n' {
+            line_end += 1;
+        }
+
+        let (start, end) = trim(bytes, line_start, line_end);
+        if start < end && bytes[start] == b'[' {
+            if in_entry {
+                break;
+            }
+            let (name_start, name_end) = trim(bytes, start + 1, find(bytes, start, end, b']'));
+            in_entry = is_entry_name(bytes, name_start, name_end, error, variant);
+        } else if in_entry && start < end && bytes[start] != b'#' {
+            let equal = find(bytes, start, end, b'=');
+            let (key_start, key_end) = trim(bytes, start, equal);
+            let (value_start, value_end) = value(bytes, equal + 1, end);
+
+            if eq_slice(bytes, key_start, key_end, b"code") {
+                code = Some(sub_str(table, value_start, value_end));
+            } else if eq_slice(bytes, key_start, key_end, b"severity") {
+                severity = parse_severity(bytes, value_start, value_end);
+            }
+        }
+
+        line_start = line_end + 1;
+    }
+
+    match code {
+        Some(code) => Some(ErrorCode { code, severity }),
+        None => None,
+    }
+}
+
+const fn parse_severity(bytes: &[u8], start: usize, end: usize) -> Severity {
+    if eq_slice(bytes, start, end, b"info") {
+        Severity::Info
+    } else if eq_slice(bytes, start, end, b"warning") {
+        Severity::Warning
+    } else if eq_slice(bytes, start, end, b"error") {
+        Severity::Error
+    } else if eq_slice(bytes, start, end, b"fatal") {
+        Severity::Fatal
+    } else {
+        panic!("invalid severity in error codes table, expected one of info, warning, error or fatal")
+    }
+}
+
+/// Returns the range of a value, with the quotes of a string value or a
+/// trailing comment of a bare value removed.
+const fn value(bytes: &[u8], start: usize, end: usize) -> (usize, usize) {
+    let (start, end) = trim(bytes, start, end);
+    if start < end && bytes[start] == b'"' {
+        (start + 1, find(bytes, start + 1, end, b'"'))
+    } else {
+        trim(bytes, start, find(bytes, start, end, b'#'))
+    }
+}
+
+/// Returns whether the table name in the given range is `error.variant`.
+const fn is_entry_name(bytes: &[u8], start: usize, end: usize, error: &str, variant: &str) -> bool {
+    let error = error.as_bytes();
+    let variant = variant.as_bytes();
+    let dot = start + error.len();
+
+    dot < end
+        && bytes[dot] == b'.'
+        && eq_slice(bytes, start, dot, error)
+        && eq_slice(bytes, dot + 1, end, variant)
+}
+
+const fn find(bytes: &[u8], start: usize, end: usize, needle: u8) -> usize {
+    let mut i = start;
+    while i < end && bytes[i] != needle {
+        i += 1;
+    }
+    i
+}
+
+const fn trim(bytes: &[u8], mut start: usize, mut end: usize) -> (usize, usize) {
+    while start < end && bytes[start].is_ascii_whitespace() {
+        start += 1;
+    }
+    while start < end && bytes[end - 1].is_ascii_whitespace() {
+        end -= 1;
+    }
+    (start, end)
+}
+
+const fn eq_slice(bytes: &[u8], start: usize, end: usize, expected: &[u8]) -> bool {
+    if end - start != expected.len() {
+        return false;
+    }
+    let mut i = 0;
+    while i < expected.len() {
+        if bytes[start + i] != expected[i] {
+            return false;
+        }
+        i += 1;
+    }
+    true
+}
+
+const fn sub_str(s: &'static str, start: usize, end: usize) -> &'static str {
+    let (_, rest) = s.as_bytes().split_at(start);
+    let (value, _) = rest.split_at(end - start);
+    match core::str::from_utf8(value) {
+        Ok(value) => value,
+        Err(_) => panic!("invalid UTF-8 in error codes table"),
+    }
+}
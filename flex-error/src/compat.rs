@@ -0,0 +1,128 @@
+/*!
+  Compatibility layer for wrapping errors defined with `flex-error` 0.4
+  from crates that depend on a different, semver-incompatible version of
+  `flex-error`.
+
+  Error types defined with `define_error!` in `flex-error` 0.4 have the form
+  `pub struct MyError(pub MyErrorDetail, pub Tracer)`. Since the traits
+  implemented by the other version of `flex-error` are not the same as the
+  traits in this crate, these errors cannot be used directly as an error source.
+  Instead, [`define_compat_source!`](crate::define_compat_source) defines a local
+  proxy type that implements [`ErrorSource`](crate::ErrorSource) for the
+  foreign error type, with the foreign error detail kept as detail and the foreign
+  error trace traced by the current tracer.
+
+  The foreign error traces, such as `eyre::Report` and `anyhow::Error`, are
+  walked through their chain of error sources, so that each message of the
+  foreign trace is added to the current trace, from the root cause to the
+  outermost message. This requires the `std` feature.
+
+  ```
+  # mod flex_error_0_4 { pub type DefaultTracer = eyre::Report; }
+  # mod old_crate {
+  #   pub mod error {
+  #     #[derive(Debug)]
+  #     pub struct ErrorDetail;
+  #     pub struct Error(pub ErrorDetail, pub eyre::Report);
+  #   }
+  # }
+  use flex_error::define_error;
+
+  flex_error::define_compat_source! {
+    /// Error source for errors coming from `old_crate`.
+    pub OldCrateSource(
+      old_crate::error::Error,
+      old_crate::error::ErrorDetail,
+      flex_error_0_4::DefaultTracer
+    )
+  }
+
+  define_error! {
+    MyError {
+      Old
+        [ OldCrateSource ]
+        | _ | { "error from old crate" },
+    }
+  }
+
+  let old = old_crate::error::Error(
+    old_crate::error::ErrorDetail,
+    eyre::eyre!("connection refused"),
+  );
+  let err = MyError::old(old);
+  assert_eq!(err.trace().to_string(), "error from old crate");
+  ```
+
+  The foreign traces that are not error chains, such as the `StringTracer`
+  of `flex-error` 0.4, are given with the `@display` option, and are traced
+  from their string representation:
+
+  ```
+  # mod flex_error_0_4 {
+  #   pub mod tracer_impl { pub mod string { pub type StringTracer = String; } }
+  # }
+  # mod old_crate {
+  #   pub mod error {
+  #     #[derive(Debug)]
+  #     pub struct ErrorDetail;
+  #     pub struct Error(pub ErrorDetail, pub String);
+  #   }
+  # }
+  flex_error::define_compat_source! {
+    pub OldCrateSource(
+      old_crate::error::Error,
+      old_crate::error::ErrorDetail,
+      flex_error_0_4::tracer_impl::string::StringTracer
+    ) @display
+  }
+  ```
+*/
+
+/// Defines a proxy type implementing [`ErrorSource`](crate::ErrorSource) for
+/// an error type defined by `flex-error` 0.4. See the [`compat`](crate::compat)
+/// module for details.
+#[macro_export]
+macro_rules! define_compat_source {
+  ( $( #[$attr:meta] )*
+    $vis:vis $proxy:ident ( $error:path, $detail:ty, $trace:ty $(,)? )
+  ) => {
+    $( #[$attr] )*
+    $vis struct $proxy;
+
+    impl<Tracer> $crate::ErrorSource<Tracer> for $proxy
+    where
+        Tracer: $crate::ErrorMessageTracer,
+        $trace: ::core::convert::AsRef<dyn $crate::StdError + Send + Sync>,
+    {
+        type Source = $error;
+        type Detail = $detail;
+
+        #[track_caller]
+        fn error_details(source: Self::Source) -> (Self::Detail, Option<Tracer>) {
+            let $error(detail, trace) = source;
+            (detail, Some($crate::trace_chain(trace.as_ref())))
+        }
+    }
+  };
+  ( $( #[$attr:meta] )*
+    $vis:vis $proxy:ident ( $error:path, $detail:ty, $trace:ty $(,)? ) @display
+  ) => {
+    $( #[$attr] )*
+    $vis struct $proxy;
+
+    impl<Tracer> $crate::ErrorSource<Tracer> for $proxy
+    where
+        Tracer: $crate::ErrorMessageTracer,
+        $trace: ::core::fmt::Display,
+    {
+        type Source = $error;
+        type Detail = $detail;
+
+        #[track_caller]
+        fn error_details(source: Self::Source) -> (Self::Detail, Option<Tracer>) {
+            let $error(detail, trace) = source;
+            (detail, Some(Tracer::new_message(&trace)))
+        }
+    }
+  };
+}
@@ -0,0 +1,165 @@
+/*!
+  Automatic enrichment of errors with context values stored in task-locals.
+
+  When the `task_context` feature is enabled, context values such as chain
+  or connection identifiers can be set for an async task with [`scope`],
+  instead of being passed manually to every error constructor:
+
+  ```
+  # flex_error::define_error! {
+  #   RelayError {
+  #     Timeout
+  #       | _ | { "timed out" },
+  #   }
+  # }
+  # async fn relay_packets() -> Result<(), RelayError> {
+  #   Err(RelayError::timeout())
+  # }
+  # async fn run(chain_id: &str, connection_id: &str) -> Result<(), RelayError> {
+  flex_error::context::scope(
+    [("chain_id", chain_id.to_string()), ("connection_id", connection_id.to_string())],
+    async move { relay_packets().await },
+  )
+  .await
+  # }
+  # let runtime = tokio::runtime::Builder::new_current_thread().build().unwrap();
+  # let err = runtime.block_on(run("ibc-0", "connection-1")).unwrap_err();
+  # assert!(format!("{:#}", err).contains("[chain_id=ibc-0, connection_id=connection-1]"));
+  ```
+
+  Every error trace created by the built-in error tracers within the task is
+  then attached with the context values, as an additional message on top of
+  the innermost message, in the form `[chain_id=ibc-0, connection_id=connection-1]`.
+  Scopes can be nested, in which case the values of the inner scope are added
+  to the values of the outer scopes.
+
+  By default all context values are attached. The keys to be attached can be
+  restricted process-wide with [`capture_keys`].
+*/
+
+use alloc::string::String;
+use alloc::vec::Vec;
+use core::fmt::{Display, Formatter};
+use core::future::Future;
+use std::sync::RwLock;
+
+use crate::tracer::ErrorMessageTracer;
+
+tokio::task_local! {
+    static TASK_CONTEXT: TaskContext;
+}
+
+static CAPTURE_KEYS: RwLock<Option<Vec<&'static str>>> = RwLock::new(None);
+
+/// The context values of a task, which are attached to the errors
+/// created within the task.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct TaskContext {
+    values: Vec<(&'static str, String)>,
+}
+
+impl TaskContext {
+    /// Returns the value of the given key, if it is set.
+    pub fn get(&self, key: &str) -> Option<&str> {
+        self.values
+            .iter()
+            .rev()
+            .find(|(k, _)| *k == key)
+            .map(|(_, value)| value.as_str())
+    }
+
+    /// Returns an iterator over the context values, in the order
+    /// they have been set.
+    pub fn iter(&self) -> impl Iterator<Item = (&'static str, &str)> {
+        self.values.iter().map(|(key, value)| (*key, value.as_str()))
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.values.is_empty()
+    }
+
+    fn extend(&self, values: impl IntoIterator<Item = (&'static str, String)>) -> Self {
+        let mut context = self.clone();
+        for (key, value) in values {
+            context.values.retain(|(k, _)| *k != key);
+            context.values.push((key, value));
+        }
+        context
+    }
+
+    fn captured(&self) -> Self {
+        let keys = CAPTURE_KEYS.read().unwrap_or_else(|e| e.into_inner());
+        match keys.as_ref() {
+            None => self.clone(),
+            Some(keys) => TaskContext {
+                values: self
+                    .values
+                    .iter()
+                    .filter(|(key, _)| keys.contains(key))
+                    .cloned()
+                    .collect(),
+            },
+        }
+    }
+}
+
+impl Display for TaskContext {
+    fn fmt(&self, f: &mut Formatter<'_>) -> core::fmt::Result {
+        write!(f, "[")?;
+        for (index, (key, value)) in self.values.iter().enumerate() {
+            if index > 0 {
+                write!(f, ", ")?;
+            }
+            write!(f, "{0}={1}", key, value)?;
+        }
+        write!(f, "]")
+    }
+}
+
+/// Runs the future with the given context values set for the task.
+pub async fn scope<F>(values: impl IntoIterator<Item = (&'static str, String)>, future: F) -> F::Output
+where
+    F: Future,
+{
+    let context = current_values().extend(values);
+    TASK_CONTEXT.scope(context, future).await
+}
+
+/// Runs the closure with the given context values set, for synchronous
+/// code running within a task.
+pub fn sync_scope<R>(values: impl IntoIterator<Item = (&'static str, String)>, f: impl FnOnce() -> R) -> R {
+    let context = current_values().extend(values);
+    TASK_CONTEXT.sync_scope(context, f)
+}
+
+/// Restricts the context values attached to errors to the given keys,
+/// process-wide.
+pub fn capture_keys(keys: &[&'static str]) {
+    *CAPTURE_KEYS.write().unwrap_or_else(|e| e.into_inner()) = Some(keys.to_vec());
+}
+
+/// Returns the context values of the current task that are attached to
+/// errors, or `None` if there is none.
+pub fn current() -> Option<TaskContext> {
+    let context = current_values().captured();
+    if context.is_empty() {
+        None
+    } else {
+        Some(context)
+    }
+}
+
+fn current_values() -> TaskContext {
+    TASK_CONTEXT
+        .try_with(|context| context.clone())
+        .unwrap_or_default()
+}
+
+/// Attaches the context values of the current task to a newly created
+/// error trace.
+pub(crate) fn attach<Trace: ErrorMessageTracer>(trace: Trace) -> Trace {
+    match current() {
+        Some(context) => trace.add_message(&context),
+        None => trace,
+    }
+}
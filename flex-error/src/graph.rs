@@ -0,0 +1,84 @@
+/*!
+  Recording of the wrapping relationships between error types, to generate
+  diagrams of error propagation across a workspace.
+
+  When the `error_graph` feature is enabled, the constructors generated by
+  [`define_error!`](crate::define_error) for sub-errors with an error source
+  record an [`ErrorEdge`] the first time they are called. The recorded edges
+  can then be dumped with [`error_graph`], or rendered in the Graphviz DOT
+  format with [`error_graph_dot`].
+*/
+
+use alloc::string::String;
+use alloc::vec::Vec;
+use core::fmt::Write;
+use std::collections::BTreeSet;
+use std::sync::Mutex;
+
+/// A wrapping relationship where an error constructor of `error`
+/// wraps an error of type `source`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct ErrorEdge {
+    /// The type name of the error being constructed.
+    pub error: &'static str,
+
+    /// The name of the error constructor.
+    pub constructor: &'static str,
+
+    /// The type name of the error source being wrapped.
+    pub source: &'static str,
+}
+
+static ERROR_GRAPH: Mutex<BTreeSet<ErrorEdge>> = Mutex::new(BTreeSet::new());
+
+/// Records an [`ErrorEdge`] into the global error graph. This is called
+/// by the constructors generated by [`define_error!`](crate::define_error).
+#[doc(hidden)]
+pub fn record_edge(edge: ErrorEdge) {
+    if let Ok(mut graph) = ERROR_GRAPH.lock() {
+        graph.insert(edge);
+    }
+}
+
+/// Returns all [`ErrorEdge`]s recorded so far, sorted by the error type names.
+pub fn error_graph() -> Vec<ErrorEdge> {
+    match ERROR_GRAPH.lock() {
+        Ok(graph) => graph.iter().copied().collect(),
+        Err(_) => Vec::new(),
+    }
+}
+
+/// Renders the recorded error graph in the Graphviz DOT format.
+pub fn error_graph_dot() -> String {
+    let mut out = String::from("digraph errors {\n");
+    for edge in error_graph() {
+        let _ = writeln!(
+            out,
+            "  {:?} -> {:?} [label={:?}];",
+            edge.error, edge.source, edge.constructor
+        );
+    }
+    out.push('}');
+    out
+}
+
+/// Internal macro used to record an error edge in error constructors.
+#[macro_export]
+#[doc(hidden)]
+macro_rules! record_error_edge {
+  ( @name( $name:ty ),
+    @constructor( $constructor:expr ),
+    @source( $source:ty ) $(,)?
+  ) => {{
+    static RECORDED: ::core::sync::atomic::AtomicBool =
+      ::core::sync::atomic::AtomicBool::new(false);
+
+    if !RECORDED.swap(true, ::core::sync::atomic::Ordering::Relaxed) {
+      $crate::graph::record_edge($crate::graph::ErrorEdge {
+        error: ::core::any::type_name::<$name>(),
+        constructor: $constructor,
+        source: ::core::any::type_name::<$source>(),
+      });
+    }
+  }};
+}
@@ -0,0 +1,243 @@
+/*!
+  A bounded registry of error traces, keyed by short unique incident IDs.
+
+  When the `incidents` feature is enabled, every error created by the
+  error types defined by [`define_error!`](crate::define_error) is assigned
+  a short unique incident ID, such as `ERR-7f3a2c`, as if all of them had
+  the `@metadata` option. The ID is kept in the [`ErrorMetadata`] of the
+  error, next to its error trace, and is carried to the errors wrapping it.
+  It is included after the error trace in the output of `render`.
+
+  When an error is constructed, a snapshot of the messages in its error
+  trace is stored in a process-wide registry, replacing the snapshot stored
+  by the error it wraps, if any. This allows user-facing messages to only
+  show the incident ID, while operators can retrieve the full error trace
+  with [`lookup`]:
+
+  ```
+  # flex_error::define_error! {
+  #   RelayError {
+  #     Refused
+  #       | _ | { "connection refused" },
+  #   }
+  # }
+  # let err = RelayError::refused();
+  match err.incident_id() {
+    Some(id) => eprintln!("internal error, please report incident {}", id),
+    None => eprintln!("internal error"),
+  }
+
+  // later, e.g. in an admin endpoint
+  let incident = flex_error::lookup("ERR-7f3a2c");
+  # let id = err.incident_id().unwrap().to_string();
+  # assert!(flex_error::lookup(&id).is_some());
+  ```
+
+  The messages added to the error trace after the construction of the
+  error, such as with `add_trace`, are not included in the snapshot. The
+  snapshot is only stored if the error tracer implements [`TraceRender`],
+  which is the case for all built-in error tracers, but not necessarily
+  for a generic error tracer of an error type defined with
+  `MyError<Tracer>`.
+
+  The registry keeps the most recently constructed incidents, up to the
+  capacity set with [`set_capacity`], which defaults to
+  [`DEFAULT_CAPACITY`].
+*/
+
+use alloc::collections::VecDeque;
+use alloc::string::{String, ToString};
+use alloc::vec::Vec;
+use core::fmt::{Display, Formatter};
+use core::str::FromStr;
+use core::sync::atomic::{AtomicU32, AtomicUsize, Ordering};
+use std::hash::{BuildHasher, Hasher};
+use std::sync::{Mutex, MutexGuard, OnceLock};
+use std::time::SystemTime;
+
+use crate::render::TraceRender;
+use crate::source::ErrorMetadata;
+
+/// The default number of incidents kept in the registry.
+pub const DEFAULT_CAPACITY: usize = 1024;
+
+const ID_PREFIX: &str = "ERR-";
+const ID_MASK: u32 = 0x00ff_ffff;
+
+static INCIDENTS: Mutex<VecDeque<Incident>> = Mutex::new(VecDeque::new());
+static CAPACITY: AtomicUsize = AtomicUsize::new(DEFAULT_CAPACITY);
+static NEXT_ID: AtomicU32 = AtomicU32::new(0);
+
+/// A short unique incident ID, displayed in the form `ERR-7f3a2c`.
+///
+/// The IDs are unique within a process for the first 16 million incidents.
+/// They start from a random offset, so that the IDs of different processes
+/// are unlikely to collide.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct IncidentId(u32);
+
+impl IncidentId {
+    pub(crate) fn next() -> Self {
+        static SEED: OnceLock<u32> = OnceLock::new();
+
+        let seed = *SEED.get_or_init(|| {
+            let mut hasher = std::collections::hash_map::RandomState::new().build_hasher();
+            hasher.write_u32(std::process::id());
+            hasher.finish() as u32
+        });
+
+        // Multiplying by an odd number is a bijection modulo 2^24, so the
+        // IDs only repeat after 2^24 incidents.
+        let count = NEXT_ID.fetch_add(1, Ordering::Relaxed);
+        IncidentId(seed.wrapping_add(count.wrapping_mul(0x9e37_79b1)) & ID_MASK)
+    }
+}
+
+impl Display for IncidentId {
+    fn fmt(&self, f: &mut Formatter<'_>) -> core::fmt::Result {
+        write!(f, "{0}{1:06x}", ID_PREFIX, self.0)
+    }
+}
+
+impl FromStr for IncidentId {
+    type Err = InvalidIncidentId;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let digits = s.trim().strip_prefix(ID_PREFIX).ok_or(InvalidIncidentId)?;
+        if digits.len() != 6 {
+            return Err(InvalidIncidentId);
+        }
+        u32::from_str_radix(digits, 16)
+            .map(IncidentId)
+            .map_err(|_| InvalidIncidentId)
+    }
+}
+
+/// The error returned when parsing a malformed incident ID.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct InvalidIncidentId;
+
+impl Display for InvalidIncidentId {
+    fn fmt(&self, f: &mut Formatter<'_>) -> core::fmt::Result {
+        write!(f, "invalid incident ID, expected the form ERR-7f3a2c")
+    }
+}
+
+impl std::error::Error for InvalidIncidentId {}
+
+/// The snapshot of an error trace stored in the registry.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Incident {
+    pub id: IncidentId,
+
+    /// The messages in the error trace, from the outermost to the
+    /// innermost message.
+    pub messages: Vec<String>,
+
+    /// The time when the innermost error of the incident was created.
+    pub created_at: SystemTime,
+
+    /// The time when the snapshot was stored.
+    pub updated_at: SystemTime,
+}
+
+/// Returns the snapshot of the error trace with the given incident ID,
+/// if it is still kept in the registry.
+pub fn lookup(id: &str) -> Option<Incident> {
+    let id = id.parse::<IncidentId>().ok()?;
+    lock().iter().rev().find(|incident| incident.id == id).cloned()
+}
+
+/// Sets the number of incidents kept in the registry, dropping the least
+/// recently constructed incidents if there are more.
+pub fn set_capacity(capacity: usize) {
+    CAPACITY.store(capacity, Ordering::Relaxed);
+    let mut incidents = lock();
+    while incidents.len() > capacity {
+        incidents.pop_front();
+    }
+}
+
+/// Removes all incidents from the registry.
+pub fn clear() {
+    lock().clear();
+}
+
+/// The error trace of a newly constructed error, whose snapshot is stored
+/// in the registry by the error types defined by
+/// [`define_error!`](crate::define_error).
+///
+/// The snapshot is stored with the [`RegisterIncident`] and [`SkipIncident`]
+/// traits, which are implemented for increasingly fewer references to
+/// `IncidentTrace`, so that the method call on `&&IncidentTrace` only stores
+/// the snapshot if the error tracer implements [`TraceRender`].
+#[doc(hidden)]
+pub struct IncidentTrace<'a, Trace: ?Sized>(pub &'a ErrorMetadata, pub &'a Trace);
+
+#[doc(hidden)]
+pub trait RegisterIncident {
+    fn register_incident(&self);
+}
+
+#[doc(hidden)]
+pub trait SkipIncident {
+    fn register_incident(&self);
+}
+
+impl<Trace: TraceRender + ?Sized> RegisterIncident for &IncidentTrace<'_, Trace> {
+    fn register_incident(&self) {
+        register(self.0, self.1);
+    }
+}
+
+impl<Trace: ?Sized> SkipIncident for IncidentTrace<'_, Trace> {
+    fn register_incident(&self) {}
+}
+
+/// Stores the snapshot of the error trace of an error with the given
+/// metadata in the registry, replacing the previous snapshot of the same
+/// incident.
+fn register<Trace>(metadata: &ErrorMetadata, trace: &Trace)
+where
+    Trace: TraceRender + ?Sized,
+{
+    let Some((id, created_at)) = metadata.incident() else {
+        return;
+    };
+    let messages = messages(trace);
+
+    let mut incidents = lock();
+    if let Some(index) = incidents.iter().rposition(|incident| incident.id == id) {
+        incidents.remove(index);
+    }
+    drop(incidents);
+
+    store(Incident {
+        id,
+        messages,
+        created_at,
+        updated_at: SystemTime::now(),
+    });
+}
+
+fn messages<Trace: TraceRender + ?Sized>(trace: &Trace) -> Vec<String> {
+    let mut messages = Vec::new();
+    let _ = trace.walk_messages(&mut |message| {
+        messages.push(message.to_string());
+        Ok(())
+    });
+    messages
+}
+
+fn store(incident: Incident) {
+    let capacity = CAPACITY.load(Ordering::Relaxed);
+    let mut incidents = lock();
+    incidents.push_back(incident);
+    while incidents.len() > capacity {
+        incidents.pop_front();
+    }
+}
+
+fn lock() -> MutexGuard<'static, VecDeque<Incident>> {
+    INCIDENTS.lock().unwrap_or_else(|e| e.into_inner())
+}
@@ -0,0 +1,183 @@
+/*!
+  Failure injection for testing error recovery paths deterministically.
+
+  When the `error_injection` feature is enabled, tests and chaos tooling can
+  force an error of a specific kind to be raised at the next injection point,
+  optionally only in a target module. The error kinds are the `MyErrorKind`
+  enums generated by [`define_error!`](crate::define_error):
+
+  ```
+  # flex_error::define_error! {
+  #   FooError {
+  #     Timeout
+  #       { timeout: u64 }
+  #       | e | { format_args!("timed out after {}ms", e.timeout) },
+  #     Parse
+  #       { input: String }
+  #       | e | { format_args!("failed to parse {}", e.input) },
+  #     Unknown
+  #       | _ | { "unknown error" },
+  #   }
+  # }
+  flex_error::inject::force_next_in("my_relayer::client", FooErrorKind::Timeout);
+  ```
+
+  Injection points are placed in the code under test with
+  [`inject_point!`](crate::inject_point), which evaluates to `Err` with the
+  error built from the forced kind, or to `Ok(())` otherwise:
+
+  ```
+  # flex_error::define_error! {
+  #   FooError {
+  #     Timeout
+  #       { timeout: u64 }
+  #       | e | { format_args!("timed out after {}ms", e.timeout) },
+  #     Parse
+  #       { input: String }
+  #       | e | { format_args!("failed to parse {}", e.input) },
+  #     Unknown
+  #       | _ | { "unknown error" },
+  #   }
+  # }
+  # struct Response;
+  # struct Client { timeout: u64 }
+  # impl Client {
+  fn query(&self) -> Result<Response, FooError> {
+    flex_error::inject_point!(FooErrorKind, |kind| match kind {
+      FooErrorKind::Timeout => FooError::timeout(self.timeout),
+      _ => FooError::unknown(),
+    })?;
+    # Ok(Response)
+  }
+  # }
+  # let client = Client { timeout: 5 };
+  # assert!(client.query().is_ok());
+  # flex_error::inject::force_next(FooErrorKind::Timeout);
+  # let err = client.query().err().unwrap();
+  # assert_eq!(err.kind(), FooErrorKind::Timeout);
+  ```
+
+  The error constructors generated by `define_error!` also consult the
+  registry, for a forced error detail of their error type. When an error
+  detail has been forced for the module that defines the error type, the
+  next error constructed with any of its constructors is an error with the
+  forced detail and a new error trace, regardless of the constructor called:
+
+  ```
+  # flex_error::define_error! {
+  #   FooError {
+  #     Timeout
+  #       { timeout: u64 }
+  #       | e | { format_args!("timed out after {}ms", e.timeout) },
+  #     Parse
+  #       { input: String }
+  #       | e | { format_args!("failed to parse {}", e.input) },
+  #     Unknown
+  #       | _ | { "unknown error" },
+  #   }
+  # }
+  flex_error::inject::force_next(FooError::timeout(5).into_detail());
+
+  // Raises the forced `Timeout` error instead of a `Parse` error.
+  let err = FooError::parse("input".to_string());
+  # assert_eq!(err.kind(), FooErrorKind::Timeout);
+  ```
+
+  Without the `error_injection` feature, `inject_point!` always evaluates
+  to `Ok(())`, and neither it nor the error constructors consult any
+  registry, so the injection points can be left in production code.
+*/
+
+use alloc::boxed::Box;
+use alloc::string::{String, ToString};
+use alloc::vec::Vec;
+use core::any::Any;
+use core::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Mutex;
+
+struct Injection {
+    kind: Box<dyn Any + Send>,
+    module: Option<String>,
+}
+
+static INJECTIONS: Mutex<Vec<Injection>> = Mutex::new(Vec::new());
+
+/// Whether any error has been forced since the last [`clear`], so that the
+/// error constructors do not take the lock when no error has been forced.
+static FORCED: AtomicBool = AtomicBool::new(false);
+
+/// Forces an error of the given kind to be raised at the next injection
+/// point for its error type, in any module. An error detail can also be
+/// forced, to be raised by the next error constructor of its error type.
+pub fn force_next<Kind>(kind: Kind)
+where
+    Kind: Any + Send,
+{
+    push(kind, None)
+}
+
+/// Forces an error of the given kind to be raised at the next injection
+/// point for its error type, in the given module or any of its submodules.
+pub fn force_next_in<Kind>(module: &str, kind: Kind)
+where
+    Kind: Any + Send,
+{
+    push(kind, Some(module.to_string()))
+}
+
+/// Removes all forced errors that have not been raised yet.
+pub fn clear() {
+    lock().clear();
+    FORCED.store(false, Ordering::Release);
+}
+
+/// Takes the first forced error kind or detail of type `Kind` that targets
+/// the given module. This is called by [`inject_point!`](crate::inject_point)
+/// and by the error constructors.
+#[doc(hidden)]
+pub fn take<Kind>(module: &str) -> Option<Kind>
+where
+    Kind: Any,
+{
+    if !FORCED.load(Ordering::Acquire) {
+        return None;
+    }
+
+    let mut injections = lock();
+
+    let index = injections.iter().position(|injection| {
+        injection.kind.is::<Kind>() && matches_module(injection.module.as_deref(), module)
+    })?;
+
+    injections
+        .remove(index)
+        .kind
+        .downcast::<Kind>()
+        .ok()
+        .map(|kind| *kind)
+}
+
+fn push<Kind>(kind: Kind, module: Option<String>)
+where
+    Kind: Any + Send,
+{
+    lock().push(Injection {
+        kind: Box::new(kind),
+        module,
+    });
+    FORCED.store(true, Ordering::Release);
+}
+
+fn lock() -> std::sync::MutexGuard<'static, Vec<Injection>> {
+    INJECTIONS.lock().unwrap_or_else(|e| e.into_inner())
+}
+
+fn matches_module(target: Option<&str>, module: &str) -> bool {
+    match target {
+        None => true,
+        Some(target) => {
+            module == target
+                || (module.starts_with(target) && module[target.len()..].starts_with("::"))
+        }
+    }
+}
@@ -0,0 +1,335 @@
+/*!
+  Process-wide interning of string values repeated across many error details,
+  such as chain identifiers or host names.
+
+  Fields of sub-errors can be typed [`Interned<str>`], which shares the storage
+  of equal strings through a process-wide table. The constructors generated by
+  [`define_error!`](crate::define_error) take the field types as they are, so
+  the values are interned with `into()` when constructing the error:
+
+  ```
+  # use flex_error::define_error;
+  # use flex_error::intern::Interned;
+  # let chain_id = String::from("ibc-0");
+  define_error! {
+    RelayerError {
+      ChainTimeout
+        { chain_id: Interned<str> }
+        | e | { format_args!("timed out waiting for chain {}", e.chain_id) },
+    }
+  }
+
+  let err = RelayerError::chain_timeout(chain_id.as_str().into());
+  # assert_eq!(err.detail().to_string(), "timed out waiting for chain ibc-0");
+  ```
+
+  The number of lookups that were served from the table is reported by
+  [`intern_stats`].
+
+  ## Aggregation Mode
+
+  When many errors are created and kept in memory at the same time, such
+  as when validating a large batch, the error traces often hold thousands
+  of identical messages. Within [`aggregate`], the built-in `eyre`, `anyhow`,
+  backtrace and `tracing` tracers store the messages in the interning table,
+  so that identical messages share the same storage across all error traces:
+
+  ```
+  # use flex_error::define_error;
+  # use flex_error::intern::aggregate;
+  # define_error! {
+  #   ValidationError {
+  #     Negative
+  #       { value: i64 }
+  #       | e | { format_args!("negative value {}", e.value) },
+  #   }
+  # }
+  # fn validate(item: &i64) -> Result<(), ValidationError> {
+  #   if *item < 0 { Err(ValidationError::negative(*item)) } else { Ok(()) }
+  # }
+  # let batch = vec![1, -2, 3, -4];
+  let errors: Vec<ValidationError> = aggregate(|| {
+    batch.iter().filter_map(|item| validate(item).err()).collect()
+  });
+  # assert_eq!(errors.len(), 2);
+  ```
+
+  `StringTracer` joins all messages of a trace into a single string, which
+  is not shared.
+*/
+
+use alloc::string::String;
+use alloc::sync::Arc;
+use core::borrow::Borrow;
+use core::cell::Cell;
+use core::fmt::{Debug, Display, Formatter};
+use core::hash::{Hash, Hasher};
+use core::ops::Deref;
+use core::sync::atomic::{AtomicU64, Ordering};
+use std::collections::HashSet;
+use std::sync::Mutex;
+
+static TABLE: Mutex<Option<HashSet<Arc<str>>>> = Mutex::new(None);
+static HITS: AtomicU64 = AtomicU64::new(0);
+static MISSES: AtomicU64 = AtomicU64::new(0);
+
+std::thread_local! {
+    static AGGREGATING: Cell<usize> = const { Cell::new(0) };
+}
+
+/// A value interned in the process-wide table, which is cheap to clone
+/// and shares its storage with all other equal interned values.
+pub struct Interned<T: ?Sized>(Arc<T>);
+
+impl Interned<str> {
+    /// Interns the given string, reusing the storage of an equal string
+    /// if it has been interned before.
+    pub fn new(value: &str) -> Self {
+        let mut table = TABLE.lock().unwrap_or_else(|e| e.into_inner());
+        let table = table.get_or_insert_with(HashSet::new);
+
+        match table.get(value) {
+            Some(interned) => {
+                HITS.fetch_add(1, Ordering::Relaxed);
+                Interned(interned.clone())
+            }
+            None => {
+                MISSES.fetch_add(1, Ordering::Relaxed);
+                let interned: Arc<str> = Arc::from(value);
+                table.insert(interned.clone());
+                Interned(interned)
+            }
+        }
+    }
+
+    pub fn as_str(&self) -> &str {
+        &self.0
+    }
+}
+
+impl<T: ?Sized> Clone for Interned<T> {
+    fn clone(&self) -> Self {
+        Interned(self.0.clone())
+    }
+}
+
+impl<T: ?Sized> Deref for Interned<T> {
+    type Target = T;
+
+    fn deref(&self) -> &T {
+        &self.0
+    }
+}
+
+impl<T: ?Sized> AsRef<T> for Interned<T> {
+    fn as_ref(&self) -> &T {
+        &self.0
+    }
+}
+
+impl Borrow<str> for Interned<str> {
+    fn borrow(&self) -> &str {
+        &self.0
+    }
+}
+
+impl From<&str> for Interned<str> {
+    fn from(value: &str) -> Self {
+        Interned::new(value)
+    }
+}
+
+impl From<String> for Interned<str> {
+    fn from(value: String) -> Self {
+        Interned::new(&value)
+    }
+}
+
+impl From<&String> for Interned<str> {
+    fn from(value: &String) -> Self {
+        Interned::new(value)
+    }
+}
+
+impl<T: ?Sized + PartialEq> PartialEq for Interned<T> {
+    fn eq(&self, other: &Self) -> bool {
+        Arc::ptr_eq(&self.0, &other.0) || self.0 == other.0
+    }
+}
+
+impl<T: ?Sized + Eq> Eq for Interned<T> {}
+
+impl<T: ?Sized + Hash> Hash for Interned<T> {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        self.0.hash(state)
+    }
+}
+
+impl<T: ?Sized + Debug> Debug for Interned<T> {
+    fn fmt(&self, f: &mut Formatter<'_>) -> core::fmt::Result {
+        Debug::fmt(&self.0, f)
+    }
+}
+
+impl<T: ?Sized + Display> Display for Interned<T> {
+    fn fmt(&self, f: &mut Formatter<'_>) -> core::fmt::Result {
+        Display::fmt(&self.0, f)
+    }
+}
+
+#[cfg(feature = "serde")]
+impl<T: ?Sized + serde::Serialize> serde::Serialize for Interned<T> {
+    fn serialize<S: serde::Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        self.0.serialize(serializer)
+    }
+}
+
+/// Statistics of the process-wide interning table.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct InternStats {
+    /// The number of lookups that reused an interned value.
+    pub hits: u64,
+
+    /// The number of lookups that interned a new value.
+    pub misses: u64,
+
+    /// The number of values currently in the table.
+    pub entries: usize,
+}
+
+impl InternStats {
+    /// Returns the ratio of lookups that reused an interned value,
+    /// or `0.0` if there has been no lookup.
+    pub fn hit_rate(&self) -> f64 {
+        let total = self.hits + self.misses;
+        if total == 0 {
+            0.0
+        } else {
+            self.hits as f64 / total as f64
+        }
+    }
+}
+
+/// Returns the statistics of the process-wide interning table.
+pub fn intern_stats() -> InternStats {
+    let table = TABLE.lock().unwrap_or_else(|e| e.into_inner());
+
+    InternStats {
+        hits: HITS.load(Ordering::Relaxed),
+        misses: MISSES.load(Ordering::Relaxed),
+        entries: table.as_ref().map_or(0, HashSet::len),
+    }
+}
+
+/// Removes the values from the interning table that are no longer
+/// referenced by any [`Interned`] value, and returns the number of
+/// removed values. This can be called periodically by long-running
+/// processes to bound the size of the table.
+pub fn purge_interned() -> usize {
+    let mut table = TABLE.lock().unwrap_or_else(|e| e.into_inner());
+
+    match table.as_mut() {
+        Some(table) => {
+            let before = table.len();
+            table.retain(|value| Arc::strong_count(value) > 1);
+            before - table.len()
+        }
+        None => 0,
+    }
+}
+
+/// Runs `f` in the aggregation mode, in which the messages traced by the
+/// built-in error tracers on the current thread are interned, so that
+/// identical messages share their storage across error traces.
+pub fn aggregate<R>(f: impl FnOnce() -> R) -> R {
+    struct AggregateGuard;
+
+    impl Drop for AggregateGuard {
+        fn drop(&mut self) {
+            let _ = AGGREGATING.try_with(|depth| depth.set(depth.get() - 1));
+        }
+    }
+
+    AGGREGATING.with(|depth| depth.set(depth.get() + 1));
+    let _guard = AggregateGuard;
+
+    f()
+}
+
+#[cfg(any(
+    feature = "anyhow_tracer",
+    feature = "eyre_tracer_minimal",
+    feature = "backtrace_tracer",
+    feature = "tracing_tracer"
+))]
+fn is_aggregating() -> bool {
+    AGGREGATING.try_with(|depth| depth.get() > 0).unwrap_or(false)
+}
+
+/// A message stored by the built-in error tracers, which is interned
+/// when it is traced within [`aggregate`].
+#[cfg(any(
+    feature = "anyhow_tracer",
+    feature = "eyre_tracer_minimal",
+    feature = "backtrace_tracer",
+    feature = "tracing_tracer"
+))]
+pub(crate) enum SharedMessage {
+    Owned(String),
+    Interned(Interned<str>),
+}
+
+#[cfg(any(
+    feature = "anyhow_tracer",
+    feature = "eyre_tracer_minimal",
+    feature = "backtrace_tracer",
+    feature = "tracing_tracer"
+))]
+impl SharedMessage {
+    pub(crate) fn as_str(&self) -> &str {
+        match self {
+            SharedMessage::Owned(message) => message,
+            SharedMessage::Interned(message) => message,
+        }
+    }
+}
+
+#[cfg(any(
+    feature = "anyhow_tracer",
+    feature = "eyre_tracer_minimal",
+    feature = "backtrace_tracer",
+    feature = "tracing_tracer"
+))]
+impl From<String> for SharedMessage {
+    fn from(message: String) -> Self {
+        if is_aggregating() {
+            SharedMessage::Interned(Interned::new(&message))
+        } else {
+            SharedMessage::Owned(message)
+        }
+    }
+}
+
+#[cfg(any(
+    feature = "anyhow_tracer",
+    feature = "eyre_tracer_minimal",
+    feature = "backtrace_tracer",
+    feature = "tracing_tracer"
+))]
+impl Debug for SharedMessage {
+    fn fmt(&self, f: &mut Formatter<'_>) -> core::fmt::Result {
+        Debug::fmt(self.as_str(), f)
+    }
+}
+
+#[cfg(any(
+    feature = "anyhow_tracer",
+    feature = "eyre_tracer_minimal",
+    feature = "backtrace_tracer",
+    feature = "tracing_tracer"
+))]
+impl Display for SharedMessage {
+    fn fmt(&self, f: &mut Formatter<'_>) -> core::fmt::Result {
+        Display::fmt(self.as_str(), f)
+    }
+}
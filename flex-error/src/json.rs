@@ -0,0 +1,197 @@
+/*!
+  Machine-readable JSON export of errors, together with their error traces.
+
+  When the `serde_json` feature is enabled, the error types defined by
+  [`define_error!`](crate::define_error) with the `@serde` option have the
+  generated methods `trace_serializer` and `to_json`, which export the error
+  detail together with the messages in the error trace:
+
+  ```
+  # use flex_error::{define_error, TraceError};
+  # fn run() -> serde_json::Result<()> {
+  define_error! {
+    @serde(tag = "kind")
+    RelayError {
+      Connection
+        { address: String }
+        [ TraceError<std::io::Error> ]
+        | e | { format_args!("failed to connect to {}", e.address) },
+    }
+  }
+
+  # let io_error = std::io::Error::new(std::io::ErrorKind::ConnectionRefused, "connection refused");
+  # let err = RelayError::connection("10.0.0.1:26657".to_string(), io_error);
+  let json = err.to_json()?;
+  # assert_eq!(json["detail"]["kind"], "Connection");
+  # Ok(())
+  # }
+  # run().unwrap();
+  ```
+
+  The exported JSON has the form:
+
+  ```json
+  {
+    "message": "failed to connect to 10.0.0.1:26657",
+    "detail": { "kind": "Connection", "address": "10.0.0.1:26657", "source": null },
+    "trace": [
+      { "message": "failed to connect to 10.0.0.1:26657", "level": "info" },
+      { "message": "connection refused", "level": "info", "location": "src/relay.rs:42:9" }
+    ]
+  }
+  ```
+
+  The `detail` member is the error detail serialized with the `@serde`
+  representation, including the details of the wrapped errors, and the
+  `trace` member holds the messages in the error trace, from the outermost
+  to the innermost message. The source location recorded by the tracer, if
+  any, is attached to the innermost message.
+
+  [`TraceSerializer`] can also be used directly with other `serde` data
+  formats, or for any error detail and tracer implementing `Serialize` and
+  [`TraceRender`].
+
+  The messages in the error trace are serialized one at a time, as they are
+  walked in the trace, so that errors aggregating thousands of messages can
+  be exported without building the whole JSON in memory. With the `std`
+  feature, the generated method `serialize_chain_into` writes the JSON
+  directly to an [`io::Write`](std::io::Write), such as the body of a
+  response:
+
+  ```
+  # use flex_error::define_error;
+  # define_error! {
+  #   @serde(tag = "kind")
+  #   RelayError {
+  #     Timeout
+  #       | _ | { "timed out" },
+  #   }
+  # }
+  # fn run() -> serde_json::Result<()> {
+  # let err = RelayError::timeout();
+  # let mut response_body = Vec::new();
+  err.serialize_chain_into(&mut response_body)?;
+  # assert!(response_body.starts_with(b"{"));
+  # Ok(())
+  # }
+  # run().unwrap();
+  ```
+*/
+
+use alloc::string::{String, ToString};
+use core::fmt::Display;
+
+use serde::ser::{Serialize, SerializeSeq, SerializeStruct, Serializer};
+
+use crate::render::{Level, TraceRender};
+
+/// Serializes an error detail together with the messages in an error trace.
+/// See the [module documentation](self).
+pub struct TraceSerializer<'a, Detail: ?Sized, Trace: ?Sized> {
+    pub detail: &'a Detail,
+    pub trace: &'a Trace,
+}
+
+impl<'a, Detail: ?Sized, Trace: ?Sized> TraceSerializer<'a, Detail, Trace> {
+    pub fn new(detail: &'a Detail, trace: &'a Trace) -> Self {
+        TraceSerializer { detail, trace }
+    }
+}
+
+impl<'a, Detail, Trace> TraceSerializer<'a, Detail, Trace>
+where
+    Detail: Serialize + Display + ?Sized,
+    Trace: TraceRender + ?Sized,
+{
+    /// Exports the error as a JSON value.
+    pub fn to_json(&self) -> serde_json::Result<serde_json::Value> {
+        serde_json::to_value(self)
+    }
+
+    /// Exports the error as a JSON string on a single line, e.g. for
+    /// newline-delimited log records.
+    pub fn to_json_string(&self) -> serde_json::Result<String> {
+        serde_json::to_string(self)
+    }
+
+    /// Writes the error as JSON to `writer`, serializing the messages in
+    /// the error trace incrementally. See the [module documentation](self).
+    #[cfg(feature = "std")]
+    pub fn serialize_chain_into<W: std::io::Write>(&self, writer: W) -> serde_json::Result<()> {
+        serde_json::to_writer(writer, self)
+    }
+}
+
+impl<'a, Detail, Trace> Serialize for TraceSerializer<'a, Detail, Trace>
+where
+    Detail: Serialize + Display + ?Sized,
+    Trace: TraceRender + ?Sized,
+{
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        let mut state = serializer.serialize_struct("ErrorReport", 3)?;
+        state.serialize_field("message", &self.detail.to_string())?;
+        state.serialize_field("detail", self.detail)?;
+        state.serialize_field("trace", &TraceEntries(self.trace))?;
+        state.end()
+    }
+}
+
+/// Serializes the messages in an error trace in the same form as
+/// [`TraceEntry`](crate::render::TraceEntry), without collecting them.
+struct TraceEntries<'a, Trace: ?Sized>(&'a Trace);
+
+impl<'a, Trace> Serialize for TraceEntries<'a, Trace>
+where
+    Trace: TraceRender + ?Sized,
+{
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        // The trace is walked twice, first to count the messages, so that the
+        // location can be attached to the innermost entry as it is reached.
+        let mut len = 0;
+        let _ = self.0.walk_messages(&mut |_| {
+            len += 1;
+            Ok(())
+        });
+
+        let location = self.0.location();
+        let mut seq = serializer.serialize_seq(Some(len))?;
+        let mut index = 0;
+        let mut error = None;
+        let _ = self.0.walk_leveled_messages(&mut |level, message| {
+            index += 1;
+            let entry = StreamedEntry {
+                message,
+                level,
+                location: if index == len { location } else { None },
+            };
+            seq.serialize_element(&entry).map_err(|e| {
+                error = Some(e);
+                core::fmt::Error
+            })
+        });
+
+        match error {
+            Some(e) => Err(e),
+            None => seq.end(),
+        }
+    }
+}
+
+struct StreamedEntry<'a> {
+    message: &'a dyn Display,
+    level: Level,
+    location: Option<&'a str>,
+}
+
+impl<'a> Serialize for StreamedEntry<'a> {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        let len = if self.location.is_some() { 3 } else { 2 };
+        let mut state = serializer.serialize_struct("TraceEntry", len)?;
+        state.serialize_field("message", &format_args!("{}", self.message))?;
+        state.serialize_field("level", &self.level)?;
+        if let Some(location) = self.location {
+            state.serialize_field("location", location)?;
+        }
+        state.end()
+    }
+}
@@ -24,46 +24,230 @@ The [`DefaultTracer`] type is set globally by the feature flag, so that
 application error types do not have to be over-generalized.
 The trade off is that it is not possible to use multiple
 [`ErrorTracer`] implementations at the same time across different crates that
-use `flex-error`.
+use `flex-error`. An error type can still drive two error tracers at once, by
+defining it with a [PairTracer](tracer_impl::pair::PairTracer) as its tracer.
+The creation of an error tracer can also be deferred until the trace is
+used, with a [LazyTracer](tracer_impl::lazy::LazyTracer), so that errors
+created and discarded on hot paths do not capture backtraces. For
+supervisors keeping the errors of their child tasks in long-lived
+structures, the [AggregatingTracer](tracer_impl::aggregating::AggregatingTracer)
+keeps a bounded number of rendered messages, and merges the traces of the
+children without rendering them again.
+
+Without the `alloc` feature, which is enabled by `std` and by the
+`string_tracer` feature, `flex-error` does not require an allocator. In this mode, the [`DefaultTracer`] is the unit
+[NoTracer](tracer_impl::no_trace::NoTracer), which discards all error
+messages, and the error details are still displayed with `core::fmt`.
+This allows `flex-error` to be used in environments without an allocator,
+such as SGX enclaves and small microcontrollers. With the `defmt_tracer`
+feature, the [`DefaultTracer`] is instead the
+[DefmtTracer](tracer_impl::defmt::DefmtTracer), which keeps the error
+messages in a fixed-size buffer and can be logged with `defmt`. The APIs collecting error
+messages into strings, such as `trace_entries` and `summarize`, and
+sub-errors with a `Self` or [`BoxSource`] source, which box the source
+detail, require the `alloc` feature.
 
 !*/
 
 #[cfg(feature = "std")]
 extern crate std;
 
+#[cfg(feature = "alloc")]
 pub extern crate alloc;
 
 #[cfg(feature = "std")]
 pub use std::error::Error as StdError;
 
+#[cfg(all(feature = "error_in_core", not(feature = "std")))]
+pub use core::error::Error as StdError;
+
+#[cfg(feature = "arbitrary")]
+pub use arbitrary;
+
+#[cfg(feature = "serde")]
+pub use serde;
+
+#[cfg(feature = "serde_json")]
+pub use serde_json;
+
+#[cfg(any(feature = "code_registry", feature = "audit-tool"))]
+#[doc(hidden)]
+pub use inventory;
+
+#[cfg(feature = "derive")]
+pub use flex_error_derive::FlexError;
+
+#[cfg(feature = "sentry")]
+pub use sentry_types;
+
+#[cfg(feature = "valuable")]
+pub use valuable;
+
+#[cfg(feature = "defmt")]
+pub use defmt;
+
+#[cfg(feature = "wasm")]
+pub use wasm_bindgen;
+
+#[cfg(feature = "axum")]
+pub use axum;
+
+#[cfg(feature = "error_graph")]
+pub use graph::{error_graph, error_graph_dot};
+
+#[cfg(feature = "error_stats")]
+pub use stats::{reset_stats, stats};
+
+#[cfg(feature = "interning")]
+pub use intern::{intern_stats, Interned};
+
+#[cfg(feature = "incidents")]
+pub use incident::lookup;
+
+#[cfg(any(feature = "log", feature = "tracing"))]
+pub use rate_limit::reset_rate_limits;
+
+#[cfg(feature = "attachments")]
+pub mod attach;
+#[cfg(feature = "audit-tool")]
+pub mod audit;
+#[cfg(feature = "alloc")]
+pub mod breadcrumbs;
+pub mod codes;
+#[cfg(feature = "compat-0-4")]
+pub mod compat;
+#[cfg(feature = "task_context")]
+pub mod context;
+#[cfg(feature = "error_graph")]
+pub mod graph;
+#[cfg(feature = "incidents")]
+pub mod incident;
+#[cfg(feature = "error_injection")]
+pub mod inject;
+#[cfg(feature = "interning")]
+pub mod intern;
+#[cfg(feature = "serde_json")]
+pub mod json;
+#[cfg(feature = "std")]
+pub mod locale;
 pub mod macros;
+#[cfg(feature = "alloc")]
+pub mod parse;
+pub mod policy;
+#[cfg(feature = "problem_details")]
+pub mod problem;
+#[cfg(any(feature = "log", feature = "tracing"))]
+pub mod rate_limit;
+pub mod render;
+#[cfg(feature = "code_registry")]
+pub mod registry;
+#[cfg(feature = "replay")]
+pub mod replay;
+#[cfg(feature = "alloc")]
+pub mod report;
+#[cfg(feature = "std")]
+pub mod redact;
+#[cfg(feature = "http")]
+pub mod retry;
+#[cfg(feature = "std")]
+pub mod scope;
+pub mod select;
+pub mod sensitive;
+#[cfg(feature = "sentry")]
+pub mod sentry;
 mod source;
+#[cfg(feature = "error_stats")]
+pub mod stats;
 mod tracer;
 pub mod tracer_impl;
+#[cfg(feature = "wasm")]
+pub mod wasm;
+#[cfg(feature = "axum")]
+pub mod web;
+pub mod wraps;
 
+#[cfg(feature = "std")]
+pub use redact::set_trace_filter;
+#[cfg(feature = "std")]
+pub use scope::with_tracer_scope;
+
+#[cfg(feature = "alloc")]
+pub use breadcrumbs::TraceBuilder;
+#[cfg(feature = "alloc")]
+pub use parse::{ParseError, ParseSource};
+#[cfg(feature = "alloc")]
+pub use policy::Policy;
+#[cfg(feature = "alloc")]
+pub use render::TraceEntry;
+#[cfg(feature = "alloc")]
+pub use report::{Report, ReportBuilder};
+
+pub use policy::{Decision, ErrorClass, HasErrorKind};
+pub use sensitive::NotForErrorDetail;
+pub use render::{set_color_mode, ColorMode, Level, Theme, TraceRender};
 pub use source::*;
 pub use tracer::*;
 
 /// The `DefaultTracer` type alias is used when defining error types
 /// using [`define_error!`]. With the default Cargo features, or when
-/// the `eyre_tracer` feature is set, this is configured to use the
-/// [EyreTracer](tracer_impl::eyre::EyreTracer). Otherwise, it will
+/// the `eyre_tracer` or `eyre_tracer_minimal` feature is set, this is
+/// configured to use the [EyreTracer](tracer_impl::eyre::EyreTracer).
+/// Otherwise, it will
 /// be set to [AnyhowTracer](tracer_impl::anyhow::AnyhowTracer) if
 /// the `anyhow_tracer` feature is set. If neither `eyre_tracer`
 /// nor `anyhow_tracer` is set, then `DefaultTracer` is set to
-/// [StringTracer](tracer_impl::string::StringTracer).
+/// [BacktraceTracer](tracer_impl::backtrace::BacktraceTracer) if the
+/// `backtrace_tracer` feature is set, or
+/// [TracingTracer](tracer_impl::tracing::TracingTracer) if the
+/// `tracing_tracer` feature is set, or
+/// [StringTracer](tracer_impl::string::StringTracer) if the `alloc`
+/// feature is set, such as through the `string_tracer` feature. Without
+/// the `alloc` feature, it is set to
+/// [DefmtTracer](tracer_impl::defmt::DefmtTracer) if the `defmt_tracer`
+/// feature is set, or [NoTracer](tracer_impl::no_trace::NoTracer) otherwise.
 ///
 /// We hard code globally the default error tracer to be used in
 /// [`define_error!`], to avoid making the error types overly generic.
-
-// If `eyre_tracer` feature is active, it is the default error tracer
-#[cfg(feature = "eyre_tracer")]
+//
+// If `eyre_tracer` or `eyre_tracer_minimal` feature is active, it is the default error tracer
+#[cfg(feature = "eyre_tracer_minimal")]
 pub type DefaultTracer = tracer_impl::eyre::EyreTracer;
 
 // Otherwise, if `eyre_tracer` feature is active, it is the default error tracer
-#[cfg(all(feature = "anyhow_tracer", not(feature = "eyre_tracer")))]
+#[cfg(all(feature = "anyhow_tracer", not(feature = "eyre_tracer_minimal")))]
 pub type DefaultTracer = tracer_impl::anyhow::AnyhowTracer;
 
-// Otherwise, if `string_tracer` feature is active, it is the default error tracer
-#[cfg(all(not(feature = "eyre_tracer"), not(feature = "anyhow_tracer")))]
+// Otherwise, if `backtrace_tracer` feature is active, it is the default error tracer
+#[cfg(all(
+    feature = "backtrace_tracer",
+    not(feature = "eyre_tracer_minimal"),
+    not(feature = "anyhow_tracer")
+))]
+pub type DefaultTracer = tracer_impl::backtrace::BacktraceTracer;
+
+// Otherwise, if `tracing_tracer` feature is active, it is the default error tracer
+#[cfg(all(
+    feature = "tracing_tracer",
+    not(feature = "eyre_tracer_minimal"),
+    not(feature = "anyhow_tracer"),
+    not(feature = "backtrace_tracer")
+))]
+pub type DefaultTracer = tracer_impl::tracing::TracingTracer;
+
+// Otherwise, if `alloc` feature is active, the string tracer is the default error tracer
+#[cfg(all(
+    feature = "alloc",
+    not(feature = "eyre_tracer_minimal"),
+    not(feature = "anyhow_tracer"),
+    not(feature = "backtrace_tracer"),
+    not(feature = "tracing_tracer")
+))]
 pub type DefaultTracer = tracer_impl::string::StringTracer;
+
+// Otherwise, without an allocator, if `defmt_tracer` feature is active, it is the default error tracer
+#[cfg(all(feature = "defmt_tracer", not(feature = "alloc")))]
+pub type DefaultTracer = tracer_impl::defmt::DefmtTracer;
+
+// Otherwise, without an allocator, the unit tracer is the default error tracer
+#[cfg(all(not(feature = "alloc"), not(feature = "defmt_tracer")))]
+pub type DefaultTracer = tracer_impl::no_trace::NoTracer;
@@ -0,0 +1,261 @@
+/*!
+  Locale-aware formatting of numbers and times in error messages.
+
+  User-facing applications may need to show error messages with numbers
+  and times formatted for the locale of the user, such as `1.234,5` instead
+  of `1234.5`. Rather than depending on a localization library, flex-error
+  provides a hook for a [`LocaleFormatter`] given by the application, which
+  is used when the error messages are rendered.
+
+  The fields to be localized are wrapped with [`Localized`] in the formatters
+  of the sub-errors:
+
+  ```
+  # use flex_error::define_error;
+  # use flex_error::locale::Localized;
+  # use std::time::SystemTime;
+  define_error! {
+    TransferError {
+      InsufficientFunds
+        { balance: f64, deadline: SystemTime }
+        | e | {
+          format_args!("insufficient funds: {} available until {}",
+            Localized(&e.balance), Localized(&e.deadline))
+        },
+    }
+  }
+  # let err = TransferError::insufficient_funds(1234.5, SystemTime::UNIX_EPOCH);
+  # assert!(err.detail().to_string().starts_with("insufficient funds: 1234.5 available until 1970-01-01T00:00:00"));
+  ```
+
+  and the error details are rendered within [`with_locale`], for example
+  with a formatter backed by ICU in the application:
+
+  ```
+  # use flex_error::define_error;
+  # use flex_error::locale::{with_locale, LocaleFormatter, Localized, Number};
+  # use std::fmt::{self, Formatter};
+  # define_error! {
+  #   TransferError {
+  #     InsufficientFunds
+  #       { balance: f64 }
+  #       | e | { format_args!("insufficient funds: {} available", Localized(&e.balance)) },
+  #   }
+  # }
+  # struct IcuFormatter;
+  # impl IcuFormatter {
+  #   fn new(_locale: &str) -> Self { IcuFormatter }
+  # }
+  # impl LocaleFormatter for IcuFormatter {
+  #   fn fmt_number(&self, number: Number, f: &mut Formatter<'_>) -> fmt::Result {
+  #     write!(f, "{}", number.to_string().replace('.', ","))
+  #   }
+  # }
+  # let user_locale = "de-DE";
+  # let err = TransferError::insufficient_funds(1234.5);
+  let message = with_locale(IcuFormatter::new(user_locale), || err.detail().to_string());
+  # assert_eq!(message, "insufficient funds: 1234,5 available");
+  ```
+
+  Outside of `with_locale`, the fields are formatted as usual, with numbers
+  formatted by `Display`, times in the RFC 3339 format in UTC, and durations
+  by `Debug`. Since the hook is applied at render time, the same error detail
+  can be rendered with different locales, such as for the users of a server
+  and for its logs. The messages in the error trace are formatted when they
+  are traced, and are not localized.
+*/
+
+use alloc::rc::Rc;
+use alloc::vec::Vec;
+use core::cell::RefCell;
+use core::fmt::{Debug, Display, Formatter};
+use core::time::Duration;
+use std::time::{SystemTime, UNIX_EPOCH};
+
+/// A number to be formatted by a [`LocaleFormatter`].
+#[derive(Debug, Clone, Copy, PartialEq, PartialOrd)]
+pub enum Number {
+    Signed(i128),
+    Unsigned(u128),
+    Float(f64),
+}
+
+impl Display for Number {
+    fn fmt(&self, f: &mut Formatter<'_>) -> core::fmt::Result {
+        match self {
+            Number::Signed(number) => Display::fmt(number, f),
+            Number::Unsigned(number) => Display::fmt(number, f),
+            Number::Float(number) => Display::fmt(number, f),
+        }
+    }
+}
+
+/// A formatter of numbers and times for a locale, given by the application
+/// with [`with_locale`]. The methods default to the formatting used outside
+/// of `with_locale`, so that only the needed methods have to be implemented.
+pub trait LocaleFormatter {
+    fn fmt_number(&self, number: Number, f: &mut Formatter<'_>) -> core::fmt::Result {
+        Display::fmt(&number, f)
+    }
+
+    fn fmt_time(&self, time: SystemTime, f: &mut Formatter<'_>) -> core::fmt::Result {
+        Display::fmt(&Rfc3339(time), f)
+    }
+
+    fn fmt_duration(&self, duration: Duration, f: &mut Formatter<'_>) -> core::fmt::Result {
+        Debug::fmt(&duration, f)
+    }
+}
+
+/// The default formatting of numbers and times, used outside of
+/// [`with_locale`].
+struct DefaultLocale;
+
+impl LocaleFormatter for DefaultLocale {}
+
+/// A value that can be formatted by a [`LocaleFormatter`] when wrapped
+/// with [`Localized`].
+pub trait LocaleValue {
+    fn fmt_localized(
+        &self,
+        locale: &dyn LocaleFormatter,
+        f: &mut Formatter<'_>,
+    ) -> core::fmt::Result;
+}
+
+macro_rules! impl_locale_number {
+  ( $variant:ident( $repr:ty ): $( $number:ty ),* ) => {
+    $(
+      impl LocaleValue for $number {
+        fn fmt_localized(
+            &self,
+            locale: &dyn LocaleFormatter,
+            f: &mut Formatter<'_>,
+        ) -> core::fmt::Result {
+            locale.fmt_number(Number::$variant(*self as $repr), f)
+        }
+      }
+    )*
+  };
+}
+
+impl_locale_number!(Signed(i128): i8, i16, i32, i64, i128, isize);
+impl_locale_number!(Unsigned(u128): u8, u16, u32, u64, u128, usize);
+impl_locale_number!(Float(f64): f32, f64);
+
+impl LocaleValue for SystemTime {
+    fn fmt_localized(
+        &self,
+        locale: &dyn LocaleFormatter,
+        f: &mut Formatter<'_>,
+    ) -> core::fmt::Result {
+        locale.fmt_time(*self, f)
+    }
+}
+
+impl LocaleValue for Duration {
+    fn fmt_localized(
+        &self,
+        locale: &dyn LocaleFormatter,
+        f: &mut Formatter<'_>,
+    ) -> core::fmt::Result {
+        locale.fmt_duration(*self, f)
+    }
+}
+
+impl<T: LocaleValue + ?Sized> LocaleValue for &T {
+    fn fmt_localized(
+        &self,
+        locale: &dyn LocaleFormatter,
+        f: &mut Formatter<'_>,
+    ) -> core::fmt::Result {
+        (**self).fmt_localized(locale, f)
+    }
+}
+
+/// Displays a number or time with the [`LocaleFormatter`] of the innermost
+/// [`with_locale`] call on the current thread, or with the default
+/// formatting if there is none.
+pub struct Localized<'a, T: ?Sized>(pub &'a T);
+
+impl<'a, T: LocaleValue + ?Sized> Display for Localized<'a, T> {
+    fn fmt(&self, f: &mut Formatter<'_>) -> core::fmt::Result {
+        match current() {
+            Some(locale) => self.0.fmt_localized(&*locale, f),
+            None => self.0.fmt_localized(&DefaultLocale, f),
+        }
+    }
+}
+
+impl<'a, T: LocaleValue + ?Sized> Debug for Localized<'a, T> {
+    fn fmt(&self, f: &mut Formatter<'_>) -> core::fmt::Result {
+        Display::fmt(self, f)
+    }
+}
+
+std::thread_local! {
+    static LOCALES: RefCell<Vec<Rc<dyn LocaleFormatter>>> = const { RefCell::new(Vec::new()) };
+}
+
+/// Runs `f` with `locale` used to format the [`Localized`] values of the
+/// error messages rendered on the current thread. Calls can be nested, in
+/// which case the innermost locale is used.
+pub fn with_locale<L, R>(locale: L, f: impl FnOnce() -> R) -> R
+where
+    L: LocaleFormatter + 'static,
+{
+    struct LocaleGuard;
+
+    impl Drop for LocaleGuard {
+        fn drop(&mut self) {
+            let _ = LOCALES.try_with(|locales| locales.borrow_mut().pop());
+        }
+    }
+
+    LOCALES.with(|locales| locales.borrow_mut().push(Rc::new(locale)));
+    let _guard = LocaleGuard;
+
+    f()
+}
+
+fn current() -> Option<Rc<dyn LocaleFormatter>> {
+    LOCALES
+        .try_with(|locales| locales.borrow().last().cloned())
+        .ok()
+        .flatten()
+}
+
+/// Displays a time in the RFC 3339 format in UTC, with microseconds.
+pub(crate) struct Rfc3339(pub SystemTime);
+
+impl Display for Rfc3339 {
+    fn fmt(&self, f: &mut Formatter<'_>) -> core::fmt::Result {
+        let since_epoch = self.0.duration_since(UNIX_EPOCH).unwrap_or_default();
+        let secs = since_epoch.as_secs();
+        let (days, secs_of_day) = (secs / 86400, secs % 86400);
+
+        // Converts the days since the epoch to a civil date, following
+        // http://howardhinnant.github.io/date_algorithms.html#civil_from_days
+        let z = days + 719_468;
+        let era = z / 146_097;
+        let doe = z - era * 146_097;
+        let yoe = (doe - doe / 1460 + doe / 36524 - doe / 146_096) / 365;
+        let doy = doe - (365 * yoe + yoe / 4 - yoe / 100);
+        let mp = (5 * doy + 2) / 153;
+        let day = doy - (153 * mp + 2) / 5 + 1;
+        let month = if mp < 10 { mp + 3 } else { mp - 9 };
+        let year = yoe + era * 400 + u64::from(month <= 2);
+
+        write!(
+            f,
+            "{0:04}-{1:02}-{2:02}T{3:02}:{4:02}:{5:02}.{6:06}Z",
+            year,
+            month,
+            day,
+            secs_of_day / 3600,
+            secs_of_day % 3600 / 60,
+            secs_of_day % 60,
+            since_epoch.subsec_micros()
+        )
+    }
+}
@@ -69,14 +69,120 @@ pub use paste::paste;
             for `MyError`, with `MyErrorDetail` being the `Detail` type,
             and `MyError` being the `Source` type.
 
+          - Implement [`TraceRender`](crate::TraceRender) for `MyError`,
+            walking the messages of its error trace, so that it can be
+            added to a [`ReportBuilder`](crate::report::ReportBuilder).
+
+          - Implement [`Wraps<Source, MySubErrorSubdetail>`](crate::wraps::Wraps)
+            for `MyError`, for the error source of each sub-error with a
+            single error source, so that the errors wrapped by `MyError`
+            can be checked with [`assert_wrappable!`](crate::assert_wrappable).
+
           - Implement the following helper methods in `impl MyError {...}`:
 
             - `pub fn detail(&self) -> &MyErrorDetail`
 
+            - `pub fn into_detail(self) -> MyErrorDetail`
+
+            - `pub fn with_new_trace(detail: MyErrorDetail) -> MyError`
+
+            - `pub fn strip_trace(self) -> MyErrorDetail`
+
             - `pub fn trace(&self) -> flex_error::DefaultTracer`
 
+            - `pub fn kind(&self) -> MyErrorKind`
+
+            - `pub fn class(&self) -> ErrorClass`, together with
+              `is_transient` and `is_fatal`.
+
+            - `pub fn detail_hash(&self, hasher: &mut impl Hasher)`, hashing
+              the sub-error and its fields marked with `#[detail(hash)]`.
+
+            - `pub fn detail_fields(&self) -> impl Iterator<Item = (&'static str, String)>`,
+              listing the names and rendered values of the fields of the sub-error.
+
+            - `pub fn root_code(&self) -> Option<&'static str>`
+
+            - `pub fn to_domain_event(&self) -> Option<MyEvent>`, if the
+              `@events(MyEvent)` option is given. See the "Domain Events"
+              section.
+
+            - `pub fn retry_after(&self) -> Option<Duration>`, returning the
+              retry hint of the innermost error source that has one.
+              See the `flex_error::retry` module.
+
+            - `pub fn exit_code(&self) -> i32`, returning the `@exit_code`
+              of the sub-error or of its nested error sources. See the
+              "Exit Codes" section.
+
+            - `pub fn root_source<T: 'static>(&self) -> Option<&T>`, returning
+              the external error of type `T` kept by the innermost error
+              source, such as `TraceClone<T>` or `DetailOnly<T>`.
+
+            - `pub fn user_message(&self, catalog: &impl MyErrorUserMessage) -> impl Display`
+
+            - `pub fn type_url() -> &'static str`
+
+            - `pub fn render(&self, theme: Theme) -> Rendered<'_, DefaultTracer>`
+
+            - `pub fn render_grouped(&self) -> Rendered<'_, DefaultTracer>`
+
+            - `pub fn trace_entries(&self) -> impl Iterator<Item = TraceEntry<'_>>`,
+              if the `"alloc"` feature is enabled.
+
+            - `pub fn summarize(&self, max_len: usize) -> String`, if the
+              `"alloc"` feature is enabled.
+
+            - `pub fn to_problem_details(&self) -> ProblemDetails`, if the
+              `"problem_details"` feature is enabled. See the
+              `flex_error::problem` module for the `@problem` option.
+
+            - `impl IntoResponse for MyError`, if the `"axum"` feature is
+              enabled. See the `flex_error::web` module.
+
+            - `pub fn trace_serializer(&self) -> TraceSerializer<'_, MyErrorDetail, DefaultTracer>`
+              and `pub fn to_json(&self) -> serde_json::Result<Value>`, if the
+              `"serde_json"` feature is enabled and the `@serde` option is
+              given, together with
+              `pub fn serialize_chain_into<W: io::Write>(&self, writer: W) -> serde_json::Result<()>`
+              if the `"std"` feature is also enabled. See the
+              `flex_error::json` module.
+
+            - `pub fn to_sentry_event(&self) -> sentry_types::protocol::v7::Event<'static>`,
+              if the `"sentry"` feature is enabled, with the error detail
+              attached if the `@serde` option is given. See the
+              `flex_error::sentry` module.
+
+            - `pub fn log_to_console(&self)`, if the `"wasm"` feature is
+              enabled, together with `impl From<MyError> for JsValue`.
+              See the `flex_error::wasm` module.
+
+            - `pub fn incident_id(&self) -> Option<IncidentId>`, if the
+              `"incidents"` feature is enabled, which implies the
+              `@metadata` option. See the `flex_error::incident` module.
+
+            - `pub fn backtrace(&self) -> Option<&Backtrace>`, if the
+              `"backtrace"` feature is enabled and the `@metadata` option
+              is given, or the `"incidents"` feature is enabled. See the
+              "Reported Errors" section.
+
+            - `pub fn log_rate_limited(&self, key: &str, interval: Duration)`,
+              if the `"log"` or `"tracing"` feature is enabled. See the
+              `flex_error::rate_limit` module.
+
+            - `pub fn trace_mut(&mut self) -> &mut flex_error::DefaultTracer`
+
+            - `pub fn map_trace(self, f: impl FnOnce(DefaultTracer) -> DefaultTracer) -> MyError`
+
+            - `pub fn mark_reported(self) -> MyError`, `pub fn is_reported(&self) -> bool`,
+              `pub fn is_source_reported(&self) -> bool` and
+              `pub fn metadata(&self) -> &ErrorMetadata`, if the `@metadata`
+              option is given.
+
             - `pub fn add_trace<E: Display>(self, e: &E) -> MyError`
 
+            - `pub fn add_trace_with_level<E: Display>(self, e: &E, level: Level) -> MyError`
+
         - Define a struct in the form
 
           ```ignore
@@ -124,6 +230,28 @@ pub use paste::paste;
             fn my_sub_error(..., source: MySource::Detail)
             ```
 
+        - Define the snake-cased methods `pub fn is_my_sub_error(&self) -> bool`
+          and `pub fn as_my_sub_error(&self) -> Option<&MySubErrorSubdetail>`
+          in both `MyError` and `MyErrorDetail`, for each variant including
+          the variant aliases. Since `MyError` also has the `is_reported` and
+          `is_source_reported` methods, there cannot be sub-errors named
+          `Reported` or `SourceReported`.
+
+        - Define a snake-cased method in the `MyErrorUserMessage` trait
+          in the form
+
+          ```ignore
+          fn my_sub_error(&self, detail: &MySubErrorSubdetail, f: &mut Formatter<'_>) -> fmt::Result;
+          ```
+
+          so that user-facing messages for all sub-errors can be provided
+          by a message catalog. See [`UserMessage`](crate::UserMessage).
+
+        - Define a snake-cased method in the `MyErrorCtor` trait, with the
+          same arguments as the error constructor, implemented by `MyError`
+          by calling the error constructor. See the
+          [Constructor Traits](#constructor-traits) section.
+
   ## Formatter
 
   For each sub-error definition, a formatter needs to be provided using the
@@ -142,7 +270,7 @@ pub use paste::paste;
 
   will include the following expansion:
 
-  ```
+  ```ignore
   impl ::core::fmt::Display for MySubErrorSubdetail {
     fn fmt(&self, f: &mut ::core::fmt::Formatter<'_>) -> ::core::fmt::Result {
       let e = self;
@@ -154,6 +282,38 @@ pub use paste::paste;
   Note that there is no need to manually display the error source, as the
   source is already automatically traced by the error tracer.
 
+  If the message should include the source anyway, e.g. when the trace is
+  not shown to the users, the formatter can call the generated
+  `source_display` method of the subdetail, which returns the source detail
+  as `Option<&dyn Display>` for all kinds of sources:
+
+  ```
+  # use flex_error::{define_error, DisplayError};
+  # define_error! {
+  #   MyError {
+  #     MySubError
+  #       { code: u32 }
+  #       [ DisplayError<String> ]
+  | e | {
+    format_args!("error with code {}: {}", e.code,
+      e.source_display().unwrap_or(&"unknown source"))
+  },
+  #   }
+  # }
+  # let err = MyError::my_sub_error(3, "disk full".to_string());
+  # assert_eq!(err.detail().to_string(), "error with code 3: disk full");
+  ```
+
+  This is `None` for sub-errors without a source, and for the sources whose
+  detail is not displayed, such as [`TraceError`](crate::TraceError) and
+  [`DetailOnly`](crate::DetailOnly). For a sub-error with two sources, the
+  detail of the first source is returned if it can be displayed.
+
+  With the `"std"` feature, numbers and times in the fields can be wrapped
+  with `Localized`, e.g. `Localized(&e.amount)`, to be formatted by the
+  locale formatter given by the application at render time. See the
+  [`locale`](crate::locale) module.
+
   If a sub-error do not have any field, we can write a simpler form of the
   formatter like:
 
@@ -264,6 +424,732 @@ pub use paste::paste;
   automatically tracked inside `foo_error`. The outer error only need to
   add additional detail about what caused the source error to be raised.
 
+  ## Field Conversions
+
+  The constructor functions take the field values with the exact field
+  types. A field can instead be given the `@into` modifier, for the
+  constructor to take any value that can be converted into the field type.
+  This is typically used with `Cow<'static, str>` fields, so that static
+  string literals can be given without allocating a `String`:
+
+  ```
+  # use flex_error::define_error;
+  # use std::borrow::Cow;
+  # let quota = 10;
+  # define_error! {
+  MyError {
+    Rejected
+      { reason: @into Cow<'static, str> }
+      | e | { format_args!("request rejected: {}", e.reason) },
+  }
+  # }
+
+  MyError::rejected("quota exceeded");
+  MyError::rejected(format!("quota of {} exceeded", quota));
+  ```
+
+  The field is still stored with the given type, with only the
+  constructor parameter being changed to `impl Into<Cow<'static, str>>`.
+
+  ## Help Text
+
+  A help text can be given to a sub-error with the `@help` option,
+  placed right before the formatter:
+
+  ```
+  # use flex_error::define_error;
+  # define_error! {
+  MyError {
+    Connect
+      { address: String }
+      @help("check that the node RPC address is reachable")
+      | e | { format_args!("failed to connect to {}", e.address) },
+  }
+  # }
+  # let err = MyError::connect("localhost:26657".to_string());
+  # assert_eq!(err.detail().help(), Some("check that the node RPC address is reachable"));
+  ```
+
+  The help text is returned by `MyErrorDetail::help`, and is appended
+  after the error trace by `MyError::render`. With `color-eyre`, it can
+  also be attached to the error trace as a section, such as with
+  `err.map_trace(|report| report.suggestion(help))`.
+
+  ## Exit Codes
+
+  A process exit code can be given to a sub-error with the `@exit_code`
+  option, such as one of the `sysexits.h` codes, so that the CLI tools
+  can exit with a meaningful status:
+
+  ```no_run
+  # use flex_error::define_error;
+  # define_error! {
+  #   ConfigError {
+  #     Missing
+  #       @exit_code(78) // EX_CONFIG
+  #       | _ | { "missing configuration" },
+  #   }
+  # }
+  # define_error! {
+  MyError {
+    Connect
+      { address: String }
+      @exit_code(69) // EX_UNAVAILABLE
+      | e | { format_args!("failed to connect to {}", e.address) },
+    Config
+      [ ConfigError ]
+      | _ | { "invalid configuration" },
+  }
+  # }
+  # let err = MyError::config(ConfigError::missing());
+
+  std::process::exit(err.exit_code());
+  ```
+
+  `MyError::exit_code` returns the exit code of the sub-error if it has
+  one, and otherwise walks its nested error sources down to the root cause,
+  returning the exit code of the outermost one that has an `@exit_code`,
+  such as `EX_CONFIG` given to a sub-error of `ConfigError`. If none of
+  them has one, it returns `1`. `MyErrorDetail::exit_code` returns `None`
+  instead.
+
+  ## Static Messages
+
+  The message of a sub-error is formatted into a new string when its error
+  trace is created. For the common sub-errors whose formatter returns a
+  static string, the `@static_msg` option passes the string to the error
+  tracer as is, with [`ErrorMessageTracer::new_static`](crate::ErrorMessageTracer::new_static):
+
+  ```
+  # use flex_error::define_error;
+  # define_error! {
+  MyError {
+    NotInitialized
+      @static_msg
+      | _ | { "not initialized" },
+  }
+  # }
+  # let err = MyError::not_initialized();
+  # match err.detail() {
+  #   MyErrorDetail::NotInitialized(e) => assert_eq!(e.static_message(), "not initialized"),
+  # }
+  ```
+
+  The formatter, or the `@trace` formatter if given, must then return a
+  `&'static str`, which is also returned by `NotInitializedSubdetail::static_message`.
+  The eyre and anyhow tracers keep the string without allocating a copy of
+  it, unless a trace filter is set or the `"interning"` or `"replay"`
+  feature is enabled, and the other tracers format it as usual. The option
+  is only supported for sub-errors without an error source.
+
+  ## Error Classes
+
+  A sub-error can be classified as transient with the `@transient` option,
+  for errors that are expected to go away when the operation is retried,
+  or as fatal with the `@fatal` option, for errors that cannot be recovered
+  from. The class is returned by `MyError::class` and `MyErrorDetail::class`
+  as an [`ErrorClass`](crate::ErrorClass), and can be checked with
+  `is_transient` and `is_fatal`, so that retry loops do not have to match
+  every sub-error:
+
+  ```
+  # use flex_error::define_error;
+  # use std::time::Duration;
+  # define_error! {
+  MyError {
+    Timeout
+      { duration: Duration }
+      @transient
+      | e | { format_args!("request timed out after {:?}", e.duration) },
+    InvalidConfig
+      @fatal
+      | _ | { "invalid configuration" },
+  }
+  # }
+  # struct Client;
+  # impl Client {
+  #   fn query(&self, _request: &str) -> Result<(), MyError> {
+  #     Err(MyError::timeout(Duration::from_secs(1)))
+  #   }
+  # }
+  # fn run(client: &Client, request: &str, max_attempts: u32) -> Result<(), MyError> {
+  # let mut attempts = 0;
+  # loop {
+  # attempts += 1;
+  match client.query(request) {
+    Err(e) if e.is_transient() && attempts < max_attempts => continue,
+    result => return result,
+  }
+  # }
+  # }
+  # assert!(run(&Client, "status", 3).unwrap_err().is_transient());
+  # assert!(MyError::invalid_config().is_fatal());
+  ```
+
+  The sub-errors with neither option have the class `ErrorClass::Unclassified`.
+
+  ## Domain Events
+
+  For event-sourced services persisting failures as events, the sub-errors
+  can be mapped to the variants of a domain event type with the `@event`
+  option, given the event type with the `@events` option of the error
+  type, after `@free_fns`:
+
+  ```
+  # use flex_error::{define_error, TraceError};
+  # #[derive(Debug, Clone, PartialEq)]
+  # enum PaymentEvent {
+  #   Declined { order_id: u64, reason: String },
+  #   Lost(u64),
+  #   Timeout,
+  # }
+  define_error! {
+    @events(PaymentEvent)
+    PaymentError {
+      Declined
+        { order_id: u64, message: String }
+        @event( PaymentEvent::Declined { order_id, reason: message } )
+        | e | { format_args!("payment of order {} declined: {}", e.order_id, e.message) },
+      Lost
+        { order_id: u64 }
+        @event( PaymentEvent::Lost(order_id) )
+        | _ | { "payment lost" },
+      Timeout
+        @event( PaymentEvent::Timeout )
+        | _ | { "payment timed out" },
+      Io
+        [ TraceError<std::io::Error> ]
+        | _ | { "I/O error" },
+    }
+  }
+  # assert_eq!(PaymentError::lost(7).to_domain_event(), Some(PaymentEvent::Lost(7)));
+  # assert_eq!(PaymentError::io(std::io::ErrorKind::Other.into()).to_domain_event(), None);
+  ```
+
+  The event is returned by `MyError::to_domain_event` and
+  `MyErrorDetail::to_domain_event`, which return `None` for the sub-errors
+  without the `@event` option. The event variant can be a unit variant, a
+  tuple variant given the fields of the sub-detail in order, or a struct
+  variant given the fields of the sub-detail with the same names, or as
+  `event_field: detail_field` for the fields with different names. The
+  fields are copied with `Clone`.
+
+  ## Variant Aliases
+
+  Multiple variants can share the same sub-detail struct and formatter,
+  by giving the additional variant names with `as` after the sub-error name:
+
+  ```
+  # use flex_error::define_error;
+  # define_error! {
+  MyError {
+    ConnectTimeout as ReadTimeout as WriteTimeout
+      { timeout: u64 }
+      | e | { format_args!("timed out after {}ms", e.timeout) },
+  }
+  # }
+  # let err = MyError::read_timeout(5);
+  # assert!(matches!(err.detail(), MyErrorDetail::ReadTimeout(ConnectTimeoutSubdetail { timeout: 5 })));
+  ```
+
+  will include the following expansion:
+
+  ```ignore
+  pub enum MyErrorDetail {
+    ConnectTimeout(ConnectTimeoutSubdetail),
+    ReadTimeout(ConnectTimeoutSubdetail),
+    WriteTimeout(ConnectTimeoutSubdetail),
+    ...
+  }
+
+  impl MyError {
+    pub fn connect_timeout(timeout: u64) -> MyError { ... }
+    pub fn read_timeout(timeout: u64) -> MyError { ... }
+    pub fn write_timeout(timeout: u64) -> MyError { ... }
+  }
+  ```
+
+  ## Custom Constructors
+
+  The `@no_constructor` option omits the generated constructor functions of
+  a sub-error, while still defining its variant and sub-detail struct. This
+  is useful when the fields have invariants that should be enforced by a
+  hand-written constructor, which can build the error with `trace_from`:
+
+  ```
+  # use flex_error::{define_error, NoSource};
+  # define_error! {
+  MyError {
+    InvalidRange
+      { start: u64, end: u64 }
+      @no_constructor
+      | e | { format_args!("invalid range {}..{}", e.start, e.end) },
+  }
+  # }
+
+  impl MyError {
+    pub fn invalid_range(start: u64, end: u64) -> MyError {
+      let (start, end) = (start.min(end), start.max(end));
+      MyError::trace_from::<NoSource, _>((), |_| {
+        MyErrorDetail::InvalidRange(InvalidRangeSubdetail { start, end })
+      })
+    }
+  }
+  # assert_eq!(MyError::invalid_range(5, 2).detail().to_string(), "invalid range 2..5");
+  ```
+
+  For sub-errors with variant aliases, the constructors of all aliases are omitted.
+
+  `trace_from_with` takes an additional context message, which is added to
+  the error trace between the trace of the source and the message of the
+  new error detail, e.g. to record the arguments of the call site:
+
+  ```
+  # use flex_error::{define_error, TraceError};
+  # use std::io;
+  # define_error! {
+  #   MyError {
+  #     Io
+  #       [ TraceError<io::Error> ]
+  #       | _ | { "I/O error" },
+  #   }
+  # }
+  # let err = io::Error::new(io::ErrorKind::UnexpectedEof, "unexpected end of file");
+  # let height = 42;
+  # let err =
+  MyError::trace_from_with::<TraceError<io::Error>, _>(
+    err,
+    format_args!("while reading block {}", height),
+    |source| MyErrorDetail::Io(IoSubdetail { source }),
+  )
+  # ;
+  # assert!(format!("{:#}", err).contains("while reading block 42"));
+  ```
+
+  ## Constructor Traits
+
+  A trait named `MyErrorCtor` is generated with a method for each error
+  constructor, and implemented by `MyError`. Code that is generic over the
+  trait can then be tested with a mock implementation, which records the
+  errors that are created instead of capturing error traces:
+
+  ```
+  # use flex_error::define_error;
+  # define_error! {
+  #   MyError {
+  #     ConnectTimeout
+  #       { timeout: u64 }
+  #       | e | { format_args!("timed out after {}ms", e.timeout) },
+  #     Refused
+  #       | _ | { "connection refused" },
+  #   }
+  # }
+  # struct Connection;
+  fn connect<E: MyErrorCtor>(timeout: u64) -> Result<Connection, E> {
+    # if timeout == 0 { return Ok(Connection); }
+    Err(E::connect_timeout(timeout))
+  }
+
+  struct Recorded(Vec<&'static str>);
+
+  impl MyErrorCtor for Recorded {
+    fn connect_timeout(_timeout: u64) -> Self {
+      Recorded(vec!["connect_timeout"])
+    }
+
+    fn refused() -> Self {
+      Recorded(vec!["refused"])
+    }
+  }
+  # let recorded = connect::<Recorded>(5).err().unwrap();
+  # assert_eq!(recorded.0, ["connect_timeout"]);
+  ```
+
+  The methods for sub-errors with a `[ Self ]` source take the source as
+  `Self`, so that the mock errors can be chained. In the generic tracer mode,
+  the trait is implemented by the main error type with the default tracer.
+  The sub-errors with the `@no_constructor` option have no trait methods.
+
+  ## From Conversions
+
+  The `@from` option on a sub-error with an error source and no other fields
+  implements `From` for the values of the error source, by calling the
+  constructor of the sub-error. This allows the `?` operator to be used
+  directly, instead of `map_err`:
+
+  ```
+  # use flex_error::{define_error, TraceError};
+  # use std::path::Path;
+  define_error! {
+    MyError {
+      Io
+        [ TraceError<std::io::Error> ]
+        @from
+        | _ | { "I/O error" },
+    }
+  }
+
+  fn read_config(path: &Path) -> Result<String, MyError> {
+    Ok(std::fs::read_to_string(path)?)
+  }
+  # assert!(read_config(Path::new("/nonexistent/config.toml")).is_err());
+  ```
+
+  The type converted from is found from the tokens of the error source, i.e.
+  `E` for `TraceError<E>`, `TraceClone<E>`, `DisplayError<E>`, `DisplayOnly<E>`,
+  `DisplayChain<E>`, `DetailOnly<E>`, `TraceOnly<E>` and `BoxSource<E>`, the boxed
+  error for `BoxedTraceError`, `DynError` and `DynErrorOnly`, and the error type
+  itself for other sources, such as
+  the error types defined by `define_error!`. The `@from` option is not
+  supported with a `[ Self ]` source, and at most one sub-error can have the
+  `@from` option for each source type.
+
+  ## Missing Values
+
+  The `@from_none` option on a sub-error without an error source defines
+  the extension trait `MyErrorOptionExt`, with the method `ok_or_not_found`
+  for the sub-error `NotFound`, which converts a missing value into the
+  sub-error. The method is implemented for `Option<T>`, and for the
+  `Result<Option<T>, MyError>` values commonly returned by lookups:
+
+  ```
+  # use flex_error::define_error;
+  # use std::collections::HashMap;
+  # type Entry = u64;
+  # struct Store(HashMap<String, Entry>);
+  # impl Store {
+  #   fn lookup(&self, key: &str) -> Result<Option<Entry>, MyError> {
+  #     Ok(self.0.get(key).copied())
+  #   }
+  # }
+  define_error! {
+    MyError {
+      NotFound
+        { key: String }
+        @from_none
+        | e | { format_args!("entry {} not found", e.key) },
+    }
+  }
+
+  fn get_entry(store: &Store, key: &str) -> Result<Entry, MyError> {
+    store.lookup(key).ok_or_not_found(|| NotFoundSubdetail { key: key.into() })
+  }
+  # let store = Store(HashMap::new());
+  # assert_eq!(get_entry(&store, "a").unwrap_err().detail().to_string(), "entry a not found");
+  ```
+
+  The closure returning the sub-detail is only called if the value is
+  missing, and the error is then created by calling the constructor of the
+  sub-error with the fields of the sub-detail. The trait is only defined if
+  at least one sub-error has the `@from_none` option, and has to be in scope
+  for the methods to be called.
+
+  ## Multiple Error Sources
+
+  A sub-error can be caused by two underlying errors, such as a primary
+  failure together with the failure to clean up after it. The two error
+  sources are given as `[ Source1, Source2 ]`:
+
+  ```
+  # use flex_error::{define_error, TraceError};
+  # use std::io;
+  # let path = "/data/block.tmp".to_string();
+  # let write_error = io::Error::new(io::ErrorKind::Other, "disk full");
+  # let remove_error = io::Error::new(io::ErrorKind::PermissionDenied, "permission denied");
+  define_error! {
+    StoreError {
+      Write
+        { path: String }
+        [ TraceError<std::io::Error>, TraceError<std::io::Error> ]
+        | e | { format_args!("failed to write {}, and to remove the partial file", e.path) },
+    }
+  }
+
+  # let err =
+  StoreError::write(path, write_error, remove_error)
+  # ;
+  # assert!(format!("{:#}", err).contains("disk full"));
+  ```
+
+  The sub-detail then has the fields `source1` and `source2` instead of
+  `source`, and the constructor takes the two source errors in order. The
+  messages in the trace of the second source are added to the trace of the
+  first source as a single message, joined by `": "`, so the error tracer
+  is required to implement [`TraceRender`](crate::TraceRender). `Self` is
+  not supported as one of two error sources, and neither is the `@from`
+  option.
+
+  ## Environment Attachments
+
+  If the `"attachments"` feature is enabled, the `@attach(env)` option on a
+  sub-error adds a snapshot of the data registered for the `env` kind, such
+  as the version and the git hash of the build, to the error trace of the
+  errors constructed for the sub-error. See the `flex_error::attach` module.
+  The option is not supported in the detail-only mode.
+
+  ## Trace Formatter
+
+  By default, the error tracer traces the `Display` representation of
+  the error detail. A different formatter can be given to a sub-error with
+  the `@trace` option, typically for a more verbose representation in the
+  logs than in the user-facing messages:
+
+  ```
+  # use flex_error::define_error;
+  # define_error! {
+  MyError {
+    Request
+      { url: String, body: String }
+      @trace(| e | { format_args!("request to {} failed with body {}", e.url, e.body) })
+      | e | { format_args!("request to {} failed", e.url) },
+  }
+  # }
+  # let err = MyError::request("/status".to_string(), "{}".to_string());
+  # assert_eq!(err.detail().to_string(), "request to /status failed");
+  # assert_eq!(format!("{:#}", err), "request to /status failed with body {}");
+  ```
+
+  The trace formatter is used through the [`TraceDisplay`](crate::TraceDisplay)
+  trait, which is implemented for the error detail and all sub-detail types.
+
+  ## Boxed Errors
+
+  If the `"std"` feature is enabled, the main error type implements
+  [`std::error::Error`], with `source` returning the error trace as given by
+  [`ErrorMessageTracer::as_error`](crate::ErrorMessageTracer::as_error).
+  The error can therefore be converted into `Box<dyn Error + Send + Sync>`
+  with the `?` operator in generic code, through the `From` implementation
+  in the standard library, while keeping the chain of error messages
+  available through `source`:
+
+  ```
+  # flex_error::define_error! {
+  #   MyError {
+  #     Refused
+  #       | _ | { "connection refused" },
+  #   }
+  # }
+  # fn connect(_address: &str) -> Result<(), MyError> {
+  #   Err(MyError::refused())
+  # }
+  # const address: &str = "localhost:26657";
+  fn run() -> Result<(), Box<dyn std::error::Error + Send + Sync>> {
+    connect(address)?;
+    Ok(())
+  }
+  # assert!(run().unwrap_err().source().is_some());
+  ```
+
+  The error chain is preserved for the eyre, anyhow, backtrace and tracing
+  tracers.
+  The [`StringTracer`](crate::tracer_impl::string::StringTracer) concatenates
+  the error messages, and has no error sources.
+
+  In no_std mode, the `"error_in_core"` feature implements
+  [`core::error::Error`] instead, which is stable since Rust 1.81, so that
+  the errors can be used with the error handling of other no_std crates.
+  [`StdError`](crate::StdError) is then an alias of `core::error::Error`,
+  and custom error tracers are required to implement `as_error` to provide
+  the error sources.
+
+  ## Error Kinds
+
+  For each error type `MyError`, a `MyErrorKind` enum is defined with a
+  fieldless variant for each sub-error, which is returned by the `kind`
+  method of `MyError` and `MyErrorDetail`. The error kinds can be used
+  to declare how errors are handled with a [`Policy`](crate::Policy):
+
+  ```
+  # use flex_error::{define_error, Decision, Policy};
+  # define_error! {
+  #   MyError {
+  #     Timeout
+  #       | _ | { "timed out" },
+  #     NotFound
+  #       | _ | { "not found" },
+  #   }
+  # }
+  # let err = MyError::timeout();
+  let policy = Policy::new()
+    .retry(MyErrorKind::Timeout)
+    .ignore(MyErrorKind::NotFound);
+
+  if policy.apply(&err) == Decision::Retry {
+    // retry the operation
+  }
+  ```
+
+  ## Detail Hashing
+
+  The `detail_hash` method of `MyError` and `MyErrorDetail` feeds the index
+  of the variant and the fields marked with `#[detail(hash)]` into a
+  [`Hasher`](core::hash::Hasher), so that errors can be used as cache keys,
+  e.g. in a negative cache of failing endpoints, without requiring all field
+  types to implement `Hash`:
+
+  ```
+  # use flex_error::define_error;
+  # use std::collections::hash_map::DefaultHasher;
+  # use std::hash::Hasher;
+  # use std::time::Duration;
+  define_error! {
+    MyError {
+      Unreachable
+        {
+          #[detail(hash)]
+          endpoint: String,
+          elapsed: Duration,
+        }
+        | e | { format_args!("{} unreachable after {:?}", e.endpoint, e.elapsed) },
+    }
+  }
+
+  # let err = MyError::unreachable("10.0.0.1".to_string(), Duration::from_secs(1));
+  let mut hasher = DefaultHasher::new();
+  err.detail_hash(&mut hasher);
+  let key = hasher.finish();
+  # let mut hasher = DefaultHasher::new();
+  # MyError::unreachable("10.0.0.1".to_string(), Duration::from_secs(2)).detail_hash(&mut hasher);
+  # assert_eq!(key, hasher.finish());
+  ```
+
+  The error sources and the fields without the attribute are left out, so
+  errors of the same sub-error with the same marked fields hash equally.
+
+  ## Trace Fields
+
+  The fields marked with `#[detail(trace)]` are added to the error trace as
+  structured key-value fields with
+  [`ErrorMessageTracer::add_fields`](crate::ErrorMessageTracer::add_fields)
+  when the error is constructed, instead of being only flattened into the
+  message of the sub-error:
+
+  ```
+  # use flex_error::define_error;
+  define_error! {
+    MyError {
+      Corrupted
+        {
+          #[detail(trace)]
+          height: u64,
+          #[detail(trace)]
+          hash: String,
+        }
+        | _ | { "corrupted state" },
+    }
+  }
+  # let err = MyError::corrupted(10, "5f2c1e9".to_string());
+  # assert!(format!("{:#}", err).contains("height=10, hash=5f2c1e9"));
+  ```
+
+  The marked fields must implement `Display`. The built-in error tracers add
+  the fields as a message of their own on top of the message of the
+  sub-error, in the form `height=10, hash=5f2c1e9`, while other error
+  tracers may keep them for machine processing. The fields can also be added
+  to any error trace with the generated `MyErrorDetail::add_trace_fields`.
+
+  ## Detail Fields
+
+  Besides the message of the sub-error, the fields of an error can be listed
+  as key-value pairs with the generated `detail_fields` method, so that log
+  backends and user interfaces can show them as a table without `serde`:
+
+  ```
+  # flex_error::define_error! {
+  #   MyError {
+  #     Timeout
+  #       { address: String, timeout: u64 }
+  #       | e | { format_args!("request to {} timed out after {}ms", e.address, e.timeout) },
+  #   }
+  # }
+  # let err = MyError::timeout("10.0.0.1".to_string(), 500);
+  for (name, value) in err.detail_fields() {
+    println!("{:>10} | {}", name, value);
+  }
+  # assert_eq!(err.detail_fields().collect::<Vec<_>>(), [("address", "10.0.0.1".to_string()), ("timeout", "500".to_string())]);
+  ```
+
+  The fields of the sub-error are listed in the order they are defined,
+  without its error sources. The values are rendered with `Display`, or with
+  `Debug` for the types that do not implement `Display`, or as `<opaque>`
+  for the types implementing neither. The method is only defined when the
+  `alloc` feature is enabled.
+
+  ## Reported Errors
+
+  When an error is already logged at the point where it is wrapped, it can
+  be marked with `mark_reported` to avoid logging its causes again in the
+  outer error report. This requires the `@metadata` option, given before
+  all other options, which keeps an [`ErrorMetadata`](crate::ErrorMetadata)
+  in a private third field of the main error struct. With the `incidents`
+  feature, all error types keep their metadata, as if they had the option:
+
+  ```
+  # use flex_error::define_error;
+  define_error! {
+    @metadata
+    FooError {
+      Baz
+        { extra: String }
+        | e | { format_args!("General Baz error with extra detail: {}", e.extra) }
+    }
+  }
+  ```
+
+  The [`ReportStatus`](crate::ReportStatus) in the metadata is carried to
+  the errors wrapping the error, if they also have the `@metadata` option:
+
+  ```
+  # use flex_error::{define_error, DisplayError};
+  # define_error! {
+  #   @metadata
+  #   FooError {
+  #     Bar
+  #       { code: u32 }
+  #       [ DisplayError<String> ]
+  #       | e | { format_args!("Bar error with code {}", e.code) },
+  #   }
+  # }
+  # define_error! {
+  #   @metadata
+  #   QuuxError {
+  #     Foo
+  #       { action: String }
+  #       [ FooError ]
+  #       | e | { format_args!("error arised from Foo when performing action {}", e.action) },
+  #   }
+  # }
+  # let (code, source, action) = (3, "disk full".to_string(), "write".to_string());
+  # mod log {
+  #   macro_rules! log { ($($t:tt)*) => { eprintln!($($t)*) } }
+  #   pub(crate) use {log as error, log as warn};
+  # }
+  let err = FooError::bar(code, source);
+  log::warn!("{}", err);
+
+  let err = QuuxError::foo(action, err.mark_reported());
+
+  // at the top-level handler
+  if err.is_source_reported() {
+    log::error!("{}", err.detail());
+  } else if !err.is_reported() {
+    log::error!("{}", err);
+  }
+  # assert!(err.is_source_reported() && !err.is_reported());
+  ```
+
+  With the `backtrace` feature, the metadata also holds a
+  `std::backtrace::Backtrace` captured when the error is constructed,
+  independently of the error tracer, so that the applications using the
+  `StringTracer` on std targets still get a native backtrace. It is
+  returned by the generated `backtrace` method, and the errors wrapping
+  the error keep the backtrace of the innermost error with metadata.
+  Only the errors with metadata carry a backtrace: the error types
+  defined without the `@metadata` option capture none, and have no
+  `backtrace` method, unless the `incidents` feature gives metadata to
+  every error. Use an error tracer such as `BacktraceTracer` to get a
+  backtrace for them.
+
   ## Attributes
 
   `define_error!` supports adding attributes to the generated error types.
@@ -331,10 +1217,54 @@ pub use paste::paste;
   If you need the main error type to implement certain traits,
   you can instead define your own custom `impl` definition for it.
 
-  ## Sub Attributes
+  Attributes such as `#[non_exhaustive]` are also forwarded in this way,
+  so that new sub-errors and fields can be added without breaking the
+  crates matching on the error details:
 
-  We can also define custom attributes for only the sub-error.
-  In that case, the attribute is given to the sub-detail type.
+  ```
+  # use flex_error::define_error;
+  define_error! {
+    #[derive(Debug)]
+    #[non_exhaustive]
+    MyError {
+      Timeout
+        { seconds: u64 }
+        | e | { format_args!("timed out after {} seconds", e.seconds) },
+    }
+  }
+  ```
+
+  ## Main Error Derives
+
+  Some traits can still be implemented for the main error type with
+  `@derive_main`, which is given before the attributes:
+
+  ```
+  # use flex_error::define_error;
+  define_error! {
+    @derive_main(Clone)
+    #[derive(Debug, Clone)]
+    MyError {
+      Timeout
+        { seconds: u64 }
+        | e | { format_args!("timed out after {} seconds", e.seconds) },
+    }
+  }
+
+  let err = MyError::timeout(3);
+  let cloned = err.clone();
+  assert_eq!(format!("{:#}", cloned), format!("{:#}", err));
+  ```
+
+  Currently only `Clone` is supported. The cloned error has a clone of
+  the error detail, together with a new error trace created from the
+  rendered messages of the original trace. As a result, backtraces in
+  the original trace are not preserved in the clone.
+
+  ## Sub Attributes
+
+  We can also define custom attributes for only the sub-error.
+  In that case, the attribute is given to the sub-detail type.
   For example:
 
   ```ignore
@@ -364,38 +1294,751 @@ pub use paste::paste;
   So there is no need to derive it again in the
   sub-errors.
 
-**/
+  ## Constructor Visibility
+
+  The constructor functions are public by default. The visibility of the
+  constructors of a sub-error can be given with the `@vis` option, so that
+  library authors can export the error type while keeping its constructors
+  internal:
+
+  ```
+  # use flex_error::define_error;
+  define_error! {
+    MyError {
+      Internal
+        { code: u32 }
+        @vis(pub(crate))
+        | e | { format_args!("internal error: {}", e.code) },
+
+      Private
+        @vis()
+        | _ | { "private error" },
+    }
+  }
+  # assert_eq!(MyError::internal(3).detail().to_string(), "internal error: 3");
+  # assert_eq!(MyError::private().detail().to_string(), "private error");
+  ```
+
+  The visibility applies to the constructors of the variant aliases, and
+  to the free functions of the `@free_fns` option. The constructors with
+  the `@vis` option are left out of the constructor trait, which is public.
+  Note that the conversions of the `@from` and `@from_none` options are
+  trait implementations, which are always public.
+
+  ## Error Codes
+
+  Error codes and severities can be assigned to all sub-errors from a
+  mapping file with the `@codes` option, which is given after
+  `@derive_main`:
+
+  ```
+  # use flex_error::define_error;
+  define_error! {
+    @codes(include_str!("../tests/error_codes.toml"))
+    #[derive(Debug, Clone)]
+    MyError {
+      ConnectTimeout
+        { timeout: u64 }
+        | e | { format_args!("timed out after {}ms", e.timeout) },
+
+      Refused
+        | _ | { "connection refused" },
+    }
+  }
+  # assert_eq!(MyError::refused().detail().error_code().code, "NET-0002");
+  # assert_eq!(MyError::refused().root_code(), Some("NET-0002"));
+  ```
+
+  The build fails if a sub-error has no entry in the mapping file.
+  See the [`codes`](crate::codes) module for the format of the mapping file.
+
+  The code of the root cause of an error is returned by the generated
+  `MyError::root_code` method, which walks the details of the nested error
+  sources defined with `define_error!`, and returns the code of the innermost
+  error that has one. For example with `RelayError` wrapping a `NetworkError`
+  wrapping a `std::io::Error`, the code of the `NetworkError` sub-error is
+  returned if `NetworkError` is defined with `@codes`, and the code of the
+  `RelayError` sub-error otherwise. Errors defined without `@codes` are walked
+  through, but do not have a code of their own.
+
+  With the `code_registry` feature, the codes are also registered in a
+  registry shared by all crates linked into a binary, so that codes shared
+  by multiple sub-errors can be detected with `assert_unique_codes!`. See
+  the [`registry`](crate::registry) module.
+
+  ## Numeric Codes
+
+  Numeric codes, such as the status codes of gRPC or ABCI responses, can be
+  given to the sub-errors right after their names, so that the codes are kept
+  next to the error definitions:
+
+  ```
+  # use flex_error::define_error;
+  define_error! {
+    MyError {
+      InsufficientFunds = 5
+        { required: u64 }
+        | e | { format_args!("insufficient funds, {} required", e.required) },
+
+      InvalidSignature = 17
+        | _ | { "invalid signature" },
+    }
+  }
+  # assert_eq!(MyError::insufficient_funds(10).code(), 5);
+  # assert_eq!(MyError::from_code(17), Some("InvalidSignature"));
+  ```
+
+  The numeric code of an error is then returned by the generated methods
+  `MyError::code` and `MyErrorDetail::code`, and the name of the sub-error
+  with a given numeric code by `MyError::from_code` and `MyErrorDetail::from_code`.
+  Either all or none of the sub-errors must be given a numeric code, and the
+  build fails if two sub-errors have the same code. Variant aliases have the
+  numeric code of their sub-error.
+
+  ## Type URL
+
+  Each error type has a `TYPE_URL` constant and a `type_url` function
+  returning a stable identifier of the error type, which can be used for
+  transporting errors in protobuf `Any` messages or looking them up in a
+  registry. The identifier is given with the `@type_url` option after
+  `@serde`, and defaults to `/` followed by the Rust path of the error type:
+
+  ```
+  # use flex_error::define_error;
+  define_error! {
+    @type_url("/ibc.client.error.FooError")
+    FooError {
+      Bar
+        | _ | { "bar error" },
+    }
+  }
+
+  assert_eq!(FooError::type_url(), "/ibc.client.error.FooError");
+  ```
+
+  ## Debug Output
+
+  By default, `Debug` of the main error type formats the error trace only,
+  so the error detail does not show up in the `{:?}` output, e.g. in the
+  failed assertions of tests. The output can be selected with the `@debug`
+  option after `@type_url`:
+
+  ```
+  # use flex_error::define_error;
+  define_error! {
+    @debug(full)
+    FooError {
+      Bar
+        { code: u32 }
+        | e | { format_args!("bar error with code {}", e.code) },
+    }
+  }
+  # let debug = format!("{:?}", FooError::bar(3));
+  # assert!(debug.starts_with("FooError { detail: Bar(BarSubdetail { code: 3 }), trace: "));
+  ```
+
+    - `@debug(trace)` - The error trace only, which is the default.
+
+    - `@debug(detail)` - The error detail only, in the form
+      `FooError(Bar(BarSubdetail { .. }))`, which does not require the
+      error tracer to implement `Debug`.
+
+    - `@debug(full)` - Both the error detail and the error trace, in the
+      form `FooError { detail: .., trace: .. }`.
+
+  The `detail` and `full` outputs require the error detail to implement
+  `Debug`, which is derived by default unless other attributes are given.
+
+  ## Display Output
+
+  By default, `Display` of the main error type also formats the error trace
+  with `Debug`, which includes the backtraces of tracers such as eyre on
+  multiple lines. The output can be selected with the `@display` option
+  after `@debug`, e.g. to keep log lines on a single line while keeping the
+  full error trace in the `Debug` output:
+
+  ```
+  # use flex_error::{define_error, DisplayError};
+  define_error! {
+    @display(chain)
+    FooError {
+      Bar
+        [ DisplayError<String> ]
+        | _ | { "bar error" },
+    }
+  }
+  # assert_eq!(FooError::bar("disk full".into()).to_string(), "bar error: disk full");
+  ```
+
+    - `@display(trace)` - The `Debug` output of the error trace, which is
+      the default.
+
+    - `@display(detail)` - The message of the error detail only, without
+      the messages of the error sources.
+
+    - `@display(chain)` - The messages of the error trace on a single line,
+      joined by `": "`, which requires the error tracer to implement
+      [`TraceRender`](crate::TraceRender).
+
+  As with eyre and anyhow, the alternate mode `{:#}` of the `trace` and
+  `detail` outputs displays the messages of the error trace on a single
+  line with the `Display` of the error tracer, from the outermost message
+  to the innermost error source. The `Display` of the tracers
+  [`EyreTracer`](crate::tracer_impl::eyre::EyreTracer),
+  [`AnyhowTracer`](crate::tracer_impl::anyhow::AnyhowTracer),
+  [`BacktraceTracer`](crate::tracer_impl::backtrace::BacktraceTracer) and
+  [`TracingTracer`](crate::tracer_impl::tracing::TracingTracer) similarly
+  displays only the outermost message in the normal mode `{}`, and the
+  whole chain in the alternate mode `{:#}`.
+
+  The width, fill, alignment and precision of the formatter apply to the
+  whole displayed message, e.g. `{:>40}` or `{:.80}` to truncate long
+  messages, and are also passed through to the `Display` of the error
+  details and of the sub-details. Custom formatters can do the same with
+  [`pad_display`](crate::render::pad_display).
+
+  ## Generic Tracers
+
+  Libraries can let applications choose the error tracer for each use of
+  their error types, instead of through the global Cargo features, with
+  the `@generic_tracer` option after `@type_url`. The option gives the name
+  of a type parameter for the tracer, which defaults to
+  [`DefaultTracer`](crate::DefaultTracer):
+
+  ```
+  # use flex_error::{define_error, ErrorMessageTracer};
+  # use flex_error::tracer_impl::string::StringTracer;
+  # define_error! {
+  #   @generic_tracer(Tracer)
+  #   QuuxError {
+  #     Timeout
+  #       | _ | { "timed out" },
+  #   }
+  # }
+  define_error! {
+    @generic_tracer(Tracer)
+    FooError {
+      Bar
+        { code: u32 }
+        [ QuuxError ]
+        | e | { format_args!("bar error with code {}", e.code) },
+    }
+  }
+
+  pub fn bar<Tracer: ErrorMessageTracer>(source: QuuxError<Tracer>) -> Result<(), FooError<Tracer>> {
+    Err(FooError::bar(3, source))
+  }
+
+  let err = bar::<StringTracer>(QuuxError::timeout()).unwrap_err();
+  assert_eq!(err.1.0, "bar error with code 3: timed out");
+  ```
+
+  This defines `pub struct FooError<Tracer = DefaultTracer>`, with all
+  methods and trait implementations generic over `Tracer`. The error detail
+  types are not generic, and are defined with the error source details
+  extracted by `DefaultTracer`. The constructors are only available if all
+  error sources can be traced by `Tracer`, e.g. `TraceError<E>` requires
+  `Tracer: ErrorTracer<E>`.
+
+  Since the constructors are generic, `Tracer` is inferred from the
+  context, such as the return type, or given explicitly as in
+  `FooError::<StringTracer>::bar(code, source)`. Functions not depending
+  on the tracer, such as `FooError::type_url`, are only defined for the
+  default tracer.
+
+  A generic error type can be used as an error source in other error
+  definitions without a type argument, e.g. `[ FooError ]`, in which case
+  the source is a `FooError<Tracer>` with the tracer of the outer error.
+
+  ## Custom Tracers
+
+  The error tracer of an error type can be chosen independently of the
+  global Cargo features, by giving it after the error name in the form
+  `tracer = ...`. This allows a single crate to define some error types
+  with [`EyreTracer`](crate::tracer_impl::eyre::EyreTracer) and others
+  with [`StringTracer`](crate::tracer_impl::string::StringTracer):
+
+  ```
+  # use flex_error::define_error;
+  use flex_error::tracer_impl::string::StringTracer;
+
+  define_error! {
+    FooError: tracer = StringTracer {
+      Bar
+        { code: u32 }
+        | e | { format_args!("bar error with code {}", e.code) },
+    }
+  }
+  ```
+
+  This defines `pub struct FooError(pub FooErrorDetail, pub StringTracer)`,
+  with the error sources of the sub-errors extracted by `StringTracer`. An
+  error type with a custom tracer can only be used as the error source
+  `[ FooError ]` of error types with the same tracer. Otherwise it can be
+  used as a `[ DisplayError<FooError> ]` source.
+
+  With the `@generic_tracer` option, the given tracer is used as the
+  default type argument instead of `DefaultTracer`.
+
+  ## Sensitive Types
+
+  Types that must never be stored in an error detail, such as private keys,
+  can be marked by implementing [`NotForErrorDetail`](crate::NotForErrorDetail).
+  `define_error!` fails to compile if a marked type is used as the type of a
+  sub-error field, or as the detail of a sub-error source. See the
+  [`sensitive`](crate::sensitive) module for details.
+
+  ## Serde
+
+  If the `"serde"` feature is enabled on the `flex-error` crate,
+  `serde::Serialize` can be implemented for the error detail with the
+  `@serde` option, which is given after `@codes`. The following
+  representations are supported:
+
+    - `@serde(external)` - Externally tagged, in the form
+      `{ "MySubError": { "my_field": ..., "source": ... } }`.
+
+    - `@serde(tag = "kind")` - Internally tagged with the given field name,
+      in the form `{ "kind": "MySubError", "my_field": ..., "source": ... }`.
+
+    - `@serde(code_message)` - A flat code and message mapping, in the form
+      `{ "code": "MySubError", "message": "..." }`, with the message
+      formatted by `Display`. If the `@codes` option is also given, the code
+      from the error codes mapping file is used instead of the sub-error name.
+
+  The types of all fields and error source details of the sub-errors must
+  implement `Serialize` for the `external` and `tag` representations,
+  except for the fields skipped with the `#[detail(skip_serde)]` attribute.
+  Fields can also be renamed with `#[detail(rename = "...")]`:
+
+*/
+#[cfg_attr(feature = "serde", doc = "  ```")]
+#[cfg_attr(not(feature = "serde"), doc = "  ```ignore")]
+/**
+  # use flex_error::define_error;
+  # use std::net::TcpStream;
+  # use std::sync::Arc;
+  define_error! {
+    @serde(tag = "kind")
+    MyError {
+      Closed
+        {
+          #[detail(skip_serde)]
+          socket: Arc<TcpStream>,
+          #[detail(rename = "addr")]
+          address: String,
+        }
+        | e | { format_args!("connection to {} closed", e.address) },
+    }
+  }
+  ```
+
+  The `skip_serde` and `rename` options only affect the serialization, and
+  are ignored without the `@serde` option. Since only `Serialize` is
+  implemented, no other option such as `default` is supported.
+
+*/
+#[cfg_attr(feature = "serde", doc = "  ```")]
+#[cfg_attr(not(feature = "serde"), doc = "  ```ignore")]
+/**
+  # use flex_error::define_error;
+  define_error! {
+    @serde(code_message)
+    MyError {
+      Closed
+        { address: String }
+        | e | { format_args!("connection to {} closed", e.address) },
+    }
+  }
+  ```
+
+  With the `"serde_json"` feature, the error detail can also be exported as
+  JSON together with the messages in the error trace, with the generated
+  `to_json` method. See the `flex_error::json` module.
+
+  ## Structured Logging
+
+  If the `"valuable"` feature is enabled on the `flex-error` crate,
+  `valuable::Valuable` is implemented for the main error type, the error
+  detail and the sub-details, so that errors can be recorded as structured
+  fields by logging libraries supporting `valuable`. The main error is
+  recorded as a struct with a `message` field holding the joined error
+  trace, and a `detail` field holding the detail enum, with the fields of
+  the sub-detail expanded.
+
+  The implementations are only available if the types of all fields and
+  error source details of the sub-errors implement `Valuable`. Otherwise
+  they are omitted, without failing the build.
+
+*/
+#[cfg_attr(feature = "valuable", doc = "  ```")]
+#[cfg_attr(not(feature = "valuable"), doc = "  ```ignore")]
+/**
+  # use flex_error::define_error;
+  use flex_error::valuable::{Valuable, Value};
+
+  define_error! {
+    RelayError {
+      Timeout
+        { height: u64 }
+        | e | { format_args!("timed out at height {}", e.height) },
+    }
+  }
+
+  let err = RelayError::timeout(10);
+  assert!(matches!(err.as_value(), Value::Structable(_)));
+  ```
+
+  With `tracing`, whose `Value` trait cannot be implemented outside of
+  `tracing` itself, the error can be recorded with
+  `tracing::field::valuable`, which requires the `valuable` feature of
+  `tracing` and the `tracing_unstable` cfg flag:
+
+  ```ignore
+  tracing::error!(error = tracing::field::valuable(&err), "failed to relay packet");
+  ```
+
+  ## Defmt
+
+  If the `"defmt"` feature is enabled on the `flex-error` crate,
+  `defmt::Format` is implemented for the main error type, the error detail
+  and the sub-details, so that errors can be logged with `defmt` in
+  firmware. The sub-details are formatted in the form
+  `MySubdetail { my_field: ..., source: ... }`, and the main error type is
+  formatted as its error detail followed by its error trace.
+
+  As with `Valuable`, the implementations for the error detail and the
+  sub-details are only available if the types of all fields and error
+  source details of the sub-errors implement `Format`, and the
+  implementation for the main error type also requires the error tracer to
+  implement `Format`, such as the
+  [`DefmtTracer`](crate::tracer_impl::defmt::DefmtTracer) enabled by the
+  `"defmt_tracer"` feature, which keeps the error trace in a fixed-size
+  buffer without requiring an allocator:
+
+*/
+#[cfg_attr(feature = "defmt_tracer", doc = "  ```no_run")]
+#[cfg_attr(not(feature = "defmt_tracer"), doc = "  ```ignore")]
+/**
+  # use flex_error::define_error;
+  use flex_error::tracer_impl::defmt::DefmtTracer;
+
+  define_error! {
+    SensorError: tracer = DefmtTracer {
+      Read
+        { id: u8 }
+        | e | { format_args!("failed to read sensor {}", e.id) },
+    }
+  }
+
+  fn report(err: &SensorError) {
+    defmt::error!("sensor failure: {}", err);
+  }
+  ```
+
+  ## Detail-Only Mode
+
+  For libraries that want the structure of `define_error!` without carrying
+  any error tracer, the `@detail_only` mode can be used:
+
+  ```
+  # use flex_error::define_error;
+  define_error! {
+    @detail_only
+    MyError {
+      Foo
+        { code: u32 }
+        | e | { format_args!("foo error with code {}", e.code) },
+    }
+  }
+  # assert!(matches!(MyError::foo(3), MyErrorDetail::Foo(FooSubdetail { code: 3 })));
+  ```
+
+  In this mode, `MyError` is a type alias to the `MyErrorDetail` enum,
+  and the constructors such as `MyError::foo` are defined on the enum itself.
+  Error sources are extracted with the
+  [`NoTracer`](crate::tracer_impl::no_trace::NoTracer) tracer, so only
+  their details are kept. `MyErrorDetail` implements
+  [`ErrorSource`](crate::ErrorSource) for all tracers, so it can still be
+  wrapped by error types that carry an error tracer.
+
+  Since no error trace is created, the constructors of sub-errors without
+  an error source can be made `const fn` with the `@const` option, so that
+  sentinel errors can be defined as constants or statics:
+
+  ```
+  # use flex_error::define_error;
+  define_error! {
+    @detail_only
+    MyError {
+      NotInitialized
+        @const
+        | _ | { "not initialized" },
+    }
+  }
+
+  const NOT_INITIALIZED: MyError = MyError::not_initialized();
+  # assert_eq!(NOT_INITIALIZED.to_string(), "not initialized");
+  ```
+
+  The `@const` option is not supported for fields with the `@into`
+  modifier, and the constructors do not record the error statistics of
+  the `"error_stats"` feature.
+
+  ## Arbitrary
+
+  If the `"arbitrary"` feature is enabled on the `flex-error` crate,
+  [`arbitrary::Arbitrary`](https://docs.rs/arbitrary) is implemented for
+  the error detail and all sub-detail types, as long as the types of all
+  fields and error source details implement `Arbitrary`. Error traces are
+  never generated, so fuzz targets can build random error details and pass
+  them through error handling code.
+
+  ## Wrap Tests
+
+  With the `"arbitrary"` feature, the `@tests(wrap)` option generates a
+  test for each sub-error wrapping an error type defined by `define_error!`,
+  directly or with `BoxSource`, such as `my_error_wraps_inner` for the
+  sub-error `Inner`, compiled with `cfg(test)`:
+
+*/
+#[cfg_attr(feature = "arbitrary", doc = "  ```")]
+#[cfg_attr(not(feature = "arbitrary"), doc = "  ```ignore")]
+/**
+  # use flex_error::define_error;
+  # define_error! {
+  #   InnerError {
+  #     Timeout
+  #       | _ | { "timed out" },
+  #   }
+  # }
+  define_error! {
+    @tests(wrap)
+    MyError {
+      Inner
+        { attempt: u32 }
+        [ InnerError ]
+        | e | { format_args!("inner error on attempt {}", e.attempt) },
+    }
+  }
+  ```
+
+  The test constructs the sub-error from arbitrary fields and an arbitrary
+  error with the detail of the source, and checks that the wrapped detail is
+  preserved in the `source` field of the sub-detail, and that the error trace
+  rendered on a single line contains the messages of both errors. This gives
+  regression coverage of the errors wrapped by each error type without
+  writing the tests by hand. As with the `@from` option, the sources other
+  than the ones provided by flex-error are assumed to be error types defined
+  by `define_error!`. The sub-errors with a `[ Self ]` source, two error
+  sources or the `@no_constructor` option are not tested, and the field and
+  source detail types must implement `Arbitrary`, and the error tracer must
+  implement `TraceRender`.
+
+  ## Display Tests
+
+  The `@tests(display)` option generates a test for each sub-error, such
+  as `my_error_displays_connect` for the sub-error `Connect`, compiled with
+  `cfg(test)`, which formats the error detail with the formatter of the
+  sub-error, and with its `@trace` formatter if any, and checks that they
+  do not panic nor return an error. This catches the formatters referencing
+  the wrong fields, such as with a slice indexed out of bounds, in large
+  error types that are rarely displayed in the tests.
+
+  The sub-detail is built with the default values of its fields and of the
+  details of its error sources, or with the fixture given to the sub-error
+  with the `@fixture` option:
+
+*/
+#[cfg_attr(feature = "arbitrary", doc = "  ```")]
+#[cfg_attr(not(feature = "arbitrary"), doc = "  ```ignore")]
+/**
+  # use flex_error::{define_error, DisplayError};
+  # use std::num::ParseIntError;
+  define_error! {
+    @tests(wrap, display)
+    MyError {
+      Connect
+        { address: String, attempt: u32 }
+        | e | { format_args!("failed to connect to {} on attempt {}", e.address, e.attempt) },
+      Parse
+        { input: String }
+        [ DisplayError<ParseIntError> ]
+        @fixture(ParseSubdetail {
+          input: "x".into(),
+          source: "x".parse::<u32>().unwrap_err(),
+        })
+        | e | { format_args!("failed to parse {}", e.input) },
+    }
+  }
+  ```
+
+  The error details defined by `define_error!` have no default value, so
+  the sub-errors wrapping them, including the ones with a `[ Self ]`
+  source, and the sub-errors with two error sources are only tested if
+  they are given a fixture. As with the `@from` option, the sources other
+  than the ones provided by flex-error, such as `DisplayError` or
+  `TraceError`, are assumed to be error types defined by `define_error!`.
+  Unlike `@tests(wrap)`, the option does not require any feature of
+  flex-error.
+
+  ## Free Functions
+
+  The `@free_fns` option also defines a free function for each constructor,
+  named after the sub-error with the given suffix, which calls the
+  constructor of the main error type. This eases the migration of code
+  written for the earlier versions of flex-error, which construct the errors
+  with free functions such as `foo_error(...)`:
+
+  ```
+  # use flex_error::define_error;
+  define_error! {
+    @free_fns(error)
+    FooError {
+      Foo
+        { count: u32 }
+        | e | { format_args!("foo error: {}", e.count) },
+    }
+  }
 
+  let err: FooError = foo_error(42); // same as FooError::foo(42)
+  # assert_eq!(err.detail().to_string(), "foo error: 42");
+  ```
+
+  The free functions are defined next to the error type, and construct the
+  error type with the default tracer in the generic tracer mode. No function
+  is defined for the sub-errors with the `@no_constructor` option.
+
+  ## Derive Macro
+
+  If the `"derive"` feature is enabled on the `flex-error` crate, error
+  types can also be defined with `#[derive(FlexError)]` on a plain enum,
+  which expands to the equivalent `define_error!` definition:
+
+*/
+#[cfg_attr(feature = "derive", doc = "  ```")]
+#[cfg_attr(not(feature = "derive"), doc = "  ```ignore")]
+/**
+  # use flex_error::{FlexError, TraceError};
+  #[derive(FlexError)]
+  #[flex_error(name = FooError)]
+  pub enum FooErrorDef {
+    #[error("failed to read {path}")]
+    Read {
+      path: String,
+      #[source(TraceError<std::io::Error>)]
+      source: std::io::Error,
+    },
+  }
+  # let source = std::io::Error::new(std::io::ErrorKind::NotFound, "not found");
+  # let err = FooError::read("config.toml".to_string(), source);
+  # assert_eq!(err.detail().to_string(), "failed to read config.toml");
+  ```
+
+  The derive only supports the sub-error fields, sources and formatters,
+  and this macro remains available for the other options and for builds
+  without proc-macros. See the `flex_error_derive` crate.
+
+**/
 #[macro_export]
 macro_rules! define_error {
-  ( $name:ident
+  ( @metadata $( $rest:tt )* ) => {
+    $crate::define_error!( @with_metadata[ metadata ] $( $rest )* );
+  };
+  ( $( @with_metadata[ $metadata:ident ] )?
+    $( @derive_main( $( $main_derive:ident ),* $(,)? ) )?
+    $( @codes( $codes:expr ) )?
+    $( @serde( $( $serde:tt )* ) )?
+    $( @type_url( $type_url:literal ) )?
+    $( @debug( $debug:ident ) )?
+    $( @display( $display:ident ) )?
+    $( @tests( $( $tests:ident ),+ $(,)? ) )?
+    $( @free_fns( $free_fns:ident ) )?
+    $( @events( $events:ty ) )?
+    $( @generic_tracer( $generic:ident ) )?
+    $name:ident $( : tracer = $tracer:ty )?
     { $($suberrors:tt)* }
   ) => {
     $crate::define_error_with_tracer![
-      @tracer( $crate::DefaultTracer ),
+      @tracer( $crate::tracer_or_default!( $( $tracer )? ) ),
+      @generic[ $( $generic )? ],
+      @metadata[ $( $metadata )? ],
+      @derive_main[ $( $( $main_derive ),* )? ],
+      $( @codes( $codes ), )?
+      $( @serde( $( $serde )* ), )?
+      $( @type_url( $type_url ), )?
+      $( @debug( $debug ), )?
+      $( @display( $display ), )?
+      $( @tests( $( $tests ),+ ), )?
+      $( @free_fns( $free_fns ), )?
+      $( @events( $events ), )?
       @attr[ derive(Debug) ],
       @name( $name ),
       @suberrors{ $($suberrors)* }
     ];
   };
-  ( #[doc = $doc:literal] $( #[$attr:meta] )*
-    $name:ident
+  ( $( @with_metadata[ $metadata:ident ] )?
+    $( @derive_main( $( $main_derive:ident ),* $(,)? ) )?
+    $( @codes( $codes:expr ) )?
+    $( @serde( $( $serde:tt )* ) )?
+    $( @type_url( $type_url:literal ) )?
+    $( @debug( $debug:ident ) )?
+    $( @display( $display:ident ) )?
+    $( @tests( $( $tests:ident ),+ $(,)? ) )?
+    $( @free_fns( $free_fns:ident ) )?
+    $( @events( $events:ty ) )?
+    $( @generic_tracer( $generic:ident ) )?
+    #[doc = $doc:literal] $( #[$attr:meta] )*
+    $name:ident $( : tracer = $tracer:ty )?
     { $($suberrors:tt)* }
   ) => {
     $crate::define_error_with_tracer![
-      @tracer( $crate::DefaultTracer ),
+      @tracer( $crate::tracer_or_default!( $( $tracer )? ) ),
+      @generic[ $( $generic )? ],
+      @metadata[ $( $metadata )? ],
+      @derive_main[ $( $( $main_derive ),* )? ],
+      $( @codes( $codes ), )?
+      $( @serde( $( $serde )* ), )?
+      $( @type_url( $type_url ), )?
+      $( @debug( $debug ), )?
+      $( @display( $display ), )?
+      $( @tests( $( $tests ),+ ), )?
+      $( @free_fns( $free_fns ), )?
+      $( @events( $events ), )?
       @doc( $doc ),
       @attr[ $( $attr ),* ],
       @name( $name ),
       @suberrors{ $($suberrors)* }
     ];
   };
-  ( $( #[$attr:meta] )*
-    $name:ident
+  ( $( @with_metadata[ $metadata:ident ] )?
+    $( @derive_main( $( $main_derive:ident ),* $(,)? ) )?
+    $( @codes( $codes:expr ) )?
+    $( @serde( $( $serde:tt )* ) )?
+    $( @type_url( $type_url:literal ) )?
+    $( @debug( $debug:ident ) )?
+    $( @display( $display:ident ) )?
+    $( @tests( $( $tests:ident ),+ $(,)? ) )?
+    $( @free_fns( $free_fns:ident ) )?
+    $( @events( $events:ty ) )?
+    $( @generic_tracer( $generic:ident ) )?
+    $( #[$attr:meta] )*
+    $name:ident $( : tracer = $tracer:ty )?
     { $($suberrors:tt)* }
   ) => {
     $crate::define_error_with_tracer![
-      @tracer( $crate::DefaultTracer ),
+      @tracer( $crate::tracer_or_default!( $( $tracer )? ) ),
+      @generic[ $( $generic )? ],
+      @metadata[ $( $metadata )? ],
+      @derive_main[ $( $( $main_derive ),* )? ],
+      $( @codes( $codes ), )?
+      $( @serde( $( $serde )* ), )?
+      $( @type_url( $type_url ), )?
+      $( @debug( $debug ), )?
+      $( @display( $display ), )?
+      $( @tests( $( $tests ),+ ), )?
+      $( @free_fns( $free_fns ), )?
+      $( @events( $events ), )?
       @attr[ $( $attr ),* ],
       @name( $name ),
       @suberrors{ $($suberrors)* }
@@ -407,6 +2050,8 @@ macro_rules! define_error {
   ) => {
     $crate::define_error_with_tracer![
       @tracer( $tracer ),
+      @generic[],
+      @metadata[],
       @attr[ derive(Debug) ],
       @name( $name ),
       @suberrors{ $($suberrors)* }
@@ -419,12 +2064,60 @@ macro_rules! define_error {
   ) => {
     $crate::define_error_with_tracer![
       @tracer( $tracer ),
+      @generic[],
+      @metadata[],
       @attr[ $( $attr ),* ],
       @name( $name ),
       @suberrors{ $($suberrors)* }
     ];
   };
-}
+  ( @detail_only
+    $name:ident
+    { $($suberrors:tt)* }
+  ) => {
+    $crate::define_error_detail_only![
+      @attr[ derive(Debug) ],
+      @name( $name ),
+      @suberrors{ $($suberrors)* }
+    ];
+  };
+  ( @detail_only
+    #[doc = $doc:literal] $( #[$attr:meta] )*
+    $name:ident
+    { $($suberrors:tt)* }
+  ) => {
+    $crate::define_error_detail_only![
+      @doc( $doc ),
+      @attr[ $( $attr ),* ],
+      @name( $name ),
+      @suberrors{ $($suberrors)* }
+    ];
+  };
+  ( @detail_only
+    $( #[$attr:meta] )*
+    $name:ident
+    { $($suberrors:tt)* }
+  ) => {
+    $crate::define_error_detail_only![
+      @attr[ $( $attr ),* ],
+      @name( $name ),
+      @suberrors{ $($suberrors)* }
+    ];
+  };
+}
+
+/// Internal macro used to select the tracer given with `tracer = ...`,
+/// or [`DefaultTracer`](crate::DefaultTracer) otherwise.
+#[macro_export]
+#[doc(hidden)]
+macro_rules! tracer_or_default {
+  () => {
+    $crate::DefaultTracer
+  };
+  ( $tracer:ty ) => {
+    $tracer
+  };
+}
 
 /// This macro allows error types to be defined with custom error tracer types
 /// other than [`DefaultTracer`](crate::DefaultTracer). Behind the scene,
@@ -434,6 +2127,43 @@ macro_rules! define_error {
 #[doc(hidden)]
 macro_rules! define_error_with_tracer {
   ( @tracer( $tracer:ty ),
+    @generic[],
+    $( $rest:tt )*
+  ) => {
+    $crate::define_error_with_tracer![
+      @tracer( $tracer ),
+      @impl_tracer( $tracer ),
+      @detail_tracer[],
+      @generic[],
+      $( $rest )*
+    ];
+  };
+  ( @tracer( $tracer:ty ),
+    @generic[ $generic:ident ],
+    $( $rest:tt )*
+  ) => {
+    $crate::define_error_with_tracer![
+      @tracer( $tracer ),
+      @impl_tracer( $generic ),
+      @detail_tracer[ $tracer ],
+      @generic[ $generic ],
+      $( $rest )*
+    ];
+  };
+  ( @tracer( $tracer:ty ),
+    @impl_tracer( $impl_tracer:ty ),
+    @detail_tracer $detail_tracer:tt,
+    @generic $generic:tt,
+    @metadata $metadata:tt,
+    $( @derive_main[ $( $main_derive:ident ),* ], )?
+    $( @codes( $codes:expr ), )?
+    $( @serde( $( $serde:tt )* ), )?
+    $( @type_url( $type_url:literal ), )?
+    $( @debug( $debug:ident ), )?
+    $( @display( $display:ident ), )?
+    $( @tests( $( $tests:ident ),+ ), )?
+    $( @free_fns( $free_fns:ident ), )?
+    $( @events( $events:ty ), )?
     $( @doc($doc:literal), )?
     @attr[ $( $attr:meta ),* ],
     @name($name:ident),
@@ -441,433 +2171,6698 @@ macro_rules! define_error_with_tracer {
   ) => {
     $crate::macros::paste![
       $crate::define_main_error!(
-        @tracer( $tracer ),
+        @tracer( $impl_tracer ),
+        @default_tracer( $tracer ),
+        @generic $generic,
+        @metadata $metadata,
         $( @doc( $doc ), )?
         @name( $name )
       );
 
+      $crate::define_main_type_url!(
+        @name( $name ),
+        @type_url[ $( $type_url )? ]
+      );
+
+      $crate::define_main_debug!(
+        @tracer( $impl_tracer ),
+        @generic $generic,
+        @name( $name ),
+        @debug[ $( $debug )? ]
+      );
+
+      $crate::define_main_display!(
+        @tracer( $impl_tracer ),
+        @generic $generic,
+        @name( $name ),
+        @display[ $( $display )? ]
+      );
+
+      $( $(
+        $crate::define_main_derive!(
+          @tracer( $impl_tracer ),
+          @generic $generic,
+          @name( $name ),
+          @derive( $main_derive )
+        );
+      )* )?
+
       $crate::define_error_detail!(
         @attr[ $( $attr ),* ] ,
         @name( $name ),
         @suberrors{ $($suberrors)* });
 
+      $crate::with_suberrors!(
+        @cont($crate::define_main_accessors),
+        @ctx[
+          @generic $generic,
+          @name( $name )
+        ],
+        @suberrors{ $($suberrors)* });
+
+      $crate::with_suberrors!(
+        @cont($crate::define_error_detail_codes),
+        @ctx[
+          @name( $name ),
+          @codes[ $( $codes )? ]
+        ],
+        @suberrors{ $($suberrors)* });
+
+      $crate::with_suberror_options!(
+        @cont($crate::define_error_detail_events),
+        @ctx[
+          @generic $generic,
+          @name( $name ),
+          @events[ $( $events )? ]
+        ],
+        @suberrors{ $($suberrors)* });
+
+      $crate::define_error_detail_root_code!(
+        @ctx[
+          @tracer( $tracer ),
+          @name( $name ),
+          @codes[ $( $codes )? ]
+        ],
+        @arms[],
+        @suberrors{ $($suberrors)* });
+
+      $crate::define_error_detail_retry_after!(
+        @ctx[
+          @tracer( $tracer ),
+          @name( $name )
+        ],
+        @arms[],
+        @suberrors{ $($suberrors)* });
+
+      $crate::define_error_detail_exit_code!(
+        @ctx[
+          @tracer( $tracer ),
+          @name( $name )
+        ],
+        @arms[],
+        @suberrors{ $($suberrors)* });
+      $crate::define_error_detail_root_source!(
+        @ctx[
+          @tracer( $tracer ),
+          @name( $name )
+        ],
+        @arms[],
+        @suberrors{ $($suberrors)* });
+
+      $crate::with_suberror_numeric_codes!(
+        @cont($crate::define_error_numeric_codes),
+        @ctx[
+          @name( $name ),
+          @main[ @generic $generic ]
+        ],
+        @suberrors{ $($suberrors)* });
+
+      $crate::define_error_detail_serde!(
+        @ctx[
+          @name( $name ),
+          @codes[ $( $codes )? ],
+          @serde[ $( $( $serde )* )? ],
+          @definitions{ $($suberrors)* }
+        ],
+        @suberrors{ $($suberrors)* });
+
       $crate::define_suberrors! {
         @tracer($tracer),
+        @impl_tracer($impl_tracer),
+        @detail_tracer $detail_tracer,
+        @generic $generic,
         @attr[ $( $attr ),* ],
         @name($name),
         { $( $suberrors )* }
       }
+
+      $crate::define_error_from_impls! {
+        @tracer($impl_tracer),
+        @detail_tracer $detail_tracer,
+        @generic $generic,
+        @name($name),
+        @suberrors{ $( $suberrors )* }
+      }
+
+      $crate::define_error_option_ext! {
+        @name($name),
+        @suberrors{ $( $suberrors )* }
+      }
+
+      $crate::define_error_tests! {
+        @tests[ $( $( $tests )+ )? ],
+        @name($name),
+        @suberrors{ $( $suberrors )* }
+      }
+
+      $crate::define_error_audit! {
+        @name($name),
+        @codes[ $( $codes )? ],
+        @suberrors{ $( $suberrors )* }
+      }
+
+      $crate::define_error_ctor_trait! {
+        @tracer($tracer),
+        @name($name),
+        @suberrors{ $( $suberrors )* }
+      }
+
+      $crate::define_error_free_fns! {
+        @free_fns[ $( $free_fns )? ],
+        @tracer($tracer),
+        @name($name),
+        @suberrors{ $( $suberrors )* }
+      }
+
+      $crate::define_error_wraps! {
+        @tracer($tracer),
+        @name($name),
+        @suberrors{ $( $suberrors )* }
+      }
+
+      $crate::define_error_detail_arbitrary! {
+        @tracer($tracer),
+        @name($name),
+        @suberrors{ $( $suberrors )* }
+      }
+
+      $crate::define_error_detail_valuable! {
+        @tracer($tracer),
+        @name($name),
+        @suberrors{ $( $suberrors )* }
+      }
+
+      $crate::define_main_valuable! {
+        @tracer($impl_tracer),
+        @generic $generic,
+        @name($name)
+      }
+
+      $crate::define_error_detail_defmt! {
+        @tracer($tracer),
+        @name($name),
+        @suberrors{ $( $suberrors )* }
+      }
+
+      $crate::define_main_defmt! {
+        @tracer($impl_tracer),
+        @generic $generic,
+        @name($name)
+      }
+
+      $crate::define_main_problem_details! {
+        @tracer($impl_tracer),
+        @generic $generic,
+        @name($name)
+      }
+
+      $crate::define_main_wasm! {
+        @tracer($impl_tracer),
+        @generic $generic,
+        @name($name)
+      }
+
+      $crate::define_main_axum! {
+        @tracer($impl_tracer),
+        @generic $generic,
+        @name($name)
+      }
+
+      $crate::define_main_json! {
+        @tracer($impl_tracer),
+        @generic $generic,
+        @name($name),
+        @serde[ $( $( $serde )* )? ]
+      }
+
+      $crate::define_main_sentry! {
+        @tracer($impl_tracer),
+        @generic $generic,
+        @name($name),
+        @serde[ $( $( $serde )* )? ]
+      }
+
+      $crate::define_main_rate_limit! {
+        @tracer($impl_tracer),
+        @generic $generic,
+        @name($name)
+      }
     ];
   };
 }
 
+/// Defines an error type in the detail-only mode, where the main error type
+/// is a type alias to the error detail enum, without any error tracer.
 #[macro_export]
 #[doc(hidden)]
-macro_rules! define_main_error {
-  ( @tracer( $tracer:ty ),
-    $( @doc( $doc:literal ), )?
-    @name( $name:ident ) $(,)?
+macro_rules! define_error_detail_only {
+  ( $( @doc($doc:literal), )?
+    @attr[ $( $attr:meta ),* ],
+    @name($name:ident),
+    @suberrors{ $($suberrors:tt)* } $(,)?
   ) => {
     $crate::macros::paste![
-      $crate::define_main_error_struct!(
-        @tracer( $tracer ),
-        $( @doc($doc), )?
-        @name( $name )
-      );
+      pub type $name = [< $name Detail >];
+
+      $crate::define_error_detail!(
+        $( @doc( $doc ), )?
+        @attr[ $( $attr ),* ] ,
+        @name( $name ),
+        @suberrors{ $($suberrors)* });
 
-      impl $crate::ErrorSource<$tracer> for $name {
+      impl<Trace> $crate::ErrorSource<Trace> for [< $name Detail >]
+      where
+          Trace: $crate::ErrorMessageTracer,
+      {
         type Source = Self;
-        type Detail = [< $name Detail >];
+        type Detail = Self;
 
-        fn error_details($name(detail, trace): Self) -> ([< $name Detail >], Option<$tracer>) {
-            (detail, Some(trace))
+        fn error_details(source: Self) -> (Self, Option<Trace>) {
+            let trace = Trace::new_message(&$crate::TraceDisplayed(&source));
+            let trace = source.add_trace_fields(trace);
+            (source, Some(trace))
         }
-      }
 
-      impl ::core::fmt::Debug for $name
-      where
-          $tracer: ::core::fmt::Debug,
-      {
-          fn fmt(&self, f: &mut ::core::fmt::Formatter<'_>) -> ::core::fmt::Result {
-              ::core::fmt::Debug::fmt(self.trace(), f)
-          }
+        fn root_code(detail: &Self) -> Option<&'static str> {
+            detail.root_code()
+        }
+
+        fn retry_after(detail: &Self) -> Option<::core::time::Duration> {
+            detail.retry_after()
+        }
+
+        fn exit_code(detail: &Self) -> Option<i32> {
+            detail.exit_code()
+        }
+
+        fn root_source<Root: 'static>(detail: &Self) -> Option<&Root> {
+            detail.root_source()
+        }
+
+        fn detail_display(detail: &Self) -> Option<&dyn ::core::fmt::Display> {
+            Some(detail)
+        }
       }
 
-      impl ::core::fmt::Display for $name
-      where
-          $tracer: ::core::fmt::Debug,
-      {
-          fn fmt(&self, f: &mut ::core::fmt::Formatter<'_>)
-            -> ::core::fmt::Result
-          {
-              // Always use `Debug` to format error traces, as eyre do not
-              // include full back trace information in normal Display mode.
-              ::core::fmt::Debug::fmt(self.trace(), f)
-          }
+      impl $crate::select::BestSource for [< $name Detail >] {
+        type Wrapper = Self;
       }
 
-      $crate::define_std_err_impl!(
-        @tracer( $tracer ),
+      $crate::define_detail_std_err_impl!(
         @name( $name )
       );
 
-      impl $name {
-        pub fn detail(&self) -> &[< $name Detail >] {
-            &self.0
-        }
+      $crate::define_error_detail_root_code!(
+        @ctx[
+          @tracer( $crate::tracer_impl::no_trace::NoTracer ),
+          @name( $name ),
+          @codes[]
+        ],
+        @arms[],
+        @suberrors{ $($suberrors)* });
 
+      $crate::define_error_detail_retry_after!(
+        @ctx[
+          @tracer( $crate::tracer_impl::no_trace::NoTracer ),
+          @name( $name )
+        ],
+        @arms[],
+        @suberrors{ $($suberrors)* });
 
-        pub fn into_detail(self) -> [< $name Detail >] {
-            self.0
-        }
+      $crate::define_error_detail_exit_code!(
+        @ctx[
+          @tracer( $crate::tracer_impl::no_trace::NoTracer ),
+          @name( $name )
+        ],
+        @arms[],
+        @suberrors{ $($suberrors)* });
+      $crate::define_error_detail_root_source!(
+        @ctx[
+          @tracer( $crate::tracer_impl::no_trace::NoTracer ),
+          @name( $name )
+        ],
+        @arms[],
+        @suberrors{ $($suberrors)* });
 
-        pub fn trace(&self) -> &$tracer {
-            &self.1
-        }
+      $crate::with_suberror_numeric_codes!(
+        @cont($crate::define_error_numeric_codes),
+        @ctx[
+          @name( $name ),
+          @main[]
+        ],
+        @suberrors{ $($suberrors)* });
 
-        pub fn into_trace(self) -> $tracer {
-            self.1
-        }
+      $crate::define_detail_only_suberrors! {
+        @attr[ $( $attr ),* ],
+        @name($name),
+        { $( $suberrors )* }
+      }
 
-        pub fn add_trace<E: ::core::fmt::Display>(self, message: &E) -> Self
-        where
-            $tracer: $crate::ErrorMessageTracer,
-        {
-            let detail = self.0;
-            let trace = $crate::ErrorMessageTracer::add_message(self.1, message);
-            $name(detail, trace)
-        }
+      $crate::define_error_from_impls! {
+        @tracer($crate::tracer_impl::no_trace::NoTracer),
+        @detail_tracer[],
+        @generic[],
+        @name($name),
+        @suberrors{ $( $suberrors )* }
+      }
 
-        pub fn trace_from<E, Cont>(source: E::Source, cont: Cont) -> Self
-        where
-            E: $crate::ErrorSource<$tracer>,
-            $tracer: $crate::ErrorMessageTracer,
-            Cont: FnOnce(E::Detail) -> [< $name Detail >],
-        {
-            let (detail1, m_trace1) = E::error_details(source);
-            let detail2 = cont(detail1);
-            match m_trace1 {
-                Some(trace1) => {
-                    let trace2 = $crate::ErrorMessageTracer::add_message(trace1, &detail2);
-                    $name(detail2, trace2)
-                }
-                None => {
-                    let trace2 = $crate::ErrorMessageTracer::new_message(&detail2);
-                    $name(detail2, trace2)
-                }
-            }
-        }
+      $crate::define_error_option_ext! {
+        @name($name),
+        @suberrors{ $( $suberrors )* }
+      }
+
+      $crate::define_error_audit! {
+        @name($name),
+        @codes[],
+        @suberrors{ $( $suberrors )* }
+      }
+
+      $crate::define_error_detail_arbitrary! {
+        @tracer($crate::tracer_impl::no_trace::NoTracer),
+        @name($name),
+        @suberrors{ $( $suberrors )* }
+      }
+
+      $crate::define_error_detail_valuable! {
+        @tracer($crate::tracer_impl::no_trace::NoTracer),
+        @name($name),
+        @suberrors{ $( $suberrors )* }
+      }
+
+      $crate::define_error_detail_defmt! {
+        @tracer($crate::tracer_impl::no_trace::NoTracer),
+        @name($name),
+        @suberrors{ $( $suberrors )* }
       }
     ];
-  }
+  };
 }
 
-// define the impl for `std::error::Error` only in std mode
-#[cfg(feature = "std")]
+/// Internal macro used to implement `Debug` for the main error type,
+/// with the output selected by the `@debug` option.
 #[macro_export]
 #[doc(hidden)]
-macro_rules! define_std_err_impl {
+macro_rules! define_main_debug {
   ( @tracer( $tracer:ty ),
-    @name( $name:ident ) $(,)?
+    @generic[ $( $generic:ident )? ],
+    @name( $name:ident ),
+    @debug[] $(,)?
   ) => {
-    $crate::macros::paste![
-      impl $crate::StdError for $name
-      where
-          [< $name Detail >]: ::core::fmt::Display,
-          $tracer: ::core::fmt::Debug + ::core::fmt::Display,
-          $tracer: $crate::ErrorMessageTracer,
-      {
-          fn source(&self) -> ::core::option::Option<&(dyn $crate::StdError + 'static)> {
-              $crate::ErrorMessageTracer::as_error(self.trace())
+    $crate::define_main_debug!(
+      @tracer( $tracer ),
+      @generic[ $( $generic )? ],
+      @name( $name ),
+      @debug[ trace ]
+    );
+  };
+  ( @tracer( $tracer:ty ),
+    @generic[ $( $generic:ident )? ],
+    @name( $name:ident ),
+    @debug[ trace ] $(,)?
+  ) => {
+    impl< $( $generic )? > ::core::fmt::Debug for $name< $( $generic )? >
+    where
+        $tracer: ::core::fmt::Debug,
+    {
+        fn fmt(&self, f: &mut ::core::fmt::Formatter<'_>) -> ::core::fmt::Result {
+            ::core::fmt::Debug::fmt(self.trace(), f)
+        }
+    }
+  };
+  ( @tracer( $tracer:ty ),
+    @generic[ $( $generic:ident )? ],
+    @name( $name:ident ),
+    @debug[ detail ] $(,)?
+  ) => {
+    impl< $( $generic )? > ::core::fmt::Debug for $name< $( $generic )? > {
+        fn fmt(&self, f: &mut ::core::fmt::Formatter<'_>) -> ::core::fmt::Result {
+            f.debug_tuple(::core::stringify!($name))
+              .field(self.detail())
+              .finish()
+        }
+    }
+  };
+  ( @tracer( $tracer:ty ),
+    @generic[ $( $generic:ident )? ],
+    @name( $name:ident ),
+    @debug[ full ] $(,)?
+  ) => {
+    impl< $( $generic )? > ::core::fmt::Debug for $name< $( $generic )? >
+    where
+        $tracer: ::core::fmt::Debug,
+    {
+        fn fmt(&self, f: &mut ::core::fmt::Formatter<'_>) -> ::core::fmt::Result {
+            f.debug_struct(::core::stringify!($name))
+              .field("detail", self.detail())
+              .field("trace", self.trace())
+              .finish()
+        }
+    }
+  };
+  ( @tracer( $tracer:ty ),
+    @generic[ $( $generic:ident )? ],
+    @name( $name:ident ),
+    @debug[ $debug:ident ] $(,)?
+  ) => {
+    ::core::compile_error!(::core::concat!(
+      "unknown `@debug` option `",
+      ::core::stringify!($debug),
+      "`, expected one of `trace`, `detail` or `full`"
+    ));
+  };
+}
+
+/// Internal macro used to implement `Display` for the main error type,
+/// with the output selected by the `@display` option.
+#[macro_export]
+#[doc(hidden)]
+macro_rules! define_main_display {
+  ( @tracer( $tracer:ty ),
+    @generic[ $( $generic:ident )? ],
+    @name( $name:ident ),
+    @display[] $(,)?
+  ) => {
+    $crate::define_main_display!(
+      @tracer( $tracer ),
+      @generic[ $( $generic )? ],
+      @name( $name ),
+      @display[ trace ]
+    );
+  };
+  ( @tracer( $tracer:ty ),
+    @generic[ $( $generic:ident )? ],
+    @name( $name:ident ),
+    @display[ trace ] $(,)?
+  ) => {
+    impl< $( $generic )? > ::core::fmt::Display for $name< $( $generic )? >
+    where
+        $tracer: ::core::fmt::Debug + ::core::fmt::Display,
+    {
+        fn fmt(&self, f: &mut ::core::fmt::Formatter<'_>) -> ::core::fmt::Result {
+            // The alternate mode `{:#}` displays the chain of messages of
+            // the error trace.
+            if f.alternate() {
+                return $crate::render::pad_display(f, self.trace());
+            }
+
+            // Use `Debug` to format error traces by default, as eyre do not
+            // include full back trace information in normal Display mode.
+            ::core::fmt::Debug::fmt(self.trace(), f)
+        }
+    }
+  };
+  ( @tracer( $tracer:ty ),
+    @generic[ $( $generic:ident )? ],
+    @name( $name:ident ),
+    @display[ detail ] $(,)?
+  ) => {
+    impl< $( $generic )? > ::core::fmt::Display for $name< $( $generic )? >
+    where
+        $tracer: ::core::fmt::Display,
+    {
+        fn fmt(&self, f: &mut ::core::fmt::Formatter<'_>) -> ::core::fmt::Result {
+            if f.alternate() {
+                return $crate::render::pad_display(f, self.trace());
+            }
+
+            ::core::fmt::Display::fmt(self.detail(), f)
+        }
+    }
+  };
+  ( @tracer( $tracer:ty ),
+    @generic[ $( $generic:ident )? ],
+    @name( $name:ident ),
+    @display[ chain ] $(,)?
+  ) => {
+    impl< $( $generic )? > ::core::fmt::Display for $name< $( $generic )? >
+    where
+        $tracer: $crate::TraceRender,
+    {
+        fn fmt(&self, f: &mut ::core::fmt::Formatter<'_>) -> ::core::fmt::Result {
+            $crate::render::pad_display(f, &$crate::render::Joined(self.trace()))
+        }
+    }
+  };
+  ( @tracer( $tracer:ty ),
+    @generic[ $( $generic:ident )? ],
+    @name( $name:ident ),
+    @display[ $display:ident ] $(,)?
+  ) => {
+    ::core::compile_error!(::core::concat!(
+      "unknown `@display` option `",
+      ::core::stringify!($display),
+      "`, expected one of `trace`, `detail` or `chain`"
+    ));
+  };
+}
+
+#[macro_export]
+#[doc(hidden)]
+macro_rules! define_main_error {
+  ( @tracer( $tracer:ty ),
+    @default_tracer( $default_tracer:ty ),
+    @generic[ $( $generic:ident )? ],
+    @metadata $metadata:tt,
+    $( @doc( $doc:literal ), )?
+    @name( $name:ident ) $(,)?
+  ) => {
+    $crate::macros::paste![
+      $crate::define_main_error_struct!(
+        @tracer( $tracer ),
+        @default_tracer( $default_tracer ),
+        @generic[ $( $generic )? ],
+        @metadata $metadata,
+        $( @doc($doc), )?
+        @name( $name )
+      );
+
+      impl< $( $generic )? > $crate::ErrorSource<$tracer> for $name {
+        type Source = $name< $( $generic )? >;
+        type Detail = [< $name Detail >];
+
+        fn error_details(
+          source: $name< $( $generic )? >,
+        ) -> ([< $name Detail >], Option<$tracer>) {
+            let (detail, trace, _) = source.into_error_parts();
+            (detail, Some(trace))
+        }
+
+        fn metadata(source: &$name< $( $generic )? >) -> Option<&$crate::ErrorMetadata> {
+            source.error_metadata()
+        }
+
+        fn root_code(detail: &[< $name Detail >]) -> Option<&'static str> {
+            detail.root_code()
+        }
+
+        fn retry_after(detail: &[< $name Detail >]) -> Option<::core::time::Duration> {
+            detail.retry_after()
+        }
+
+        fn exit_code(detail: &[< $name Detail >]) -> Option<i32> {
+            detail.exit_code()
+        }
+
+        fn root_source<Root: 'static>(detail: &[< $name Detail >]) -> Option<&Root> {
+            detail.root_source()
+        }
+
+        fn detail_display(detail: &[< $name Detail >]) -> Option<&dyn ::core::fmt::Display> {
+            Some(detail)
+        }
+      }
+
+      impl< $( $generic )? > $crate::select::BestSource for $name< $( $generic )? > {
+        type Wrapper = Self;
+      }
+
+      $crate::define_std_err_impl!(
+        @tracer( $tracer ),
+        @generic[ $( $generic )? ],
+        @name( $name )
+      );
+
+      $crate::define_main_alloc_methods!(
+        @tracer( $tracer ),
+        @generic[ $( $generic )? ],
+        @name( $name )
+      );
+
+      impl< $( $generic )? > $crate::TraceRender for $name< $( $generic )? >
+      where
+          $tracer: $crate::TraceRender,
+      {
+          fn walk_messages(
+            &self,
+            f: &mut dyn FnMut(&dyn ::core::fmt::Display) -> ::core::fmt::Result,
+          ) -> ::core::fmt::Result {
+              $crate::TraceRender::walk_messages(&self.1, f)
+          }
+
+          fn walk_leveled_messages(
+            &self,
+            f: &mut dyn FnMut($crate::Level, &dyn ::core::fmt::Display) -> ::core::fmt::Result,
+          ) -> ::core::fmt::Result {
+              $crate::TraceRender::walk_leveled_messages(&self.1, f)
+          }
+
+          fn location(&self) -> ::core::option::Option<&str> {
+              $crate::TraceRender::location(&self.1)
+          }
+
+          fn metadata(&self) -> ::core::option::Option<&$crate::ErrorMetadata> {
+              self.error_metadata()
+          }
+      }
+
+      impl< $( $generic )? > $crate::HasErrorKind for $name< $( $generic )? > {
+        type Kind = [< $name Kind >];
+
+        fn kind(&self) -> [< $name Kind >] {
+          self.0.kind()
+        }
+      }
+
+      impl< $( $generic )? > $name< $( $generic )? > {
+        pub fn detail(&self) -> &[< $name Detail >] {
+            &self.0
+        }
+
+
+        /// Converts the error into its detail, dropping the error trace,
+        /// e.g. to send the error to another thread as a plain value.
+        pub fn into_detail(self) -> [< $name Detail >] {
+            self.0
+        }
+
+        /// Creates the error from a detail with a new error trace, which
+        /// starts from the message of the detail. This is the inverse of
+        /// `into_detail`, although the messages of the original error trace,
+        /// such as from the error sources, are not restored.
+        #[track_caller]
+        pub fn with_new_trace(detail: [< $name Detail >]) -> Self
+        where
+            $tracer: $crate::ErrorMessageTracer,
+        {
+            let metadata = Self::capture_error_metadata(&[]);
+            let trace = $crate::ErrorMessageTracer::new_message(&$crate::TraceDisplayed(&detail));
+            Self::construct_error(detail, trace, metadata)
+        }
+
+        /// Strips the error trace and the metadata off the error, returning
+        /// only its detail, e.g. to send the error to another thread as a
+        /// plain value and trace it again there with `with_new_trace`.
+        pub fn strip_trace(self) -> [< $name Detail >] {
+            self.0
+        }
+
+        pub fn trace(&self) -> &$tracer {
+            &self.1
+        }
+
+        pub fn kind(&self) -> [< $name Kind >] {
+            self.0.kind()
+        }
+
+        /// Returns the class given to the sub-error with `@transient`
+        /// or `@fatal`, e.g. to decide whether to retry the operation.
+        pub fn class(&self) -> $crate::ErrorClass {
+            self.0.class()
+        }
+
+        pub fn is_transient(&self) -> bool {
+            self.0.is_transient()
+        }
+
+        pub fn is_fatal(&self) -> bool {
+            self.0.is_fatal()
+        }
+
+        /// Feeds the sub-error and the fields marked with `#[detail(hash)]`
+        /// into the hasher, e.g. to key a cache on the error.
+        pub fn detail_hash(&self, hasher: &mut impl ::core::hash::Hasher) {
+            self.0.detail_hash(hasher)
+        }
+
+        /// Returns the code of the innermost error in the chain of nested
+        /// error details that has an error code assigned with `@codes`,
+        /// e.g. to attribute failures to root cause categories.
+        pub fn root_code(&self) -> Option<&'static str> {
+            self.0.root_code()
+        }
+
+        /// Returns the delay before a retry hinted by the server that
+        /// returned the innermost error carrying a retry hint, e.g. with
+        /// the `Retry-After` header. See the `flex_error::retry` module.
+        pub fn retry_after(&self) -> Option<::core::time::Duration> {
+            self.0.retry_after()
+        }
+
+        /// Returns the process exit code given with `@exit_code` to the
+        /// sub-error, or to the outermost nested error source that has one,
+        /// or `1` for a generic failure, e.g. to exit from a CLI tool with
+        /// `std::process::exit(err.exit_code())`.
+        pub fn exit_code(&self) -> i32 {
+            self.0.exit_code().unwrap_or(1)
+        }
+
+        /// Returns the external error of type `Root` kept by the innermost
+        /// error source in the chain of nested errors, such as the error of
+        /// a `TraceClone` or `DetailOnly` source, e.g. to handle the
+        /// root cause without matching on each nested error detail.
+        pub fn root_source<Root: 'static>(&self) -> Option<&Root> {
+            self.0.root_source()
+        }
+
+        pub fn into_trace(self) -> $tracer {
+            self.1
+        }
+
+        /// Displays the user-facing message of the error from the given
+        /// message catalog, instead of the technical error trace.
+        pub fn user_message<'a, Catalog>(
+          &'a self,
+          catalog: &'a Catalog,
+        ) -> $crate::UserMessageDisplayed<'a, [< $name Detail >], Catalog>
+        where
+            Catalog: [< $name UserMessage >] + ?Sized,
+        {
+            $crate::UserMessageDisplayed(&self.0, catalog)
+        }
+
+        pub fn render(&self, theme: $crate::Theme) -> $crate::render::Rendered<'_, $tracer>
+        where
+            $tracer: $crate::TraceRender,
+        {
+            $crate::render::Rendered::new(&self.1, theme)
+                .with_help(self.0.help())
+                .with_metadata(self.error_metadata())
+        }
+
+        pub fn render_grouped(&self) -> $crate::render::Rendered<'_, $tracer>
+        where
+            $tracer: $crate::TraceRender,
+        {
+            $crate::render::Rendered::new(&self.1, $crate::Theme::Plain)
+                .grouped()
+                .with_help(self.0.help())
+                .with_metadata(self.error_metadata())
+        }
+
+        pub fn trace_mut(&mut self) -> &mut $tracer {
+            &mut self.1
+        }
+
+        pub fn map_trace<F>(self, f: F) -> Self
+        where
+            F: FnOnce($tracer) -> $tracer,
+        {
+            let (detail, trace, metadata) = self.into_error_parts();
+            Self::from_error_parts(detail, f(trace), metadata)
+        }
+
+        pub fn add_trace<E: ::core::fmt::Display>(self, message: &E) -> Self
+        where
+            $tracer: $crate::ErrorMessageTracer,
+        {
+            let (detail, trace, metadata) = self.into_error_parts();
+            let trace = $crate::ErrorMessageTracer::add_message(trace, message);
+            Self::from_error_parts(detail, trace, metadata)
+        }
+
+        /// Adds a message to the error trace with the given level, so that
+        /// it can be filtered out when rendering with `Rendered::min_level`.
+        pub fn add_trace_with_level<E: ::core::fmt::Display>(
+            self,
+            message: &E,
+            level: $crate::Level,
+        ) -> Self
+        where
+            $tracer: $crate::ErrorMessageTracer,
+        {
+            let (detail, trace, metadata) = self.into_error_parts();
+            let trace = $crate::ErrorMessageTracer::add_message_with_level(trace, message, level);
+            Self::from_error_parts(detail, trace, metadata)
+        }
+
+        #[track_caller]
+        pub fn trace_from<E, Cont>(source: E::Source, cont: Cont) -> Self
+        where
+            E: $crate::ErrorSource<$tracer>,
+            $tracer: $crate::ErrorMessageTracer,
+            Cont: FnOnce(E::Detail) -> [< $name Detail >],
+        {
+            let metadata = Self::capture_error_metadata(&[E::metadata(&source)]);
+            let (detail1, m_trace1) = E::error_details(source);
+            let detail2 = cont(detail1);
+            match m_trace1 {
+                Some(trace1) => {
+                    let trace2 = $crate::ErrorMessageTracer::add_message(
+                        trace1, &$crate::TraceDisplayed(&detail2));
+                    Self::construct_error(detail2, trace2, metadata)
+                }
+                None => {
+                    let trace2 = $crate::ErrorMessageTracer::new_message(
+                        &$crate::TraceDisplayed(&detail2));
+                    Self::construct_error(detail2, trace2, metadata)
+                }
+            }
+        }
+
+        /// Creates an error from an error source as with `trace_from`, with
+        /// the context message added to the trace of the source before the
+        /// message of the new error detail, e.g. to record the arguments of
+        /// the call site without defining a new sub-error.
+        #[track_caller]
+        pub fn trace_from_with<E, Cont>(
+            source: E::Source,
+            context: impl ::core::fmt::Display,
+            cont: Cont,
+        ) -> Self
+        where
+            E: $crate::ErrorSource<$tracer>,
+            $tracer: $crate::ErrorMessageTracer,
+            Cont: FnOnce(E::Detail) -> [< $name Detail >],
+        {
+            let metadata = Self::capture_error_metadata(&[E::metadata(&source)]);
+            let (detail1, m_trace1) = E::error_details(source);
+            let detail2 = cont(detail1);
+            let trace1 = match m_trace1 {
+                Some(trace1) => $crate::ErrorMessageTracer::add_message(trace1, &context),
+                None => $crate::ErrorMessageTracer::new_message(&context),
+            };
+            let trace2 = $crate::ErrorMessageTracer::add_message(
+                trace1, &$crate::TraceDisplayed(&detail2));
+            Self::construct_error(detail2, trace2, metadata)
+        }
+
+        /// Creates an error from two error sources, with the trace of the
+        /// second source added to the trace of the first source as a single
+        /// message, followed by the message of the new error detail.
+        #[track_caller]
+        pub fn trace_from_both<E1, E2, Cont>(
+            source1: E1::Source,
+            source2: E2::Source,
+            cont: Cont,
+        ) -> Self
+        where
+            E1: $crate::ErrorSource<$tracer>,
+            E2: $crate::ErrorSource<$tracer>,
+            $tracer: $crate::ErrorMessageTracer + $crate::TraceRender,
+            Cont: FnOnce(E1::Detail, E2::Detail) -> [< $name Detail >],
+        {
+            let metadata = Self::capture_error_metadata(
+                &[E1::metadata(&source1), E2::metadata(&source2)]);
+            let (detail1, m_trace1) = E1::error_details(source1);
+            let (detail2, m_trace2) = E2::error_details(source2);
+            let detail = cont(detail1, detail2);
+            let m_trace = match (m_trace1, m_trace2) {
+                (Some(trace1), Some(trace2)) => Some($crate::ErrorMessageTracer::add_message(
+                    trace1, &$crate::render::Joined(&trace2))),
+                (Some(trace), None) | (None, Some(trace)) => Some(trace),
+                (None, None) => None,
+            };
+            let trace = match m_trace {
+                Some(trace) => $crate::ErrorMessageTracer::add_message(
+                    trace, &$crate::TraceDisplayed(&detail)),
+                None => $crate::ErrorMessageTracer::new_message(
+                    &$crate::TraceDisplayed(&detail)),
+            };
+            Self::construct_error(detail, trace, metadata)
+        }
+      }
+    ];
+  }
+}
+
+// define the impl for `std::error::Error` only in std mode, or in no_std
+// mode with `core::error::Error` when the `error_in_core` feature is enabled
+#[cfg(any(feature = "std", feature = "error_in_core"))]
+#[macro_export]
+#[doc(hidden)]
+macro_rules! define_std_err_impl {
+  ( @tracer( $tracer:ty ),
+    @generic[ $( $generic:ident )? ],
+    @name( $name:ident ) $(,)?
+  ) => {
+    $crate::macros::paste![
+      impl< $( $generic )? > $crate::StdError for $name< $( $generic )? >
+      where
+          [< $name Detail >]: ::core::fmt::Display,
+          $tracer: ::core::fmt::Debug + ::core::fmt::Display,
+          $tracer: $crate::ErrorMessageTracer,
+      {
+          fn source(&self) -> ::core::option::Option<&(dyn $crate::StdError + 'static)> {
+              $crate::ErrorMessageTracer::as_error(self.trace())
+          }
+      }
+    ];
+  }
+}
+
+// do not define the impl for `std::error::Error` when in no_std mode
+// without the `error_in_core` feature
+#[cfg(not(any(feature = "std", feature = "error_in_core")))]
+#[macro_export]
+#[doc(hidden)]
+macro_rules! define_std_err_impl {
+    ( @tracer( $tracer:ty ),
+    @generic[ $( $generic:ident )? ],
+    @name( $name:ident ) $(,)?
+  ) => {};
+}
+
+// define the methods collecting the error trace only when
+// the `alloc` feature is enabled
+#[cfg(feature = "alloc")]
+#[macro_export]
+#[doc(hidden)]
+macro_rules! define_main_alloc_methods {
+  ( @tracer( $tracer:ty ),
+    @generic[ $( $generic:ident )? ],
+    @name( $name:ident ) $(,)?
+  ) => {
+    impl< $( $generic )? > $name< $( $generic )? > {
+      pub fn trace_entries(&self) -> impl Iterator<Item = $crate::TraceEntry<'_>>
+      where
+          $tracer: $crate::TraceRender,
+      {
+          $crate::render::trace_entries(&self.1).into_iter()
+      }
+
+      /// Summarizes the error trace on a single line of at most `max_len`
+      /// characters, keeping the outermost and innermost messages.
+      pub fn summarize(&self, max_len: usize) -> $crate::alloc::string::String
+      where
+          $tracer: $crate::TraceRender,
+      {
+          $crate::render::summarize(&self.1, max_len)
+      }
+
+      /// Returns the names and the rendered values of the fields of the
+      /// sub-error, e.g. to show them as a table next to the message.
+      pub fn detail_fields(
+        &self,
+      ) -> impl Iterator<Item = (&'static str, $crate::alloc::string::String)> {
+          self.0.detail_fields()
+      }
+    }
+  };
+}
+
+#[cfg(not(feature = "alloc"))]
+#[macro_export]
+#[doc(hidden)]
+macro_rules! define_main_alloc_methods {
+  ( $( $args:tt )* ) => {};
+}
+
+#[cfg(any(feature = "std", feature = "error_in_core"))]
+#[macro_export]
+#[doc(hidden)]
+macro_rules! define_detail_std_err_impl {
+  ( @name( $name:ident ) $(,)?
+  ) => {
+    $crate::macros::paste![
+      impl $crate::StdError for [< $name Detail >] {}
+    ];
+  }
+}
+
+#[cfg(not(any(feature = "std", feature = "error_in_core")))]
+#[macro_export]
+#[doc(hidden)]
+macro_rules! define_detail_std_err_impl {
+  ( @name( $name:ident ) $(,)?
+  ) => {};
+}
+
+/// Internal macro used to define the type URL of the main error type,
+/// given with the `@type_url` option or derived from its module path.
+#[macro_export]
+#[doc(hidden)]
+macro_rules! define_main_type_url {
+  ( @name( $name:ident ),
+    @type_url[]
+  ) => {
+    $crate::define_main_type_url!(
+      @name( $name ),
+      @type_url[ ::core::concat!("/", ::core::module_path!(), "::", ::core::stringify!($name)) ]
+    );
+  };
+  ( @name( $name:ident ),
+    @type_url[ $type_url:expr ]
+  ) => {
+    impl $name {
+      /// The stable identifier of the error type, such as for transporting
+      /// errors in protobuf `Any` messages.
+      pub const TYPE_URL: &'static str = $type_url;
+
+      pub fn type_url() -> &'static str {
+        Self::TYPE_URL
+      }
+    }
+  };
+}
+
+/// Internal macro used to implement the traits given in `@derive_main`
+/// for the main error type.
+#[macro_export]
+#[doc(hidden)]
+macro_rules! define_main_derive {
+  ( @tracer( $tracer:ty ),
+    @generic[ $( $generic:ident )? ],
+    @name( $name:ident ),
+    @derive( Clone ) $(,)?
+  ) => {
+    $crate::macros::paste![
+      impl< $( $generic )? > ::core::clone::Clone for $name< $( $generic )? >
+      where
+          [< $name Detail >]: ::core::clone::Clone,
+          $tracer: $crate::ErrorMessageTracer + $crate::TraceRender,
+      {
+          fn clone(&self) -> Self {
+              let trace = $crate::ErrorMessageTracer::new_message(
+                  &$crate::render::Joined(&self.1));
+              Self::from_error_parts(
+                  self.0.clone(), trace, self.error_metadata().cloned().unwrap_or_default())
+          }
+      }
+    ];
+  };
+}
+
+#[macro_export]
+#[doc(hidden)]
+macro_rules! define_main_error_struct {
+  ( @tracer( $tracer:ty ),
+    @default_tracer( $default_tracer:ty ),
+    @generic[ $( $generic:ident )? ],
+    @metadata[],
+    $( @doc( $doc:literal ), )?
+    @name( $name:ident ) $(,)?
+  ) => {
+    $crate::define_main_plain_error_struct! {
+      @tracer( $tracer ),
+      @default_tracer( $default_tracer ),
+      @generic[ $( $generic )? ],
+      $( @doc( $doc ), )?
+      @name( $name )
+    }
+  };
+  ( @tracer( $tracer:ty ),
+    @default_tracer( $default_tracer:ty ),
+    @generic[ $( $generic:ident )? ],
+    @metadata[ metadata ],
+    $( @doc( $doc:literal ), )?
+    @name( $name:ident ) $(,)?
+  ) => {
+    $crate::macros::paste![
+      $( #[doc = $doc] )?
+      pub struct $name< $( $generic = $default_tracer )? > (
+        pub [< $name Detail >],
+        pub $tracer,
+        $crate::ErrorMetadata,
+      );
+
+      impl< $( $generic )? > $name< $( $generic )? > {
+        #[track_caller]
+        fn capture_error_metadata(
+          sources: &[Option<&$crate::ErrorMetadata>],
+        ) -> $crate::ErrorMetadata {
+            $crate::ErrorMetadata::capture(sources)
+        }
+
+        fn from_error_parts(
+          detail: [< $name Detail >],
+          trace: $tracer,
+          metadata: $crate::ErrorMetadata,
+        ) -> Self {
+            $name(detail, trace, metadata)
+        }
+
+        /// Creates a newly constructed error, registering the snapshot of
+        /// its error trace under its incident ID with the `incidents`
+        /// feature.
+        fn construct_error(
+          detail: [< $name Detail >],
+          trace: $tracer,
+          metadata: $crate::ErrorMetadata,
+        ) -> Self {
+            $crate::register_incident!(&metadata, &trace);
+            $name(detail, trace, metadata)
+        }
+
+        fn into_error_parts(self) -> ([< $name Detail >], $tracer, $crate::ErrorMetadata) {
+            (self.0, self.1, self.2)
+        }
+
+        fn error_metadata(&self) -> Option<&$crate::ErrorMetadata> {
+            Some(&self.2)
+        }
+
+        /// Returns the metadata kept next to the error detail and trace.
+        pub fn metadata(&self) -> &$crate::ErrorMetadata {
+            &self.2
+        }
+
+        /// Marks the error as already reported, e.g. after it has been logged
+        /// at the point of wrapping. Errors wrapping this error are marked with
+        /// `ReportStatus::SourceReported`.
+        pub fn mark_reported(self) -> Self {
+            $name(self.0, self.1, self.2.with_status($crate::ReportStatus::Reported))
+        }
+
+        /// Returns whether the error itself has been marked as reported.
+        pub fn is_reported(&self) -> bool {
+            self.2.report_status() == $crate::ReportStatus::Reported
+        }
+
+        /// Returns whether an error wrapped by this error has been marked as
+        /// reported, so that its causes do not need to be reported again.
+        pub fn is_source_reported(&self) -> bool {
+            self.2.report_status() == $crate::ReportStatus::SourceReported
+        }
+
+        pub fn report_status(&self) -> $crate::ReportStatus {
+            self.2.report_status()
+        }
+      }
+
+      $crate::define_main_incident! {
+        @tracer( $tracer ),
+        @generic[ $( $generic )? ],
+        @name( $name )
+      }
+
+      $crate::define_main_backtrace! {
+        @generic[ $( $generic )? ],
+        @name( $name )
+      }
+    ];
+  };
+}
+
+// define the main error struct without the `@metadata` option, which
+// keeps the metadata anyway when the `incidents` feature is enabled, so
+// that every error is assigned an incident ID
+#[cfg(not(feature = "incidents"))]
+#[macro_export]
+#[doc(hidden)]
+macro_rules! define_main_plain_error_struct {
+  ( @tracer( $tracer:ty ),
+    @default_tracer( $default_tracer:ty ),
+    @generic[ $( $generic:ident )? ],
+    $( @doc( $doc:literal ), )?
+    @name( $name:ident ) $(,)?
+  ) => {
+    $crate::macros::paste![
+      $( #[doc = $doc] )?
+      pub struct $name< $( $generic = $default_tracer )? > (
+        pub [< $name Detail >],
+        pub $tracer,
+      );
+
+      impl< $( $generic )? > $name< $( $generic )? > {
+        #[track_caller]
+        fn capture_error_metadata(
+          _sources: &[Option<&$crate::ErrorMetadata>],
+        ) -> $crate::ErrorMetadata {
+            $crate::ErrorMetadata::default()
+        }
+
+        fn from_error_parts(
+          detail: [< $name Detail >],
+          trace: $tracer,
+          _metadata: $crate::ErrorMetadata,
+        ) -> Self {
+            $name(detail, trace)
+        }
+
+        fn construct_error(
+          detail: [< $name Detail >],
+          trace: $tracer,
+          metadata: $crate::ErrorMetadata,
+        ) -> Self {
+            Self::from_error_parts(detail, trace, metadata)
+        }
+
+        fn into_error_parts(self) -> ([< $name Detail >], $tracer, $crate::ErrorMetadata) {
+            (self.0, self.1, $crate::ErrorMetadata::default())
+        }
+
+        fn error_metadata(&self) -> Option<&$crate::ErrorMetadata> {
+            None
+        }
+      }
+    ];
+  };
+}
+
+#[cfg(feature = "incidents")]
+#[macro_export]
+#[doc(hidden)]
+macro_rules! define_main_plain_error_struct {
+  ( @tracer( $tracer:ty ),
+    @default_tracer( $default_tracer:ty ),
+    @generic[ $( $generic:ident )? ],
+    $( @doc( $doc:literal ), )?
+    @name( $name:ident ) $(,)?
+  ) => {
+    $crate::define_main_error_struct! {
+      @tracer( $tracer ),
+      @default_tracer( $default_tracer ),
+      @generic[ $( $generic )? ],
+      @metadata[ metadata ],
+      $( @doc( $doc ), )?
+      @name( $name )
+    }
+  };
+}
+
+#[macro_export]
+#[doc(hidden)]
+macro_rules! with_suberrors {
+  ( @cont($cont:path),
+    @ctx[ $($args:tt)* ],
+    @suberrors{
+      $(
+        $( #[$sub_attr:meta] )*
+        $suberror:ident $( as $alias:ident )* $( = $numeric_code:literal )?
+        $( { $( $( #[detail $field_opts:tt] )* $arg_name:ident : $( @ $modifier:ident )? $arg_type:ty ),* $(,)? } )?
+        $( [ $source:ty $( , $source2:ty )? ] )?
+        $( @ $option:ident $( ( $( $option_arg:tt )* ) )? )*
+        | $formatter_arg:pat | $formatter:expr
+      ),* $(,)?
+    } $(,)?
+  ) => {
+    $cont!(
+      @ctx[ $( $args )* ],
+      @suberrors{ $( $suberror: $suberror $( , $alias: $suberror )* ),* }
+    );
+  }
+}
+
+#[macro_export]
+#[doc(hidden)]
+macro_rules! with_suberror_options {
+  ( @cont($cont:path),
+    @ctx[ $($args:tt)* ],
+    @suberrors{
+      $(
+        $( #[$sub_attr:meta] )*
+        $suberror:ident $( as $alias:ident )* $( = $numeric_code:literal )?
+        $( { $( $( #[detail $field_opts:tt] )* $arg_name:ident : $( @ $modifier:ident )? $arg_type:ty ),* $(,)? } )?
+        $( [ $source:ty $( , $source2:ty )? ] )?
+        $( @ $option:ident $( ( $( $option_arg:tt )* ) )? )*
+        | $formatter_arg:pat | $formatter:expr
+      ),* $(,)?
+    } $(,)?
+  ) => {
+    $cont!(
+      @ctx[ $( $args )* ],
+      @suberrors{
+        $( $suberror [ $( $alias ),* ] { $( @ $option ( $( $( $option_arg )* )? ) )* } ),*
+      }
+    );
+  }
+}
+
+/// Internal macro used to collect the field names of all sub-errors,
+/// together with the field options and the error source types, and pass
+/// them to the continuation macro. The error source of a sub-error is
+/// named `source`, or `source1` and `source2` if it has two error sources.
+#[macro_export]
+#[doc(hidden)]
+macro_rules! with_suberror_fields {
+  ( @cont($cont:path),
+    @ctx $ctx:tt,
+    @fields[ $( $fields:tt )* ],
+    @suberrors{} $(,)?
+  ) => {
+    $crate::with_suberror_fields!( @emit($cont), @ctx $ctx, @fields[ $( $fields )* ] );
+  };
+  ( @emit($cont:path),
+    @ctx[ $($args:tt)* ],
+    @fields[ $( $suberror:ident $aliases:tt $fields:tt )* ]
+  ) => {
+    $cont!(
+      @ctx[ $( $args )* ],
+      @suberrors{ $( $suberror $aliases $fields ),* }
+    );
+  };
+  ( @cont($cont:path),
+    @ctx $ctx:tt,
+    @fields[ $( $fields:tt )* ],
+    @suberrors{
+      $( #[$sub_attr:meta] )*
+      $suberror:ident $( as $alias:ident )* $( = $numeric_code:literal )?
+        $( { $( $( #[detail $field_opts:tt] )* $arg_name:ident : $( @ $modifier:ident )? $arg_type:ty ),* $(,)? } )?
+        [ $source:ty, $source2:ty ]
+        $( @ $option:ident $( ( $( $option_arg:tt )* ) )? )*
+        | $formatter_arg:pat | $formatter:expr
+      $( , $($tail:tt)* )?
+    }
+  ) => {
+    $crate::with_suberror_fields! {
+      @cont($cont),
+      @ctx $ctx,
+      @fields[
+        $( $fields )*
+        $suberror [ $( $alias ),* ]
+        { $( $( $arg_name [ $( $field_opts )* ], )* )? source1 [] [ $source ], source2 [] [ $source2 ], }
+      ],
+      @suberrors{ $( $( $tail )* )? }
+    }
+  };
+  ( @cont($cont:path),
+    @ctx $ctx:tt,
+    @fields[ $( $fields:tt )* ],
+    @suberrors{
+      $( #[$sub_attr:meta] )*
+      $suberror:ident $( as $alias:ident )* $( = $numeric_code:literal )?
+        $( { $( $( #[detail $field_opts:tt] )* $arg_name:ident : $( @ $modifier:ident )? $arg_type:ty ),* $(,)? } )?
+        $( [ $source:ty ] )?
+        $( @ $option:ident $( ( $( $option_arg:tt )* ) )? )*
+        | $formatter_arg:pat | $formatter:expr
+      $( , $($tail:tt)* )?
+    }
+  ) => {
+    $crate::with_suberror_fields! {
+      @cont($cont),
+      @ctx $ctx,
+      @fields[
+        $( $fields )*
+        $suberror [ $( $alias ),* ]
+        { $( $( $arg_name [ $( $field_opts )* ], )* )? $( source [] [ $source ], )? }
+      ],
+      @suberrors{ $( $( $tail )* )? }
+    }
+  };
+  ( @cont($cont:path),
+    @ctx $ctx:tt,
+    @suberrors $suberrors:tt $(,)?
+  ) => {
+    $crate::with_suberror_fields! {
+      @cont($cont),
+      @ctx $ctx,
+      @fields[],
+      @suberrors $suberrors
+    }
+  };
+}
+
+#[macro_export]
+#[doc(hidden)]
+macro_rules! define_error_detail {
+  ( $( @doc( $doc:literal ), )?
+    @attr[ $( $attr:meta ),* ],
+    @name( $name:ident ),
+    @suberrors{ $($suberrors:tt)* } $(,)?
+  ) => {
+    $crate::with_suberrors!(
+      @cont($crate::define_error_detail_enum),
+      @ctx[
+        $( @doc( $doc ), )?
+        @attr[ $( $attr ),* ],
+        @name($name)
+      ],
+      @suberrors{ $( $suberrors )* }
+    );
+
+    $crate::with_suberrors!(
+      @cont($crate::define_error_detail_display),
+      @ctx[
+        @name($name)
+      ],
+      @suberrors{ $( $suberrors )* }
+    );
+
+    $crate::with_suberrors!(
+      @cont($crate::define_error_detail_trace_display),
+      @ctx[
+        @name($name)
+      ],
+      @suberrors{ $( $suberrors )* }
+    );
+
+    $crate::with_suberrors!(
+      @cont($crate::define_error_detail_kind),
+      @ctx[
+        @name($name)
+      ],
+      @suberrors{ $( $suberrors )* }
+    );
+
+    $crate::with_suberrors!(
+      @cont($crate::define_error_detail_accessors),
+      @ctx[
+        @name($name)
+      ],
+      @suberrors{ $( $suberrors )* }
+    );
+
+    $crate::with_suberrors!(
+      @cont($crate::define_error_detail_user_message),
+      @ctx[
+        @name($name)
+      ],
+      @suberrors{ $( $suberrors )* }
+    );
+
+    $crate::with_suberror_options!(
+      @cont($crate::define_error_detail_help),
+      @ctx[
+        @name($name)
+      ],
+      @suberrors{ $( $suberrors )* }
+    );
+
+    $crate::with_suberror_options!(
+      @cont($crate::define_error_detail_class),
+      @ctx[
+        @name($name)
+      ],
+      @suberrors{ $( $suberrors )* }
+    );
+
+    $crate::with_suberror_fields!(
+      @cont($crate::define_error_detail_hash),
+      @ctx[
+        @name($name)
+      ],
+      @suberrors{ $( $suberrors )* }
+    );
+
+    $crate::with_suberror_fields!(
+      @cont($crate::define_error_detail_trace_fields),
+      @ctx[
+        @name($name)
+      ],
+      @suberrors{ $( $suberrors )* }
+    );
+
+    $crate::with_suberror_fields!(
+      @cont($crate::define_error_detail_fields),
+      @ctx[
+        @name($name)
+      ],
+      @suberrors{ $( $suberrors )* }
+    );
+
+    $crate::with_suberror_options!(
+      @cont($crate::define_error_detail_problem),
+      @ctx[
+        @name($name)
+      ],
+      @suberrors{ $( $suberrors )* }
+    );
+  }
+}
+
+#[macro_export]
+#[doc(hidden)]
+macro_rules! define_error_detail_enum {
+  ( @ctx[
+      $( @doc( $doc:literal ), )?
+      @attr[ $( $attr:meta ),* ],
+      @name($name:ident)
+    ],
+    @suberrors{ $( $variant:ident: $suberror:ident ),* } $(,)?
+  ) => {
+    $crate::macros::paste! [
+      $( #[doc = $doc] )?
+      $( #[$attr] )*
+      pub enum [< $name Detail >] {
+        $(
+          $variant (
+            [< $suberror Subdetail >]
+          )
+        ),*
+      }
+    ];
+  }
+}
+
+#[macro_export]
+#[doc(hidden)]
+macro_rules! define_error_detail_kind {
+  ( @ctx[
+      @name( $name:ident )
+    ],
+    @suberrors{ $( $variant:ident: $suberror:ident ),* } $(,)?
+  ) => {
+    $crate::macros::paste! [
+      #[doc = ::core::concat!("The kinds of [`", ::core::stringify!($name), "`] without their details.")]
+      #[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+      pub enum [< $name Kind >] {
+        $( $variant ),*
+      }
+
+      impl [< $name Detail >] {
+        pub fn kind(&self) -> [< $name Kind >] {
+          match self {
+            $( Self::$variant( _ ) => [< $name Kind >]::$variant ),*
+          }
+        }
+      }
+
+      impl $crate::HasErrorKind for [< $name Detail >] {
+        type Kind = [< $name Kind >];
+
+        fn kind(&self) -> [< $name Kind >] {
+          [< $name Detail >]::kind(self)
+        }
+      }
+    ];
+  }
+}
+
+#[macro_export]
+#[doc(hidden)]
+macro_rules! define_error_detail_accessors {
+  ( @ctx[
+      @name( $name:ident )
+    ],
+    @suberrors{ $( $variant:ident: $suberror:ident ),* } $(,)?
+  ) => {
+    $crate::macros::paste! [
+      impl [< $name Detail >] {
+        $(
+          #[doc = ::core::concat!("Returns whether the detail is a `", ::core::stringify!($variant), "` error.")]
+          pub fn [< is_ $variant:snake >](&self) -> bool {
+            ::core::matches!(self, Self::$variant( _ ))
+          }
+
+          #[doc = ::core::concat!("Returns the sub-detail if the detail is a `", ::core::stringify!($variant), "` error.")]
+          pub fn [< as_ $variant:snake >](&self) -> ::core::option::Option<&[< $suberror Subdetail >]> {
+            match self {
+              Self::$variant( subdetail ) => ::core::option::Option::Some( subdetail ),
+              #[allow(unreachable_patterns)]
+              _ => ::core::option::Option::None,
+            }
+          }
+        )*
+      }
+    ];
+  }
+}
+
+/// Internal macro used to define the `is_*` and `as_*` methods of the main
+/// error type, forwarding to the methods of the error detail.
+#[macro_export]
+#[doc(hidden)]
+macro_rules! define_main_accessors {
+  ( @ctx[
+      @generic[ $( $generic:ident )? ],
+      @name( $name:ident )
+    ],
+    @suberrors{ $( $variant:ident: $suberror:ident ),* } $(,)?
+  ) => {
+    $crate::macros::paste! [
+      impl< $( $generic )? > $name< $( $generic )? > {
+        $(
+          #[doc = ::core::concat!("Returns whether the error is a `", ::core::stringify!($variant), "` error.")]
+          pub fn [< is_ $variant:snake >](&self) -> bool {
+            self.0.[< is_ $variant:snake >]()
+          }
+
+          #[doc = ::core::concat!("Returns the sub-detail if the error is a `", ::core::stringify!($variant), "` error.")]
+          pub fn [< as_ $variant:snake >](&self) -> ::core::option::Option<&[< $suberror Subdetail >]> {
+            self.0.[< as_ $variant:snake >]()
+          }
+        )*
+      }
+    ];
+  }
+}
+
+#[macro_export]
+#[doc(hidden)]
+macro_rules! define_error_detail_display {
+  ( @ctx[
+      @name( $name:ident )
+    ],
+    @suberrors{ $( $variant:ident: $suberror:ident ),* } $(,)?
+  ) => {
+    $crate::macros::paste! [
+      impl ::core::fmt::Display for [< $name Detail >] {
+        fn fmt(&self, f: &mut ::core::fmt::Formatter<'_>)
+          -> ::core::fmt::Result
+        {
+          match self {
+            $(
+              Self::$variant( suberror ) => {
+                ::core::fmt::Display::fmt( suberror, f )
+              }
+            ),*
+          }
+        }
+      }
+    ];
+  }
+}
+
+#[macro_export]
+#[doc(hidden)]
+macro_rules! define_error_detail_trace_display {
+  ( @ctx[
+      @name( $name:ident )
+    ],
+    @suberrors{ $( $variant:ident: $suberror:ident ),* } $(,)?
+  ) => {
+    $crate::macros::paste! [
+      impl $crate::TraceDisplay for [< $name Detail >] {
+        fn fmt_trace(&self, f: &mut ::core::fmt::Formatter<'_>)
+          -> ::core::fmt::Result
+        {
+          match self {
+            $(
+              Self::$variant( suberror ) => {
+                $crate::TraceDisplay::fmt_trace( suberror, f )
+              }
+            ),*
+          }
+        }
+      }
+    ];
+  }
+}
+
+#[macro_export]
+#[doc(hidden)]
+macro_rules! define_error_detail_user_message {
+  ( @ctx[
+      @name( $name:ident )
+    ],
+    @suberrors{ $( $variant:ident: $suberror:ident ),* } $(,)?
+  ) => {
+    $crate::macros::paste! [
+      #[doc = ::core::concat!(
+        "A catalog of user-facing messages for [`", ::core::stringify!($name),
+        "`], with one method for each sub-error."
+      )]
+      pub trait [< $name UserMessage >] {
+        $(
+          fn [< $variant:snake >](
+            &self,
+            detail: &[< $suberror Subdetail >],
+            f: &mut ::core::fmt::Formatter<'_>,
+          ) -> ::core::fmt::Result;
+        )*
+      }
+
+      impl<Catalog> $crate::UserMessage<Catalog> for [< $name Detail >]
+      where
+          Catalog: [< $name UserMessage >] + ?Sized,
+      {
+        fn fmt_user_message(
+          &self,
+          catalog: &Catalog,
+          f: &mut ::core::fmt::Formatter<'_>,
+        ) -> ::core::fmt::Result {
+          match self {
+            $(
+              Self::$variant( detail ) => {
+                [< $name UserMessage >]::[< $variant:snake >]( catalog, detail, f )
+              }
+            ),*
+          }
+        }
+      }
+
+      impl [< $name Detail >] {
+        /// Displays the user-facing message of the error detail from the
+        /// given message catalog.
+        pub fn user_message<'a, Catalog>(
+          &'a self,
+          catalog: &'a Catalog,
+        ) -> $crate::UserMessageDisplayed<'a, Self, Catalog>
+        where
+            Catalog: [< $name UserMessage >] + ?Sized,
+        {
+          $crate::UserMessageDisplayed(self, catalog)
+        }
+      }
+    ];
+  }
+}
+
+/// Internal macro used to implement `TraceDisplay` for a sub-detail,
+/// using the formatter given in the `@trace` option of the sub-error,
+/// or `Display` otherwise.
+#[macro_export]
+#[doc(hidden)]
+macro_rules! define_subdetail_trace_display {
+  ( @suberror( $suberror:ident ),
+    @options[]
+  ) => {
+    $crate::macros::paste! [
+      impl $crate::TraceDisplay for [< $suberror Subdetail >] {
+        fn fmt_trace(&self, f: &mut ::core::fmt::Formatter<'_>) -> ::core::fmt::Result {
+          ::core::fmt::Display::fmt(self, f)
+        }
+      }
+    ];
+  };
+  ( @suberror( $suberror:ident ),
+    @options[
+      @trace( | $formatter_arg:pat | $formatter:expr )
+      $( $rest:tt )*
+    ]
+  ) => {
+    $crate::macros::paste! [
+      impl $crate::TraceDisplay for [< $suberror Subdetail >] {
+        fn fmt_trace(&self, f: &mut ::core::fmt::Formatter<'_>) -> ::core::fmt::Result {
+          use ::core::format_args;
+          let $formatter_arg = self;
+          ::core::write!(f, "{}",  $formatter)
+        }
+      }
+    ];
+  };
+  ( @suberror( $suberror:ident ),
+    @options[
+      @ $option:ident $option_args:tt
+      $( $rest:tt )*
+    ]
+  ) => {
+    $crate::define_subdetail_trace_display! {
+      @suberror( $suberror ),
+      @options[ $( $rest )* ]
+    }
+  };
+}
+
+/// Internal macro used to define `static_message` for the sub-detail of
+/// a sub-error with the `@static_msg` option, returning the message traced
+/// for the sub-error, which is given by the `@trace` formatter if any.
+#[macro_export]
+#[doc(hidden)]
+macro_rules! define_subdetail_static_message {
+  ( @suberror( $suberror:ident ),
+    @formatter $formatter:tt,
+    @static_msg[],
+    @options[]
+  ) => {};
+  ( @suberror( $suberror:ident ),
+    @formatter( | $formatter_arg:pat | $formatter:expr ),
+    @static_msg[ static ],
+    @options[]
+  ) => {
+    $crate::macros::paste! [
+      impl [< $suberror Subdetail >] {
+        /// Returns the static message traced for the sub-error, which is
+        /// passed to the error tracer without being formatted.
+        #[allow(unused_braces)]
+        pub fn static_message(&self) -> &'static str {
+          #[allow(unused_variables)]
+          let $formatter_arg = self;
+          $formatter
+        }
+      }
+    ];
+  };
+  ( @suberror( $suberror:ident ),
+    @formatter $formatter:tt,
+    @static_msg $static_msg:tt,
+    @options[ @static_msg() $( $rest:tt )* ]
+  ) => {
+    $crate::define_subdetail_static_message! {
+      @suberror( $suberror ),
+      @formatter $formatter,
+      @static_msg[ static ],
+      @options[ $( $rest )* ]
+    }
+  };
+  ( @suberror( $suberror:ident ),
+    @formatter $formatter:tt,
+    @static_msg $static_msg:tt,
+    @options[ @trace( | $formatter_arg:pat | $trace_formatter:expr ) $( $rest:tt )* ]
+  ) => {
+    $crate::define_subdetail_static_message! {
+      @suberror( $suberror ),
+      @formatter( | $formatter_arg | $trace_formatter ),
+      @static_msg $static_msg,
+      @options[ $( $rest )* ]
+    }
+  };
+  ( @suberror( $suberror:ident ),
+    @formatter $formatter:tt,
+    @static_msg $static_msg:tt,
+    @options[ @ $option:ident $option_args:tt $( $rest:tt )* ]
+  ) => {
+    $crate::define_subdetail_static_message! {
+      @suberror( $suberror ),
+      @formatter $formatter,
+      @static_msg $static_msg,
+      @options[ $( $rest )* ]
+    }
+  };
+}
+
+#[macro_export]
+#[doc(hidden)]
+macro_rules! define_error_detail_help {
+  ( @ctx[
+      @name( $name:ident )
+    ],
+    @suberrors{ $( $suberror:ident [ $( $alias:ident ),* ] { $( $options:tt )* } ),* } $(,)?
+  ) => {
+    $crate::macros::paste! [
+      impl [< $name Detail >] {
+        /// Returns the help text given to the sub-error with `@help`, if any.
+        pub fn help(&self) -> ::core::option::Option<&'static str> {
+          match self {
+            $(
+              Self::$suberror( _ ) $( | Self::$alias( _ ) )* => {
+                $crate::suberror_help!( $( $options )* )
+              }
+            ),*
+          }
+        }
+      }
+    ];
+  }
+}
+
+#[macro_export]
+#[doc(hidden)]
+macro_rules! define_error_detail_events {
+  ( @ctx[
+      @generic $generic:tt,
+      @name( $name:ident ),
+      @events[]
+    ],
+    @suberrors{ $( $suberror:ident [ $( $alias:ident ),* ] { $( $options:tt )* } ),* } $(,)?
+  ) => {
+    $(
+      $crate::suberror_event!( @check $( $options )* );
+    )*
+  };
+  ( @ctx[
+      @generic[ $( $generic:ident )? ],
+      @name( $name:ident ),
+      @events[ $events:ty ]
+    ],
+    @suberrors{ $( $suberror:ident [ $( $alias:ident ),* ] { $( $options:tt )* } ),* } $(,)?
+  ) => {
+    $crate::macros::paste! [
+      impl [< $name Detail >] {
+        /// Returns the domain event given to the sub-error with `@event`,
+        /// with the fields copied from the sub-detail, if any.
+        pub fn to_domain_event(&self) -> ::core::option::Option<$events> {
+          match self {
+            $(
+              Self::$suberror( sub ) $( | Self::$alias( sub ) )* => {
+                $crate::suberror_event!( @event( sub ) $( $options )* )
+              }
+            ),*
+          }
+        }
+      }
+
+      impl< $( $generic )? > $name< $( $generic )? > {
+        /// Returns the domain event given to the sub-error with `@event`,
+        /// e.g. to persist the failure in an event-sourced service.
+        pub fn to_domain_event(&self) -> ::core::option::Option<$events> {
+          self.0.to_domain_event()
+        }
+      }
+    ];
+  };
+}
+
+#[macro_export]
+#[doc(hidden)]
+macro_rules! define_error_detail_class {
+  ( @ctx[
+      @name( $name:ident )
+    ],
+    @suberrors{ $( $suberror:ident [ $( $alias:ident ),* ] { $( $options:tt )* } ),* } $(,)?
+  ) => {
+    $crate::macros::paste! [
+      impl [< $name Detail >] {
+        /// Returns the class given to the sub-error with `@transient`
+        /// or `@fatal`, or `ErrorClass::Unclassified`.
+        pub fn class(&self) -> $crate::ErrorClass {
+          match self {
+            $(
+              Self::$suberror( _ ) $( | Self::$alias( _ ) )* => {
+                $crate::suberror_class!( $( $options )* )
+              }
+            ),*
+          }
+        }
+
+        /// Returns whether the sub-error has the `@transient` option.
+        pub fn is_transient(&self) -> bool {
+          self.class() == $crate::ErrorClass::Transient
+        }
+
+        /// Returns whether the sub-error has the `@fatal` option.
+        pub fn is_fatal(&self) -> bool {
+          self.class() == $crate::ErrorClass::Fatal
+        }
+      }
+    ];
+  }
+}
+
+/// Internal macro used to define `detail_hash` for the error detail,
+/// hashing the index of the variant followed by the fields marked with
+/// `#[detail(hash)]`, in the order they are defined.
+#[macro_export]
+#[doc(hidden)]
+macro_rules! define_error_detail_hash {
+  ( @ctx[
+      @name( $name:ident )
+    ],
+    @suberrors{
+      $(
+        $suberror:ident [ $( $alias:ident ),* ]
+        { $( $field:ident $field_opts:tt $( [ $source:ty ] )?, )* }
+      ),*
+    } $(,)?
+  ) => {
+    $crate::macros::paste! [
+      impl [< $name Detail >] {
+        /// Feeds the index of the variant and the fields marked with
+        /// `#[detail(hash)]` into the hasher. Unlike a derived `Hash`,
+        /// this does not require the other fields to implement `Hash`.
+        pub fn detail_hash(&self, hasher: &mut impl ::core::hash::Hasher) {
+          #[allow(dead_code)]
+          enum Index { $( $suberror $( , $alias )* ),* }
+
+          let index = match self {
+            $(
+              Self::$suberror( _ ) => Index::$suberror as u32,
+              $( Self::$alias( _ ) => Index::$alias as u32, )*
+            )*
+          };
+          ::core::hash::Hash::hash(&index, hasher);
+
+          match self {
+            $(
+              #[allow(unused_variables)]
+              Self::$suberror( sub ) $( | Self::$alias( sub ) )* => {
+                $(
+                  $crate::hash_detail_field!(
+                    @hasher( hasher ),
+                    @field( sub.$field ),
+                    $field_opts
+                  );
+                )*
+              }
+            )*
+          }
+        }
+      }
+    ];
+  };
+}
+
+/// Internal macro used to hash a field of a sub-detail, if it is marked
+/// with `#[detail(hash)]`.
+#[macro_export]
+#[doc(hidden)]
+macro_rules! hash_detail_field {
+  ( @hasher( $hasher:ident ),
+    @field( $field:expr ),
+    []
+  ) => {};
+  ( @hasher( $hasher:ident ),
+    @field( $field:expr ),
+    [ ( hash $( , $( $rest:tt )* )? ) $( $opts:tt )* ]
+  ) => {
+    ::core::hash::Hash::hash(&$field, $hasher)
+  };
+  ( @hasher( $hasher:ident ),
+    @field( $field:expr ),
+    [ ( $key:ident $( = $value:literal )? $( , $( $rest:tt )* )? ) $( $opts:tt )* ]
+  ) => {
+    $crate::hash_detail_field!(
+      @hasher( $hasher ),
+      @field( $field ),
+      [ ( $( $( $rest )* )? ) $( $opts )* ]
+    )
+  };
+  ( @hasher( $hasher:ident ),
+    @field( $field:expr ),
+    [ () $( $opts:tt )* ]
+  ) => {
+    $crate::hash_detail_field!(
+      @hasher( $hasher ),
+      @field( $field ),
+      [ $( $opts )* ]
+    )
+  };
+}
+
+/// Internal macro used to define `add_trace_fields` for the error detail,
+/// adding the fields marked with `#[detail(trace)]` to an error trace.
+#[macro_export]
+#[doc(hidden)]
+macro_rules! define_error_detail_trace_fields {
+  ( @ctx[
+      @name( $name:ident )
+    ],
+    @suberrors{
+      $(
+        $suberror:ident [ $( $alias:ident ),* ]
+        { $( $field:ident $field_opts:tt $( [ $source:ty ] )?, )* }
+      ),*
+    } $(,)?
+  ) => {
+    $crate::macros::paste! [
+      impl [< $name Detail >] {
+        /// Adds the fields marked with `#[detail(trace)]` to the error
+        /// trace as structured key-value fields, if there is any.
+        pub fn add_trace_fields<Tracer>(&self, trace: Tracer) -> Tracer
+        where
+          Tracer: $crate::ErrorMessageTracer,
+        {
+          match self {
+            $(
+              #[allow(unused_variables)]
+              Self::$suberror( sub ) $( | Self::$alias( sub ) )* => {
+                $crate::trace_detail_fields!(
+                  @trace( trace ),
+                  @fields[],
+                  $( sub.$field $field_opts, )*
+                )
+              }
+            )*
+          }
+        }
+      }
+    ];
+  };
+}
+
+// define `detail_fields`, which collects the rendered fields into
+// strings, only when the `alloc` feature is enabled
+#[cfg(feature = "alloc")]
+#[macro_export]
+#[doc(hidden)]
+macro_rules! define_error_detail_fields {
+  ( @ctx[
+      @name( $name:ident )
+    ],
+    @suberrors{
+      $(
+        $suberror:ident [ $( $alias:ident ),* ]
+        { $( $field:ident $field_opts:tt $( [ $source:ty ] )?, )* }
+      ),*
+    } $(,)?
+  ) => {
+    $crate::macros::paste! [
+      impl [< $name Detail >] {
+        /// Returns the names and the rendered values of the fields of the
+        /// sub-error, in the order they are defined, without its error
+        /// sources, rendered with `Display`, or with `Debug` for the types
+        /// that do not implement `Display`.
+        pub fn detail_fields(
+          &self,
+        ) -> impl Iterator<Item = (&'static str, $crate::alloc::string::String)> {
+          #[allow(unused_imports)]
+          use $crate::render::{RenderDebugField, RenderDisplayField, RenderOpaqueField};
+
+          #[allow(unused_mut)]
+          let mut fields = $crate::alloc::vec::Vec::new();
+          match self {
+            $(
+              #[allow(unused_variables)]
+              Self::$suberror( sub ) $( | Self::$alias( sub ) )* => {
+                $(
+                  $crate::push_detail_field!(
+                    @fields( fields ),
+                    @field( sub.$field )
+                    $( @source[ $source ] )?
+                  );
+                )*
+              }
+            )*
+          }
+          fields.into_iter()
+        }
+      }
+    ];
+  };
+}
+
+#[cfg(not(feature = "alloc"))]
+#[macro_export]
+#[doc(hidden)]
+macro_rules! define_error_detail_fields {
+  ( $( $args:tt )* ) => {};
+}
+
+/// Internal macro used to add a rendered field of a sub-detail to the
+/// fields returned by `detail_fields`, skipping the error sources.
+#[macro_export]
+#[doc(hidden)]
+macro_rules! push_detail_field {
+  ( @fields( $fields:ident ),
+    @field( $sub:ident . $field:ident )
+  ) => {
+    $fields.push((
+      ::core::stringify!( $field ),
+      (&&&$crate::render::DetailField(&$sub.$field)).render_field(),
+    ))
+  };
+  ( @fields( $fields:ident ),
+    @field( $sub:ident . $field:ident )
+    @source[ $source:ty ]
+  ) => {};
+}
+
+/// Internal macro used to collect the fields of a sub-detail marked with
+/// `#[detail(trace)]`, and to add them to the error trace.
+#[macro_export]
+#[doc(hidden)]
+macro_rules! trace_detail_fields {
+  ( @trace( $trace:ident ),
+    @fields[]
+    $(,)?
+  ) => {
+    $trace
+  };
+  ( @trace( $trace:ident ),
+    @fields[ $( $sub:ident . $field:ident )+ ]
+    $(,)?
+  ) => {
+    $crate::ErrorMessageTracer::add_fields( $trace, &[
+      $( ( ::core::stringify!( $field ), &$sub.$field as &dyn ::core::fmt::Display ) ),+
+    ])
+  };
+  ( @trace( $trace:ident ),
+    @fields[ $( $fields:tt )* ],
+    $sub:ident . $field:ident [],
+    $( $rest:tt )*
+  ) => {
+    $crate::trace_detail_fields!(
+      @trace( $trace ),
+      @fields[ $( $fields )* ],
+      $( $rest )*
+    )
+  };
+  ( @trace( $trace:ident ),
+    @fields[ $( $fields:tt )* ],
+    $sub:ident . $field:ident [ ( trace $( , $( $opt_rest:tt )* )? ) $( $opts:tt )* ],
+    $( $rest:tt )*
+  ) => {
+    $crate::trace_detail_fields!(
+      @trace( $trace ),
+      @fields[ $( $fields )* $sub.$field ],
+      $( $rest )*
+    )
+  };
+  ( @trace( $trace:ident ),
+    @fields $fields:tt,
+    $sub:ident . $field:ident [ ( $key:ident $( = $value:literal )? $( , $( $opt_rest:tt )* )? ) $( $opts:tt )* ],
+    $( $rest:tt )*
+  ) => {
+    $crate::trace_detail_fields!(
+      @trace( $trace ),
+      @fields $fields,
+      $sub.$field [ ( $( $( $opt_rest )* )? ) $( $opts )* ],
+      $( $rest )*
+    )
+  };
+  ( @trace( $trace:ident ),
+    @fields $fields:tt,
+    $sub:ident . $field:ident [ () $( $opts:tt )* ],
+    $( $rest:tt )*
+  ) => {
+    $crate::trace_detail_fields!(
+      @trace( $trace ),
+      @fields $fields,
+      $sub.$field [ $( $opts )* ],
+      $( $rest )*
+    )
+  };
+}
+
+/// Internal macro used to look up the error codes of all sub-errors
+/// given with the `@codes` option, failing the build on missing entries.
+#[macro_export]
+#[doc(hidden)]
+macro_rules! define_error_detail_codes {
+  ( @ctx[
+      @name( $name:ident ),
+      @codes[]
+    ],
+    @suberrors{ $( $variant:ident: $suberror:ident ),* } $(,)?
+  ) => {};
+  ( @ctx[
+      @name( $name:ident ),
+      @codes[ $codes:expr ]
+    ],
+    @suberrors{ $( $variant:ident: $suberror:ident ),* } $(,)?
+  ) => {
+    $crate::macros::paste! [
+      impl [< $name Detail >] {
+        /// Returns the error code and severity assigned to the sub-error
+        /// in the error codes mapping file given with `@codes`.
+        pub fn error_code(&self) -> $crate::codes::ErrorCode {
+          match self {
+            $(
+              Self::$variant( _ ) => {
+                const CODE: $crate::codes::ErrorCode = match $crate::codes::lookup(
+                  $codes,
+                  ::core::stringify!($name),
+                  ::core::stringify!($variant),
+                ) {
+                  ::core::option::Option::Some(code) => code,
+                  ::core::option::Option::None => ::core::panic!(::core::concat!(
+                    "missing code for `",
+                    ::core::stringify!($name),
+                    ".",
+                    ::core::stringify!($variant),
+                    "` in error codes table"
+                  )),
+                };
+                CODE
+              }
+            ),*
+          }
+        }
+      }
+    ];
+
+    $crate::define_error_code_registry! {
+      @name( $name ),
+      @codes( $codes ),
+      @variants[ $( $variant ),* ]
+    }
+  }
+}
+
+// register the error codes only when the `code_registry` feature is enabled
+#[cfg(feature = "code_registry")]
+#[macro_export]
+#[doc(hidden)]
+macro_rules! define_error_code_registry {
+  ( $( $args:tt )* ) => {
+    $crate::register_error_codes! { $( $args )* }
+  };
+}
+
+#[cfg(not(feature = "code_registry"))]
+#[macro_export]
+#[doc(hidden)]
+macro_rules! define_error_code_registry {
+  ( $( $args:tt )* ) => {};
+}
+
+// register the error types for the audit report only when the
+// `audit-tool` feature is enabled
+#[cfg(feature = "audit-tool")]
+#[macro_export]
+#[doc(hidden)]
+macro_rules! define_error_audit {
+  ( @name( $name:ident ),
+    @codes $codes:tt,
+    @suberrors $suberrors:tt $(,)?
+  ) => {
+    $crate::register_error_audit! {
+      @ctx[ @name( $name ), @codes $codes ],
+      @variants[],
+      @suberrors $suberrors
+    }
+  };
+}
+
+#[cfg(not(feature = "audit-tool"))]
+#[macro_export]
+#[doc(hidden)]
+macro_rules! define_error_audit {
+  ( $( $args:tt )* ) => {};
+}
+
+/// Internal macro used to collect the numeric codes given to the sub-errors
+/// in the form `MySubError = 17`, and pass them to the continuation macro.
+#[macro_export]
+#[doc(hidden)]
+macro_rules! with_suberror_numeric_codes {
+  ( @cont($cont:path),
+    @ctx[ $($args:tt)* ],
+    @suberrors{
+      $(
+        $( #[$sub_attr:meta] )*
+        $suberror:ident $( as $alias:ident )* $( = $numeric_code:literal )?
+        $( { $( $( #[detail $field_opts:tt] )* $arg_name:ident : $( @ $modifier:ident )? $arg_type:ty ),* $(,)? } )?
+        $( [ $source:ty $( , $source2:ty )? ] )?
+        $( @ $option:ident $( ( $( $option_arg:tt )* ) )? )*
+        | $formatter_arg:pat | $formatter:expr
+      ),* $(,)?
+    } $(,)?
+  ) => {
+    $cont!(
+      @ctx[ $( $args )* ],
+      @suberrors{ $( $suberror [ $( $alias ),* ] [ $( $numeric_code )? ] ),* }
+    );
+  }
+}
+
+/// Internal macro used to define `code` and `from_code` for the numeric
+/// codes of the sub-errors, if any sub-error has a numeric code.
+#[macro_export]
+#[doc(hidden)]
+macro_rules! define_error_numeric_codes {
+  ( @ctx $ctx:tt,
+    @suberrors{ $( $suberror:ident [ $( $alias:ident ),* ] [] ),* } $(,)?
+  ) => {};
+  ( @ctx[
+      @name( $name:ident ),
+      @main[ $( @generic[ $( $generic:ident )? ] )? ]
+    ],
+    @suberrors{ $( $suberror:ident [ $( $alias:ident ),* ] [ $numeric_code:literal ] ),* } $(,)?
+  ) => {
+    $crate::macros::paste! [
+      impl [< $name Detail >] {
+        /// Returns the numeric code given to the sub-error, e.g. for the
+        /// status codes of gRPC or ABCI responses.
+        pub fn code(&self) -> u32 {
+          match self {
+            $( Self::$suberror( _ ) $( | Self::$alias( _ ) )* => $numeric_code ),*
+          }
+        }
+
+        /// Returns the name of the sub-error with the given numeric code, if any.
+        pub fn from_code(code: u32) -> ::core::option::Option<&'static str> {
+          match code {
+            $( $numeric_code => ::core::option::Option::Some( ::core::stringify!($suberror) ), )*
+            _ => ::core::option::Option::None,
+          }
+        }
+      }
+
+      const _: () = {
+        const CODES: &[u32] = &[ $( $numeric_code ),* ];
+
+        let mut i = 0;
+        while i < CODES.len() {
+          let mut j = i + 1;
+          while j < CODES.len() {
+            if CODES[i] == CODES[j] {
+              ::core::panic!(::core::concat!(
+                "duplicate numeric codes in the sub-errors of `", ::core::stringify!($name), "`"
+              ));
+            }
+            j += 1;
+          }
+          i += 1;
+        }
+      };
+
+      $(
+        impl< $( $generic )? > $name< $( $generic )? > {
+          /// Returns the numeric code given to the sub-error of the error.
+          pub fn code(&self) -> u32 {
+            self.0.code()
+          }
+
+          /// Returns the name of the sub-error with the given numeric code, if any.
+          pub fn from_code(code: u32) -> ::core::option::Option<&'static str> {
+            [< $name Detail >]::from_code(code)
+          }
+        }
+      )?
+    ];
+  };
+  ( @ctx[
+      @name( $name:ident ),
+      $( $ctx:tt )*
+    ],
+    @suberrors $suberrors:tt $(,)?
+  ) => {
+    ::core::compile_error!(::core::concat!(
+      "either all or none of the sub-errors of `", ::core::stringify!($name),
+      "` must be given a numeric code"
+    ));
+  };
+}
+
+/// Internal macro used to define `root_code` for the error detail, which
+/// walks the details of the nested error sources to the innermost error code.
+#[macro_export]
+#[doc(hidden)]
+macro_rules! define_error_detail_root_code {
+  ( @ctx[
+      @tracer( $tracer:ty ),
+      @name( $name:ident ),
+      @codes[ $( $codes:expr )? ]
+    ],
+    @arms[ $( $arm:tt )* ],
+    @suberrors{} $(,)?
+  ) => {
+    $crate::macros::paste! [
+      impl [< $name Detail >] {
+        /// Returns the code of the innermost error in the chain of nested
+        /// error details that has an error code assigned with `@codes`.
+        pub fn root_code(&self) -> ::core::option::Option<&'static str> {
+          let source_code = match self {
+            $( $arm )*
+          };
+          source_code.or( $crate::define_error_detail_root_code!( @own_code[ $( $codes )? ], self ) )
+        }
+      }
+    ];
+  };
+  ( @own_code[], $detail:expr ) => {
+    ::core::option::Option::None
+  };
+  ( @own_code[ $codes:expr ], $detail:expr ) => {
+    ::core::option::Option::Some( $detail.error_code().code )
+  };
+  ( @ctx $ctx:tt,
+    @arms[ $( $arm:tt )* ],
+    @suberrors{
+      $( #[$sub_attr:meta] )*
+      $suberror:ident $( as $alias:ident )* $( = $numeric_code:literal )?
+        $( { $( $( #[detail $field_opts:tt] )* $arg_name:ident : $( @ $modifier:ident )? $arg_type:ty ),* $(,)? } )?
+        [ Self ]
+        $( @ $option:ident $( ( $( $option_arg:tt )* ) )? )*
+        | $formatter_arg:pat | $formatter:expr
+      $( , $($tail:tt)* )?
+    }
+  ) => {
+    $crate::define_error_detail_root_code! {
+      @ctx $ctx,
+      @arms[
+        $( $arm )*
+        Self::$suberror(detail) $( | Self::$alias(detail) )* => detail.source.root_code(),
+      ],
+      @suberrors{ $( $( $tail )* )? }
+    }
+  };
+  ( @ctx[
+      @tracer( $tracer:ty ),
+      $( $ctx:tt )*
+    ],
+    @arms[ $( $arm:tt )* ],
+    @suberrors{
+      $( #[$sub_attr:meta] )*
+      $suberror:ident $( as $alias:ident )* $( = $numeric_code:literal )?
+        $( { $( $( #[detail $field_opts:tt] )* $arg_name:ident : $( @ $modifier:ident )? $arg_type:ty ),* $(,)? } )?
+        [ $source:ty, $source2:ty ]
+        $( @ $option:ident $( ( $( $option_arg:tt )* ) )? )*
+        | $formatter_arg:pat | $formatter:expr
+      $( , $($tail:tt)* )?
+    }
+  ) => {
+    $crate::define_error_detail_root_code! {
+      @ctx[ @tracer( $tracer ), $( $ctx )* ],
+      @arms[
+        $( $arm )*
+        Self::$suberror(detail) $( | Self::$alias(detail) )* =>
+          <$source as $crate::ErrorSource<$tracer>>::root_code(&detail.source1)
+            .or_else(|| <$source2 as $crate::ErrorSource<$tracer>>::root_code(&detail.source2)),
+      ],
+      @suberrors{ $( $( $tail )* )? }
+    }
+  };
+  ( @ctx[
+      @tracer( $tracer:ty ),
+      $( $ctx:tt )*
+    ],
+    @arms[ $( $arm:tt )* ],
+    @suberrors{
+      $( #[$sub_attr:meta] )*
+      $suberror:ident $( as $alias:ident )* $( = $numeric_code:literal )?
+        $( { $( $( #[detail $field_opts:tt] )* $arg_name:ident : $( @ $modifier:ident )? $arg_type:ty ),* $(,)? } )?
+        [ $source:ty ]
+        $( @ $option:ident $( ( $( $option_arg:tt )* ) )? )*
+        | $formatter_arg:pat | $formatter:expr
+      $( , $($tail:tt)* )?
+    }
+  ) => {
+    $crate::define_error_detail_root_code! {
+      @ctx[ @tracer( $tracer ), $( $ctx )* ],
+      @arms[
+        $( $arm )*
+        Self::$suberror(detail) $( | Self::$alias(detail) )* =>
+          <$source as $crate::ErrorSource<$tracer>>::root_code(&detail.source),
+      ],
+      @suberrors{ $( $( $tail )* )? }
+    }
+  };
+  ( @ctx $ctx:tt,
+    @arms[ $( $arm:tt )* ],
+    @suberrors{
+      $( #[$sub_attr:meta] )*
+      $suberror:ident $( as $alias:ident )* $( = $numeric_code:literal )?
+        $( { $( $( #[detail $field_opts:tt] )* $arg_name:ident : $( @ $modifier:ident )? $arg_type:ty ),* $(,)? } )?
+        $( @ $option:ident $( ( $( $option_arg:tt )* ) )? )*
+        | $formatter_arg:pat | $formatter:expr
+      $( , $($tail:tt)* )?
+    }
+  ) => {
+    $crate::define_error_detail_root_code! {
+      @ctx $ctx,
+      @arms[
+        $( $arm )*
+        Self::$suberror(_) $( | Self::$alias(_) )* => ::core::option::Option::None,
+      ],
+      @suberrors{ $( $( $tail )* )? }
+    }
+  };
+}
+
+/// Internal macro used to define the `retry_after` method of the error
+/// detail, returning the retry hint of the innermost error source that has one.
+#[macro_export]
+#[doc(hidden)]
+macro_rules! define_error_detail_retry_after {
+  ( @ctx[
+      @tracer( $tracer:ty ),
+      @name( $name:ident )
+    ],
+    @arms[ $( $arm:tt )* ],
+    @suberrors{} $(,)?
+  ) => {
+    $crate::macros::paste! [
+      impl [< $name Detail >] {
+        /// Returns the delay before a retry hinted by the server that
+        /// returned the innermost error source carrying a retry hint.
+        pub fn retry_after(&self) -> ::core::option::Option<::core::time::Duration> {
+          match self {
+            $( $arm )*
+          }
+        }
+      }
+    ];
+  };
+  ( @ctx $ctx:tt,
+    @arms[ $( $arm:tt )* ],
+    @suberrors{
+      $( #[$sub_attr:meta] )*
+      $suberror:ident $( as $alias:ident )* $( = $numeric_code:literal )?
+        $( { $( $( #[detail $field_opts:tt] )* $arg_name:ident : $( @ $modifier:ident )? $arg_type:ty ),* $(,)? } )?
+        [ Self ]
+        $( @ $option:ident $( ( $( $option_arg:tt )* ) )? )*
+        | $formatter_arg:pat | $formatter:expr
+      $( , $($tail:tt)* )?
+    }
+  ) => {
+    $crate::define_error_detail_retry_after! {
+      @ctx $ctx,
+      @arms[
+        $( $arm )*
+        Self::$suberror(detail) $( | Self::$alias(detail) )* => detail.source.retry_after(),
+      ],
+      @suberrors{ $( $( $tail )* )? }
+    }
+  };
+  ( @ctx[
+      @tracer( $tracer:ty ),
+      $( $ctx:tt )*
+    ],
+    @arms[ $( $arm:tt )* ],
+    @suberrors{
+      $( #[$sub_attr:meta] )*
+      $suberror:ident $( as $alias:ident )* $( = $numeric_code:literal )?
+        $( { $( $( #[detail $field_opts:tt] )* $arg_name:ident : $( @ $modifier:ident )? $arg_type:ty ),* $(,)? } )?
+        [ $source:ty, $source2:ty ]
+        $( @ $option:ident $( ( $( $option_arg:tt )* ) )? )*
+        | $formatter_arg:pat | $formatter:expr
+      $( , $($tail:tt)* )?
+    }
+  ) => {
+    $crate::define_error_detail_retry_after! {
+      @ctx[ @tracer( $tracer ), $( $ctx )* ],
+      @arms[
+        $( $arm )*
+        Self::$suberror(detail) $( | Self::$alias(detail) )* =>
+          <$source as $crate::ErrorSource<$tracer>>::retry_after(&detail.source1)
+            .or_else(|| <$source2 as $crate::ErrorSource<$tracer>>::retry_after(&detail.source2)),
+      ],
+      @suberrors{ $( $( $tail )* )? }
+    }
+  };
+  ( @ctx[
+      @tracer( $tracer:ty ),
+      $( $ctx:tt )*
+    ],
+    @arms[ $( $arm:tt )* ],
+    @suberrors{
+      $( #[$sub_attr:meta] )*
+      $suberror:ident $( as $alias:ident )* $( = $numeric_code:literal )?
+        $( { $( $( #[detail $field_opts:tt] )* $arg_name:ident : $( @ $modifier:ident )? $arg_type:ty ),* $(,)? } )?
+        [ $source:ty ]
+        $( @ $option:ident $( ( $( $option_arg:tt )* ) )? )*
+        | $formatter_arg:pat | $formatter:expr
+      $( , $($tail:tt)* )?
+    }
+  ) => {
+    $crate::define_error_detail_retry_after! {
+      @ctx[ @tracer( $tracer ), $( $ctx )* ],
+      @arms[
+        $( $arm )*
+        Self::$suberror(detail) $( | Self::$alias(detail) )* =>
+          <$source as $crate::ErrorSource<$tracer>>::retry_after(&detail.source),
+      ],
+      @suberrors{ $( $( $tail )* )? }
+    }
+  };
+  ( @ctx $ctx:tt,
+    @arms[ $( $arm:tt )* ],
+    @suberrors{
+      $( #[$sub_attr:meta] )*
+      $suberror:ident $( as $alias:ident )* $( = $numeric_code:literal )?
+        $( { $( $( #[detail $field_opts:tt] )* $arg_name:ident : $( @ $modifier:ident )? $arg_type:ty ),* $(,)? } )?
+        $( @ $option:ident $( ( $( $option_arg:tt )* ) )? )*
+        | $formatter_arg:pat | $formatter:expr
+      $( , $($tail:tt)* )?
+    }
+  ) => {
+    $crate::define_error_detail_retry_after! {
+      @ctx $ctx,
+      @arms[
+        $( $arm )*
+        Self::$suberror(_) $( | Self::$alias(_) )* => ::core::option::Option::None,
+      ],
+      @suberrors{ $( $( $tail )* )? }
+    }
+  };
+}
+
+/// Internal macro used to define the `exit_code` method of the error
+/// detail, returning the `@exit_code` of the sub-error, or else the exit
+/// code of its error source.
+#[macro_export]
+#[doc(hidden)]
+macro_rules! define_error_detail_exit_code {
+  ( @ctx[
+      @tracer( $tracer:ty ),
+      @name( $name:ident )
+    ],
+    @arms[ $( $arm:tt )* ],
+    @suberrors{} $(,)?
+  ) => {
+    $crate::macros::paste! [
+      impl [< $name Detail >] {
+        /// Returns the process exit code given with `@exit_code` to the
+        /// sub-error, or to the outermost nested error source that has one.
+        pub fn exit_code(&self) -> ::core::option::Option<i32> {
+          match self {
+            $( $arm )*
+          }
+        }
+      }
+    ];
+  };
+  ( @ctx $ctx:tt,
+    @arms[ $( $arm:tt )* ],
+    @suberrors{
+      $( #[$sub_attr:meta] )*
+      $suberror:ident $( as $alias:ident )* $( = $numeric_code:literal )?
+        $( { $( $( #[detail $field_opts:tt] )* $arg_name:ident : $( @ $modifier:ident )? $arg_type:ty ),* $(,)? } )?
+        [ Self ]
+        $( @ $option:ident $( ( $( $option_arg:tt )* ) )? )*
+        | $formatter_arg:pat | $formatter:expr
+      $( , $($tail:tt)* )?
+    }
+  ) => {
+    $crate::define_error_detail_exit_code! {
+      @ctx $ctx,
+      @arms[
+        $( $arm )*
+        Self::$suberror(detail) $( | Self::$alias(detail) )* =>
+          $crate::suberror_exit_code!( $( @ $option ( $( $( $option_arg )* )? ) )* )
+            .or_else(|| detail.source.exit_code()),
+      ],
+      @suberrors{ $( $( $tail )* )? }
+    }
+  };
+  ( @ctx[
+      @tracer( $tracer:ty ),
+      $( $ctx:tt )*
+    ],
+    @arms[ $( $arm:tt )* ],
+    @suberrors{
+      $( #[$sub_attr:meta] )*
+      $suberror:ident $( as $alias:ident )* $( = $numeric_code:literal )?
+        $( { $( $( #[detail $field_opts:tt] )* $arg_name:ident : $( @ $modifier:ident )? $arg_type:ty ),* $(,)? } )?
+        [ $source:ty, $source2:ty ]
+        $( @ $option:ident $( ( $( $option_arg:tt )* ) )? )*
+        | $formatter_arg:pat | $formatter:expr
+      $( , $($tail:tt)* )?
+    }
+  ) => {
+    $crate::define_error_detail_exit_code! {
+      @ctx[ @tracer( $tracer ), $( $ctx )* ],
+      @arms[
+        $( $arm )*
+        Self::$suberror(detail) $( | Self::$alias(detail) )* =>
+          $crate::suberror_exit_code!( $( @ $option ( $( $( $option_arg )* )? ) )* )
+            .or_else(|| <$source as $crate::ErrorSource<$tracer>>::exit_code(&detail.source1))
+            .or_else(|| <$source2 as $crate::ErrorSource<$tracer>>::exit_code(&detail.source2)),
+      ],
+      @suberrors{ $( $( $tail )* )? }
+    }
+  };
+  ( @ctx[
+      @tracer( $tracer:ty ),
+      $( $ctx:tt )*
+    ],
+    @arms[ $( $arm:tt )* ],
+    @suberrors{
+      $( #[$sub_attr:meta] )*
+      $suberror:ident $( as $alias:ident )* $( = $numeric_code:literal )?
+        $( { $( $( #[detail $field_opts:tt] )* $arg_name:ident : $( @ $modifier:ident )? $arg_type:ty ),* $(,)? } )?
+        [ $source:ty ]
+        $( @ $option:ident $( ( $( $option_arg:tt )* ) )? )*
+        | $formatter_arg:pat | $formatter:expr
+      $( , $($tail:tt)* )?
+    }
+  ) => {
+    $crate::define_error_detail_exit_code! {
+      @ctx[ @tracer( $tracer ), $( $ctx )* ],
+      @arms[
+        $( $arm )*
+        Self::$suberror(detail) $( | Self::$alias(detail) )* =>
+          $crate::suberror_exit_code!( $( @ $option ( $( $( $option_arg )* )? ) )* )
+            .or_else(|| <$source as $crate::ErrorSource<$tracer>>::exit_code(&detail.source)),
+      ],
+      @suberrors{ $( $( $tail )* )? }
+    }
+  };
+  ( @ctx $ctx:tt,
+    @arms[ $( $arm:tt )* ],
+    @suberrors{
+      $( #[$sub_attr:meta] )*
+      $suberror:ident $( as $alias:ident )* $( = $numeric_code:literal )?
+        $( { $( $( #[detail $field_opts:tt] )* $arg_name:ident : $( @ $modifier:ident )? $arg_type:ty ),* $(,)? } )?
+        $( @ $option:ident $( ( $( $option_arg:tt )* ) )? )*
+        | $formatter_arg:pat | $formatter:expr
+      $( , $($tail:tt)* )?
+    }
+  ) => {
+    $crate::define_error_detail_exit_code! {
+      @ctx $ctx,
+      @arms[
+        $( $arm )*
+        Self::$suberror(_) $( | Self::$alias(_) )* =>
+          $crate::suberror_exit_code!( $( @ $option ( $( $( $option_arg )* )? ) )* ),
+      ],
+      @suberrors{ $( $( $tail )* )? }
+    }
+  };
+}
+
+/// Internal macro used to define the `root_source` method of the error
+/// detail, returning the external error of the given type kept by the
+/// innermost error source.
+#[macro_export]
+#[doc(hidden)]
+macro_rules! define_error_detail_root_source {
+  ( @ctx[
+      @tracer( $tracer:ty ),
+      @name( $name:ident )
+    ],
+    @arms[ $( $arm:tt )* ],
+    @suberrors{} $(,)?
+  ) => {
+    $crate::macros::paste! [
+      impl [< $name Detail >] {
+        /// Returns the external error of type `Root` kept by the innermost
+        /// error source in the chain of nested error details, if any.
+        pub fn root_source<Root: 'static>(&self) -> ::core::option::Option<&Root> {
+          match self {
+            $( $arm )*
+          }
+        }
+      }
+    ];
+  };
+  ( @ctx $ctx:tt,
+    @arms[ $( $arm:tt )* ],
+    @suberrors{
+      $( #[$sub_attr:meta] )*
+      $suberror:ident $( as $alias:ident )* $( = $numeric_code:literal )?
+        $( { $( $( #[detail $field_opts:tt] )* $arg_name:ident : $( @ $modifier:ident )? $arg_type:ty ),* $(,)? } )?
+        [ Self ]
+        $( @ $option:ident $( ( $( $option_arg:tt )* ) )? )*
+        | $formatter_arg:pat | $formatter:expr
+      $( , $($tail:tt)* )?
+    }
+  ) => {
+    $crate::define_error_detail_root_source! {
+      @ctx $ctx,
+      @arms[
+        $( $arm )*
+        Self::$suberror(detail) $( | Self::$alias(detail) )* =>
+          detail.source.root_source(),
+      ],
+      @suberrors{ $( $( $tail )* )? }
+    }
+  };
+  ( @ctx[
+      @tracer( $tracer:ty ),
+      $( $ctx:tt )*
+    ],
+    @arms[ $( $arm:tt )* ],
+    @suberrors{
+      $( #[$sub_attr:meta] )*
+      $suberror:ident $( as $alias:ident )* $( = $numeric_code:literal )?
+        $( { $( $( #[detail $field_opts:tt] )* $arg_name:ident : $( @ $modifier:ident )? $arg_type:ty ),* $(,)? } )?
+        [ $source:ty, $source2:ty ]
+        $( @ $option:ident $( ( $( $option_arg:tt )* ) )? )*
+        | $formatter_arg:pat | $formatter:expr
+      $( , $($tail:tt)* )?
+    }
+  ) => {
+    $crate::define_error_detail_root_source! {
+      @ctx[ @tracer( $tracer ), $( $ctx )* ],
+      @arms[
+        $( $arm )*
+        Self::$suberror(detail) $( | Self::$alias(detail) )* =>
+          <$source as $crate::ErrorSource<$tracer>>::root_source(&detail.source1)
+            .or_else(|| <$source2 as $crate::ErrorSource<$tracer>>::root_source(&detail.source2)),
+      ],
+      @suberrors{ $( $( $tail )* )? }
+    }
+  };
+  ( @ctx[
+      @tracer( $tracer:ty ),
+      $( $ctx:tt )*
+    ],
+    @arms[ $( $arm:tt )* ],
+    @suberrors{
+      $( #[$sub_attr:meta] )*
+      $suberror:ident $( as $alias:ident )* $( = $numeric_code:literal )?
+        $( { $( $( #[detail $field_opts:tt] )* $arg_name:ident : $( @ $modifier:ident )? $arg_type:ty ),* $(,)? } )?
+        [ $source:ty ]
+        $( @ $option:ident $( ( $( $option_arg:tt )* ) )? )*
+        | $formatter_arg:pat | $formatter:expr
+      $( , $($tail:tt)* )?
+    }
+  ) => {
+    $crate::define_error_detail_root_source! {
+      @ctx[ @tracer( $tracer ), $( $ctx )* ],
+      @arms[
+        $( $arm )*
+        Self::$suberror(detail) $( | Self::$alias(detail) )* =>
+          <$source as $crate::ErrorSource<$tracer>>::root_source(&detail.source),
+      ],
+      @suberrors{ $( $( $tail )* )? }
+    }
+  };
+  ( @ctx $ctx:tt,
+    @arms[ $( $arm:tt )* ],
+    @suberrors{
+      $( #[$sub_attr:meta] )*
+      $suberror:ident $( as $alias:ident )* $( = $numeric_code:literal )?
+        $( { $( $( #[detail $field_opts:tt] )* $arg_name:ident : $( @ $modifier:ident )? $arg_type:ty ),* $(,)? } )?
+        $( @ $option:ident $( ( $( $option_arg:tt )* ) )? )*
+        | $formatter_arg:pat | $formatter:expr
+      $( , $($tail:tt)* )?
+    }
+  ) => {
+    $crate::define_error_detail_root_source! {
+      @ctx $ctx,
+      @arms[
+        $( $arm )*
+        Self::$suberror(_) $( | Self::$alias(_) )* => ::core::option::Option::None,
+      ],
+      @suberrors{ $( $( $tail )* )? }
+    }
+  };
+}
+
+/// Internal macro used to implement `serde::Serialize` for the error
+/// detail with the representation given in the `@serde` option.
+#[cfg(feature = "serde")]
+#[macro_export]
+#[doc(hidden)]
+macro_rules! define_error_detail_serde {
+  ( @ctx[
+      @name( $name:ident ),
+      @codes $codes:tt,
+      @serde[],
+      @definitions $definitions:tt
+    ],
+    @suberrors{ $( $variant:ident: $suberror:ident ),* } $(,)?
+  ) => {};
+  ( @ctx[
+      @name( $name:ident ),
+      @codes $codes:tt,
+      @serde $serde:tt,
+      @definitions $definitions:tt
+    ],
+    @suberrors{ $( $variant:ident: $suberror:ident ),* } $(,)?
+  ) => {
+    $crate::with_suberror_fields!(
+      @cont($crate::define_error_detail_serialize),
+      @ctx[
+        @name( $name ),
+        @codes $codes,
+        @serde $serde,
+        @variants[ $( $variant ),* ]
+      ],
+      @suberrors $definitions);
+  };
+  ( $( $args:tt )* ) => {
+    $crate::with_suberrors!( @cont($crate::define_error_detail_serde), $( $args )* );
+  };
+}
+
+#[cfg(not(feature = "serde"))]
+#[macro_export]
+#[doc(hidden)]
+macro_rules! define_error_detail_serde {
+  ( @ctx[
+      @name( $name:ident ),
+      @codes $codes:tt,
+      @serde[],
+      @definitions $definitions:tt
+    ],
+    @suberrors $suberrors:tt $(,)?
+  ) => {};
+  ( @ctx[
+      @name( $name:ident ),
+      @codes $codes:tt,
+      @serde $serde:tt,
+      @definitions $definitions:tt
+    ],
+    @suberrors $suberrors:tt $(,)?
+  ) => {
+    ::core::compile_error!(
+      "the `@serde` option requires the `serde` feature of `flex-error` to be enabled");
+  };
+}
+
+#[cfg(feature = "serde")]
+#[macro_export]
+#[doc(hidden)]
+macro_rules! define_error_detail_serialize {
+  ( @ctx[
+      @name( $name:ident ),
+      @codes $codes:tt,
+      @serde $serde:tt,
+      @variants[ $( $variant:ident ),* ]
+    ],
+    @suberrors{
+      $(
+        $suberror:ident [ $( $alias:ident ),* ]
+        { $( $field:ident $field_opts:tt $( [ $source:ty ] )?, )* }
+      ),*
+    } $(,)?
+  ) => {
+    $crate::macros::paste! [
+      impl $crate::serde::Serialize for [< $name Detail >] {
+        fn serialize<S>(&self, serializer: S) -> ::core::result::Result<S::Ok, S::Error>
+        where
+            S: $crate::serde::Serializer,
+        {
+          #[allow(unused_imports)]
+          use $crate::serde::ser::{SerializeStruct, SerializeStructVariant};
+
+          #[allow(dead_code)]
+          enum Index { $( $variant ),* }
+
+          #[allow(unused_variables)]
+          let (variant, index) = match self {
+            $( Self::$variant( _ ) => ( ::core::stringify!($variant), Index::$variant as u32 ) ),*
+          };
+
+          match self {
+            $(
+              Self::$suberror( sub ) $( | Self::$alias( sub ) )* => {
+                $crate::serialize_error_detail!(
+                  @serde $serde,
+                  @codes $codes,
+                  @name( ::core::concat!(::core::stringify!($name), "Detail") ),
+                  @detail( self ),
+                  @variant( variant, index ),
+                  @serializer( serializer ),
+                  @fields( sub; $( $field $field_opts ),* )
+                )
+              }
+            ),*
+          }
+        }
+      }
+    ];
+  }
+}
+
+/// Internal macro used to serialize a sub-detail with the representation
+/// given in the `@serde` option.
+#[cfg(feature = "serde")]
+#[macro_export]
+#[doc(hidden)]
+macro_rules! serialize_error_detail {
+  ( @serde[ external ],
+    @codes $codes:tt,
+    @name( $name:expr ),
+    @detail( $detail:expr ),
+    @variant( $variant:expr, $index:expr ),
+    @serializer( $serializer:expr ),
+    @fields( $sub:ident; $( $field:ident $field_opts:tt ),* )
+  ) => {{
+    let len = 0 $( + $crate::serde_field_len!( $field_opts ) )*;
+    let mut state = $serializer.serialize_struct_variant($name, $index, $variant, len)?;
+    $(
+      $crate::serialize_detail_field!(
+        @state( state ),
+        @field( $sub.$field ),
+        @name( ::core::stringify!($field) ),
+        $field_opts
+      );
+    )*
+    state.end()
+  }};
+  ( @serde[ tag = $tag:literal ],
+    @codes $codes:tt,
+    @name( $name:expr ),
+    @detail( $detail:expr ),
+    @variant( $variant:expr, $index:expr ),
+    @serializer( $serializer:expr ),
+    @fields( $sub:ident; $( $field:ident $field_opts:tt ),* )
+  ) => {{
+    let len = 1 $( + $crate::serde_field_len!( $field_opts ) )*;
+    let mut state = $serializer.serialize_struct($name, len)?;
+    state.serialize_field($tag, $variant)?;
+    $(
+      $crate::serialize_detail_field!(
+        @state( state ),
+        @field( $sub.$field ),
+        @name( ::core::stringify!($field) ),
+        $field_opts
+      );
+    )*
+    state.end()
+  }};
+  ( @serde[ code_message ],
+    @codes[],
+    @name( $name:expr ),
+    @detail( $detail:expr ),
+    @variant( $variant:expr, $index:expr ),
+    @serializer( $serializer:expr ),
+    @fields( $sub:ident; $( $field:ident $field_opts:tt ),* )
+  ) => {{
+    let mut state = $serializer.serialize_struct($name, 2)?;
+    state.serialize_field("code", $variant)?;
+    state.serialize_field("message", &$crate::alloc::format!("{}", $detail))?;
+    state.end()
+  }};
+  ( @serde[ code_message ],
+    @codes[ $codes:expr ],
+    @name( $name:expr ),
+    @detail( $detail:expr ),
+    @variant( $variant:expr, $index:expr ),
+    @serializer( $serializer:expr ),
+    @fields( $sub:ident; $( $field:ident $field_opts:tt ),* )
+  ) => {{
+    let mut state = $serializer.serialize_struct($name, 2)?;
+    state.serialize_field("code", $detail.error_code().code)?;
+    state.serialize_field("message", &$crate::alloc::format!("{}", $detail))?;
+    state.end()
+  }};
+  ( @serde[ $( $serde:tt )* ], $( $rest:tt )* ) => {
+    ::core::compile_error!(::core::concat!(
+      "unsupported `@serde` representation `",
+      ::core::stringify!($( $serde )*),
+      "`, expected one of `external`, `tag = \"...\"` or `code_message`"
+    ))
+  };
+}
+
+/// Internal macro used to serialize a field of a sub-detail, according
+/// to the options given in its `#[detail(...)]` attributes.
+#[cfg(feature = "serde")]
+#[macro_export]
+#[doc(hidden)]
+macro_rules! serialize_detail_field {
+  ( @state( $state:ident ),
+    @field( $field:expr ),
+    @name( $name:expr ),
+    []
+  ) => {
+    $state.serialize_field($name, &$field)?
+  };
+  ( @state( $state:ident ),
+    @field( $field:expr ),
+    @name( $name:expr ),
+    [ ( skip_serde $( , $( $rest:tt )* )? ) $( $opts:tt )* ]
+  ) => {
+    $state.skip_field($name)?
+  };
+  ( @state( $state:ident ),
+    @field( $field:expr ),
+    @name( $name:expr ),
+    [ ( rename = $rename:literal $( , $( $rest:tt )* )? ) $( $opts:tt )* ]
+  ) => {
+    $crate::serialize_detail_field!(
+      @state( $state ),
+      @field( $field ),
+      @name( $rename ),
+      [ ( $( $( $rest )* )? ) $( $opts )* ]
+    )
+  };
+  ( @state( $state:ident ),
+    @field( $field:expr ),
+    @name( $name:expr ),
+    [ ( hash $( , $( $rest:tt )* )? ) $( $opts:tt )* ]
+  ) => {
+    $crate::serialize_detail_field!(
+      @state( $state ),
+      @field( $field ),
+      @name( $name ),
+      [ ( $( $( $rest )* )? ) $( $opts )* ]
+    )
+  };
+  ( @state( $state:ident ),
+    @field( $field:expr ),
+    @name( $name:expr ),
+    [ ( trace $( , $( $rest:tt )* )? ) $( $opts:tt )* ]
+  ) => {
+    $crate::serialize_detail_field!(
+      @state( $state ),
+      @field( $field ),
+      @name( $name ),
+      [ ( $( $( $rest )* )? ) $( $opts )* ]
+    )
+  };
+  ( @state( $state:ident ),
+    @field( $field:expr ),
+    @name( $name:expr ),
+    [ () $( $opts:tt )* ]
+  ) => {
+    $crate::serialize_detail_field!(
+      @state( $state ),
+      @field( $field ),
+      @name( $name ),
+      [ $( $opts )* ]
+    )
+  };
+  ( @state( $state:ident ),
+    @field( $field:expr ),
+    @name( $name:expr ),
+    [ ( $( $opt:tt )* ) $( $opts:tt )* ]
+  ) => {
+    ::core::compile_error!(::core::concat!(
+      "unsupported field option `#[detail(",
+      ::core::stringify!($( $opt )*),
+      ")]`, expected `skip_serde`, `rename = \"...\"`, `hash` or `trace`"
+    ))
+  };
+}
+
+/// Internal macro used to count the serialized fields of a sub-detail,
+/// which is `0` for fields skipped with `#[detail(skip_serde)]`.
+#[cfg(feature = "serde")]
+#[macro_export]
+#[doc(hidden)]
+macro_rules! serde_field_len {
+  ( [] ) => {
+    1
+  };
+  ( [ ( skip_serde $( , $( $rest:tt )* )? ) $( $opts:tt )* ] ) => {
+    0
+  };
+  ( [ ( $key:ident $( = $value:literal )? $( , $( $rest:tt )* )? ) $( $opts:tt )* ] ) => {
+    $crate::serde_field_len!( [ ( $( $( $rest )* )? ) $( $opts )* ] )
+  };
+  ( [ () $( $opts:tt )* ] ) => {
+    $crate::serde_field_len!( [ $( $opts )* ] )
+  };
+}
+
+/// Internal macro used to expand the parameter type of a sub-error field
+/// in the constructor functions, according to its field modifier.
+#[macro_export]
+#[doc(hidden)]
+macro_rules! suberror_field_param {
+  ( $arg_type:ty ) => {
+    $arg_type
+  };
+  ( @into $arg_type:ty ) => {
+    impl ::core::convert::Into< $arg_type >
+  };
+  ( @ $modifier:ident $arg_type:ty ) => {
+    ::core::compile_error!(::core::concat!(
+      "unsupported field modifier `@", ::core::stringify!($modifier), "`, expected `@into`"
+    ))
+  };
+}
+
+/// Internal macro used to convert a constructor argument into the value
+/// of a sub-error field, according to its field modifier.
+#[macro_export]
+#[doc(hidden)]
+macro_rules! suberror_field_value {
+  ( $arg_name:ident ) => {
+    $arg_name
+  };
+  ( @into $arg_name:ident ) => {
+    ::core::convert::Into::into( $arg_name )
+  };
+  ( @ $modifier:ident $arg_name:ident ) => {
+    $arg_name
+  };
+}
+
+// define the problem types of the sub-errors only when the
+// `problem_details` feature is enabled
+#[cfg(feature = "problem_details")]
+#[macro_export]
+#[doc(hidden)]
+macro_rules! define_error_detail_problem {
+  ( @ctx[
+      @name( $name:ident )
+    ],
+    @suberrors{ $( $suberror:ident [ $( $alias:ident ),* ] { $( $options:tt )* } ),* } $(,)?
+  ) => {
+    $crate::macros::paste! [
+      impl [< $name Detail >] {
+        /// Returns the problem type given to the sub-error with `@problem`,
+        /// or a problem type with status 500 otherwise.
+        pub fn problem_type(&self) -> $crate::problem::ProblemType {
+          match self {
+            $(
+              Self::$suberror( _ ) $( | Self::$alias( _ ) )* => {
+                $crate::suberror_problem!( $( $options )* )
+              }
+            ),*
+          }
+        }
+      }
+    ];
+  }
+}
+
+#[cfg(not(feature = "problem_details"))]
+#[macro_export]
+#[doc(hidden)]
+macro_rules! define_error_detail_problem {
+  ( $( $args:tt )* ) => {};
+}
+
+/// Internal macro used to extract the `@problem` option of a sub-error
+#[macro_export]
+#[doc(hidden)]
+macro_rules! suberror_problem {
+  () => {
+    $crate::problem::ProblemType::new(500)
+  };
+  ( @problem(
+      status = $status:literal
+      $( , title = $title:literal )?
+      $( , type = $type_uri:literal )?
+      $(,)?
+    )
+    $( $rest:tt )*
+  ) => {
+    $crate::problem::ProblemType::new( $status )
+      $( .with_title( $title ) )?
+      $( .with_type( $type_uri ) )?
+  };
+  ( @problem $option_args:tt $( $rest:tt )* ) => {
+    ::core::compile_error!(::core::concat!(
+      "invalid `@problem", ::core::stringify!($option_args),
+      "` option, expected `@problem(status = ..., title = \"...\", type = \"...\")`",
+      " with the title and type being optional"
+    ))
+  };
+  ( @ $option:ident $option_args:tt $( $rest:tt )* ) => {
+    $crate::suberror_problem!( $( $rest )* )
+  };
+}
+
+// define `to_problem_details` for the main error type only when
+// the `problem_details` feature is enabled
+#[cfg(feature = "problem_details")]
+#[macro_export]
+#[doc(hidden)]
+macro_rules! define_main_problem_details {
+  ( @tracer( $tracer:ty ),
+    @generic[ $( $generic:ident )? ],
+    @name( $name:ident ) $(,)?
+  ) => {
+    impl< $( $generic )? > $name< $( $generic )? > {
+      /// Returns the RFC 7807 problem details of the error, with the
+      /// messages in the error trace as the `trace` extension member.
+      pub fn to_problem_details(&self) -> $crate::problem::ProblemDetails
+      where
+          $tracer: $crate::TraceRender,
+      {
+        $crate::problem::ProblemDetails::new(
+          self.0.problem_type(),
+          $crate::alloc::string::ToString::to_string(&self.0),
+          $crate::render::trace_entries(&self.1)
+            .into_iter()
+            .map(|entry| entry.message)
+            .collect(),
+        )
+      }
+    }
+  };
+}
+
+#[cfg(not(feature = "problem_details"))]
+#[macro_export]
+#[doc(hidden)]
+macro_rules! define_main_problem_details {
+  ( $( $args:tt )* ) => {};
+}
+
+// define `IntoResponse` for the main error type only when the
+// `axum` feature is enabled
+#[cfg(feature = "axum")]
+#[macro_export]
+#[doc(hidden)]
+macro_rules! define_main_axum {
+  ( @tracer( $tracer:ty ),
+    @generic[ $( $generic:ident )? ],
+    @name( $name:ident ) $(,)?
+  ) => {
+    impl< $( $generic )? > $crate::axum::response::IntoResponse for $name< $( $generic )? >
+    where
+        $tracer: $crate::TraceRender,
+    {
+      fn into_response(self) -> $crate::axum::response::Response {
+        $crate::web::problem_response(self.to_problem_details())
+      }
+    }
+  };
+}
+
+#[cfg(not(feature = "axum"))]
+#[macro_export]
+#[doc(hidden)]
+macro_rules! define_main_axum {
+  ( $( $args:tt )* ) => {};
+}
+
+// define `trace_serializer` and `to_json` for the main error type only
+// when the `serde_json` feature is enabled and the detail is serializable
+#[cfg(feature = "serde_json")]
+#[macro_export]
+#[doc(hidden)]
+macro_rules! define_main_json {
+  ( @tracer( $tracer:ty ),
+    @generic[ $( $generic:ident )? ],
+    @name( $name:ident ),
+    @serde[] $(,)?
+  ) => {};
+  ( @tracer( $tracer:ty ),
+    @generic[ $( $generic:ident )? ],
+    @name( $name:ident ),
+    @serde[ $( $serde:tt )+ ] $(,)?
+  ) => {
+    $crate::macros::paste![
+      impl< $( $generic )? > $name< $( $generic )? > {
+        /// Returns a serializer of the error detail together with the
+        /// messages in the error trace. See the `flex_error::json` module.
+        pub fn trace_serializer(&self) -> $crate::json::TraceSerializer<'_, [< $name Detail >], $tracer> {
+          $crate::json::TraceSerializer::new(&self.0, &self.1)
+        }
+
+        /// Exports the error detail together with the messages in the
+        /// error trace as a JSON value.
+        pub fn to_json(&self) -> $crate::serde_json::Result<$crate::serde_json::Value>
+        where
+            $tracer: $crate::TraceRender,
+        {
+          self.trace_serializer().to_json()
+        }
+
+        $crate::define_main_json_writer!( @tracer( $tracer ) );
+      }
+    ];
+  };
+}
+
+// define `serialize_chain_into` for the main error type only when the
+// `std` feature is also enabled, as it writes to an `io::Write`
+#[cfg(feature = "std")]
+#[macro_export]
+#[doc(hidden)]
+macro_rules! define_main_json_writer {
+  ( @tracer( $tracer:ty ) ) => {
+    /// Writes the error detail together with the messages in the error
+    /// trace as JSON to `writer`, serializing the messages incrementally.
+    pub fn serialize_chain_into<W: ::std::io::Write>(&self, writer: W) -> $crate::serde_json::Result<()>
+    where
+        $tracer: $crate::TraceRender,
+    {
+      self.trace_serializer().serialize_chain_into(writer)
+    }
+  };
+}
+
+#[cfg(not(feature = "std"))]
+#[macro_export]
+#[doc(hidden)]
+macro_rules! define_main_json_writer {
+  ( $( $args:tt )* ) => {};
+}
+
+#[cfg(not(feature = "serde_json"))]
+#[macro_export]
+#[doc(hidden)]
+macro_rules! define_main_json {
+  ( $( $args:tt )* ) => {};
+}
+
+// define `to_sentry_event` for the main error type only when the
+// `sentry` feature is enabled, with the error detail attached as context
+// only if it is serializable
+#[cfg(feature = "sentry")]
+#[macro_export]
+#[doc(hidden)]
+macro_rules! define_main_sentry {
+  ( @tracer( $tracer:ty ),
+    @generic[ $( $generic:ident )? ],
+    @name( $name:ident ),
+    @serde[] $(,)?
+  ) => {
+    $crate::define_main_sentry! {
+      @tracer( $tracer ),
+      @generic[ $( $generic )? ],
+      @name( $name ),
+      @detail( |_detail| ::core::option::Option::None )
+    }
+  };
+  ( @tracer( $tracer:ty ),
+    @generic[ $( $generic:ident )? ],
+    @name( $name:ident ),
+    @serde[ $( $serde:tt )+ ] $(,)?
+  ) => {
+    $crate::define_main_sentry! {
+      @tracer( $tracer ),
+      @generic[ $( $generic )? ],
+      @name( $name ),
+      @detail( |detail| $crate::serde_json::to_value(detail).ok() )
+    }
+  };
+  ( @tracer( $tracer:ty ),
+    @generic[ $( $generic:ident )? ],
+    @name( $name:ident ),
+    @detail( $detail:expr ) $(,)?
+  ) => {
+    impl< $( $generic )? > $name< $( $generic )? > {
+      /// Exports the error as a Sentry event, with the messages in the
+      /// error trace as the exception chain. See the `flex_error::sentry`
+      /// module.
+      pub fn to_sentry_event(&self) -> $crate::sentry_types::protocol::v7::Event<'static>
+      where
+          $tracer: $crate::TraceRender,
+      {
+        $crate::sentry::trace_event(
+          $crate::sentry::error_type(::core::stringify!($name), &self.0.kind()),
+          &self.1,
+          self.0.root_code(),
+          ($detail)(&self.0),
+        )
+      }
+    }
+  };
+}
+
+#[cfg(not(feature = "sentry"))]
+#[macro_export]
+#[doc(hidden)]
+macro_rules! define_main_sentry {
+  ( $( $args:tt )* ) => {};
+}
+
+// define `From<MyError> for JsValue` and `log_to_console` for the main
+// error type only when the `wasm` feature is enabled
+#[cfg(feature = "wasm")]
+#[macro_export]
+#[doc(hidden)]
+macro_rules! define_main_wasm {
+  ( @tracer( $tracer:ty ),
+    @generic[ $( $generic:ident )? ],
+    @name( $name:ident ) $(,)?
+  ) => {
+    impl< $( $generic )? > $name< $( $generic )? > {
+      fn wasm_messages(&self) -> $crate::alloc::vec::Vec<$crate::alloc::string::String>
+      where
+          for<'a> $tracer: $crate::TraceRender,
+      {
+        $crate::render::trace_entries(&self.1)
+          .into_iter()
+          .map(|entry| entry.message)
+          .collect()
+      }
+
+      /// Logs the error to `console.error`, with the messages in the
+      /// error trace in a collapsed group.
+      pub fn log_to_console(&self)
+      where
+          for<'a> $tracer: $crate::TraceRender,
+      {
+        $crate::wasm::log_error(
+          ::core::stringify!($name),
+          &$crate::alloc::format!("{:?}", self.kind()),
+          &self.wasm_messages(),
+        )
+      }
+    }
+
+    impl< $( $generic )? > ::core::convert::From<$name< $( $generic )? >>
+      for $crate::wasm_bindgen::JsValue
+    where
+        for<'a> $tracer: $crate::TraceRender,
+    {
+      fn from(err: $name< $( $generic )? >) -> Self {
+        $crate::wasm::to_js_error(
+          ::core::stringify!($name),
+          &$crate::alloc::format!("{:?}", err.kind()),
+          &err.wasm_messages(),
+        )
+      }
+    }
+  };
+}
+
+#[cfg(not(feature = "wasm"))]
+#[macro_export]
+#[doc(hidden)]
+macro_rules! define_main_wasm {
+  ( $( $args:tt )* ) => {};
+}
+
+// define `incident_id` for the main error type, which always keeps its
+// metadata with the `incidents` feature, only when the feature is enabled
+#[cfg(feature = "incidents")]
+#[macro_export]
+#[doc(hidden)]
+macro_rules! define_main_incident {
+  ( @tracer( $tracer:ty ),
+    @generic[ $( $generic:ident )? ],
+    @name( $name:ident ) $(,)?
+  ) => {
+    impl< $( $generic )? > $name< $( $generic )? > {
+      /// Returns the incident ID of the error, which can be used to look up
+      /// the snapshot of the error trace stored in the incident registry
+      /// when the error was constructed, with `flex_error::lookup`.
+      pub fn incident_id(&self) -> ::core::option::Option<$crate::incident::IncidentId> {
+        self.2.incident_id()
+      }
+    }
+  };
+}
+
+#[cfg(not(feature = "incidents"))]
+#[macro_export]
+#[doc(hidden)]
+macro_rules! define_main_incident {
+  ( $( $args:tt )* ) => {};
+}
+
+// store the snapshot of the error trace of a newly constructed error in
+// the incident registry only when the `incidents` feature is enabled
+#[cfg(feature = "incidents")]
+#[macro_export]
+#[doc(hidden)]
+macro_rules! register_incident {
+  ( $metadata:expr, $trace:expr ) => {{
+    #[allow(unused_imports)]
+    use $crate::incident::{RegisterIncident, SkipIncident};
+    (&&$crate::incident::IncidentTrace($metadata, $trace)).register_incident();
+  }};
+}
+
+#[cfg(not(feature = "incidents"))]
+#[macro_export]
+#[doc(hidden)]
+macro_rules! register_incident {
+  ( $( $args:tt )* ) => {};
+}
+
+// define `backtrace` for the main error type with the `@metadata` option
+// only when the `backtrace` feature is enabled
+#[cfg(feature = "backtrace")]
+#[macro_export]
+#[doc(hidden)]
+macro_rules! define_main_backtrace {
+  ( @generic[ $( $generic:ident )? ],
+    @name( $name:ident ) $(,)?
+  ) => {
+    impl< $( $generic )? > $name< $( $generic )? > {
+      /// Returns the backtrace captured when the error, or the innermost
+      /// error with the `@metadata` option wrapped by it, was created.
+      pub fn backtrace(&self) -> ::core::option::Option<&::std::backtrace::Backtrace> {
+        self.2.backtrace()
+      }
+    }
+  };
+}
+
+#[cfg(not(feature = "backtrace"))]
+#[macro_export]
+#[doc(hidden)]
+macro_rules! define_main_backtrace {
+  ( $( $args:tt )* ) => {};
+}
+
+// define the rate-limited logging method only when the `log` or
+// `tracing` feature is enabled
+#[cfg(any(feature = "log", feature = "tracing"))]
+#[macro_export]
+#[doc(hidden)]
+macro_rules! define_main_rate_limit {
+  ( @tracer( $tracer:ty ),
+    @generic[ $( $generic:ident )? ],
+    @name( $name:ident ) $(,)?
+  ) => {
+    impl< $( $generic )? > $name< $( $generic )? > {
+      /// Logs the error with its full trace at most once per `interval` for
+      /// the given key and sub-error, and with a short message counting the
+      /// repetitions otherwise. See `flex_error::rate_limit`.
+      pub fn log_rate_limited(&self, key: &str, interval: ::core::time::Duration)
+      where
+          $tracer: ::core::fmt::Debug + ::core::fmt::Display,
+      {
+        $crate::rate_limit::log_rate_limited(
+          key,
+          &$crate::rate_limit::error_type(::core::stringify!($name), &self.kind()),
+          interval,
+          self,
+          self.detail(),
+        )
+      }
+    }
+  };
+}
+
+#[cfg(not(any(feature = "log", feature = "tracing")))]
+#[macro_export]
+#[doc(hidden)]
+macro_rules! define_main_rate_limit {
+  ( $( $args:tt )* ) => {};
+}
+
+/// Internal macro used to extract the `@transient` or `@fatal` option
+/// of a sub-error, of which at most one can be given
+#[macro_export]
+#[doc(hidden)]
+macro_rules! suberror_class {
+  () => {
+    $crate::ErrorClass::Unclassified
+  };
+  ( @transient() $( $rest:tt )* ) => {
+    $crate::suberror_class!( @found( Transient ) $( $rest )* )
+  };
+  ( @fatal() $( $rest:tt )* ) => {
+    $crate::suberror_class!( @found( Fatal ) $( $rest )* )
+  };
+  ( @found( $class:ident ) ) => {
+    $crate::ErrorClass::$class
+  };
+  ( @found( $class:ident ) @transient() $( $rest:tt )* ) => {
+    $crate::suberror_class!( @conflict )
+  };
+  ( @found( $class:ident ) @fatal() $( $rest:tt )* ) => {
+    $crate::suberror_class!( @conflict )
+  };
+  ( @found( $class:ident ) @ $option:ident $option_args:tt $( $rest:tt )* ) => {
+    $crate::suberror_class!( @found( $class ) $( $rest )* )
+  };
+  ( @conflict ) => {
+    ::core::compile_error!(
+      "at most one of the `@transient` and `@fatal` options can be given to a sub-error"
+    )
+  };
+  ( @ $option:ident $option_args:tt $( $rest:tt )* ) => {
+    $crate::suberror_class!( $( $rest )* )
+  };
+}
+
+/// Internal macro used to extract the `@help` option of a sub-error
+#[macro_export]
+#[doc(hidden)]
+macro_rules! suberror_help {
+  () => {
+    ::core::option::Option::None
+  };
+  ( @help( $help:literal ) $( $rest:tt )* ) => {
+    ::core::option::Option::Some( $help )
+  };
+  ( @ $option:ident $option_args:tt $( $rest:tt )* ) => {
+    $crate::suberror_help!( $( $rest )* )
+  };
+}
+
+/// Internal macro used to extract the `@exit_code` option of a sub-error
+#[macro_export]
+#[doc(hidden)]
+macro_rules! suberror_exit_code {
+  () => {
+    ::core::option::Option::None
+  };
+  ( @exit_code( $code:expr ) $( $rest:tt )* ) => {
+    ::core::option::Option::Some::<i32>( $code )
+  };
+  ( @ $option:ident $option_args:tt $( $rest:tt )* ) => {
+    $crate::suberror_exit_code!( $( $rest )* )
+  };
+}
+
+/// Internal macro used to extract the `@event` option of a sub-error, and
+/// to build the domain event from the fields of its sub-detail.
+#[macro_export]
+#[doc(hidden)]
+macro_rules! suberror_event {
+  ( @event( $sub:ident ) ) => {{
+    let _ = $sub;
+    ::core::option::Option::None
+  }};
+  ( @event( $sub:ident )
+    @event( $( $path:ident )::+ )
+    $( $rest:tt )*
+  ) => {{
+    let _ = $sub;
+    ::core::option::Option::Some( $( $path )::+ )
+  }};
+  ( @event( $sub:ident )
+    @event( $( $path:ident )::+ ( $( $detail_field:ident ),* $(,)? ) )
+    $( $rest:tt )*
+  ) => {
+    ::core::option::Option::Some( $( $path )::+ (
+      $( ::core::clone::Clone::clone( &$sub.$detail_field ) ),*
+    ) )
+  };
+  ( @event( $sub:ident )
+    @event( $( $path:ident )::+ { $( $event_field:ident $( : $detail_field:ident )? ),* $(,)? } )
+    $( $rest:tt )*
+  ) => {
+    ::core::option::Option::Some( $( $path )::+ {
+      $(
+        $event_field: $crate::suberror_event!( @field( $sub ) $event_field $( $detail_field )? )
+      ),*
+    } )
+  };
+  ( @event( $sub:ident ) @ $option:ident $option_args:tt $( $rest:tt )* ) => {
+    $crate::suberror_event!( @event( $sub ) $( $rest )* )
+  };
+  ( @field( $sub:ident ) $event_field:ident ) => {
+    ::core::clone::Clone::clone( &$sub.$event_field )
+  };
+  ( @field( $sub:ident ) $event_field:ident $detail_field:ident ) => {
+    ::core::clone::Clone::clone( &$sub.$detail_field )
+  };
+  ( @check ) => {};
+  ( @check @event $option_args:tt $( $rest:tt )* ) => {
+    ::core::compile_error!(
+      "the `@event` option of a sub-error requires the `@events` option of the error type"
+    );
+  };
+  ( @check @ $option:ident $option_args:tt $( $rest:tt )* ) => {
+    $crate::suberror_event!( @check $( $rest )* );
+  };
+}
+
+#[macro_export]
+#[doc(hidden)]
+macro_rules! define_suberrors {
+  ( @tracer($tracer:ty),
+    @impl_tracer($impl_tracer:ty),
+    @detail_tracer $detail_tracer:tt,
+    @generic[ $( $generic:ident )? ],
+    @attr[ $( $attr:meta ),* ],
+    @name($name:ident),
+    {} $(,)?
+  ) => { };
+  ( @tracer($tracer:ty),
+    @impl_tracer($impl_tracer:ty),
+    @detail_tracer $detail_tracer:tt,
+    @generic[ $( $generic:ident )? ],
+    @attr[ $( $attr:meta ),* ],
+    @name($name:ident),
+    {
+      $( #[$sub_attr:meta] )*
+      $suberror:ident $( as $alias:ident )* $( = $numeric_code:literal )?
+        $( { $( $( #[detail $field_opts:tt] )* $arg_name:ident : $( @ $modifier:ident )? $arg_type:ty ),* $(,)? } )?
+        $( [ $source:ty $( , $source2:ty )? ] )?
+        $( @ $option:ident $( ( $( $option_arg:tt )* ) )? )*
+        | $formatter_arg:pat | $formatter:expr
+
+      $( , $($tail:tt)* )?
+    }
+  ) => {
+    $crate::macros::paste![
+      $crate::define_suberror! {
+        @tracer( $tracer ),
+        @attr[ $( $attr ),* ],
+        @sub_attr[ $( $sub_attr ),* ],
+        @name( $name ),
+        @suberror( $suberror ),
+        @args( $( $( $arg_name : $arg_type ),* )? )
+        $( @source[ $source $( , $source2 )? ] )?
+      }
+
+      impl ::core::fmt::Display for [< $suberror Subdetail >] {
+        fn fmt(&self, f: &mut ::core::fmt::Formatter<'_>) -> ::core::fmt::Result {
+          use ::core::format_args;
+          let $formatter_arg = self;
+          $crate::render::pad_display(f, &$formatter)
+        }
+      }
+
+      $crate::define_subdetail_trace_display! {
+        @suberror( $suberror ),
+        @options[ $( @ $option ( $( $( $option_arg )* )? ) )* ]
+      }
+
+      $crate::define_subdetail_static_message! {
+        @suberror( $suberror ),
+        @formatter( | $formatter_arg | $formatter ),
+        @static_msg[],
+        @options[ $( @ $option ( $( $( $option_arg )* )? ) )* ]
+      }
+
+      impl< $( $generic )? > $name< $( $generic )? >
+      where
+        $impl_tracer: $crate::ErrorMessageTracer,
+      {
+        $crate::define_error_constructors! {
+          @options[ $( @ $option ( $( $( $option_arg )* )? ) )* ],
+          @attach[],
+          @static_msg[],
+          @vis[ pub ],
+          @tracer( $impl_tracer ),
+          @detail_tracer $detail_tracer,
+          @name( $name ),
+          @suberror( $suberror ),
+          @variants[ $suberror $( , $alias )* ],
+          @args( $( $( $arg_name : $( @ $modifier )? $arg_type ),* )? )
+          $( @source[ $source $( , $source2 )? ] )?
+        }
+      }
+    ];
+
+    $crate::define_suberrors! {
+      @tracer($tracer),
+      @impl_tracer($impl_tracer),
+      @detail_tracer $detail_tracer,
+      @generic[ $( $generic )? ],
+      @attr[ $( $attr ),* ],
+      @name($name),
+      { $( $( $tail )* )? }
+    }
+  };
+}
+
+/// Internal macro used to collect the field types of all sub-errors into
+/// `where` bounds, together with the field names of each sub-detail, and
+/// pass them to the continuation macro. The detail of a recursive `Self`
+/// or `BoxSource` source is not collected, so that the solver does not have
+/// to go through the detail type itself, which may wrap this error type.
+#[macro_export]
+#[doc(hidden)]
+macro_rules! with_suberror_bounds {
+  ( @cont($cont:path),
+    @ctx[ $($args:tt)* ],
+    @tracer( $tracer:ty ),
+    @bounds $bounds:tt,
+    @subdetails $subdetails:tt,
+    @suberrors{} $(,)?
+  ) => {
+    $cont! {
+      @ctx[ $( $args )* ],
+      @bounds $bounds,
+      @subdetails $subdetails
+    }
+  };
+  ( @cont($cont:path),
+    @ctx $ctx:tt,
+    @tracer( $tracer:ty ),
+    @bounds[ $( $bound:ty, )* ],
+    @subdetails[ $( $subdetail:tt )* ],
+    @suberrors{
+      $( #[$sub_attr:meta] )*
+      $suberror:ident $( as $alias:ident )* $( = $numeric_code:literal )?
+        $( { $( $( #[detail $field_opts:tt] )* $arg_name:ident : $( @ $modifier:ident )? $arg_type:ty ),* $(,)? } )?
+        [ Self ]
+        $( @ $option:ident $( ( $( $option_arg:tt )* ) )? )*
+        | $formatter_arg:pat | $formatter:expr
+      $( , $($tail:tt)* )?
+    }
+  ) => {
+    $crate::with_suberror_bounds! {
+      @cont( $cont ),
+      @ctx $ctx,
+      @tracer( $tracer ),
+      @bounds[ $( $bound, )* $( $( $arg_type, )* )? ],
+      @subdetails[ $( $subdetail )* ( $suberror [ $( $alias ),* ]; $( $( $arg_name, )* )? source, ) ],
+      @suberrors{ $( $( $tail )* )? }
+    }
+  };
+  ( @cont($cont:path),
+    @ctx $ctx:tt,
+    @tracer( $tracer:ty ),
+    @bounds[ $( $bound:ty, )* ],
+    @subdetails[ $( $subdetail:tt )* ],
+    @suberrors{
+      $( #[$sub_attr:meta] )*
+      $suberror:ident $( as $alias:ident )* $( = $numeric_code:literal )?
+        $( { $( $( #[detail $field_opts:tt] )* $arg_name:ident : $( @ $modifier:ident )? $arg_type:ty ),* $(,)? } )?
+        [ $( $( :: )? flex_error :: )? BoxSource < $boxed:ty > ]
+        $( @ $option:ident $( ( $( $option_arg:tt )* ) )? )*
+        | $formatter_arg:pat | $formatter:expr
+      $( , $($tail:tt)* )?
+    }
+  ) => {
+    $crate::with_suberror_bounds! {
+      @cont( $cont ),
+      @ctx $ctx,
+      @tracer( $tracer ),
+      @bounds[ $( $bound, )* $( $( $arg_type, )* )? ],
+      @subdetails[ $( $subdetail )* ( $suberror [ $( $alias ),* ]; $( $( $arg_name, )* )? source, ) ],
+      @suberrors{ $( $( $tail )* )? }
+    }
+  };
+  ( @cont($cont:path),
+    @ctx $ctx:tt,
+    @tracer( $tracer:ty ),
+    @bounds[ $( $bound:ty, )* ],
+    @subdetails[ $( $subdetail:tt )* ],
+    @suberrors{
+      $( #[$sub_attr:meta] )*
+      $suberror:ident $( as $alias:ident )* $( = $numeric_code:literal )?
+        $( { $( $( #[detail $field_opts:tt] )* $arg_name:ident : $( @ $modifier:ident )? $arg_type:ty ),* $(,)? } )?
+        [ $source:ty, $source2:ty ]
+        $( @ $option:ident $( ( $( $option_arg:tt )* ) )? )*
+        | $formatter_arg:pat | $formatter:expr
+      $( , $($tail:tt)* )?
+    }
+  ) => {
+    $crate::with_suberror_bounds! {
+      @cont( $cont ),
+      @ctx $ctx,
+      @tracer( $tracer ),
+      @bounds[
+        $( $bound, )*
+        $( $( $arg_type, )* )?
+        $crate::AsErrorDetail<$source, $tracer>,
+        $crate::AsErrorDetail<$source2, $tracer>,
+      ],
+      @subdetails[ $( $subdetail )* ( $suberror [ $( $alias ),* ]; $( $( $arg_name, )* )? source1, source2, ) ],
+      @suberrors{ $( $( $tail )* )? }
+    }
+  };
+  ( @cont($cont:path),
+    @ctx $ctx:tt,
+    @tracer( $tracer:ty ),
+    @bounds[ $( $bound:ty, )* ],
+    @subdetails[ $( $subdetail:tt )* ],
+    @suberrors{
+      $( #[$sub_attr:meta] )*
+      $suberror:ident $( as $alias:ident )* $( = $numeric_code:literal )?
+        $( { $( $( #[detail $field_opts:tt] )* $arg_name:ident : $( @ $modifier:ident )? $arg_type:ty ),* $(,)? } )?
+        [ $source:ty ]
+        $( @ $option:ident $( ( $( $option_arg:tt )* ) )? )*
+        | $formatter_arg:pat | $formatter:expr
+      $( , $($tail:tt)* )?
+    }
+  ) => {
+    $crate::with_suberror_bounds! {
+      @cont( $cont ),
+      @ctx $ctx,
+      @tracer( $tracer ),
+      @bounds[
+        $( $bound, )*
+        $( $( $arg_type, )* )?
+        $crate::AsErrorDetail<$source, $tracer>,
+      ],
+      @subdetails[ $( $subdetail )* ( $suberror [ $( $alias ),* ]; $( $( $arg_name, )* )? source, ) ],
+      @suberrors{ $( $( $tail )* )? }
+    }
+  };
+  ( @cont($cont:path),
+    @ctx $ctx:tt,
+    @tracer( $tracer:ty ),
+    @bounds[ $( $bound:ty, )* ],
+    @subdetails[ $( $subdetail:tt )* ],
+    @suberrors{
+      $( #[$sub_attr:meta] )*
+      $suberror:ident $( as $alias:ident )* $( = $numeric_code:literal )?
+        $( { $( $( #[detail $field_opts:tt] )* $arg_name:ident : $( @ $modifier:ident )? $arg_type:ty ),* $(,)? } )?
+        $( @ $option:ident $( ( $( $option_arg:tt )* ) )? )*
+        | $formatter_arg:pat | $formatter:expr
+      $( , $($tail:tt)* )?
+    }
+  ) => {
+    $crate::with_suberror_bounds! {
+      @cont( $cont ),
+      @ctx $ctx,
+      @tracer( $tracer ),
+      @bounds[ $( $bound, )* $( $( $arg_type, )* )? ],
+      @subdetails[ $( $subdetail )* ( $suberror [ $( $alias ),* ]; $( $( $arg_name, )* )? ) ],
+      @suberrors{ $( $( $tail )* )? }
+    }
+  };
+}
+
+// define `Arbitrary` for the error detail only when the `arbitrary`
+// feature is enabled. The field types of all sub-errors are collected
+// into the `where` bounds, which are generic over the lifetime `'a`.
+// So error types with fields that do not implement `Arbitrary` are
+// simply left without an implementation.
+#[cfg(feature = "arbitrary")]
+#[macro_export]
+#[doc(hidden)]
+macro_rules! define_error_detail_arbitrary {
+  ( @tracer( $tracer:ty ),
+    @name( $name:ident ),
+    @suberrors $suberrors:tt $(,)?
+  ) => {
+    $crate::with_suberror_bounds! {
+      @cont( $crate::define_error_detail_arbitrary ),
+      @ctx[ @name( $name ) ],
+      @tracer( $tracer ),
+      @bounds[],
+      @subdetails[],
+      @suberrors $suberrors
+    }
+  };
+  ( @ctx[ @name( $name:ident ) ],
+    @bounds $bounds:tt,
+    @subdetails[ $( ( $suberror:ident [ $( $alias:ident ),* ]; $( $field:ident, )* ) )* ] $(,)?
+  ) => {
+    $crate::define_error_detail_arbitrary! {
+      @detail( $name ),
+      @bounds $bounds,
+      @variants[ $( $suberror $( , $alias )* ),* ]
+    }
+
+    $(
+      $crate::define_error_detail_arbitrary! {
+        @subdetail( $suberror ),
+        @bounds $bounds,
+        @fields[ $( $field ),* ]
+      }
+    )*
+  };
+  ( @detail( $name:ident ),
+    @bounds[ $( $bound:ty, )* ],
+    @variants[ $( $variant:ident ),* ] $(,)?
+  ) => {
+    $crate::macros::paste! [
+      impl<'a> $crate::arbitrary::Arbitrary<'a> for [< $name Detail >]
+      where
+        $( $bound: $crate::arbitrary::Arbitrary<'a>, )*
+      {
+        fn arbitrary(u: &mut $crate::arbitrary::Unstructured<'a>)
+          -> $crate::arbitrary::Result<Self>
+        {
+          let variants: &[
+            fn(&mut $crate::arbitrary::Unstructured<'a>) -> $crate::arbitrary::Result<Self>
+          ] = &[
+            $(
+              | u | {
+                ::core::result::Result::Ok(
+                  Self::$variant( $crate::arbitrary::Arbitrary::arbitrary(u)? ))
+              }
+            ),*
+          ];
+
+          let variant = u.choose(variants)?;
+          variant(u)
+        }
+      }
+    ];
+  };
+  ( @subdetail( $suberror:ident ),
+    @bounds[ $( $bound:ty, )* ],
+    @fields[ $( $field:ident ),* ] $(,)?
+  ) => {
+    $crate::macros::paste! [
+      impl<'a> $crate::arbitrary::Arbitrary<'a> for [< $suberror Subdetail >]
+      where
+        $( $bound: $crate::arbitrary::Arbitrary<'a>, )*
+      {
+        fn arbitrary(u: &mut $crate::arbitrary::Unstructured<'a>)
+          -> $crate::arbitrary::Result<Self>
+        {
+          let _ = &u;
+          ::core::result::Result::Ok( [< $suberror Subdetail >] {
+            $( $field: $crate::arbitrary::Arbitrary::arbitrary(u)?, )*
+          })
+        }
+      }
+    ];
+  };
+}
+
+#[cfg(not(feature = "arbitrary"))]
+#[macro_export]
+#[doc(hidden)]
+macro_rules! define_error_detail_arbitrary {
+  ( $( $args:tt )* ) => {};
+}
+
+// define `Valuable` for the error detail only when the `valuable`
+// feature is enabled, with the same `where` bounds as for `Arbitrary`.
+// The `for<'a>` quantifier keeps the bounds on concrete field types
+// from being rejected as trivially false, so error types with fields
+// that do not implement `Valuable` are left without an implementation.
+#[cfg(feature = "valuable")]
+#[macro_export]
+#[doc(hidden)]
+macro_rules! define_error_detail_valuable {
+  ( @tracer( $tracer:ty ),
+    @name( $name:ident ),
+    @suberrors $suberrors:tt $(,)?
+  ) => {
+    $crate::with_suberror_bounds! {
+      @cont( $crate::define_error_detail_valuable ),
+      @ctx[ @name( $name ) ],
+      @tracer( $tracer ),
+      @bounds[],
+      @subdetails[],
+      @suberrors $suberrors
+    }
+  };
+  ( @ctx[ @name( $name:ident ) ],
+    @bounds $bounds:tt,
+    @subdetails[ $( ( $suberror:ident [ $( $alias:ident ),* ]; $( $field:ident, )* ) )* ] $(,)?
+  ) => {
+    $crate::define_error_detail_valuable! {
+      @detail( $name ),
+      @bounds $bounds,
+      @suberrors[ $( $suberror [ $( $alias ),* ] ),* ]
+    }
+
+    $(
+      $crate::define_error_detail_valuable! {
+        @subdetail( $suberror ),
+        @bounds $bounds,
+        @fields[ $( $field ),* ]
+      }
+    )*
+  };
+  ( @detail( $name:ident ),
+    @bounds[ $( $bound:ty, )* ],
+    @suberrors[ $( $suberror:ident [ $( $alias:ident ),* ] ),* ] $(,)?
+  ) => {
+    $crate::macros::paste! [
+      impl [< $name Detail >] {
+        // In the same order as the variants of the kind enum
+        const VALUABLE_VARIANTS: &'static [ $crate::valuable::VariantDef<'static> ] = &[
+          $(
+            $crate::valuable::VariantDef::new(
+              ::core::stringify!( $suberror ),
+              $crate::valuable::Fields::Named( [< $suberror Subdetail >]::VALUABLE_FIELDS ),
+            ),
+            $(
+              $crate::valuable::VariantDef::new(
+                ::core::stringify!( $alias ),
+                $crate::valuable::Fields::Named( [< $suberror Subdetail >]::VALUABLE_FIELDS ),
+              ),
+            )*
+          )*
+        ];
+      }
+
+      impl $crate::valuable::Valuable for [< $name Detail >]
+      where
+        $( for<'a> $bound: $crate::valuable::Valuable, )*
+      {
+        fn as_value(&self) -> $crate::valuable::Value<'_> {
+          $crate::valuable::Value::Enumerable(self)
+        }
+
+        fn visit(&self, visit: &mut dyn $crate::valuable::Visit) {
+          match self {
+            $(
+              Self::$suberror( sub ) $( | Self::$alias( sub ) )* => {
+                $crate::valuable::Valuable::visit(sub, visit)
+              }
+            )*
+          }
+        }
+      }
+
+      impl $crate::valuable::Enumerable for [< $name Detail >]
+      where
+        $( for<'a> $bound: $crate::valuable::Valuable, )*
+      {
+        fn definition(&self) -> $crate::valuable::EnumDef<'_> {
+          $crate::valuable::EnumDef::new_static(
+            ::core::concat!( ::core::stringify!( $name ), "Detail" ),
+            Self::VALUABLE_VARIANTS,
+          )
+        }
+
+        fn variant(&self) -> $crate::valuable::Variant<'_> {
+          $crate::valuable::Variant::Static(
+            &Self::VALUABLE_VARIANTS[ self.kind() as usize ])
+        }
+      }
+    ];
+  };
+  ( @subdetail( $suberror:ident ),
+    @bounds[ $( $bound:ty, )* ],
+    @fields[ $( $field:ident ),* ] $(,)?
+  ) => {
+    $crate::macros::paste! [
+      impl [< $suberror Subdetail >] {
+        const VALUABLE_FIELDS: &'static [ $crate::valuable::NamedField<'static> ] = &[
+          $( $crate::valuable::NamedField::new( ::core::stringify!( $field ) ) ),*
+        ];
+      }
+
+      impl $crate::valuable::Valuable for [< $suberror Subdetail >]
+      where
+        $( for<'a> $bound: $crate::valuable::Valuable, )*
+      {
+        fn as_value(&self) -> $crate::valuable::Value<'_> {
+          $crate::valuable::Value::Structable(self)
+        }
+
+        fn visit(&self, visit: &mut dyn $crate::valuable::Visit) {
+          visit.visit_named_fields(&$crate::valuable::NamedValues::new(
+            Self::VALUABLE_FIELDS,
+            &[ $( $crate::valuable::Valuable::as_value(&self.$field) ),* ],
+          ));
+        }
+      }
+
+      impl $crate::valuable::Structable for [< $suberror Subdetail >]
+      where
+        $( for<'a> $bound: $crate::valuable::Valuable, )*
+      {
+        fn definition(&self) -> $crate::valuable::StructDef<'_> {
+          $crate::valuable::StructDef::new_static(
+            ::core::concat!( ::core::stringify!( $suberror ), "Subdetail" ),
+            $crate::valuable::Fields::Named( Self::VALUABLE_FIELDS ),
+          )
+        }
+      }
+    ];
+  };
+}
+
+#[cfg(not(feature = "valuable"))]
+#[macro_export]
+#[doc(hidden)]
+macro_rules! define_error_detail_valuable {
+  ( $( $args:tt )* ) => {};
+}
+
+// define `Valuable` for the main error type only when the `valuable`
+// feature is enabled, as a struct with the error message and the
+// expanded error detail.
+#[cfg(feature = "valuable")]
+#[macro_export]
+#[doc(hidden)]
+macro_rules! define_main_valuable {
+  ( @tracer( $tracer:ty ),
+    @generic[ $( $generic:ident )? ],
+    @name( $name:ident ) $(,)?
+  ) => {
+    $crate::macros::paste! [
+      impl< $( $generic )? > $name< $( $generic )? > {
+        const VALUABLE_FIELDS: &'static [ $crate::valuable::NamedField<'static> ] = &[
+          $crate::valuable::NamedField::new("message"),
+          $crate::valuable::NamedField::new("detail"),
+        ];
+      }
+
+      impl< $( $generic )? > $crate::valuable::Valuable for $name< $( $generic )? >
+      where
+        for<'a> [< $name Detail >]: $crate::valuable::Valuable,
+        $tracer: $crate::TraceRender,
+      {
+        fn as_value(&self) -> $crate::valuable::Value<'_> {
+          $crate::valuable::Value::Structable(self)
+        }
+
+        fn visit(&self, visit: &mut dyn $crate::valuable::Visit) {
+          let message = $crate::alloc::string::ToString::to_string(
+            &$crate::render::Joined(&self.1));
+          visit.visit_named_fields(&$crate::valuable::NamedValues::new(
+            Self::VALUABLE_FIELDS,
+            &[
+              $crate::valuable::Value::String(&message),
+              $crate::valuable::Valuable::as_value(&self.0),
+            ],
+          ));
+        }
+      }
+
+      impl< $( $generic )? > $crate::valuable::Structable for $name< $( $generic )? >
+      where
+        for<'a> [< $name Detail >]: $crate::valuable::Valuable,
+        $tracer: $crate::TraceRender,
+      {
+        fn definition(&self) -> $crate::valuable::StructDef<'_> {
+          $crate::valuable::StructDef::new_static(
+            ::core::stringify!( $name ),
+            $crate::valuable::Fields::Named( Self::VALUABLE_FIELDS ),
+          )
+        }
+      }
+    ];
+  };
+}
+
+#[cfg(not(feature = "valuable"))]
+#[macro_export]
+#[doc(hidden)]
+macro_rules! define_main_valuable {
+  ( $( $args:tt )* ) => {};
+}
+
+// define `defmt::Format` for the error detail only when the `defmt`
+// feature is enabled, with the same `for<'a>` bounds as for `Valuable`,
+// so that error types with fields that do not implement `Format` are
+// left without an implementation.
+#[cfg(feature = "defmt")]
+#[macro_export]
+#[doc(hidden)]
+macro_rules! define_error_detail_defmt {
+  ( @tracer( $tracer:ty ),
+    @name( $name:ident ),
+    @suberrors $suberrors:tt $(,)?
+  ) => {
+    $crate::with_suberror_bounds! {
+      @cont( $crate::define_error_detail_defmt ),
+      @ctx[ @name( $name ) ],
+      @tracer( $tracer ),
+      @bounds[],
+      @subdetails[],
+      @suberrors $suberrors
+    }
+  };
+  ( @ctx[ @name( $name:ident ) ],
+    @bounds $bounds:tt,
+    @subdetails[ $( ( $suberror:ident [ $( $alias:ident ),* ]; $( $field:ident, )* ) )* ] $(,)?
+  ) => {
+    $crate::define_error_detail_defmt! {
+      @detail( $name ),
+      @bounds $bounds,
+      @suberrors[ $( $suberror [ $( $alias ),* ] ),* ]
+    }
+
+    $(
+      $crate::define_error_detail_defmt! {
+        @subdetail( $suberror ),
+        @bounds $bounds,
+        @fields[ $( $field, )* ]
+      }
+    )*
+  };
+  ( @detail( $name:ident ),
+    @bounds[ $( $bound:ty, )* ],
+    @suberrors[ $( $suberror:ident [ $( $alias:ident ),* ] ),* ] $(,)?
+  ) => {
+    $crate::macros::paste! [
+      impl $crate::defmt::Format for [< $name Detail >]
+      where
+        $( for<'a> $bound: $crate::defmt::Format, )*
+      {
+        fn format(&self, f: $crate::defmt::Formatter<'_>) {
+          match self {
+            $(
+              Self::$suberror( sub ) $( | Self::$alias( sub ) )* => {
+                $crate::defmt::Format::format(sub, f)
+              }
+            )*
+          }
+        }
+      }
+    ];
+  };
+  ( @subdetail( $suberror:ident ),
+    @bounds[ $( $bound:ty, )* ],
+    @fields $fields:tt $(,)?
+  ) => {
+    $crate::macros::paste! [
+      impl $crate::defmt::Format for [< $suberror Subdetail >]
+      where
+        $( for<'a> $bound: $crate::defmt::Format, )*
+      {
+        fn format(&self, f: $crate::defmt::Formatter<'_>) {
+          $crate::define_error_detail_defmt! {
+            @format( self, f, [< $suberror Subdetail >] ),
+            @fields $fields
+          }
+        }
+      }
+    ];
+  };
+  ( @format( $self:ident, $f:ident, $subdetail:ident ),
+    @fields[] $(,)?
+  ) => {
+    $crate::defmt::Format::format(::core::stringify!( $subdetail ), $f)
+  };
+  ( @format( $self:ident, $f:ident, $subdetail:ident ),
+    @fields[ $( $field:ident, )+ ] $(,)?
+  ) => {
+    $crate::defmt::Format::format(
+      &$crate::render::DefmtStruct {
+        name: ::core::stringify!( $subdetail ),
+        fields: $crate::define_error_detail_defmt!(
+          @fields( $self, "" ),
+          $( $field, )+
+        ),
+      },
+      $f,
+    )
+  };
+  ( @fields( $self:ident, $separator:expr ), ) => {
+    $crate::render::DefmtFieldsEnd
+  };
+  ( @fields( $self:ident, $separator:expr ),
+    $field:ident, $( $rest:ident, )*
+  ) => {
+    $crate::render::DefmtField {
+      separator: $separator,
+      name: ::core::stringify!( $field ),
+      value: &$self.$field,
+      rest: $crate::define_error_detail_defmt!(
+        @fields( $self, ", " ),
+        $( $rest, )*
+      ),
+    }
+  };
+}
+
+#[cfg(not(feature = "defmt"))]
+#[macro_export]
+#[doc(hidden)]
+macro_rules! define_error_detail_defmt {
+  ( $( $args:tt )* ) => {};
+}
+
+// define `defmt::Format` for the main error type only when the `defmt`
+// feature is enabled, as the error detail followed by the error trace.
+#[cfg(feature = "defmt")]
+#[macro_export]
+#[doc(hidden)]
+macro_rules! define_main_defmt {
+  ( @tracer( $tracer:ty ),
+    @generic[ $( $generic:ident )? ],
+    @name( $name:ident ) $(,)?
+  ) => {
+    $crate::macros::paste! [
+      impl< $( $generic )? > $crate::defmt::Format for $name< $( $generic )? >
+      where
+        for<'a> [< $name Detail >]: $crate::defmt::Format,
+        for<'a> $tracer: $crate::defmt::Format,
+      {
+        fn format(&self, f: $crate::defmt::Formatter<'_>) {
+          $crate::defmt::Format::format(
+            &$crate::render::DefmtError { detail: &self.0, trace: &self.1 },
+            f,
+          )
+        }
+      }
+    ];
+  };
+}
+
+#[cfg(not(feature = "defmt"))]
+#[macro_export]
+#[doc(hidden)]
+macro_rules! define_main_defmt {
+  ( $( $args:tt )* ) => {};
+}
+
+// record the wrapping relationships between error types only
+// when the `error_graph` feature is enabled
+#[cfg(feature = "error_graph")]
+#[macro_export]
+#[doc(hidden)]
+macro_rules! define_error_edge {
+  ( @name( $name:ident ),
+    @suberror( $suberror:ident ),
+    @source( $source:ty ) $(,)?
+  ) => {
+    $crate::macros::paste! [
+      $crate::record_error_edge! {
+        @name( $name ),
+        @constructor( ::core::stringify!( [< $suberror:snake >] ) ),
+        @source( $source )
+      }
+    ];
+  };
+}
+
+#[cfg(not(feature = "error_graph"))]
+#[macro_export]
+#[doc(hidden)]
+macro_rules! define_error_edge {
+  ( @name( $name:ident ),
+    @suberror( $suberror:ident ),
+    @source( $source:ty ) $(,)?
+  ) => {};
+}
+
+// count the constructed errors only when the `error_stats` feature is enabled
+#[cfg(feature = "error_stats")]
+#[macro_export]
+#[doc(hidden)]
+macro_rules! define_error_stat {
+  ( @name( $name:ident ),
+    @suberror( $suberror:ident ) $(,)?
+  ) => {
+    $crate::record_error_stat! {
+      @name( $name ),
+      @variant( ::core::stringify!( $suberror ) )
+    }
+  };
+}
+
+#[cfg(not(feature = "error_stats"))]
+#[macro_export]
+#[doc(hidden)]
+macro_rules! define_error_stat {
+  ( @name( $name:ident ),
+    @suberror( $suberror:ident ) $(,)?
+  ) => {};
+}
+
+/// Internal macro used by the error constructors to return the error detail
+/// forced with [`inject::force_next`](crate::inject::force_next) for the
+/// module of the error type, instead of constructing the requested error.
+#[cfg(feature = "error_injection")]
+#[macro_export]
+#[doc(hidden)]
+macro_rules! define_error_injection {
+  ( @name( $name:ident ),
+    @tracer( $tracer:ty ) $(,)?
+  ) => {
+    $crate::macros::paste! [
+      if let ::core::option::Option::Some(detail) =
+        $crate::inject::take::<[< $name Detail >]>(::core::module_path!())
+      {
+        let trace = <$tracer as $crate::ErrorMessageTracer>::new_message(&detail);
+        return Self::construct_error(detail, trace, Self::capture_error_metadata(&[]));
+      }
+    ];
+  };
+}
+
+#[cfg(not(feature = "error_injection"))]
+#[macro_export]
+#[doc(hidden)]
+macro_rules! define_error_injection {
+  ( @name( $name:ident ),
+    @tracer( $tracer:ty ) $(,)?
+  ) => {};
+}
+
+/// Marks an injection point for the error kind type given as the first
+/// argument. It evaluates to `Err` with the error built by the closure
+/// given as the second argument, if an error of that kind has been forced
+/// with [`inject::force_next`](crate::inject::force_next) for the current
+/// module, or to `Ok(())` otherwise.
+///
+/// Without the `error_injection` feature, it always evaluates to `Ok(())`.
+#[cfg(feature = "error_injection")]
+#[macro_export]
+macro_rules! inject_point {
+  ( $kind:ty, $make:expr $(,)? ) => {
+    match $crate::inject::take::<$kind>(::core::module_path!()) {
+      ::core::option::Option::Some(kind) => ::core::result::Result::<(), $kind>::Err(kind),
+      ::core::option::Option::None => ::core::result::Result::<(), $kind>::Ok(()),
+    }
+    .map_err($make)
+  };
+}
+
+/// Marks an injection point for the error kind type given as the first
+/// argument. Without the `error_injection` feature, it always evaluates
+/// to `Ok(())`.
+#[cfg(not(feature = "error_injection"))]
+#[macro_export]
+macro_rules! inject_point {
+  ( $kind:ty, $make:expr $(,)? ) => {
+    ::core::result::Result::<(), $kind>::Ok(()).map_err($make)
+  };
+}
+
+/// Internal macro used to define suberrors in the detail-only mode
+#[macro_export]
+#[doc(hidden)]
+macro_rules! define_detail_only_suberrors {
+  ( @attr[ $( $attr:meta ),* ],
+    @name($name:ident),
+    {} $(,)?
+  ) => { };
+  ( @attr[ $( $attr:meta ),* ],
+    @name($name:ident),
+    {
+      $( #[$sub_attr:meta] )*
+      $suberror:ident $( as $alias:ident )* $( = $numeric_code:literal )?
+        $( { $( $( #[detail $field_opts:tt] )* $arg_name:ident : $( @ $modifier:ident )? $arg_type:ty ),* $(,)? } )?
+        $( [ $source:ty $( , $source2:ty )? ] )?
+        $( @ $option:ident $( ( $( $option_arg:tt )* ) )? )*
+        | $formatter_arg:pat | $formatter:expr
+
+      $( , $($tail:tt)* )?
+    }
+  ) => {
+    $crate::macros::paste![
+      $crate::define_suberror! {
+        @tracer( $crate::tracer_impl::no_trace::NoTracer ),
+        @attr[ $( $attr ),* ],
+        @sub_attr[ $( $sub_attr ),* ],
+        @name( $name ),
+        @suberror( $suberror ),
+        @args( $( $( $arg_name : $arg_type ),* )? )
+        $( @source[ $source $( , $source2 )? ] )?
+      }
+
+      impl ::core::fmt::Display for [< $suberror Subdetail >] {
+        fn fmt(&self, f: &mut ::core::fmt::Formatter<'_>) -> ::core::fmt::Result {
+          use ::core::format_args;
+          let $formatter_arg = self;
+          $crate::render::pad_display(f, &$formatter)
+        }
+      }
+
+      $crate::define_subdetail_trace_display! {
+        @suberror( $suberror ),
+        @options[ $( @ $option ( $( $( $option_arg )* )? ) )* ]
+      }
+
+      impl [< $name Detail >] {
+        $crate::define_detail_constructors! {
+          @options[ $( @ $option ( $( $( $option_arg )* )? ) )* ],
+          @vis[ pub ],
+          @name( $name ),
+          @suberror( $suberror ),
+          @variants[ $suberror $( , $alias )* ],
+          @args( $( $( $arg_name : $( @ $modifier )? $arg_type ),* )? )
+          $( @source[ $source $( , $source2 )? ] )?
+        }
+      }
+    ];
+
+    $crate::define_detail_only_suberrors! {
+      @attr[ $( $attr ),* ],
+      @name($name),
+      { $( $( $tail )* )? }
+    }
+  };
+}
+
+/// Internal macro used to define the tests given with the `@tests` option,
+/// which can be `wrap` or `display`.
+#[macro_export]
+#[doc(hidden)]
+macro_rules! define_error_tests {
+  ( @tests[],
+    @name( $name:ident ),
+    @suberrors $suberrors:tt $(,)?
+  ) => {};
+  ( @tests[ wrap $( $tests:ident )* ],
+    @name( $name:ident ),
+    @suberrors $suberrors:tt $(,)?
+  ) => {
+    $crate::define_error_wrap_tests! {
+      @tests[ wrap ],
+      @name( $name ),
+      @suberrors $suberrors
+    }
+
+    $crate::define_error_tests! {
+      @tests[ $( $tests )* ],
+      @name( $name ),
+      @suberrors $suberrors
+    }
+  };
+  ( @tests[ display $( $tests:ident )* ],
+    @name( $name:ident ),
+    @suberrors $suberrors:tt $(,)?
+  ) => {
+    $crate::define_error_display_tests! {
+      @name( $name ),
+      @suberrors $suberrors
+    }
+
+    $crate::define_error_tests! {
+      @tests[ $( $tests )* ],
+      @name( $name ),
+      @suberrors $suberrors
+    }
+  };
+  ( @tests[ $test:ident $( $tests:ident )* ],
+    @name( $name:ident ),
+    @suberrors $suberrors:tt $(,)?
+  ) => {
+    ::core::compile_error!(::core::concat!(
+      "unknown `@tests` option `", ::core::stringify!($test),
+      "`, expected `wrap` or `display`"
+    ));
+  };
+}
+
+/// Internal macro used to define the tests of the formatters of the
+/// `@tests(display)` option, one for each sub-error.
+#[macro_export]
+#[doc(hidden)]
+macro_rules! define_error_display_tests {
+  ( @name( $name:ident ),
+    @suberrors{
+      $(
+        $( #[$sub_attr:meta] )*
+        $suberror:ident $( as $alias:ident )* $( = $numeric_code:literal )?
+        $( { $( $( #[detail $field_opts:tt] )* $arg_name:ident : $( @ $modifier:ident )? $arg_type:ty ),* $(,)? } )?
+        $( [ $( $source:tt )+ ] )?
+        $( @ $option:ident $( ( $( $option_arg:tt )* ) )? )*
+        | $formatter_arg:pat | $formatter:expr
+      ),* $(,)?
+    } $(,)?
+  ) => {
+    $(
+      $crate::define_error_display_test! {
+        @options[ $( @ $option ( $( $( $option_arg )* )? ) )* ],
+        @name( $name ),
+        @suberror( $suberror ),
+        @args( $( $( $arg_name ),* )? )
+        $( @source[ $( $source )+ ] )?
+      }
+    )*
+  };
+}
+
+/// Internal macro used to define the test of the formatter of a sub-error,
+/// with the sub-detail given with `@fixture`, or else with the default
+/// values of its fields and error source detail. As with `@tests(wrap)`,
+/// the sources are matched by their tokens, and the sources other than the
+/// ones provided by flex-error are assumed to be error types defined by
+/// `define_error!`, whose details have no default value.
+#[macro_export]
+#[doc(hidden)]
+macro_rules! define_error_display_test {
+  ( @options[ @fixture( $fixture:expr ) $( $options:tt )* ],
+    @name( $name:ident ),
+    @suberror( $suberror:ident ),
+    $( $rest:tt )*
+  ) => {
+    $crate::define_error_display_test! {
+      @test( $fixture ),
+      @name( $name ),
+      @suberror( $suberror )
+    }
+  };
+  ( @options[ @ $option:ident $option_args:tt $( $options:tt )* ],
+    $( $rest:tt )*
+  ) => {
+    $crate::define_error_display_test! {
+      @options[ $( $options )* ],
+      $( $rest )*
+    }
+  };
+  ( @options[],
+    @name( $name:ident ),
+    @suberror( $suberror:ident ),
+    @args( $( $arg_name:ident ),* )
+  ) => {
+    $crate::macros::paste! [
+      $crate::define_error_display_test! {
+        @test( [< $suberror Subdetail >] {
+          $( $arg_name: ::core::default::Default::default(), )*
+        } ),
+        @name( $name ),
+        @suberror( $suberror )
+      }
+    ];
+  };
+  ( @options[],
+    @name( $name:ident ),
+    @suberror( $suberror:ident ),
+    @args $args:tt
+    @source[ Self ]
+  ) => {};
+  ( @options[],
+    @name( $name:ident ),
+    @suberror( $suberror:ident ),
+    @args $args:tt
+    @source[ $source:ty, $source2:ty ]
+  ) => {};
+  ( @options[],
+    @name( $name:ident ),
+    @suberror( $suberror:ident ),
+    @args $args:tt
+    @source[ $( $source:tt )+ ]
+  ) => {
+    $crate::define_error_display_test! {
+      @source[ $( $source )+ ],
+      @name( $name ),
+      @suberror( $suberror ),
+      @args $args
+    }
+  };
+  ( @source[ :: flex_error :: $( $source:tt )+ ],
+    $( $rest:tt )*
+  ) => {
+    $crate::define_error_display_test! {
+      @source[ $( $source )+ ],
+      $( $rest )*
+    }
+  };
+  ( @source[ flex_error :: $( $source:tt )+ ],
+    $( $rest:tt )*
+  ) => {
+    $crate::define_error_display_test! {
+      @source[ $( $source )+ ],
+      $( $rest )*
+    }
+  };
+  ( @source[ BoxSource < $( $source:tt )+ ], $( $rest:tt )* ) => {
+    $crate::define_error_display_test! {
+      @source[ $( $source )+ ],
+      $( $rest )*
+    }
+  };
+  ( @source[ TraceError < $( $source:tt )+ ], $( $rest:tt )* ) => {
+    $crate::define_error_display_test! { @default_source, $( $rest )* }
+  };
+  ( @source[ TraceClone < $( $source:tt )+ ], $( $rest:tt )* ) => {
+    $crate::define_error_display_test! { @default_source, $( $rest )* }
+  };
+  ( @source[ DisplayError < $( $source:tt )+ ], $( $rest:tt )* ) => {
+    $crate::define_error_display_test! { @default_source, $( $rest )* }
+  };
+  ( @source[ DisplayOnly < $( $source:tt )+ ], $( $rest:tt )* ) => {
+    $crate::define_error_display_test! { @default_source, $( $rest )* }
+  };
+  ( @source[ DisplayChain < $( $source:tt )+ ], $( $rest:tt )* ) => {
+    $crate::define_error_display_test! { @default_source, $( $rest )* }
+  };
+  ( @source[ DetailOnly < $( $source:tt )+ ], $( $rest:tt )* ) => {
+    $crate::define_error_display_test! { @default_source, $( $rest )* }
+  };
+  ( @source[ TraceOnly < $( $source:tt )+ ], $( $rest:tt )* ) => {
+    $crate::define_error_display_test! { @default_source, $( $rest )* }
+  };
+  ( @source[ ParseSource < $( $source:tt )+ ], $( $rest:tt )* ) => {
+    $crate::define_error_display_test! { @default_source, $( $rest )* }
+  };
+  ( @source[ BoxedTraceError ], $( $rest:tt )* ) => {
+    $crate::define_error_display_test! { @default_source, $( $rest )* }
+  };
+  ( @source[ DynErrorOnly ], $( $rest:tt )* ) => {
+    $crate::define_error_display_test! { @default_source, $( $rest )* }
+  };
+  ( @source[ NoSource ], $( $rest:tt )* ) => {
+    $crate::define_error_display_test! { @default_source, $( $rest )* }
+  };
+  ( @source[ $( $source:tt )+ ], $( $rest:tt )* ) => {};
+  ( @default_source,
+    @name( $name:ident ),
+    @suberror( $suberror:ident ),
+    @args( $( $arg_name:ident ),* )
+  ) => {
+    $crate::macros::paste! [
+      $crate::define_error_display_test! {
+        @test( [< $suberror Subdetail >] {
+          $( $arg_name: ::core::default::Default::default(), )*
+          source: ::core::default::Default::default(),
+        } ),
+        @name( $name ),
+        @suberror( $suberror )
+      }
+    ];
+  };
+  ( @test( $fixture:expr ),
+    @name( $name:ident ),
+    @suberror( $suberror:ident )
+  ) => {
+    $crate::macros::paste! [
+      #[cfg(test)]
+      #[test]
+      fn [< $name:snake _displays_ $suberror:snake >]() {
+        struct Sink;
+
+        impl ::core::fmt::Write for Sink {
+          fn write_str(&mut self, _: &str) -> ::core::fmt::Result {
+            Ok(())
+          }
+        }
+
+        let subdetail: [< $suberror Subdetail >] = $fixture;
+        let detail = [< $name Detail >]::$suberror(subdetail);
+
+        ::core::assert!(
+          ::core::fmt::Write::write_fmt(&mut Sink, ::core::format_args!("{}", detail)).is_ok(),
+          "the formatter of `{}` returned an error", ::core::stringify!($suberror));
+        ::core::assert!(
+          ::core::fmt::Write::write_fmt(
+            &mut Sink,
+            ::core::format_args!("{}", $crate::TraceDisplayed(&detail))
+          ).is_ok(),
+          "the trace formatter of `{}` returned an error", ::core::stringify!($suberror));
+      }
+    ];
+  };
+}
+
+// define the tests of the wrap relationships of the `@tests(wrap)` option
+// only when the `arbitrary` feature is enabled, as the details of the
+// wrapped errors are generated with `Arbitrary`. The tests are defined
+// next to the error type in the calling crate, so that they are run by
+// its own `cargo test`, and the paths of the sources resolve as in the
+// definition.
+#[cfg(all(feature = "arbitrary", feature = "alloc"))]
+#[macro_export]
+#[doc(hidden)]
+macro_rules! define_error_wrap_tests {
+  ( @tests[ wrap ],
+    @name( $name:ident ),
+    @suberrors{
+      $(
+        $( #[$sub_attr:meta] )*
+        $suberror:ident $( as $alias:ident )* $( = $numeric_code:literal )?
+        $( { $( $( #[detail $field_opts:tt] )* $arg_name:ident : $( @ $modifier:ident )? $arg_type:ty ),* $(,)? } )?
+        $( [ $( $source:tt )+ ] )?
+        $( @ $option:ident $( ( $( $option_arg:tt )* ) )? )*
+        | $formatter_arg:pat | $formatter:expr
+      ),* $(,)?
+    } $(,)?
+  ) => {
+    $(
+      $crate::define_error_wrap_test! {
+        @options[ $( @ $option ( $( $( $option_arg )* )? ) )* ],
+        @name( $name ),
+        @suberror( $suberror ),
+        @args( $( $( $arg_name ),* )? )
+        $( @source[ $( $source )+ ] )?
+      }
+    )*
+  };
+}
+
+#[cfg(not(all(feature = "arbitrary", feature = "alloc")))]
+#[macro_export]
+#[doc(hidden)]
+macro_rules! define_error_wrap_tests {
+  ( @tests $tests:tt,
+    @name( $name:ident ),
+    @suberrors $suberrors:tt $(,)?
+  ) => {
+    ::core::compile_error!(
+      "the `@tests(wrap)` option requires the `arbitrary` and `alloc` features of `flex-error` to be enabled");
+  };
+}
+
+/// Internal macro used to define the test of the wrap relationship of
+/// a sub-error, if its error source is an error type defined by
+/// `define_error!`. As with the `@from` option, the sources are matched
+/// by their tokens, and sources other than the ones provided by flex-error
+/// are assumed to be error types defined by `define_error!`.
+#[macro_export]
+#[doc(hidden)]
+macro_rules! define_error_wrap_test {
+  ( @options[ @no_constructor() $( $options:tt )* ],
+    $( $rest:tt )*
+  ) => {};
+  ( @options[ @ $option:ident $option_args:tt $( $options:tt )* ],
+    $( $rest:tt )*
+  ) => {
+    $crate::define_error_wrap_test! {
+      @options[ $( $options )* ],
+      $( $rest )*
+    }
+  };
+  ( @options[],
+    @name( $name:ident ),
+    @suberror( $suberror:ident ),
+    @args $args:tt
+  ) => {};
+  ( @options[],
+    @name( $name:ident ),
+    @suberror( $suberror:ident ),
+    @args $args:tt
+    @source[ Self ]
+  ) => {};
+  ( @options[],
+    @name( $name:ident ),
+    @suberror( $suberror:ident ),
+    @args $args:tt
+    @source[ $source:ty, $source2:ty ]
+  ) => {};
+  ( @options[],
+    @name( $name:ident ),
+    @suberror( $suberror:ident ),
+    @args $args:tt
+    @source[ $( $source:tt )+ ]
+  ) => {
+    $crate::define_error_wrap_test! {
+      @source[ $( $source )+ ],
+      @name( $name ),
+      @suberror( $suberror ),
+      @args $args
+    }
+  };
+  ( @source[ :: flex_error :: $( $source:tt )+ ],
+    $( $rest:tt )*
+  ) => {
+    $crate::define_error_wrap_test! {
+      @source[ $( $source )+ ],
+      $( $rest )*
+    }
+  };
+  ( @source[ flex_error :: $( $source:tt )+ ],
+    $( $rest:tt )*
+  ) => {
+    $crate::define_error_wrap_test! {
+      @source[ $( $source )+ ],
+      $( $rest )*
+    }
+  };
+  ( @source[ TraceError < $( $source:tt )+ ], $( $rest:tt )* ) => {};
+  ( @source[ TraceClone < $( $source:tt )+ ], $( $rest:tt )* ) => {};
+  ( @source[ DisplayError < $( $source:tt )+ ], $( $rest:tt )* ) => {};
+  ( @source[ DisplayOnly < $( $source:tt )+ ], $( $rest:tt )* ) => {};
+  ( @source[ DisplayChain < $( $source:tt )+ ], $( $rest:tt )* ) => {};
+  ( @source[ DetailOnly < $( $source:tt )+ ], $( $rest:tt )* ) => {};
+  ( @source[ TraceOnly < $( $source:tt )+ ], $( $rest:tt )* ) => {};
+  ( @source[ BoxDetail < $( $source:tt )+ ], $( $rest:tt )* ) => {};
+  ( @source[ Both < $( $source:tt )+ ], $( $rest:tt )* ) => {};
+  ( @source[ ParseSource < $( $source:tt )+ ], $( $rest:tt )* ) => {};
+  ( @source[ Rejection < $( $source:tt )+ ], $( $rest:tt )* ) => {};
+  ( @source[ BoxedTraceError ], $( $rest:tt )* ) => {};
+  ( @source[ DynError ], $( $rest:tt )* ) => {};
+  ( @source[ DynErrorOnly ], $( $rest:tt )* ) => {};
+  ( @source[ NoSource ], $( $rest:tt )* ) => {};
+  ( @source[ BoxSource < $source:ty > ],
+    $( $rest:tt )*
+  ) => {
+    $crate::define_error_wrap_test! {
+      @test( $source ),
+      @unbox[ * ],
+      $( $rest )*
+    }
+  };
+  ( @source[ $source:ty ],
+    $( $rest:tt )*
+  ) => {
+    $crate::define_error_wrap_test! {
+      @test( $source ),
+      @unbox[],
+      $( $rest )*
+    }
+  };
+  ( @test( $source:ty ),
+    @unbox[ $( $unbox:tt )* ],
+    @name( $name:ident ),
+    @suberror( $suberror:ident ),
+    @args( $( $arg_name:ident ),* )
+  ) => {
+    $crate::macros::paste! [
+      #[cfg(test)]
+      #[test]
+      fn [< $name:snake _wraps_ $suberror:snake >]() {
+        use $crate::alloc::string::ToString;
+
+        let mut wrapped = 0;
+
+        for seed in 0u8..16 {
+          let mut bytes = [0u8; 256];
+          for (index, byte) in bytes.iter_mut().enumerate() {
+            *byte = (index as u8).wrapping_mul(31).wrapping_add(seed.wrapping_mul(17));
+          }
+
+          let mut u = $crate::arbitrary::Unstructured::new(&bytes);
+          let [< $suberror Subdetail >] { $( $arg_name, )* source } =
+            match $crate::arbitrary::Arbitrary::arbitrary(&mut u) {
+              ::core::result::Result::Ok(subdetail) => subdetail,
+              ::core::result::Result::Err(_) => continue,
+            };
+
+          let inner = <$source>::with_new_trace( $( $unbox )* source );
+          let inner_detail = inner.detail().to_string();
+          let inner_message = $crate::TraceDisplayed(inner.detail()).to_string();
+
+          let error = <$name>::[< $suberror:snake >]( $( $arg_name, )* inner );
+          let message = $crate::TraceDisplayed(error.detail()).to_string();
+
+          match error.detail() {
+            [< $name Detail >]::$suberror(subdetail) => ::core::assert_eq!(
+              subdetail.source.to_string(),
+              inner_detail,
+              "the wrapped detail is not preserved"
+            ),
+            _ => ::core::panic!(
+              "the error is not the sub-error `{}`", ::core::stringify!($suberror)),
           }
+
+          let rendered = $crate::render::Joined(error.trace()).to_string();
+          ::core::assert!(rendered.contains(&message),
+            "the error trace `{}` does not contain the message `{}`", rendered, message);
+          ::core::assert!(rendered.contains(&inner_message),
+            "the error trace `{}` does not contain the wrapped message `{}`", rendered, inner_message);
+
+          wrapped += 1;
+        }
+
+        ::core::assert!(wrapped > 0, "no arbitrary sub-detail could be generated");
+      }
+    ];
+  };
+}
+
+/// Internal macro used to define suberror constructor functions
+/// for a suberror and its variant aliases in the detail-only mode
+#[macro_export]
+#[doc(hidden)]
+macro_rules! define_detail_constructors {
+  ( @options[ @no_constructor() $( $options:tt )* ],
+    $( $rest:tt )*
+  ) => {};
+  ( @options[ @const() $( $options:tt )* ],
+    $( $rest:tt )*
+  ) => {
+    $crate::define_detail_constructors! {
+      @options[ $( $options )* ],
+      $( $rest )*
+      @const_fn()
+    }
+  };
+  ( @options[ @attach $attach_args:tt $( $options:tt )* ],
+    $( $rest:tt )*
+  ) => {
+    ::core::compile_error!(
+      "the `@attach` option is not supported in the `@detail_only` mode, \
+       as the errors have no error trace to attach to"
+    );
+  };
+  ( @options[ @vis( $( $vis:tt )* ) $( $options:tt )* ],
+    @vis $old_vis:tt,
+    $( $rest:tt )*
+  ) => {
+    $crate::define_detail_constructors! {
+      @options[ $( $options )* ],
+      @vis[ $( $vis )* ],
+      $( $rest )*
+    }
+  };
+  ( @options[ @ $option:ident $option_args:tt $( $options:tt )* ],
+    $( $rest:tt )*
+  ) => {
+    $crate::define_detail_constructors! {
+      @options[ $( $options )* ],
+      $( $rest )*
+    }
+  };
+  ( @options[],
+    $( $rest:tt )*
+  ) => {
+    $crate::define_detail_constructors! {
+      $( $rest )*
+    }
+  };
+  ( @vis $vis:tt,
+    @name( $name:ident ),
+    @suberror( $suberror:ident ),
+    @variants[],
+    @args $args:tt
+    $( @source $source:tt )?
+    $( @const_fn $const_fn:tt )*
+  ) => {};
+  ( @vis $vis:tt,
+    @name( $name:ident ),
+    @suberror( $suberror:ident ),
+    @variants[ $variant:ident $( , $rest:ident )* ],
+    @args $args:tt
+    $( @source $source:tt )?
+    $( @const_fn $const_fn:tt )*
+  ) => {
+    $crate::define_detail_constructor! {
+      @vis $vis,
+      @name( $name ),
+      @suberror( $suberror ),
+      @variant( $variant ),
+      @args $args
+      $( @source $source )?
+      $( @const_fn $const_fn )*
+    }
+
+    $crate::define_detail_constructors! {
+      @vis $vis,
+      @name( $name ),
+      @suberror( $suberror ),
+      @variants[ $( $rest ),* ],
+      @args $args
+      $( @source $source )?
+      $( @const_fn $const_fn )*
+    }
+  };
+}
+
+/// Internal macro used to define suberror constructor functions
+/// in the detail-only mode
+#[macro_export]
+#[doc(hidden)]
+macro_rules! define_detail_constructor {
+  ( @vis[ $( $vis:tt )* ],
+    @name( $name:ident ),
+    @suberror( $suberror:ident ),
+    @variant( $variant:ident ),
+    @args( $( $arg_name:ident: $arg_type:ty ),* )
+    $( @const_fn() )+
+  ) => {
+    $crate::macros::paste! [
+      $( $vis )* const fn [< $variant:snake >](
+        $( $arg_name: $arg_type, )*
+      ) -> $name
+      {
+        [< $name Detail >]::$variant([< $suberror Subdetail >] {
+          $( $arg_name, )*
+        })
+      }
+    ];
+  };
+  ( @vis[ $( $vis:tt )* ],
+    @name( $name:ident ),
+    @suberror( $suberror:ident ),
+    @variant( $variant:ident ),
+    @args $args:tt
+    @source $source:tt
+    $( @const_fn() )+
+  ) => {
+    ::core::compile_error!(::core::concat!(
+      "the `@const` option of `", ::core::stringify!($suberror),
+      "` is not supported for sub-errors with an error source"
+    ));
+  };
+  ( @vis[ $( $vis:tt )* ],
+    @name( $name:ident ),
+    @suberror( $suberror:ident ),
+    @variant( $variant:ident ),
+    @args $args:tt
+    $( @const_fn() )+
+  ) => {
+    ::core::compile_error!(::core::concat!(
+      "the `@const` option of `", ::core::stringify!($suberror),
+      "` is not supported for fields with modifiers"
+    ));
+  };
+  ( @vis[ $( $vis:tt )* ],
+    @name( $name:ident ),
+    @suberror( $suberror:ident ),
+    @variant( $variant:ident ),
+    @args( $( $arg_name:ident: $( @ $modifier:ident )? $arg_type:ty ),* ) $(,)?
+  ) => {
+    $crate::macros::paste! [
+      $( $vis )* fn [< $variant:snake >](
+        $( $arg_name: $crate::suberror_field_param!( $( @ $modifier )? $arg_type ), )*
+      ) -> $name
+      {
+        $( let $arg_name = $crate::suberror_field_value!( $( @ $modifier )? $arg_name ); )*
+
+        $crate::define_error_stat! {
+          @name( $name ),
+          @suberror( $variant )
+        }
+
+        [< $name Detail >]::$variant([< $suberror Subdetail >] {
+          $( $arg_name, )*
+        })
+      }
+    ];
+  };
+  ( @vis[ $( $vis:tt )* ],
+    @name( $name:ident ),
+    @suberror( $suberror:ident ),
+    @variant( $variant:ident ),
+    @args( $( $arg_name:ident: $( @ $modifier:ident )? $arg_type:ty ),* )
+    @source[ Self ]
+  ) => {
+    $crate::macros::paste! [
+      $( $vis )* fn [< $variant:snake >](
+        $( $arg_name: $crate::suberror_field_param!( $( @ $modifier )? $arg_type ), )*
+        source: $name
+      ) -> $name
+      {
+        $( let $arg_name = $crate::suberror_field_value!( $( @ $modifier )? $arg_name ); )*
+
+        $crate::define_error_stat! {
+          @name( $name ),
+          @suberror( $variant )
+        }
+
+        $crate::define_error_edge! {
+          @name( $name ),
+          @suberror( $variant ),
+          @source( $name )
+        }
+
+        [< $name Detail >]::$variant([< $suberror Subdetail >] {
+          $( $arg_name, )*
+          source: $crate::alloc::boxed::Box::new(source),
+        })
+      }
+    ];
+  };
+  ( @vis[ $( $vis:tt )* ],
+    @name( $name:ident ),
+    @suberror( $suberror:ident ),
+    @variant( $variant:ident ),
+    @args( $( $arg_name:ident: $( @ $modifier:ident )? $arg_type:ty ),* )
+    @source[ $source:ty, $source2:ty ]
+  ) => {
+    $crate::macros::paste! [
+      $( $vis )* fn [< $variant:snake >](
+        $( $arg_name: $crate::suberror_field_param!( $( @ $modifier )? $arg_type ), )*
+        source1: $crate::AsErrorSource< $source, $crate::tracer_impl::no_trace::NoTracer >,
+        source2: $crate::AsErrorSource< $source2, $crate::tracer_impl::no_trace::NoTracer >
+      ) -> $name
+      {
+        $( let $arg_name = $crate::suberror_field_value!( $( @ $modifier )? $arg_name ); )*
+
+        $crate::define_error_stat! {
+          @name( $name ),
+          @suberror( $variant )
+        }
+
+        $crate::define_error_edge! {
+          @name( $name ),
+          @suberror( $variant ),
+          @source( $source )
+        }
+
+        $crate::define_error_edge! {
+          @name( $name ),
+          @suberror( $variant ),
+          @source( $source2 )
+        }
+
+        let (source1, _) = <
+          $source as $crate::ErrorSource< $crate::tracer_impl::no_trace::NoTracer >
+        >::error_details(source1);
+
+        let (source2, _) = <
+          $source2 as $crate::ErrorSource< $crate::tracer_impl::no_trace::NoTracer >
+        >::error_details(source2);
+
+        [< $name Detail >]::$variant([< $suberror Subdetail >] {
+          $( $arg_name, )*
+          source1,
+          source2,
+        })
+      }
+    ];
+  };
+  ( @vis[ $( $vis:tt )* ],
+    @name( $name:ident ),
+    @suberror( $suberror:ident ),
+    @variant( $variant:ident ),
+    @args( $( $arg_name:ident: $( @ $modifier:ident )? $arg_type:ty ),* )
+    @source[ $source:ty ]
+  ) => {
+    $crate::macros::paste! [
+      $( $vis )* fn [< $variant:snake >](
+        $( $arg_name: $crate::suberror_field_param!( $( @ $modifier )? $arg_type ), )*
+        source: $crate::AsErrorSource< $source, $crate::tracer_impl::no_trace::NoTracer >
+      ) -> $name
+      {
+        $( let $arg_name = $crate::suberror_field_value!( $( @ $modifier )? $arg_name ); )*
+
+        $crate::define_error_stat! {
+          @name( $name ),
+          @suberror( $variant )
+        }
+
+        $crate::define_error_edge! {
+          @name( $name ),
+          @suberror( $variant ),
+          @source( $source )
+        }
+
+        let (source_detail, _) = <
+          $source as $crate::ErrorSource< $crate::tracer_impl::no_trace::NoTracer >
+        >::error_details(source);
+
+        [< $name Detail >]::$variant([< $suberror Subdetail >] {
+          $( $arg_name, )*
+          source: source_detail,
+        })
+      }
+    ];
+  };
+}
+
+/// Internal macro used to define suberror structs
+#[macro_export]
+#[doc(hidden)]
+macro_rules! define_suberror {
+  ( @tracer( $tracer:ty ),
+    @attr[ $( $attr:meta ),* ],
+    @sub_attr[ $( $sub_attr:meta ),* ],
+    @name( $name:ident ),
+    @suberror( $suberror:ident ),
+    @args( $( $arg_name:ident: $arg_type:ty ),* )
+    @source[ Self ]
+  ) => {
+    $crate::macros::paste! [
+      $( #[ $attr ] )*
+      $( #[ $sub_attr ] )*
+      pub struct [< $suberror Subdetail >] {
+        $( pub $arg_name: $arg_type, )*
+        pub source: $crate::alloc::boxed::Box< [< $name Detail >] >
+      }
+
+      impl [< $suberror Subdetail >] {
+        /// Returns the detail of the error source as `Display`.
+        pub fn source_display(&self) -> ::core::option::Option<&dyn ::core::fmt::Display> {
+          ::core::option::Option::Some(&*self.source)
+        }
+      }
+    ];
+
+    $( $crate::assert_error_detail_field!( $arg_type ); )*
+  };
+  ( @tracer( $tracer:ty ),
+    @attr[ $( $attr:meta ),* ],
+    @sub_attr[ $( $sub_attr:meta ),* ],
+    @name( $name:ident ),
+    @suberror( $suberror:ident ),
+    @args( $( $arg_name:ident: $arg_type:ty ),* )
+    @source[ $source:ty, $source2:ty ]
+  ) => {
+    $crate::macros::paste! [
+      $crate::assert_suberror_sources!( $suberror, $source, $source2 );
+
+      $( #[ $attr ] )*
+      $( #[ $sub_attr ] )*
+      pub struct [< $suberror Subdetail >] {
+        $( pub $arg_name: $arg_type, )*
+        pub source1: $crate::AsErrorDetail<$source, $tracer>,
+        pub source2: $crate::AsErrorDetail<$source2, $tracer>,
+      }
+
+      impl [< $suberror Subdetail >] {
+        /// Returns the detail of the first error source as `Display`, or
+        /// of the second one if the first cannot be displayed.
+        pub fn source_display(&self) -> ::core::option::Option<&dyn ::core::fmt::Display> {
+          <$source as $crate::ErrorSource<$tracer>>::detail_display(&self.source1)
+            .or_else(|| <$source2 as $crate::ErrorSource<$tracer>>::detail_display(&self.source2))
+        }
       }
     ];
-  }
+
+    $( $crate::assert_error_detail_field!( $arg_type ); )*
+    $crate::assert_error_detail_field!( $crate::AsErrorDetail<$source, $tracer> );
+    $crate::assert_error_detail_field!( $crate::AsErrorDetail<$source2, $tracer> );
+  };
+  ( @tracer( $tracer:ty ),
+    @attr[ $( $attr:meta ),* ],
+    @sub_attr[ $( $sub_attr:meta ),* ],
+    @name( $name:ident ),
+    @suberror( $suberror:ident ),
+    @args( $( $arg_name:ident: $arg_type:ty ),* )
+    $( @source[ $source:ty ] )?
+  ) => {
+    $crate::macros::paste! [
+      $( #[ $attr ] )*
+      $( #[ $sub_attr ] )*
+      pub struct [< $suberror Subdetail >] {
+        $( pub $arg_name: $arg_type, )*
+        $( pub source: $crate::AsErrorDetail<$source, $tracer> )?
+      }
+
+      impl [< $suberror Subdetail >] {
+        /// Returns the detail of the error source as `Display`, if the
+        /// sub-error has an error source whose detail can be displayed.
+        pub fn source_display(&self) -> ::core::option::Option<&dyn ::core::fmt::Display> {
+          $crate::suberror_source_display!( self, $tracer $( , $source )? )
+        }
+      }
+    ];
+
+    $( $crate::assert_error_detail_field!( $arg_type ); )*
+    $( $crate::assert_error_detail_field!( $crate::AsErrorDetail<$source, $tracer> ); )?
+  };
 }
 
-// do not define the impl for `std::error::Error` when in no_std mode
-#[cfg(not(feature = "std"))]
+/// Internal macro used to get the detail of the error source of a sub-error
+/// as `Display`, if it has one.
 #[macro_export]
 #[doc(hidden)]
-macro_rules! define_std_err_impl {
-    ( @tracer( $tracer:ty ),
-    @name( $name:ident ) $(,)?
-  ) => {};
+macro_rules! suberror_source_display {
+  ( $self:ident, $tracer:ty ) => {
+    ::core::option::Option::None
+  };
+  ( $self:ident, $tracer:ty, $source:ty ) => {
+    <$source as $crate::ErrorSource<$tracer>>::detail_display(&$self.source)
+  };
 }
 
+/// Internal macro used to reject `Self` as one of the two error sources
+/// of a sub-error, which is only supported as the single error source.
 #[macro_export]
 #[doc(hidden)]
-macro_rules! define_main_error_struct {
-  ( @tracer( $tracer:ty ),
-    $( @doc( $doc:literal ), )?
-    @name( $name:ident ) $(,)?
-  ) => {
-    $crate::macros::paste![
-      $( #[doc = $doc] )?
-      pub struct $name (pub [< $name Detail >], pub $tracer);
-    ];
-  }
+macro_rules! assert_suberror_sources {
+  ( $suberror:ident, Self, $source2:ty ) => {
+    $crate::assert_suberror_sources!( @self $suberror );
+  };
+  ( $suberror:ident, $source:ty, Self ) => {
+    $crate::assert_suberror_sources!( @self $suberror );
+  };
+  ( $suberror:ident, $source:ty, $source2:ty ) => {};
+  ( @self $suberror:ident ) => {
+    ::core::compile_error!(::core::concat!(
+      "the sub-error `", ::core::stringify!($suberror),
+      "` cannot have `Self` as one of two error sources"
+    ));
+  };
 }
 
+/// Internal macro used to define the constructor trait of the main error
+/// type, with one method per constructor, and its implementation by the
+/// main error type forwarding to the constructors.
 #[macro_export]
 #[doc(hidden)]
-macro_rules! with_suberrors {
-  ( @cont($cont:path),
-    @ctx[ $($args:tt)* ],
+macro_rules! define_error_ctor_trait {
+  ( @tracer( $tracer:ty ),
+    @name( $name:ident ),
     @suberrors{
       $(
         $( #[$sub_attr:meta] )*
-        $suberror:ident
-        $( { $( $arg_name:ident : $arg_type:ty ),* $(,)? } )?
-        $( [ $source:ty ] )?
+        $suberror:ident $( as $alias:ident )* $( = $numeric_code:literal )?
+        $( { $( $( #[detail $field_opts:tt] )* $arg_name:ident : $( @ $modifier:ident )? $arg_type:ty ),* $(,)? } )?
+        $( [ $source:ty $( , $source2:ty )? ] )?
+        $( @ $option:ident $( ( $( $option_arg:tt )* ) )? )*
         | $formatter_arg:pat | $formatter:expr
       ),* $(,)?
     } $(,)?
   ) => {
-    $cont!( @ctx[ $( $args )* ], @suberrors{ $( $suberror ),* } );
-  }
+    $crate::macros::paste! [
+      #[doc = ::core::concat!(
+        "The constructors of [`", ::core::stringify!($name), "`], with one method ",
+        "per constructor, for creating the errors through a mock implementation ",
+        "in tests.")]
+      pub trait [< $name Ctor >]: Sized {
+        $(
+          $crate::define_error_ctor_methods! {
+            @options[ $( @ $option ( $( $( $option_arg )* )? ) )* ],
+            @mode[ trait ],
+            @tracer( $tracer ),
+            @name( $name ),
+            @variants[ $suberror $( , $alias )* ],
+            @args( $( $( $arg_name : $( @ $modifier )? $arg_type ),* )? )
+            $( @source[ $source $( , $source2 )? ] )?
+          }
+        )*
+      }
+
+      impl [< $name Ctor >] for $name {
+        $(
+          $crate::define_error_ctor_methods! {
+            @options[ $( @ $option ( $( $( $option_arg )* )? ) )* ],
+            @mode[ impl ],
+            @tracer( $tracer ),
+            @name( $name ),
+            @variants[ $suberror $( , $alias )* ],
+            @args( $( $( $arg_name : $( @ $modifier )? $arg_type ),* )? )
+            $( @source[ $source $( , $source2 )? ] )?
+          }
+        )*
+      }
+    ];
+  };
 }
 
+/// Internal macro used to define the methods of the constructor trait
+/// for a suberror and its variant aliases
 #[macro_export]
 #[doc(hidden)]
-macro_rules! define_error_detail {
-  ( @attr[ $( $attr:meta ),* ],
+macro_rules! define_error_ctor_methods {
+  ( @options[ @no_constructor() $( $options:tt )* ],
+    $( $rest:tt )*
+  ) => {};
+  ( @options[ @vis( $( $vis:tt )* ) $( $options:tt )* ],
+    @mode[ free $suffix:ident $old_vis:tt ],
+    $( $rest:tt )*
+  ) => {
+    $crate::define_error_ctor_methods! {
+      @options[ $( $options )* ],
+      @mode[ free $suffix [ $( $vis )* ] ],
+      $( $rest )*
+    }
+  };
+  // the constructors with a restricted visibility are not part of
+  // the constructor trait, which is public
+  ( @options[ @vis $vis_args:tt $( $options:tt )* ],
+    @mode[ $mode:ident ],
+    $( $rest:tt )*
+  ) => {};
+  ( @options[ @ $option:ident $option_args:tt $( $options:tt )* ],
+    $( $rest:tt )*
+  ) => {
+    $crate::define_error_ctor_methods! {
+      @options[ $( $options )* ],
+      $( $rest )*
+    }
+  };
+  ( @options[],
+    $( $rest:tt )*
+  ) => {
+    $crate::define_error_ctor_methods! {
+      $( $rest )*
+    }
+  };
+  ( @mode $mode:tt,
+    @tracer( $tracer:ty ),
     @name( $name:ident ),
-    @suberrors{ $($suberrors:tt)* } $(,)?
+    @variants[],
+    @args $args:tt
+    $( @source $source:tt )?
+  ) => {};
+  ( @mode $mode:tt,
+    @tracer( $tracer:ty ),
+    @name( $name:ident ),
+    @variants[ $variant:ident $( , $rest:ident )* ],
+    @args $args:tt
+    $( @source $source:tt )?
   ) => {
-    $crate::with_suberrors!(
-      @cont($crate::define_error_detail_enum),
-      @ctx[
-        @attr[ $( $attr ),* ],
-        @name($name)
-      ],
-      @suberrors{ $( $suberrors )* }
-    );
+    $crate::define_error_ctor_method! {
+      @mode $mode,
+      @tracer( $tracer ),
+      @name( $name ),
+      @variant( $variant ),
+      @args $args
+      $( @source $source )?
+    }
 
-    $crate::with_suberrors!(
-      @cont($crate::define_error_detail_display),
-      @ctx[
-        @name($name)
-      ],
-      @suberrors{ $( $suberrors )* }
-    );
-  }
+    $crate::define_error_ctor_methods! {
+      @mode $mode,
+      @tracer( $tracer ),
+      @name( $name ),
+      @variants[ $( $rest ),* ],
+      @args $args
+      $( @source $source )?
+    }
+  };
 }
 
+/// Internal macro used to define a method of the constructor trait,
+/// or its implementation forwarding to the constructor function
 #[macro_export]
 #[doc(hidden)]
-macro_rules! define_error_detail_enum {
-  ( @ctx[
-      @attr[ $( $attr:meta ),* ],
-      @name($name:ident)
-    ],
-    @suberrors{ $( $suberror:ident ),* } $(,)?
+macro_rules! define_error_ctor_method {
+  ( @mode[ trait ],
+    @tracer( $tracer:ty ),
+    @name( $name:ident ),
+    @variant( $variant:ident ),
+    @args( $( $arg_name:ident: $( @ $modifier:ident )? $arg_type:ty ),* )
+    @source[ Self ]
   ) => {
     $crate::macros::paste! [
-      $( #[$attr] )*
-      pub enum [< $name Detail >] {
-        $(
-          $suberror (
-            [< $suberror Subdetail >]
-          )
-        ),*
+      fn [< $variant:snake >](
+        $( $arg_name: $crate::suberror_field_param!( $( @ $modifier )? $arg_type ), )*
+        source: Self
+      ) -> Self;
+    ];
+  };
+  ( @mode[ trait ],
+    @tracer( $tracer:ty ),
+    @name( $name:ident ),
+    @variant( $variant:ident ),
+    @args( $( $arg_name:ident: $( @ $modifier:ident )? $arg_type:ty ),* )
+    @source[ $source:ty, $source2:ty ]
+  ) => {
+    $crate::macros::paste! [
+      fn [< $variant:snake >](
+        $( $arg_name: $crate::suberror_field_param!( $( @ $modifier )? $arg_type ), )*
+        source1: $crate::AsErrorSource< $source, $tracer >,
+        source2: $crate::AsErrorSource< $source2, $tracer >
+      ) -> Self;
+    ];
+  };
+  ( @mode[ trait ],
+    @tracer( $tracer:ty ),
+    @name( $name:ident ),
+    @variant( $variant:ident ),
+    @args( $( $arg_name:ident: $( @ $modifier:ident )? $arg_type:ty ),* )
+    $( @source[ $source:ty ] )?
+  ) => {
+    $crate::macros::paste! [
+      fn [< $variant:snake >](
+        $( $arg_name: $crate::suberror_field_param!( $( @ $modifier )? $arg_type ), )*
+        $( source: $crate::AsErrorSource< $source, $tracer > )?
+      ) -> Self;
+    ];
+  };
+  ( @mode[ impl ],
+    @tracer( $tracer:ty ),
+    @name( $name:ident ),
+    @variant( $variant:ident ),
+    @args( $( $arg_name:ident: $( @ $modifier:ident )? $arg_type:ty ),* )
+    @source[ Self ]
+  ) => {
+    $crate::macros::paste! [
+      fn [< $variant:snake >](
+        $( $arg_name: $crate::suberror_field_param!( $( @ $modifier )? $arg_type ), )*
+        source: Self
+      ) -> Self {
+        $name::[< $variant:snake >]( $( $arg_name, )* source )
       }
     ];
-  }
+  };
+  ( @mode[ impl ],
+    @tracer( $tracer:ty ),
+    @name( $name:ident ),
+    @variant( $variant:ident ),
+    @args( $( $arg_name:ident: $( @ $modifier:ident )? $arg_type:ty ),* )
+    @source[ $source:ty, $source2:ty ]
+  ) => {
+    $crate::macros::paste! [
+      #[track_caller]
+      fn [< $variant:snake >](
+        $( $arg_name: $crate::suberror_field_param!( $( @ $modifier )? $arg_type ), )*
+        source1: $crate::AsErrorSource< $source, $tracer >,
+        source2: $crate::AsErrorSource< $source2, $tracer >
+      ) -> Self {
+        $name::[< $variant:snake >]( $( $arg_name, )* source1, source2 )
+      }
+    ];
+  };
+  ( @mode[ impl ],
+    @tracer( $tracer:ty ),
+    @name( $name:ident ),
+    @variant( $variant:ident ),
+    @args( $( $arg_name:ident: $( @ $modifier:ident )? $arg_type:ty ),* )
+    @source[ $source:ty ]
+  ) => {
+    $crate::macros::paste! [
+      #[track_caller]
+      fn [< $variant:snake >](
+        $( $arg_name: $crate::suberror_field_param!( $( @ $modifier )? $arg_type ), )*
+        source: $crate::AsErrorSource< $source, $tracer >
+      ) -> Self {
+        $name::[< $variant:snake >]( $( $arg_name, )* source )
+      }
+    ];
+  };
+  ( @mode[ impl ],
+    @tracer( $tracer:ty ),
+    @name( $name:ident ),
+    @variant( $variant:ident ),
+    @args( $( $arg_name:ident: $( @ $modifier:ident )? $arg_type:ty ),* )
+  ) => {
+    $crate::macros::paste! [
+      #[track_caller]
+      fn [< $variant:snake >](
+        $( $arg_name: $crate::suberror_field_param!( $( @ $modifier )? $arg_type ), )*
+      ) -> Self {
+        $name::[< $variant:snake >]( $( $arg_name ),* )
+      }
+    ];
+  };
+  ( @mode[ free $suffix:ident [ $( $vis:tt )* ] ],
+    @tracer( $tracer:ty ),
+    @name( $name:ident ),
+    @variant( $variant:ident ),
+    @args( $( $arg_name:ident: $( @ $modifier:ident )? $arg_type:ty ),* )
+    @source[ Self ]
+  ) => {
+    $crate::macros::paste! [
+      #[doc = ::core::concat!("Calls [`", ::core::stringify!($name), "::",
+        ::core::stringify!([< $variant:snake >]), "`].")]
+      $( $vis )* fn [< $variant:snake _ $suffix >](
+        $( $arg_name: $crate::suberror_field_param!( $( @ $modifier )? $arg_type ), )*
+        source: $name
+      ) -> $name {
+        $name::[< $variant:snake >]( $( $arg_name, )* source )
+      }
+    ];
+  };
+  ( @mode[ free $suffix:ident [ $( $vis:tt )* ] ],
+    @tracer( $tracer:ty ),
+    @name( $name:ident ),
+    @variant( $variant:ident ),
+    @args( $( $arg_name:ident: $( @ $modifier:ident )? $arg_type:ty ),* )
+    @source[ $source:ty, $source2:ty ]
+  ) => {
+    $crate::macros::paste! [
+      #[doc = ::core::concat!("Calls [`", ::core::stringify!($name), "::",
+        ::core::stringify!([< $variant:snake >]), "`].")]
+      #[track_caller]
+      $( $vis )* fn [< $variant:snake _ $suffix >](
+        $( $arg_name: $crate::suberror_field_param!( $( @ $modifier )? $arg_type ), )*
+        source1: $crate::AsErrorSource< $source, $tracer >,
+        source2: $crate::AsErrorSource< $source2, $tracer >
+      ) -> $name {
+        $name::[< $variant:snake >]( $( $arg_name, )* source1, source2 )
+      }
+    ];
+  };
+  ( @mode[ free $suffix:ident [ $( $vis:tt )* ] ],
+    @tracer( $tracer:ty ),
+    @name( $name:ident ),
+    @variant( $variant:ident ),
+    @args( $( $arg_name:ident: $( @ $modifier:ident )? $arg_type:ty ),* )
+    @source[ $source:ty ]
+  ) => {
+    $crate::macros::paste! [
+      #[doc = ::core::concat!("Calls [`", ::core::stringify!($name), "::",
+        ::core::stringify!([< $variant:snake >]), "`].")]
+      #[track_caller]
+      $( $vis )* fn [< $variant:snake _ $suffix >](
+        $( $arg_name: $crate::suberror_field_param!( $( @ $modifier )? $arg_type ), )*
+        source: $crate::AsErrorSource< $source, $tracer >
+      ) -> $name {
+        $name::[< $variant:snake >]( $( $arg_name, )* source )
+      }
+    ];
+  };
+  ( @mode[ free $suffix:ident [ $( $vis:tt )* ] ],
+    @tracer( $tracer:ty ),
+    @name( $name:ident ),
+    @variant( $variant:ident ),
+    @args( $( $arg_name:ident: $( @ $modifier:ident )? $arg_type:ty ),* )
+  ) => {
+    $crate::macros::paste! [
+      #[doc = ::core::concat!("Calls [`", ::core::stringify!($name), "::",
+        ::core::stringify!([< $variant:snake >]), "`].")]
+      #[track_caller]
+      $( $vis )* fn [< $variant:snake _ $suffix >](
+        $( $arg_name: $crate::suberror_field_param!( $( @ $modifier )? $arg_type ), )*
+      ) -> $name {
+        $name::[< $variant:snake >]( $( $arg_name ),* )
+      }
+    ];
+  };
 }
 
+/// Internal macro used to define the free constructor functions of the
+/// `@free_fns` option, forwarding to the constructors of the main error type
 #[macro_export]
 #[doc(hidden)]
-macro_rules! define_error_detail_display {
-  ( @ctx[
-      @name( $name:ident )
-    ],
-    @suberrors{ $( $suberror:ident ),* } $(,)?
+macro_rules! define_error_free_fns {
+  ( @free_fns[],
+    $( $rest:tt )*
+  ) => {};
+  ( @free_fns[ $suffix:ident ],
+    @tracer( $tracer:ty ),
+    @name( $name:ident ),
+    @suberrors{
+      $(
+        $( #[$sub_attr:meta] )*
+        $suberror:ident $( as $alias:ident )* $( = $numeric_code:literal )?
+        $( { $( $( #[detail $field_opts:tt] )* $arg_name:ident : $( @ $modifier:ident )? $arg_type:ty ),* $(,)? } )?
+        $( [ $( $source:tt )+ ] )?
+        $( @ $option:ident $( ( $( $option_arg:tt )* ) )? )*
+        | $formatter_arg:pat | $formatter:expr
+      ),* $(,)?
+    } $(,)?
   ) => {
-    $crate::macros::paste! [
-      impl ::core::fmt::Display for [< $name Detail >] {
-        fn fmt(&self, f: &mut ::core::fmt::Formatter<'_>)
-          -> ::core::fmt::Result
-        {
-          match self {
-            $(
-              Self::$suberror( suberror ) => {
-                ::core::write!( f, "{}",  suberror )
-              }
-            ),*
-          }
+    $(
+      $crate::define_error_ctor_methods! {
+        @options[ $( @ $option ( $( $( $option_arg )* )? ) )* ],
+        @mode[ free $suffix [ pub ] ],
+        @tracer( $tracer ),
+        @name( $name ),
+        @variants[ $suberror $( , $alias )* ],
+        @args( $( $( $arg_name : $( @ $modifier )? $arg_type ),* )? )
+        $( @source[ $( $source )+ ] )?
+      }
+    )*
+  };
+}
+
+/// Internal macro used to implement `From` for the error sources of the
+/// sub-errors with the `@from` option. The sources are matched as raw
+/// tokens, so that the source types can be found by `suberror_from_source`.
+#[macro_export]
+#[doc(hidden)]
+macro_rules! define_error_from_impls {
+  ( @tracer( $tracer:ty ),
+    @detail_tracer $detail_tracer:tt,
+    @generic $generic:tt,
+    @name( $name:ident ),
+    @suberrors{
+      $(
+        $( #[$sub_attr:meta] )*
+        $suberror:ident $( as $alias:ident )* $( = $numeric_code:literal )?
+        $( { $( $( #[detail $field_opts:tt] )* $arg_name:ident : $( @ $modifier:ident )? $arg_type:ty ),* $(,)? } )?
+        $( [ $( $source:tt )+ ] )?
+        $( @ $option:ident $( ( $( $option_arg:tt )* ) )? )*
+        | $formatter_arg:pat | $formatter:expr
+      ),* $(,)?
+    } $(,)?
+  ) => {
+    $(
+      $crate::define_error_from! {
+        @options[ $( @ $option ( $( $( $option_arg )* )? ) )* ],
+        @tracer( $tracer ),
+        @detail_tracer $detail_tracer,
+        @generic $generic,
+        @name( $name ),
+        @suberror( $suberror ),
+        @args( $( $( $arg_name ),* )? )
+        $( @source[ $( $source )+ ] )?
+      }
+    )*
+  };
+}
+
+/// Internal macro used to implement `From` for the error source of a
+/// sub-error with the `@from` option, by calling its constructor function
+#[macro_export]
+#[doc(hidden)]
+macro_rules! define_error_from {
+  ( @options[ @from() $( $options:tt )* ],
+    $( $rest:tt )*
+  ) => {
+    $crate::define_error_from! {
+      @from
+      $( $rest )*
+    }
+  };
+  ( @options[ @ $option:ident $option_args:tt $( $options:tt )* ],
+    $( $rest:tt )*
+  ) => {
+    $crate::define_error_from! {
+      @options[ $( $options )* ],
+      $( $rest )*
+    }
+  };
+  ( @options[],
+    $( $rest:tt )*
+  ) => {};
+  ( @from
+    @tracer( $tracer:ty ),
+    @detail_tracer $detail_tracer:tt,
+    @generic $generic:tt,
+    @name( $name:ident ),
+    @suberror( $suberror:ident ),
+    @args()
+    @source[ Self ]
+  ) => {
+    ::core::compile_error!(::core::concat!(
+      "the `@from` option of the sub-error `", ::core::stringify!($suberror),
+      "` is not supported with a `[ Self ]` source"
+    ));
+  };
+  ( @from
+    @tracer( $tracer:ty ),
+    @detail_tracer $detail_tracer:tt,
+    @generic $generic:tt,
+    @name( $name:ident ),
+    @suberror( $suberror:ident ),
+    @args()
+    @source[ $source:ty, $source2:ty ]
+  ) => {
+    ::core::compile_error!(::core::concat!(
+      "the `@from` option of the sub-error `", ::core::stringify!($suberror),
+      "` is not supported with two error sources"
+    ));
+  };
+  ( @from
+    @tracer( $tracer:ty ),
+    @detail_tracer $detail_tracer:tt,
+    @generic $generic:tt,
+    @name( $name:ident ),
+    @suberror( $suberror:ident ),
+    @args()
+    @source[ $( $source:tt )+ ]
+  ) => {
+    $crate::define_error_from! {
+      @impl
+      @tracer( $tracer ),
+      @detail_tracer $detail_tracer,
+      @generic $generic,
+      @name( $name ),
+      @suberror( $suberror ),
+      @source( $( $source )+ ),
+      @from_source[ $( $source )+ ]
+    }
+  };
+  ( @from
+    @tracer( $tracer:ty ),
+    @detail_tracer $detail_tracer:tt,
+    @generic $generic:tt,
+    @name( $name:ident ),
+    @suberror( $suberror:ident ),
+    @args $args:tt
+    $( @source $source:tt )?
+  ) => {
+    ::core::compile_error!(::core::concat!(
+      "the `@from` option of the sub-error `", ::core::stringify!($suberror),
+      "` is only supported for sub-errors with an error source and no other fields"
+    ));
+  };
+  ( @impl
+    @tracer( $tracer:ty ),
+    @detail_tracer[ $( $detail_tracer:ty )? ],
+    @generic[ $( $generic:ident )? ],
+    @name( $name:ident ),
+    @suberror( $suberror:ident ),
+    @source( $source:ty ),
+    @from_source[ $( $from_source:tt )+ ]
+  ) => {
+    $crate::macros::paste! [
+      impl< $( $generic )? > ::core::convert::From< $crate::suberror_from_source!( $( $from_source )+ ) >
+        for $name< $( $generic )? >
+      where
+        $tracer: $crate::ErrorMessageTracer,
+        $source: $crate::ErrorSource<
+          $tracer,
+          Source = $crate::suberror_from_source!( $( $from_source )+ ),
+        >,
+        $(
+          $source: $crate::ErrorSource<
+            $tracer,
+            Detail = $crate::AsErrorDetail< $source, $detail_tracer >,
+          >,
+        )?
+      {
+        #[track_caller]
+        fn from(source: $crate::suberror_from_source!( $( $from_source )+ )) -> Self {
+          Self::[< $suberror:snake >](source)
         }
       }
     ];
-  }
+  };
 }
 
+/// Internal macro used to find the type of the values of an error source,
+/// i.e. the `ErrorSource::Source` type, from the tokens of the source.
+/// The associated type itself cannot be used, as `From` implementations
+/// for projections are rejected by the coherence check. Sources other than
+/// the ones provided by flex-error are assumed to be error types defined by
+/// `define_error!`, which are their own `Source` type.
 #[macro_export]
 #[doc(hidden)]
-macro_rules! define_suberrors {
-  ( @tracer($tracer:ty),
-    @attr[ $( $attr:meta ),* ],
-    @name($name:ident),
-    {} $(,)?
-  ) => { };
-  ( @tracer($tracer:ty),
-    @attr[ $( $attr:meta ),* ],
-    @name($name:ident),
-    {
-      $( #[$sub_attr:meta] )*
-      $suberror:ident
-        $( { $( $arg_name:ident : $arg_type:ty ),* $(,)? } )?
-        $( [ $source:ty ] )?
-        | $formatter_arg:pat | $formatter:expr
+macro_rules! suberror_from_source {
+  ( :: flex_error :: $( $source:tt )+ ) => {
+    $crate::suberror_from_source!( $( $source )+ )
+  };
+  ( flex_error :: $( $source:tt )+ ) => {
+    $crate::suberror_from_source!( $( $source )+ )
+  };
+  ( TraceError < $source:ty > ) => { $source };
+  ( TraceClone < $source:ty > ) => { $source };
+  ( DisplayError < $source:ty > ) => { $source };
+  ( DisplayOnly < $source:ty > ) => { $source };
+  ( DisplayChain < $source:ty > ) => { $source };
+  ( DetailOnly < $source:ty > ) => { $source };
+  ( TraceOnly < $source:ty > ) => { $source };
+  ( BoxSource < $source:ty > ) => { $source };
+  ( BoxedTraceError ) => {
+    ::std::boxed::Box< dyn ::std::error::Error + ::core::marker::Send + ::core::marker::Sync >
+  };
+  ( DynError ) => {
+    ::std::boxed::Box< dyn ::std::error::Error + ::core::marker::Send + ::core::marker::Sync >
+  };
+  ( DynErrorOnly ) => {
+    ::std::boxed::Box< dyn ::std::error::Error + ::core::marker::Send + ::core::marker::Sync >
+  };
+  ( $source:ty ) => { $source };
+}
 
-      $( , $($tail:tt)* )?
+/// Internal macro used to define the extension trait converting `None`
+/// values into the sub-errors with the `@from_none` option. The trait is
+/// only defined if at least one sub-error has the option.
+#[macro_export]
+#[doc(hidden)]
+macro_rules! define_error_option_ext {
+  ( @name( $name:ident ),
+    @suberrors{
+      $(
+        $( #[$sub_attr:meta] )*
+        $suberror:ident $( as $alias:ident )* $( = $numeric_code:literal )?
+        $( { $( $( #[detail $field_opts:tt] )* $arg_name:ident : $( @ $modifier:ident )? $arg_type:ty ),* $(,)? } )?
+        $( [ $( $source:tt )+ ] )?
+        $( @ $option:ident $( ( $( $option_arg:tt )* ) )? )*
+        | $formatter_arg:pat | $formatter:expr
+      ),* $(,)?
+    } $(,)?
+  ) => {
+    $crate::define_error_option_ext! {
+      @name( $name ),
+      @methods[],
+      @pending[
+        $( {
+          @options[ $( @ $option ( $( $( $option_arg )* )? ) )* ],
+          @suberror( $suberror ),
+          @args( $( $( $arg_name ),* )? )
+          $( @source[ $( $source )+ ] )?
+        } )*
+      ]
     }
+  };
+  ( @name( $name:ident ),
+    @methods[ $( $methods:tt )* ],
+    @pending[
+      { @options[ @from_none() $( $options:tt )* ], $( $method:tt )* }
+      $( $pending:tt )*
+    ]
   ) => {
-    $crate::macros::paste![
-      $crate::define_suberror! {
-        @tracer( $tracer ),
-        @attr[ $( $attr ),* ],
-        @sub_attr[ $( $sub_attr ),* ],
-        @name( $name ),
-        @suberror( $suberror ),
-        @args( $( $( $arg_name : $arg_type ),* )? )
-        $( @source[ $source ] )?
+    $crate::define_error_option_ext! {
+      @name( $name ),
+      @methods[ $( $methods )* { $( $method )* } ],
+      @pending[ $( $pending )* ]
+    }
+  };
+  ( @name( $name:ident ),
+    @methods[ $( $methods:tt )* ],
+    @pending[
+      { @options[ @ $option:ident $option_args:tt $( $options:tt )* ], $( $method:tt )* }
+      $( $pending:tt )*
+    ]
+  ) => {
+    $crate::define_error_option_ext! {
+      @name( $name ),
+      @methods[ $( $methods )* ],
+      @pending[ { @options[ $( $options )* ], $( $method )* } $( $pending )* ]
+    }
+  };
+  ( @name( $name:ident ),
+    @methods[ $( $methods:tt )* ],
+    @pending[ { @options[], $( $method:tt )* } $( $pending:tt )* ]
+  ) => {
+    $crate::define_error_option_ext! {
+      @name( $name ),
+      @methods[ $( $methods )* ],
+      @pending[ $( $pending )* ]
+    }
+  };
+  ( @name( $name:ident ),
+    @methods[],
+    @pending[]
+  ) => {};
+  ( @name( $name:ident ),
+    @methods[ $( { $( $method:tt )* } )+ ],
+    @pending[]
+  ) => {
+    $crate::macros::paste! [
+      #[doc = ::core::concat!(
+        "Conversions of missing values into [`", ::core::stringify!($name), "`], ",
+        "with one method per sub-error with the `@from_none` option.")]
+      pub trait [< $name OptionExt >]<T>: Sized {
+        $(
+          $crate::define_error_option_method! {
+            @mode[ trait ],
+            @name( $name ),
+            $( $method )*
+          }
+        )+
       }
 
-      impl ::core::fmt::Display for [< $suberror Subdetail >] {
-        fn fmt(&self, f: &mut ::core::fmt::Formatter<'_>) -> ::core::fmt::Result {
-          use ::core::format_args;
-          let $formatter_arg = self;
-          ::core::write!(f, "{}",  $formatter)
-        }
+      impl<T> [< $name OptionExt >]<T> for ::core::option::Option<T> {
+        $(
+          $crate::define_error_option_method! {
+            @mode[ option ],
+            @name( $name ),
+            $( $method )*
+          }
+        )+
       }
 
-      impl $name {
-        $crate::define_error_constructor! {
-          @tracer( $tracer ),
-          @name( $name ),
-          @suberror( $suberror ),
-          @args( $( $( $arg_name : $arg_type ),* )? )
-          $( @source[ $source ] )?
-        }
+      impl<T> [< $name OptionExt >]<T>
+        for ::core::result::Result< ::core::option::Option<T>, $name >
+      {
+        $(
+          $crate::define_error_option_method! {
+            @mode[ result ],
+            @name( $name ),
+            $( $method )*
+          }
+        )+
       }
     ];
-
-    $crate::define_suberrors! {
-      @tracer($tracer),
-      @attr[ $( $attr ),* ],
-      @name($name),
-      { $( $( $tail )* )? }
-    }
   };
 }
 
-/// Internal macro used to define suberror structs
+/// Internal macro used to define a method of the extension trait for
+/// a sub-error with the `@from_none` option, or its implementations by
+/// `Option<T>` and `Result<Option<T>, _>`.
 #[macro_export]
 #[doc(hidden)]
-macro_rules! define_suberror {
-  ( @tracer( $tracer:ty ),
-    @attr[ $( $attr:meta ),* ],
-    @sub_attr[ $( $sub_attr:meta ),* ],
+macro_rules! define_error_option_method {
+  ( @mode[ trait ],
     @name( $name:ident ),
     @suberror( $suberror:ident ),
-    @args( $( $arg_name:ident: $arg_type:ty ),* )
-    @source[ Self ]
+    @args $args:tt
+    @source $source:tt
+  ) => {
+    ::core::compile_error!(::core::concat!(
+      "the `@from_none` option of the sub-error `", ::core::stringify!($suberror),
+      "` is only supported for sub-errors without an error source"
+    ));
+  };
+  ( @mode $mode:tt,
+    @name( $name:ident ),
+    @suberror( $suberror:ident ),
+    @args $args:tt
+    @source $source:tt
+  ) => {};
+  ( @mode[ trait ],
+    @name( $name:ident ),
+    @suberror( $suberror:ident ),
+    @args( $( $arg_name:ident ),* )
   ) => {
     $crate::macros::paste! [
-      $( #[ $attr ] )*
-      $( #[ $sub_attr ] )*
-      pub struct [< $suberror Subdetail >] {
-        $( pub $arg_name: $arg_type, )*
-        pub source: $crate::alloc::boxed::Box< [< $name Detail >] >
+      #[doc = ::core::concat!(
+        "Returns the value, or the `", ::core::stringify!($suberror), "` error ",
+        "with the sub-detail returned by `detail` if the value is missing.")]
+      fn [< ok_or_ $suberror:snake >](
+        self,
+        detail: impl ::core::ops::FnOnce() -> [< $suberror Subdetail >],
+      ) -> ::core::result::Result<T, $name>;
+    ];
+  };
+  ( @mode[ option ],
+    @name( $name:ident ),
+    @suberror( $suberror:ident ),
+    @args( $( $arg_name:ident ),* )
+  ) => {
+    $crate::macros::paste! [
+      #[track_caller]
+      fn [< ok_or_ $suberror:snake >](
+        self,
+        detail: impl ::core::ops::FnOnce() -> [< $suberror Subdetail >],
+      ) -> ::core::result::Result<T, $name> {
+        match self {
+          ::core::option::Option::Some(value) => ::core::result::Result::Ok(value),
+          ::core::option::Option::None => {
+            let [< $suberror Subdetail >] { $( $arg_name ),* } = detail();
+            ::core::result::Result::Err($name::[< $suberror:snake >]( $( $arg_name ),* ))
+          }
+        }
       }
     ];
   };
-  ( @tracer( $tracer:ty ),
-    @attr[ $( $attr:meta ),* ],
-    @sub_attr[ $( $sub_attr:meta ),* ],
+  ( @mode[ result ],
     @name( $name:ident ),
     @suberror( $suberror:ident ),
-    @args( $( $arg_name:ident: $arg_type:ty ),* )
-    $( @source[ $source:ty ] )?
+    @args( $( $arg_name:ident ),* )
   ) => {
     $crate::macros::paste! [
-      $( #[ $attr ] )*
-      $( #[ $sub_attr ] )*
-      pub struct [< $suberror Subdetail >] {
-        $( pub $arg_name: $arg_type, )*
-        $( pub source: $crate::AsErrorDetail<$source, $tracer> )?
+      #[track_caller]
+      fn [< ok_or_ $suberror:snake >](
+        self,
+        detail: impl ::core::ops::FnOnce() -> [< $suberror Subdetail >],
+      ) -> ::core::result::Result<T, $name> {
+        match self {
+          ::core::result::Result::Ok(value) => value.[< ok_or_ $suberror:snake >](detail),
+          ::core::result::Result::Err(error) => ::core::result::Result::Err(error),
+        }
       }
     ];
   };
 }
 
+/// Internal macro used to define suberror constructor functions
+/// for a suberror and its variant aliases
+#[macro_export]
+#[doc(hidden)]
+macro_rules! define_error_constructors {
+  ( @options[ @no_constructor() $( $options:tt )* ],
+    $( $rest:tt )*
+  ) => {};
+  ( @options[ @const() $( $options:tt )* ],
+    $( $rest:tt )*
+  ) => {
+    ::core::compile_error!(
+      "the `@const` option is only supported in the `@detail_only` mode, \
+       as error traces cannot be created in const contexts"
+    );
+  };
+  ( @options[ @attach( $( $kind:ident ),* $(,)? ) $( $options:tt )* ],
+    @attach[ $( $kinds:ident )* ],
+    $( $rest:tt )*
+  ) => {
+    $crate::define_error_constructors! {
+      @options[ $( $options )* ],
+      @attach[ $( $kinds )* $( $kind )* ],
+      $( $rest )*
+    }
+  };
+  ( @options[ @static_msg() $( $options:tt )* ],
+    @attach $attach:tt,
+    @static_msg $static_msg:tt,
+    $( $rest:tt )*
+  ) => {
+    $crate::define_error_constructors! {
+      @options[ $( $options )* ],
+      @attach $attach,
+      @static_msg[ static ],
+      $( $rest )*
+    }
+  };
+  ( @options[ @vis( $( $vis:tt )* ) $( $options:tt )* ],
+    @attach $attach:tt,
+    @static_msg $static_msg:tt,
+    @vis $old_vis:tt,
+    $( $rest:tt )*
+  ) => {
+    $crate::define_error_constructors! {
+      @options[ $( $options )* ],
+      @attach $attach,
+      @static_msg $static_msg,
+      @vis[ $( $vis )* ],
+      $( $rest )*
+    }
+  };
+  ( @options[ @ $option:ident $option_args:tt $( $options:tt )* ],
+    $( $rest:tt )*
+  ) => {
+    $crate::define_error_constructors! {
+      @options[ $( $options )* ],
+      $( $rest )*
+    }
+  };
+  ( @options[],
+    $( $rest:tt )*
+  ) => {
+    $crate::define_error_constructors! {
+      $( $rest )*
+    }
+  };
+  ( @attach $attach:tt,
+    @static_msg $static_msg:tt,
+    @vis $vis:tt,
+    @tracer( $tracer:ty ),
+    @detail_tracer $detail_tracer:tt,
+    @name( $name:ident ),
+    @suberror( $suberror:ident ),
+    @variants[],
+    @args $args:tt
+    $( @source $source:tt )?
+  ) => {};
+  ( @attach $attach:tt,
+    @static_msg[ static ],
+    @vis $vis:tt,
+    @tracer( $tracer:ty ),
+    @detail_tracer $detail_tracer:tt,
+    @name( $name:ident ),
+    @suberror( $suberror:ident ),
+    @variants $variants:tt,
+    @args $args:tt
+    @source $source:tt
+  ) => {
+    ::core::compile_error!(::core::concat!(
+      "the `@static_msg` option of the sub-error `", ::core::stringify!($suberror),
+      "` is only supported for sub-errors without an error source"
+    ));
+  };
+  ( @attach $attach:tt,
+    @static_msg $static_msg:tt,
+    @vis $vis:tt,
+    @tracer( $tracer:ty ),
+    @detail_tracer $detail_tracer:tt,
+    @name( $name:ident ),
+    @suberror( $suberror:ident ),
+    @variants[ $variant:ident $( , $rest:ident )* ],
+    @args $args:tt
+    $( @source $source:tt )?
+  ) => {
+    $crate::define_error_constructor! {
+      @attach $attach,
+      @static_msg $static_msg,
+      @vis $vis,
+      @tracer( $tracer ),
+      @detail_tracer $detail_tracer,
+      @name( $name ),
+      @suberror( $suberror ),
+      @variant( $variant ),
+      @args $args
+      $( @source $source )?
+    }
+
+    $crate::define_error_constructors! {
+      @attach $attach,
+      @static_msg $static_msg,
+      @vis $vis,
+      @tracer( $tracer ),
+      @detail_tracer $detail_tracer,
+      @name( $name ),
+      @suberror( $suberror ),
+      @variants[ $( $rest ),* ],
+      @args $args
+      $( @source $source )?
+    }
+  };
+}
+
+// attach the environment snapshots to the errors constructed for the
+// sub-errors with the `@attach` option only when the `attachments`
+// feature is enabled
+#[cfg(feature = "attachments")]
+#[macro_export]
+#[doc(hidden)]
+macro_rules! attach_snapshots {
+  ( @attach[], @ $form:ident $value:expr ) => {
+    $value
+  };
+  ( @attach[ $( $kind:ident )+ ], @trace $trace:expr ) => {
+    $crate::attach::attach( $trace, &[ $( ::core::stringify!( $kind ) ),+ ] )
+  };
+  ( @attach[ $( $kind:ident )+ ], @error $error:expr ) => {
+    ( $error ).map_trace(| trace | {
+      $crate::attach::attach( trace, &[ $( ::core::stringify!( $kind ) ),+ ] )
+    })
+  };
+}
+
+#[cfg(not(feature = "attachments"))]
+#[macro_export]
+#[doc(hidden)]
+macro_rules! attach_snapshots {
+  ( @attach $attach:tt, @ $form:ident $value:expr ) => {
+    $value
+  };
+}
+
 /// Internal macro used to define suberror constructor functions
 #[macro_export]
 #[doc(hidden)]
 macro_rules! define_error_constructor {
-  ( @tracer( $tracer:ty ),
+  ( @attach $attach:tt,
+    @static_msg $static_msg:tt,
+    @vis[ $( $vis:tt )* ],
+    @tracer( $tracer:ty ),
+    @detail_tracer $detail_tracer:tt,
     @name( $name:ident ),
     @suberror( $suberror:ident ),
-    @args( $( $arg_name:ident: $arg_type:ty ),* ) $(,)?
+    @variant( $variant:ident ),
+    @args( $( $arg_name:ident: $( @ $modifier:ident )? $arg_type:ty ),* ) $(,)?
   ) => {
     $crate::macros::paste! [
-      pub fn [< $suberror:snake >](
-        $( $arg_name: $arg_type, )*
-      ) -> $name
+      #[track_caller]
+      $( $vis )* fn [< $variant:snake >](
+        $( $arg_name: $crate::suberror_field_param!( $( @ $modifier )? $arg_type ), )*
+      ) -> Self
       {
-        let detail = [< $name Detail >]::$suberror([< $suberror Subdetail >] {
+        $( let $arg_name = $crate::suberror_field_value!( $( @ $modifier )? $arg_name ); )*
+
+        $crate::define_error_injection! {
+          @name( $name ),
+          @tracer( $tracer )
+        }
+
+        $crate::define_error_stat! {
+          @name( $name ),
+          @suberror( $variant )
+        }
+
+        let subdetail = [< $suberror Subdetail >] {
           $( $arg_name, )*
-        });
+        };
 
-        let trace = < $tracer as $crate::ErrorMessageTracer >::new_message(&detail);
-        $name(detail, trace)
+        let metadata = Self::capture_error_metadata(&[]);
+        let trace = $crate::suberror_new_trace!(
+          @static_msg $static_msg,
+          @tracer( $tracer ),
+          @subdetail( subdetail )
+        );
+        let detail = [< $name Detail >]::$variant(subdetail);
+        let trace = detail.add_trace_fields(trace);
+        let trace = $crate::attach_snapshots!( @attach $attach, @trace trace );
+        Self::construct_error(detail, trace, metadata)
       }
     ];
   };
-  ( @tracer( $tracer:ty ),
+  ( @attach $attach:tt,
+    @static_msg $static_msg:tt,
+    @vis[ $( $vis:tt )* ],
+    @tracer( $tracer:ty ),
+    @detail_tracer $detail_tracer:tt,
     @name( $name:ident ),
     @suberror( $suberror:ident ),
-    @args( $( $arg_name:ident: $arg_type:ty ),* )
+    @variant( $variant:ident ),
+    @args( $( $arg_name:ident: $( @ $modifier:ident )? $arg_type:ty ),* )
     @source[ Self ]
   ) => {
     $crate::macros::paste! [
-      pub fn [< $suberror:snake >](
-        $( $arg_name: $arg_type, )*
-        source: $name
-      ) -> $name
+      $( $vis )* fn [< $variant:snake >](
+        $( $arg_name: $crate::suberror_field_param!( $( @ $modifier )? $arg_type ), )*
+        source: Self
+      ) -> Self
       {
-        let detail = [< $name Detail >]::$suberror([< $suberror Subdetail >] {
+        $( let $arg_name = $crate::suberror_field_value!( $( @ $modifier )? $arg_name ); )*
+
+        $crate::define_error_injection! {
+          @name( $name ),
+          @tracer( $tracer )
+        }
+
+        $crate::define_error_stat! {
+          @name( $name ),
+          @suberror( $variant )
+        }
+
+        $crate::define_error_edge! {
+          @name( $name ),
+          @suberror( $variant ),
+          @source( $name )
+        }
+
+        let metadata = Self::capture_error_metadata(&[source.error_metadata()]);
+        let (source_detail, source_trace, _) = source.into_error_parts();
+        let detail = [< $name Detail >]::$variant([< $suberror Subdetail >] {
           $( $arg_name, )*
-          source: Box::new(source.0),
+          source: $crate::alloc::boxed::Box::new(source_detail),
         });
 
-        let trace = source.1.add_message(&detail);
+        let trace = $crate::ErrorMessageTracer::add_message(
+          source_trace, &$crate::TraceDisplayed(&detail));
+        let trace = detail.add_trace_fields(trace);
+        let trace = $crate::attach_snapshots!( @attach $attach, @trace trace );
 
-        $name(detail, trace)
+        Self::construct_error(detail, trace, metadata)
       }
     ];
   };
-  ( @tracer( $tracer:ty ),
+  ( @attach $attach:tt,
+    @static_msg $static_msg:tt,
+    @vis[ $( $vis:tt )* ],
+    @tracer( $tracer:ty ),
+    @detail_tracer[ $( $detail_tracer:ty )? ],
     @name( $name:ident ),
     @suberror( $suberror:ident ),
-    @args( $( $arg_name:ident: $arg_type:ty ),* )
+    @variant( $variant:ident ),
+    @args( $( $arg_name:ident: $( @ $modifier:ident )? $arg_type:ty ),* )
+    @source[ $source:ty, $source2:ty ]
+  ) => {
+    $crate::macros::paste! [
+      #[track_caller]
+      $( $vis )* fn [< $variant:snake >](
+        $( $arg_name: $crate::suberror_field_param!( $( @ $modifier )? $arg_type ), )*
+        source1: $crate::AsErrorSource< $source, $tracer >,
+        source2: $crate::AsErrorSource< $source2, $tracer >
+      ) -> Self
+      where
+        $tracer: $crate::TraceRender,
+        $(
+          // only bound in the generic tracer mode, where the detail
+          // types are defined with the default tracer
+          $source: $crate::ErrorSource<
+            $tracer,
+            Detail = $crate::AsErrorDetail< $source, $detail_tracer >,
+          >,
+          $source2: $crate::ErrorSource<
+            $tracer,
+            Detail = $crate::AsErrorDetail< $source2, $detail_tracer >,
+          >,
+        )?
+      {
+        $( let $arg_name = $crate::suberror_field_value!( $( @ $modifier )? $arg_name ); )*
+
+        $crate::define_error_injection! {
+          @name( $name ),
+          @tracer( $tracer )
+        }
+
+        $crate::define_error_stat! {
+          @name( $name ),
+          @suberror( $variant )
+        }
+
+        $crate::define_error_edge! {
+          @name( $name ),
+          @suberror( $variant ),
+          @source( $source )
+        }
+
+        $crate::define_error_edge! {
+          @name( $name ),
+          @suberror( $variant ),
+          @source( $source2 )
+        }
+
+        let error = Self::trace_from_both::<$source, $source2, _>(source1, source2,
+          | source1, source2 | {
+            [< $name Detail >]::$variant([< $suberror Subdetail >] {
+              $( $arg_name, )*
+              source1,
+              source2,
+            })
+          });
+        let (detail, trace, metadata) = error.into_error_parts();
+        let trace = detail.add_trace_fields(trace);
+        $crate::attach_snapshots!( @attach $attach, @error Self::from_error_parts(detail, trace, metadata) )
+      }
+    ];
+  };
+  ( @attach $attach:tt,
+    @static_msg $static_msg:tt,
+    @vis[ $( $vis:tt )* ],
+    @tracer( $tracer:ty ),
+    @detail_tracer[ $( $detail_tracer:ty )? ],
+    @name( $name:ident ),
+    @suberror( $suberror:ident ),
+    @variant( $variant:ident ),
+    @args( $( $arg_name:ident: $( @ $modifier:ident )? $arg_type:ty ),* )
     @source[ $source:ty ]
   ) => {
     $crate::macros::paste! [
-      pub fn [< $suberror:snake >](
-        $( $arg_name: $arg_type, )*
+      #[track_caller]
+      $( $vis )* fn [< $variant:snake >](
+        $( $arg_name: $crate::suberror_field_param!( $( @ $modifier )? $arg_type ), )*
         source: $crate::AsErrorSource< $source, $tracer >
-      ) -> $name
+      ) -> Self
+      where
+        $(
+          // only bound in the generic tracer mode, where the detail
+          // types are defined with the default tracer
+          $source: $crate::ErrorSource<
+            $tracer,
+            Detail = $crate::AsErrorDetail< $source, $detail_tracer >,
+          >,
+        )?
       {
-        $name::trace_from::<$source, _>(source,
+        $( let $arg_name = $crate::suberror_field_value!( $( @ $modifier )? $arg_name ); )*
+
+        $crate::define_error_injection! {
+          @name( $name ),
+          @tracer( $tracer )
+        }
+
+        $crate::define_error_stat! {
+          @name( $name ),
+          @suberror( $variant )
+        }
+
+        $crate::define_error_edge! {
+          @name( $name ),
+          @suberror( $variant ),
+          @source( $source )
+        }
+
+        let error = Self::trace_from::<$source, _>(source,
           | source_detail | {
-            [< $name Detail >]::$suberror([< $suberror Subdetail >] {
+            [< $name Detail >]::$variant([< $suberror Subdetail >] {
               $( $arg_name, )*
               source: source_detail,
             })
-          })
+          });
+        let (detail, trace, metadata) = error.into_error_parts();
+        let trace = detail.add_trace_fields(trace);
+        $crate::attach_snapshots!( @attach $attach, @error Self::from_error_parts(detail, trace, metadata) )
       }
     ];
   };
 }
+
+/// Internal macro used to create the error trace of a sub-error without
+/// an error source, from its static message if it has the `@static_msg`
+/// option, or from its formatted message otherwise.
+#[macro_export]
+#[doc(hidden)]
+macro_rules! suberror_new_trace {
+  ( @static_msg[],
+    @tracer( $tracer:ty ),
+    @subdetail( $subdetail:ident )
+  ) => {
+    < $tracer as $crate::ErrorMessageTracer >::new_message(
+      &$crate::TraceDisplayed(&$subdetail))
+  };
+  ( @static_msg[ static ],
+    @tracer( $tracer:ty ),
+    @subdetail( $subdetail:ident )
+  ) => {
+    < $tracer as $crate::ErrorMessageTracer >::new_static( $subdetail.static_message() )
+  };
+}
@@ -0,0 +1,187 @@
+/*!
+  Structured error sources for parser errors, keeping the location of the
+  parse failure instead of flattening it into a string.
+
+  A [`ParseError`] is built from the parser error and the original input,
+  with either the byte offset of the failure, or the remaining input at
+  the point of failure as returned by parser combinator libraries such as
+  `nom`:
+
+  ```
+  # use flex_error::define_error;
+  # use flex_error::parse::{ParseError, ParseSource};
+  # mod nom {
+  #   pub enum Err<E> { Incomplete(usize), Error(E), Failure(E) }
+  #   pub mod error {
+  #     #[derive(Debug, Clone, Copy, PartialEq, Eq)]
+  #     pub enum ErrorKind { Tag }
+  #     impl std::fmt::Display for ErrorKind {
+  #       fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+  #         write!(f, "error Tag")
+  #       }
+  #     }
+  #     pub struct Error<I> { pub input: I, pub code: ErrorKind }
+  #   }
+  # }
+  # fn parse_packet(input: &str) -> Result<(&str, &str), nom::Err<nom::error::Error<&str>>> {
+  #   let (_, rest) = input.split_at(input.find('\n').map_or(0, |i| i + 1));
+  #   Err(nom::Err::Error(nom::error::Error { input: rest, code: nom::error::ErrorKind::Tag }))
+  # }
+  # let input = "PACKET\n?";
+  define_error! {
+    CodecError {
+      Parse
+        [ ParseSource<nom::error::ErrorKind> ]
+        | _ | { "failed to parse packet" },
+      # Incomplete
+      #   | _ | { "incomplete packet" },
+    }
+  }
+
+  let err = match parse_packet(input) {
+    Err(nom::Err::Error(e)) | Err(nom::Err::Failure(e)) =>
+      CodecError::parse(ParseError::from_remaining(e.code, input, e.input)),
+    Err(nom::Err::Incomplete(_)) => CodecError::incomplete(),
+    # Ok(_) => unreachable!(),
+  };
+  # match err.detail() {
+  #   CodecErrorDetail::Parse(e) => assert_eq!((e.source.span.line, e.source.span.column), (2, 1)),
+  #   _ => unreachable!(),
+  # }
+  ```
+*/
+
+use alloc::string::String;
+use core::fmt::{Display, Formatter};
+use core::marker::PhantomData;
+
+use crate::source::ErrorSource;
+use crate::tracer::ErrorMessageTracer;
+
+/// The location of a parse failure in the original input.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SourceSpan {
+    /// The byte offset of the failure in the input.
+    pub offset: usize,
+
+    /// The 1-based line number of the failure.
+    pub line: usize,
+
+    /// The 1-based column number of the failure, counted in characters.
+    pub column: usize,
+
+    /// The length in bytes of the offending span, at least 1.
+    pub len: usize,
+
+    /// The line in the input that contains the failure.
+    pub snippet: String,
+}
+
+impl SourceSpan {
+    /// Locates the span of length `len` starting at byte `offset` in `input`.
+    /// The offset is clamped to the input length and to a character boundary.
+    pub fn locate(input: &str, offset: usize, len: usize) -> Self {
+        let mut offset = offset.min(input.len());
+        while !input.is_char_boundary(offset) {
+            offset -= 1;
+        }
+
+        let line_start = input[..offset].rfind('\n').map_or(0, |i| i + 1);
+        let line_end = input[offset..]
+            .find('\n')
+            .map_or(input.len(), |i| offset + i);
+
+        SourceSpan {
+            offset,
+            line: input[..offset].matches('\n').count() + 1,
+            column: input[line_start..offset].chars().count() + 1,
+            len: len.max(1),
+            snippet: String::from(&input[line_start..line_end]),
+        }
+    }
+
+    /// Returns a wrapper that displays the snippet with the offending
+    /// span underlined with `^` on the next line.
+    pub fn underline(&self) -> Underline<'_> {
+        Underline(self)
+    }
+}
+
+/// Displays a [`SourceSpan`] with the offending span underlined.
+pub struct Underline<'a>(&'a SourceSpan);
+
+impl<'a> Display for Underline<'a> {
+    fn fmt(&self, f: &mut Formatter<'_>) -> core::fmt::Result {
+        let span = self.0;
+        // The fields are public, so the span may have been built with a
+        // zero column or length instead of with `SourceSpan::locate`.
+        let column = span.column.saturating_sub(1);
+        let width = span
+            .snippet
+            .chars()
+            .skip(column)
+            .count()
+            .max(1)
+            .min(span.len.max(1));
+
+        writeln!(f, "{}", span.snippet)?;
+        write!(f, "{0:1$}{2:^<3$}", "", column, "", width)
+    }
+}
+
+/// A parser error of type `E`, together with the [`SourceSpan`]
+/// where the failure occurred.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ParseError<E> {
+    pub error: E,
+    pub span: SourceSpan,
+}
+
+impl<E> ParseError<E> {
+    /// Creates a parse error that occurred at byte `offset` of `input`.
+    pub fn new(error: E, input: &str, offset: usize) -> Self {
+        ParseError {
+            error,
+            span: SourceSpan::locate(input, offset, 1),
+        }
+    }
+
+    /// Creates a parse error from the `remaining` input at the point of
+    /// failure, which must be a suffix of `input`.
+    pub fn from_remaining(error: E, input: &str, remaining: &str) -> Self {
+        let offset = input.len().saturating_sub(remaining.len());
+        Self::new(error, input, offset)
+    }
+}
+
+impl<E: Display> Display for ParseError<E> {
+    fn fmt(&self, f: &mut Formatter<'_>) -> core::fmt::Result {
+        write!(
+            f,
+            "{} at line {}, column {}",
+            self.error, self.span.line, self.span.column
+        )
+    }
+}
+
+/// An [`ErrorSource`] for parser errors, which keeps the [`ParseError`]
+/// with its location as detail, and traces its `Display` message.
+pub struct ParseSource<E>(PhantomData<E>);
+
+impl<E, Tracer> ErrorSource<Tracer> for ParseSource<E>
+where
+    E: Display,
+    Tracer: ErrorMessageTracer,
+{
+    type Detail = ParseError<E>;
+    type Source = ParseError<E>;
+
+    fn error_details(source: Self::Source) -> (Self::Detail, Option<Tracer>) {
+        let trace = Tracer::new_message(&source);
+        (source, Some(trace))
+    }
+
+    fn detail_display(detail: &Self::Detail) -> Option<&dyn Display> {
+        Some(detail)
+    }
+}
@@ -0,0 +1,149 @@
+/*!
+  Declarative policies for deciding how to handle errors based on their kinds.
+
+  For each error type `MyError`, [`define_error!`](crate::define_error) generates
+  a `MyErrorKind` enum with one fieldless variant per sub-error, which is returned
+  by the `kind` method of both `MyError` and `MyErrorDetail`. A [`Policy`] maps
+  these kinds to a [`Decision`]:
+
+  ```
+  # use flex_error::policy::{Decision, Policy};
+  # flex_error::define_error! {
+  #   FooError {
+  #     Timeout
+  #       | _ | { "timed out" },
+  #     NotFound
+  #       | _ | { "not found" },
+  #     Corrupted
+  #       | _ | { "corrupted data" },
+  #   }
+  # }
+  # let err = FooError::timeout();
+  let policy = Policy::new()
+    .retry(FooErrorKind::Timeout)
+    .ignore(FooErrorKind::NotFound);
+
+  match policy.apply(&err) {
+    Decision::Retry => println!("retrying after error: {:#}", err),
+    Decision::Ignore => {}
+    Decision::Escalate => panic!("unrecoverable error: {:#}", err),
+  }
+  # assert_eq!(policy.apply(&FooError::corrupted()), Decision::Escalate);
+  ```
+*/
+
+#[cfg(feature = "alloc")]
+use alloc::vec::Vec;
+
+/// The decision made by a [`Policy`] for an error.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Decision {
+    /// The operation that raised the error should be retried.
+    Retry,
+
+    /// The error can be safely ignored.
+    Ignore,
+
+    /// The error should be propagated to the caller.
+    Escalate,
+}
+
+/// The class given to a sub-error with the `@transient` or `@fatal` option
+/// of [`define_error!`](crate::define_error), which is returned by the
+/// `class` method of the error and error detail types.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum ErrorClass {
+    /// The error is expected to go away when the operation is retried,
+    /// such as a timeout or a connection reset.
+    Transient,
+
+    /// The error cannot be recovered from, and the operation should not
+    /// be retried.
+    Fatal,
+
+    /// The sub-error has neither the `@transient` nor the `@fatal` option.
+    Unclassified,
+}
+
+/// Implemented by the error and error detail types defined by
+/// [`define_error!`](crate::define_error), which have a generated kind enum.
+pub trait HasErrorKind {
+    /// The kind enum of the error.
+    type Kind: Copy + Eq;
+
+    /// Returns the kind of the error.
+    fn kind(&self) -> Self::Kind;
+}
+
+/// A policy mapping error kinds to [`Decision`]s.
+///
+/// When the same kind is given multiple times, the last decision given for
+/// it is used. Kinds that are not given are mapped to the fallback decision,
+/// which is [`Decision::Escalate`] by default.
+#[cfg(feature = "alloc")]
+#[derive(Debug, Clone)]
+pub struct Policy<Kind> {
+    rules: Vec<(Kind, Decision)>,
+    fallback: Decision,
+}
+
+#[cfg(feature = "alloc")]
+impl<Kind: Copy + Eq> Policy<Kind> {
+    pub fn new() -> Self {
+        Policy {
+            rules: Vec::new(),
+            fallback: Decision::Escalate,
+        }
+    }
+
+    /// Retries errors of the given kind.
+    pub fn retry(self, kind: Kind) -> Self {
+        self.decide(kind, Decision::Retry)
+    }
+
+    /// Ignores errors of the given kind.
+    pub fn ignore(self, kind: Kind) -> Self {
+        self.decide(kind, Decision::Ignore)
+    }
+
+    /// Escalates errors of the given kind.
+    pub fn escalate(self, kind: Kind) -> Self {
+        self.decide(kind, Decision::Escalate)
+    }
+
+    /// Uses the given decision for errors of the given kind.
+    pub fn decide(mut self, kind: Kind, decision: Decision) -> Self {
+        self.rules.push((kind, decision));
+        self
+    }
+
+    /// Uses the given decision for the kinds that are not given
+    /// in the policy.
+    pub fn otherwise(self, fallback: Decision) -> Self {
+        Policy { fallback, ..self }
+    }
+
+    /// Returns the decision of the policy for the given error kind.
+    pub fn decision(&self, kind: Kind) -> Decision {
+        self.rules
+            .iter()
+            .rev()
+            .find(|(rule_kind, _)| *rule_kind == kind)
+            .map_or(self.fallback, |(_, decision)| *decision)
+    }
+
+    /// Returns the decision of the policy for the given error.
+    pub fn apply<E>(&self, err: &E) -> Decision
+    where
+        E: HasErrorKind<Kind = Kind> + ?Sized,
+    {
+        self.decision(err.kind())
+    }
+}
+
+#[cfg(feature = "alloc")]
+impl<Kind: Copy + Eq> Default for Policy<Kind> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
@@ -0,0 +1,147 @@
+/*!
+  Rendering of errors as HTTP problem details, as defined in
+  [RFC 7807](https://www.rfc-editor.org/rfc/rfc7807).
+
+  When the `problem_details` feature is enabled, the problem type of each
+  sub-error is given with the `@problem` option, with an HTTP status code,
+  and optionally a title and a type URI:
+
+  ```
+  # use flex_error::define_error;
+  define_error! {
+    MyError {
+      NotFound
+        { id: u64 }
+        @problem(status = 404, title = "Resource not found", type = "/problems/not-found")
+        | e | { format_args!("no resource with id {}", e.id) },
+      Internal
+        | _ | { "internal error" },
+    }
+  }
+  # let problem = MyError::not_found(7).to_problem_details();
+  # assert_eq!((problem.status, problem.type_uri.as_str()), (404, "/problems/not-found"));
+  # assert_eq!(MyError::internal().to_problem_details().status, 500);
+  ```
+
+  The problem details are then returned by the generated
+  `MyError::to_problem_details` method, with the `detail` member holding
+  the error detail message, and the `trace` extension member holding the
+  messages in the error trace. Sub-errors without the `@problem` option
+  are rendered with status 500. If no type URI is given, `about:blank`
+  is used, and the title defaults to the reason phrase of the status code.
+
+  With the `serde` feature, [`ProblemDetails`] implements `Serialize`, and
+  can be used as an `application/problem+json` response body. The `trace`
+  member is omitted when the trace is empty.
+*/
+
+use alloc::string::{String, ToString};
+use alloc::vec::Vec;
+
+/// The media type of problem details serialized as JSON.
+pub const CONTENT_TYPE: &str = "application/problem+json";
+
+/// The problem type of a sub-error, as given with the `@problem` option.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ProblemType {
+    pub status: u16,
+    pub title: Option<&'static str>,
+    pub type_uri: Option<&'static str>,
+}
+
+impl ProblemType {
+    pub const fn new(status: u16) -> Self {
+        ProblemType {
+            status,
+            title: None,
+            type_uri: None,
+        }
+    }
+
+    pub const fn with_title(self, title: &'static str) -> Self {
+        ProblemType {
+            title: Some(title),
+            ..self
+        }
+    }
+
+    pub const fn with_type(self, type_uri: &'static str) -> Self {
+        ProblemType {
+            type_uri: Some(type_uri),
+            ..self
+        }
+    }
+}
+
+/// The problem details of an error, with the error trace in the `trace`
+/// extension member.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ProblemDetails {
+    pub type_uri: String,
+    pub title: String,
+    pub status: u16,
+    pub detail: String,
+    pub trace: Vec<String>,
+}
+
+impl ProblemDetails {
+    /// Creates the problem details of the given problem type, filling in the
+    /// default type URI and title if they are not given.
+    pub fn new(problem: ProblemType, detail: String, trace: Vec<String>) -> Self {
+        ProblemDetails {
+            type_uri: problem.type_uri.unwrap_or("about:blank").to_string(),
+            title: problem
+                .title
+                .unwrap_or_else(|| reason_phrase(problem.status))
+                .to_string(),
+            status: problem.status,
+            detail,
+            trace,
+        }
+    }
+}
+
+/// Returns the reason phrase of common HTTP status codes, used as the
+/// default title of problem types.
+pub fn reason_phrase(status: u16) -> &'static str {
+    match status {
+        400 => "Bad Request",
+        401 => "Unauthorized",
+        403 => "Forbidden",
+        404 => "Not Found",
+        405 => "Method Not Allowed",
+        408 => "Request Timeout",
+        409 => "Conflict",
+        410 => "Gone",
+        412 => "Precondition Failed",
+        413 => "Payload Too Large",
+        415 => "Unsupported Media Type",
+        422 => "Unprocessable Entity",
+        429 => "Too Many Requests",
+        500 => "Internal Server Error",
+        501 => "Not Implemented",
+        502 => "Bad Gateway",
+        503 => "Service Unavailable",
+        504 => "Gateway Timeout",
+        _ if (400..500).contains(&status) => "Client Error",
+        _ => "Server Error",
+    }
+}
+
+#[cfg(feature = "serde")]
+impl serde::Serialize for ProblemDetails {
+    fn serialize<S: serde::Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        use serde::ser::SerializeStruct;
+
+        let len = if self.trace.is_empty() { 4 } else { 5 };
+        let mut state = serializer.serialize_struct("ProblemDetails", len)?;
+        state.serialize_field("type", &self.type_uri)?;
+        state.serialize_field("title", &self.title)?;
+        state.serialize_field("status", &self.status)?;
+        state.serialize_field("detail", &self.detail)?;
+        if !self.trace.is_empty() {
+            state.serialize_field("trace", &self.trace)?;
+        }
+        state.end()
+    }
+}
@@ -0,0 +1,158 @@
+/*!
+  Rate-limited logging of repeated errors.
+
+  When a dependency is down, the same error may be returned by every
+  request, and logging the full error trace each time floods the logs.
+  With the `log` or `tracing` feature, the error types defined by
+  [`define_error!`](crate::define_error) have the generated method
+  `log_rate_limited`, which logs the full error trace at most once per
+  interval for each fingerprint, and a short message with the number of
+  repetitions otherwise:
+
+  ```
+  # use core::time::Duration;
+  # flex_error::define_error! {
+  #   RelayError {
+  #     Timeout
+  #       | _ | { "timed out" },
+  #   }
+  # }
+  # struct Packet;
+  # fn relay_packet(_packet: &Packet) -> Result<(), RelayError> {
+  #   Err(RelayError::timeout())
+  # }
+  # let packet = Packet;
+  if let Err(e) = relay_packet(&packet) {
+    e.log_rate_limited("relay_packet", Duration::from_secs(60));
+  }
+  ```
+
+  The fingerprint of an error is made of the given key, which usually names
+  the call site, and of the error type and sub-error, such as
+  `MyError::Timeout`. The first occurrence of an error is always logged with
+  its full trace, so that its detail is kept, and the next full trace
+  reports the number of occurrences suppressed in between.
+
+  The messages are logged at the error level with the `flex_error` target,
+  with the `log` crate if the `log` feature is enabled, and as `tracing`
+  events if the `tracing` feature is enabled. One state is kept per
+  fingerprint for the lifetime of the process, or until
+  [`reset_rate_limits`] is called.
+*/
+
+use alloc::collections::BTreeMap;
+use alloc::format;
+use alloc::string::{String, ToString};
+use core::fmt::{Debug, Display};
+use std::sync::Mutex;
+use std::time::{Duration, Instant};
+
+/// How an occurrence of an error is logged by [`log_rate_limited`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Throttle {
+    /// The full error trace is logged, with the number of occurrences
+    /// suppressed since the last full trace.
+    Full { suppressed: u64 },
+
+    /// A short message is logged, with the number of occurrences since
+    /// the last full trace, including this one.
+    Repeated { count: u64 },
+}
+
+struct Occurrences {
+    logged_at: Instant,
+    suppressed: u64,
+}
+
+static OCCURRENCES: Mutex<BTreeMap<(String, String), Occurrences>> = Mutex::new(BTreeMap::new());
+
+/// Records an occurrence of an error with the given key and error type,
+/// and returns how it should be logged.
+pub fn throttle(key: &str, error_type: &str, interval: Duration) -> Throttle {
+    let now = Instant::now();
+    let mut occurrences = OCCURRENCES.lock().unwrap_or_else(|e| e.into_inner());
+    let fingerprint = (key.to_string(), error_type.to_string());
+
+    match occurrences.get_mut(&fingerprint) {
+        Some(entry) if now.duration_since(entry.logged_at) < interval => {
+            entry.suppressed += 1;
+            Throttle::Repeated {
+                count: entry.suppressed,
+            }
+        }
+        Some(entry) => {
+            let suppressed = entry.suppressed;
+            entry.logged_at = now;
+            entry.suppressed = 0;
+            Throttle::Full { suppressed }
+        }
+        None => {
+            occurrences.insert(
+                fingerprint,
+                Occurrences {
+                    logged_at: now,
+                    suppressed: 0,
+                },
+            );
+            Throttle::Full { suppressed: 0 }
+        }
+    }
+}
+
+/// Clears the state of all fingerprints, so that the next occurrence
+/// of each error is logged with its full trace.
+pub fn reset_rate_limits() {
+    OCCURRENCES
+        .lock()
+        .unwrap_or_else(|e| e.into_inner())
+        .clear();
+}
+
+/// Logs an error with its full trace, or with its short message if the
+/// full trace has been logged for the same fingerprint within `interval`.
+/// See the [module documentation](self).
+pub fn log_rate_limited(
+    key: &str,
+    error_type: &str,
+    interval: Duration,
+    full: &dyn Display,
+    short: &dyn Display,
+) {
+    match throttle(key, error_type, interval) {
+        Throttle::Full { suppressed: 0 } => {
+            emit(key, &format!("{}: {}", error_type, full));
+        }
+        Throttle::Full { suppressed } => {
+            emit(
+                key,
+                &format!(
+                    "{}: {} ({} similar errors suppressed)",
+                    error_type, full, suppressed
+                ),
+            );
+        }
+        Throttle::Repeated { count } => {
+            emit(
+                key,
+                &format!(
+                    "{}: {} ({} occurrences since the last full trace)",
+                    error_type, short, count
+                ),
+            );
+        }
+    }
+}
+
+fn emit(key: &str, message: &str) {
+    #[cfg(feature = "log")]
+    log::error!(target: "flex_error", "[{}] {}", key, message);
+
+    #[cfg(feature = "tracing")]
+    tracing::error!(target: "flex_error", key, "{}", message);
+}
+
+/// Returns the error type of the fingerprint, in the form `MyError::Variant`.
+#[doc(hidden)]
+pub fn error_type(name: &str, kind: &dyn Debug) -> String {
+    format!("{}::{:?}", name, kind)
+}
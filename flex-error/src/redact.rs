@@ -0,0 +1,107 @@
+/*!
+  Redaction of error messages before they are stored in error traces.
+
+  Error messages and source locations often embed file paths, such as the
+  paths to the sources of dependencies in `$CARGO_HOME`, which may disclose
+  the user name or the layout of the build machine when the error traces
+  of release builds are shown to users or sent to a telemetry service.
+
+  [`set_trace_filter`] sets a global filter which is applied by the built-in
+  error tracers to each message before it is stored, and by the minimal
+  `eyre` handler to the recorded location. The filter [`strip_home_paths`]
+  replaces the paths to `$CARGO_HOME` and `$HOME` with the names of the
+  environment variables:
+
+  ```
+  #[cfg(not(debug_assertions))]
+  flex_error::set_trace_filter(flex_error::redact::strip_home_paths);
+  ```
+
+  While a filter is set, the `eyre` and `anyhow` tracers trace the error
+  sources by their filtered messages, instead of keeping the error sources.
+  When no filter is set, the messages are stored without taking any lock.
+  The stack backtraces captured by the tracers are not filtered.
+*/
+
+use alloc::string::String;
+use alloc::vec::Vec;
+use core::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{OnceLock, RwLock};
+
+/// A filter applied to the messages before they are stored in error traces.
+pub type TraceFilter = fn(&str) -> String;
+
+static FILTER: RwLock<Option<TraceFilter>> = RwLock::new(None);
+
+/// Whether a filter is set, so that the messages are traced without taking
+/// the lock on [`FILTER`] when no filter is set.
+static HAS_FILTER: AtomicBool = AtomicBool::new(false);
+
+/// Sets the filter applied to the messages before they are stored in
+/// error traces, replacing any filter previously set.
+pub fn set_trace_filter(filter: TraceFilter) {
+    let mut current = FILTER.write().unwrap_or_else(|e| e.into_inner());
+    *current = Some(filter);
+    HAS_FILTER.store(true, Ordering::Release);
+}
+
+/// Removes the filter set with [`set_trace_filter`].
+pub fn clear_trace_filter() {
+    let mut current = FILTER.write().unwrap_or_else(|e| e.into_inner());
+    *current = None;
+    HAS_FILTER.store(false, Ordering::Release);
+}
+
+fn current_filter() -> Option<TraceFilter> {
+    if !HAS_FILTER.load(Ordering::Acquire) {
+        return None;
+    }
+
+    *FILTER.read().unwrap_or_else(|e| e.into_inner())
+}
+
+/// Returns whether a filter has been set with [`set_trace_filter`].
+#[cfg(any(feature = "eyre_tracer_minimal", feature = "anyhow_tracer"))]
+pub(crate) fn has_filter() -> bool {
+    current_filter().is_some()
+}
+
+/// Applies the filter set with [`set_trace_filter`] to a message, if any.
+pub(crate) fn filter_message(message: String) -> String {
+    match current_filter() {
+        Some(filter) => filter(&message),
+        None => message,
+    }
+}
+
+/// A filter replacing the paths to the `$CARGO_HOME` and `$HOME`
+/// directories in a message with `$CARGO_HOME` and `$HOME` respectively.
+///
+/// The directories are read from the environment the first time the filter
+/// is applied. `$CARGO_HOME` defaults to `$HOME/.cargo` if it is not set.
+pub fn strip_home_paths(message: &str) -> String {
+    static PREFIXES: OnceLock<Vec<(String, &'static str)>> = OnceLock::new();
+
+    let prefixes = PREFIXES.get_or_init(|| {
+        let home = std::env::var("HOME").ok().filter(|home| !home.is_empty());
+        let cargo_home = std::env::var("CARGO_HOME")
+            .ok()
+            .filter(|cargo_home| !cargo_home.is_empty())
+            .or_else(|| home.as_ref().map(|home| alloc::format!("{}/.cargo", home)));
+
+        // The `$CARGO_HOME` directory is usually inside `$HOME`,
+        // so it has to be replaced first.
+        let mut prefixes = Vec::new();
+        prefixes.extend(cargo_home.map(|cargo_home| (cargo_home, "$CARGO_HOME")));
+        prefixes.extend(home.map(|home| (home, "$HOME")));
+        prefixes
+    });
+
+    let mut message = String::from(message);
+    for (prefix, name) in prefixes {
+        if message.contains(prefix.as_str()) {
+            message = message.replace(prefix.as_str(), name);
+        }
+    }
+    message
+}
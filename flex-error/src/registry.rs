@@ -0,0 +1,153 @@
+/*!
+  Workspace-wide registry of the error codes assigned with `@codes`, to
+  enforce that error codes are globally unique.
+
+  When the `code_registry` feature is enabled, each error type defined by
+  [`define_error!`](crate::define_error) with the `@codes` option registers
+  the codes of its sub-errors with [`inventory`](https://docs.rs/inventory),
+  so that the codes of all crates linked into a binary can be inspected
+  with [`registered_codes`]. Two sub-errors sharing the same code, such as
+  when two crates in a workspace assign codes from overlapping ranges, can
+  then be detected in a test with [`assert_unique_codes!`](crate::assert_unique_codes):
+
+  ```
+  # flex_error::define_error! {
+  #   @codes(include_str!("../tests/error_codes.toml"))
+  #   MyError {
+  #     ConnectTimeout
+  #       | _ | { "connection timed out" },
+  #     Refused
+  #       | _ | { "connection refused" },
+  #   }
+  # }
+  // in a test of the binary crate
+  flex_error::assert_unique_codes!();
+  ```
+
+  Only the crates that are linked into the test binary are checked, so the
+  test should be placed in a crate depending on all the crates with error
+  codes, such as the main binary crate of the workspace.
+*/
+
+use alloc::string::String;
+use alloc::vec::Vec;
+use core::fmt::Write;
+
+/// The error code assigned to a sub-error, as registered by
+/// [`define_error!`](crate::define_error).
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct RegisteredCode {
+    /// The error code assigned to the sub-error.
+    pub code: &'static str,
+
+    /// The path of the module defining the error type.
+    pub module: &'static str,
+
+    /// The name of the error type.
+    pub error: &'static str,
+
+    /// The name of the sub-error.
+    pub variant: &'static str,
+}
+
+inventory::collect!(RegisteredCode);
+
+/// Returns the codes registered by all the error types linked into the
+/// current binary, sorted by their codes.
+pub fn registered_codes() -> Vec<RegisteredCode> {
+    let mut codes: Vec<RegisteredCode> = inventory::iter::<RegisteredCode>
+        .into_iter()
+        .copied()
+        .collect();
+    codes.sort();
+    codes.dedup();
+    codes
+}
+
+/// Returns the groups of sub-errors sharing the same error code. The same
+/// sub-error registered more than once, such as by multiple versions of a
+/// crate, is not counted as a collision.
+pub fn code_collisions() -> Vec<Vec<RegisteredCode>> {
+    let mut collisions: Vec<Vec<RegisteredCode>> = Vec::new();
+    for code in registered_codes() {
+        match collisions.last_mut() {
+            Some(group) if group[0].code == code.code => {
+                if !group.iter().any(|other| {
+                    other.error == code.error && other.variant == code.variant
+                }) {
+                    group.push(code);
+                }
+            }
+            _ => collisions.push(alloc::vec![code]),
+        }
+    }
+    collisions.retain(|group| group.len() > 1);
+    collisions
+}
+
+/// Panics with a list of the colliding sub-errors if any two sub-errors
+/// share the same error code. Called by [`assert_unique_codes!`](crate::assert_unique_codes).
+#[doc(hidden)]
+#[track_caller]
+pub fn assert_unique_codes() {
+    let collisions = code_collisions();
+    if collisions.is_empty() {
+        return;
+    }
+
+    let mut message = String::from("error codes are not unique:");
+    for group in collisions {
+        let _ = write!(message, "\n  {}:", group[0].code);
+        for code in group {
+            let _ = write!(message, "\n    {}::{}.{}", code.module, code.error, code.variant);
+        }
+    }
+    panic!("{}", message);
+}
+
+/// Returns the code of the sub-error `variant` of the error type `error` in
+/// the error codes mapping file `table`, for registering it at compile time.
+#[doc(hidden)]
+pub const fn code_of(table: &'static str, error: &str, variant: &str) -> &'static str {
+    match crate::codes::lookup(table, error, variant) {
+        Some(code) => code.code,
+        None => panic!("missing code in error codes table"),
+    }
+}
+
+/// Asserts that no two sub-errors of the error types linked into the current
+/// binary share the same error code assigned with `@codes`, panicking with a
+/// list of the colliding sub-errors otherwise. See the [`registry`](crate::registry)
+/// module.
+#[macro_export]
+macro_rules! assert_unique_codes {
+  () => {
+    $crate::registry::assert_unique_codes()
+  };
+}
+
+/// Internal macro used to register the codes of the sub-errors of an
+/// error type with `@codes`.
+#[macro_export]
+#[doc(hidden)]
+macro_rules! register_error_codes {
+  ( @name( $name:ident ),
+    @codes( $codes:expr ),
+    @variants[ $( $variant:ident ),* ] $(,)?
+  ) => {
+    $(
+      $crate::inventory::submit! {
+        $crate::registry::RegisteredCode {
+          code: $crate::registry::code_of(
+            $codes,
+            ::core::stringify!($name),
+            ::core::stringify!($variant),
+          ),
+          module: ::core::module_path!(),
+          error: ::core::stringify!($name),
+          variant: ::core::stringify!($variant),
+        }
+      }
+    )*
+  };
+}
@@ -0,0 +1,826 @@
+#[cfg(feature = "alloc")]
+use alloc::string::{String, ToString};
+#[cfg(feature = "alloc")]
+use alloc::vec::Vec;
+use core::fmt::{Display, Formatter, Write};
+use core::sync::atomic::{AtomicU8, Ordering};
+
+use crate::source::ErrorMetadata;
+
+/// The global color mode used when rendering error traces with [`Rendered`].
+///
+/// The color mode is set process-wide with [`set_color_mode`], so that
+/// binaries using different tracer features produce consistent terminal
+/// output.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ColorMode {
+    /// Use colors only if the `std` feature is enabled, stderr is a terminal,
+    /// and the `NO_COLOR` environment variable is not set.
+    Auto,
+
+    /// Always use colors, even if the output is not a terminal.
+    Always,
+
+    /// Never use colors, regardless of the [`Theme`] used.
+    Never,
+}
+
+/// The color theme used for rendering error traces with [`Rendered`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Theme {
+    /// Plain text without any ANSI escape codes.
+    Plain,
+
+    /// Standard ANSI colors.
+    Ansi,
+
+    /// Bright ANSI colors with bold messages.
+    Bright,
+}
+
+static COLOR_MODE: AtomicU8 = AtomicU8::new(0);
+
+/// Sets the global [`ColorMode`] to be honored by all rendered error traces.
+pub fn set_color_mode(mode: ColorMode) {
+    let value = match mode {
+        ColorMode::Auto => 0,
+        ColorMode::Always => 1,
+        ColorMode::Never => 2,
+    };
+    COLOR_MODE.store(value, Ordering::Relaxed);
+}
+
+/// Gets the global [`ColorMode`]. Defaults to [`ColorMode::Auto`].
+pub fn color_mode() -> ColorMode {
+    match COLOR_MODE.load(Ordering::Relaxed) {
+        1 => ColorMode::Always,
+        2 => ColorMode::Never,
+        _ => ColorMode::Auto,
+    }
+}
+
+/// Returns whether ANSI colors should be used according to the
+/// global [`ColorMode`].
+pub fn use_colors() -> bool {
+    match color_mode() {
+        ColorMode::Always => true,
+        ColorMode::Never => false,
+        ColorMode::Auto => auto_colors(),
+    }
+}
+
+#[cfg(feature = "std")]
+fn auto_colors() -> bool {
+    use std::io::IsTerminal;
+
+    std::env::var_os("NO_COLOR").is_none() && std::io::stderr().is_terminal()
+}
+
+#[cfg(not(feature = "std"))]
+fn auto_colors() -> bool {
+    false
+}
+
+/// The level of a message in an error trace, which allows verbose messages
+/// to be filtered out when rendering the trace with [`Rendered`].
+///
+/// Messages added without a level, such as with `add_trace`, have the level
+/// [`Level::Info`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum Level {
+    Trace,
+    Debug,
+    Info,
+    Warn,
+    Error,
+}
+
+impl Level {
+    /// Returns the lowercase name of the level, e.g. `"warn"`.
+    pub fn as_str(self) -> &'static str {
+        match self {
+            Level::Trace => "trace",
+            Level::Debug => "debug",
+            Level::Info => "info",
+            Level::Warn => "warn",
+            Level::Error => "error",
+        }
+    }
+}
+
+/// Levels are serialized as their lowercase name.
+#[cfg(feature = "serde")]
+impl serde::Serialize for Level {
+    fn serialize<S: serde::Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.serialize_str(self.as_str())
+    }
+}
+
+/// An error tracer implements `TraceRender` if it can walk through the
+/// messages in its trace, starting from the outermost message.
+///
+/// This is used by [`Rendered`] to render the error trace independently
+/// of how the underlying tracer formats itself.
+pub trait TraceRender {
+    /// Calls `f` on each message in the trace, from the outermost
+    /// to the innermost message.
+    fn walk_messages(
+        &self,
+        f: &mut dyn FnMut(&dyn Display) -> core::fmt::Result,
+    ) -> core::fmt::Result;
+
+    /// Calls `f` on each message in the trace together with its [`Level`],
+    /// from the outermost to the innermost message.
+    ///
+    /// By default all messages have the level [`Level::Info`]. Error tracers
+    /// that keep the level of the messages added with
+    /// [`add_message_with_level`](crate::ErrorMessageTracer::add_message_with_level)
+    /// override this method.
+    fn walk_leveled_messages(
+        &self,
+        f: &mut dyn FnMut(Level, &dyn Display) -> core::fmt::Result,
+    ) -> core::fmt::Result {
+        self.walk_messages(&mut |message| f(Level::Info, message))
+    }
+
+    /// Returns the source location where the error trace was created,
+    /// if it is recorded by the tracer.
+    fn location(&self) -> Option<&str> {
+        None
+    }
+
+    /// Returns the metadata kept next to the error trace, for the error
+    /// types defined with the `@metadata` option of
+    /// [`define_error!`](crate::define_error).
+    fn metadata(&self) -> Option<&ErrorMetadata> {
+        None
+    }
+}
+
+impl<Trace: TraceRender + ?Sized> TraceRender for &Trace {
+    fn walk_messages(
+        &self,
+        f: &mut dyn FnMut(&dyn Display) -> core::fmt::Result,
+    ) -> core::fmt::Result {
+        (**self).walk_messages(f)
+    }
+
+    fn walk_leveled_messages(
+        &self,
+        f: &mut dyn FnMut(Level, &dyn Display) -> core::fmt::Result,
+    ) -> core::fmt::Result {
+        (**self).walk_leveled_messages(f)
+    }
+
+    fn location(&self) -> Option<&str> {
+        (**self).location()
+    }
+
+    fn metadata(&self) -> Option<&ErrorMetadata> {
+        (**self).metadata()
+    }
+}
+
+/// A message in an error trace, together with its level and the source
+/// location where it was raised, if known.
+#[cfg(feature = "alloc")]
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct TraceEntry<'a> {
+    pub message: String,
+    pub level: Level,
+    pub location: Option<&'a str>,
+}
+
+#[cfg(all(feature = "alloc", feature = "serde"))]
+impl<'a> serde::Serialize for TraceEntry<'a> {
+    fn serialize<S: serde::Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        use serde::ser::SerializeStruct;
+
+        let len = if self.location.is_some() { 3 } else { 2 };
+        let mut state = serializer.serialize_struct("TraceEntry", len)?;
+        state.serialize_field("message", &self.message)?;
+        state.serialize_field("level", &self.level)?;
+        if let Some(location) = self.location {
+            state.serialize_field("location", location)?;
+        }
+        state.end()
+    }
+}
+
+/// Collects the messages in an error trace as [`TraceEntry`]s, from the
+/// outermost to the innermost message, so that they can be post-processed
+/// before being reported.
+///
+/// The location recorded by the tracer is attached to the innermost entry,
+/// which is where the error trace was created.
+#[cfg(feature = "alloc")]
+pub fn trace_entries<Trace>(trace: &Trace) -> Vec<TraceEntry<'_>>
+where
+    Trace: TraceRender + ?Sized,
+{
+    let mut entries = Vec::new();
+    let _ = trace.walk_leveled_messages(&mut |level, message| {
+        entries.push(TraceEntry {
+            message: message.to_string(),
+            level,
+            location: None,
+        });
+        Ok(())
+    });
+
+    if let Some(entry) = entries.last_mut() {
+        entry.location = trace.location();
+    }
+
+    entries
+}
+
+/// Summarizes an error trace on a single line of at most `max_len`
+/// characters, for use in alerts and other size-limited reports.
+///
+/// If the messages joined by `": "` do not fit, the outermost and innermost
+/// messages are kept, and as many of the middle messages as fit are replaced
+/// by the number of elided messages, e.g.
+/// `"failed to relay: [... 3 more ...]: connection refused"`. Messages closest
+/// to the root cause are kept in preference to the outer messages. If even
+/// the outermost and innermost messages do not fit, the outermost message is
+/// truncated first, and the innermost message is only truncated as a last
+/// resort.
+#[cfg(feature = "alloc")]
+pub fn summarize<Trace>(trace: &Trace, max_len: usize) -> String
+where
+    Trace: TraceRender + ?Sized,
+{
+    let mut messages = Vec::new();
+    let _ = trace.walk_messages(&mut |message| {
+        messages.push(message.to_string());
+        Ok(())
+    });
+
+    let full = messages.join(": ");
+    if char_len(&full) <= max_len {
+        return full;
+    }
+
+    match messages.as_slice() {
+        [] => String::new(),
+        [message] => truncate(message, max_len),
+        _ => summarize_messages(&messages, max_len),
+    }
+}
+
+#[cfg(feature = "alloc")]
+fn summarize_messages(messages: &[String], max_len: usize) -> String {
+    let total = messages.len();
+    let join = |outer: usize, inner: usize| {
+        let mut summary = messages[..outer].join(": ");
+        let elided = total - outer - inner;
+        if elided > 0 {
+            summary.push_str(&alloc::format!(": [... {} more ...]", elided));
+        }
+        summary.push_str(": ");
+        summary.push_str(&messages[total - inner..].join(": "));
+        summary
+    };
+
+    let (mut outer, mut inner) = (1, 1);
+    let mut summary = join(outer, inner);
+
+    if char_len(&summary) > max_len {
+        let last = &messages[total - 1];
+        let suffix = &summary[messages[0].len()..];
+        let budget = max_len.saturating_sub(char_len(suffix));
+
+        // Keep at least a few characters of the outermost message,
+        // otherwise only keep the root cause.
+        return if budget > 3 {
+            let mut summary = truncate(&messages[0], budget);
+            summary.push_str(suffix);
+            summary
+        } else {
+            truncate(last, max_len)
+        };
+    }
+
+    // Grow the kept messages from both ends, preferring the inner side.
+    while outer + inner < total {
+        let candidates = [(outer, inner + 1), (outer + 1, inner)];
+        match candidates
+            .iter()
+            .map(|&(outer, inner)| (outer, inner, join(outer, inner)))
+            .find(|(_, _, candidate)| char_len(candidate) <= max_len)
+        {
+            Some((new_outer, new_inner, candidate)) => {
+                outer = new_outer;
+                inner = new_inner;
+                summary = candidate;
+            }
+            None => break,
+        }
+    }
+
+    summary
+}
+
+#[cfg(feature = "alloc")]
+fn char_len(s: &str) -> usize {
+    s.chars().count()
+}
+
+/// Truncates the string to at most `max_len` characters, marking the
+/// truncation with a trailing `…`.
+#[cfg(feature = "alloc")]
+fn truncate(s: &str, max_len: usize) -> String {
+    if char_len(s) <= max_len {
+        return s.to_string();
+    }
+
+    match max_len.checked_sub(1) {
+        Some(keep) => {
+            let mut truncated: String = s.chars().take(keep).collect();
+            truncated.push('…');
+            truncated
+        }
+        None => String::new(),
+    }
+}
+
+/// A wrapper that renders an error trace with a [`Theme`] in its
+/// `Display` implementation, honoring the global [`ColorMode`].
+///
+/// This is constructed with the `render` method of the error types
+/// defined by [`define_error!`](crate::define_error).
+///
+/// If `grouped` is set, consecutive identical messages in the trace are
+/// rendered only once, together with the number of times they repeat.
+/// This is useful for traces aggregated from errors raised repeatedly in a
+/// loop, where the same root cause repeats many times. Messages repeated
+/// apart from each other are rendered at each occurrence.
+///
+/// If `help` is set, the help text is rendered after the error trace.
+///
+/// If `metadata` is set with the `incidents` feature, the incident ID of
+/// the error is rendered last. See the `flex_error::incident` module.
+///
+/// Messages with a [`Level`] below `min_level` are not rendered, so that
+/// verbose intermediate messages can be hidden in production output.
+///
+/// If `dedup_sources` is set, a message is not rendered if it is the same
+/// as the previous message, or if the previous message ends with it after
+/// a `": "` separator. This is the case for sources such as
+/// [`DisplayError`](crate::DisplayError), which are traced while also being
+/// printed by the formatter of the sub-error, e.g. with
+/// `format_args!("failed to read config: {}", e.source)`.
+///
+/// Without the `alloc` feature, the messages are rendered as they are
+/// walked, and `grouped` and `dedup_sources` are ignored.
+pub struct Rendered<'a, Trace: ?Sized> {
+    pub trace: &'a Trace,
+    pub theme: Theme,
+    pub grouped: bool,
+    pub help: Option<&'a str>,
+    pub metadata: Option<&'a ErrorMetadata>,
+    pub min_level: Level,
+    pub dedup_sources: bool,
+}
+
+impl<'a, Trace: ?Sized> Rendered<'a, Trace> {
+    pub fn new(trace: &'a Trace, theme: Theme) -> Self {
+        Rendered {
+            trace,
+            theme,
+            grouped: false,
+            help: None,
+            metadata: None,
+            min_level: Level::Trace,
+            dedup_sources: false,
+        }
+    }
+
+    /// Renders the given help text after the error trace.
+    pub fn with_help(self, help: Option<&'a str>) -> Self {
+        Rendered { help, ..self }
+    }
+
+    /// Renders the incident ID kept in the given error metadata after
+    /// the error trace.
+    pub fn with_metadata(self, metadata: Option<&'a ErrorMetadata>) -> Self {
+        Rendered { metadata, ..self }
+    }
+
+    /// Renders only the messages with the given level or above.
+    pub fn min_level(self, min_level: Level) -> Self {
+        Rendered { min_level, ..self }
+    }
+
+    /// Skips the messages already printed at the end of the previous message.
+    pub fn dedup_sources(self) -> Self {
+        Rendered {
+            dedup_sources: true,
+            ..self
+        }
+    }
+
+    /// Renders consecutive identical messages only once, together with
+    /// their counts.
+    pub fn grouped(self) -> Self {
+        Rendered {
+            grouped: true,
+            ..self
+        }
+    }
+}
+
+impl<'a, Trace> Display for Rendered<'a, Trace>
+where
+    Trace: TraceRender + ?Sized,
+{
+    fn fmt(&self, f: &mut Formatter<'_>) -> core::fmt::Result {
+        let styles = Styles::new(self.theme);
+
+        #[cfg(feature = "alloc")]
+        {
+            let messages = visible_messages(self.trace, self.min_level, self.dedup_sources)?;
+            if self.grouped {
+                fmt_grouped(&messages, f, &styles)?;
+            } else {
+                fmt_trace(&messages, f, &styles)?;
+            }
+        }
+
+        #[cfg(not(feature = "alloc"))]
+        fmt_streamed(self.trace, f, &styles, self.min_level)?;
+
+        if let Some(help) = self.help {
+            write!(f, "\n\n{0}help:{1} {2}", styles.index, styles.reset, help)?;
+        }
+
+        #[cfg(feature = "incidents")]
+        if let Some(id) = self.metadata.and_then(ErrorMetadata::incident_id) {
+            write!(f, "\n\n{0}incident:{1} {2}", styles.index, styles.reset, id)?;
+        }
+
+        Ok(())
+    }
+}
+
+pub(crate) struct Styles {
+    pub(crate) index: &'static str,
+    pub(crate) message: &'static str,
+    pub(crate) reset: &'static str,
+}
+
+impl Styles {
+    /// Returns the ANSI styles of the theme, honoring the global [`ColorMode`].
+    pub(crate) fn new(theme: Theme) -> Self {
+        let (index, message) = match theme {
+            _ if !use_colors() => ("", ""),
+            Theme::Plain => ("", ""),
+            Theme::Ansi => ("\x1b[31m", ""),
+            Theme::Bright => ("\x1b[91;1m", "\x1b[1m"),
+        };
+        let reset = if index.is_empty() { "" } else { "\x1b[0m" };
+        Styles {
+            index,
+            message,
+            reset,
+        }
+    }
+}
+
+fn fmt_entry(
+    f: &mut Formatter<'_>,
+    styles: &Styles,
+    index: usize,
+    message: &dyn Display,
+) -> core::fmt::Result {
+    if index > 0 {
+        writeln!(f)?;
+    }
+    write!(
+        f,
+        "{0}{1:>4}:{2} {3}{4}{2}",
+        styles.index, index, styles.reset, styles.message, message
+    )
+}
+
+/// Collects the messages in the trace that are rendered, skipping the
+/// messages below `min_level`, and with `dedup_sources`, the messages already
+/// printed at the end of the previous rendered message.
+#[cfg(feature = "alloc")]
+fn visible_messages<Trace>(
+    trace: &Trace,
+    min_level: Level,
+    dedup_sources: bool,
+) -> Result<Vec<String>, core::fmt::Error>
+where
+    Trace: TraceRender + ?Sized,
+{
+    let mut messages: Vec<String> = Vec::new();
+    trace.walk_leveled_messages(&mut |level, message| {
+        if level < min_level {
+            return Ok(());
+        }
+        let message = message.to_string();
+        if dedup_sources && !message.is_empty() {
+            if let Some(previous) = messages.last() {
+                if ends_with_source(previous, &message) {
+                    return Ok(());
+                }
+            }
+        }
+        messages.push(message);
+        Ok(())
+    })?;
+    Ok(messages)
+}
+
+/// Returns whether the message is the same as the previous message, or is
+/// printed at the end of it as a source after a `": "` separator.
+#[cfg(feature = "alloc")]
+fn ends_with_source(previous: &str, message: &str) -> bool {
+    previous == message
+        || previous
+            .strip_suffix(message)
+            .is_some_and(|rest| rest.ends_with(": "))
+}
+
+#[cfg(feature = "alloc")]
+fn fmt_trace(messages: &[String], f: &mut Formatter<'_>, styles: &Styles) -> core::fmt::Result {
+    for (index, message) in messages.iter().enumerate() {
+        fmt_entry(f, styles, index, message)?;
+    }
+    Ok(())
+}
+
+#[cfg(feature = "alloc")]
+fn fmt_grouped(messages: &[String], f: &mut Formatter<'_>, styles: &Styles) -> core::fmt::Result {
+    let mut groups: Vec<(&String, usize)> = Vec::new();
+    for message in messages {
+        match groups.last_mut() {
+            Some((last, count)) if *last == message => *count += 1,
+            _ => groups.push((message, 1)),
+        }
+    }
+
+    for (index, (message, count)) in groups.iter().enumerate() {
+        fmt_entry(f, styles, index, message)?;
+        if *count > 1 {
+            write!(f, " (x{})", count)?;
+        }
+    }
+
+    Ok(())
+}
+
+/// Renders the messages in the trace as they are walked, skipping the
+/// messages below `min_level`, without allocating.
+#[cfg(not(feature = "alloc"))]
+fn fmt_streamed<Trace>(
+    trace: &Trace,
+    f: &mut Formatter<'_>,
+    styles: &Styles,
+    min_level: Level,
+) -> core::fmt::Result
+where
+    Trace: TraceRender + ?Sized,
+{
+    let mut index = 0;
+    trace.walk_leveled_messages(&mut |level, message| {
+        if level < min_level {
+            return Ok(());
+        }
+        fmt_entry(f, styles, index, message)?;
+        index += 1;
+        Ok(())
+    })
+}
+
+/// A wrapper that displays all messages in an error trace on a single line,
+/// joined by `": "`, from the outermost to the innermost message.
+pub struct Joined<'a, Trace: ?Sized>(pub &'a Trace);
+
+impl<'a, Trace> Display for Joined<'a, Trace>
+where
+    Trace: TraceRender + ?Sized,
+{
+    fn fmt(&self, f: &mut Formatter<'_>) -> core::fmt::Result {
+        let mut first = true;
+        self.0.walk_messages(&mut |message| {
+            if !first {
+                write!(f, ": ")?;
+            }
+            first = false;
+            write!(f, "{}", message)
+        })
+    }
+}
+
+/// Formats a value with the width, precision, fill and alignment of the
+/// formatter applied to its whole output, as [`Formatter::pad`] does for
+/// strings, where the precision truncates the output to the given number of
+/// characters. The alternate flag is passed through to the value.
+///
+/// This is used by the `Display` implementations of the error details
+/// defined by [`define_error!`](crate::define_error), whose messages are
+/// usually formatted with `format_args!`, which ignores these flags.
+pub fn pad_display(f: &mut Formatter<'_>, value: &dyn Display) -> core::fmt::Result {
+    if f.width().is_none() && f.precision().is_none() {
+        return value.fmt(f);
+    }
+
+    let alternate = f.alternate();
+    let mut count = CharCount(0);
+    write_flagged(&mut count, value, alternate)?;
+
+    let len = match f.precision() {
+        Some(precision) => count.0.min(precision),
+        None => count.0,
+    };
+    let padding = f.width().unwrap_or(0).saturating_sub(len);
+    let (before, after) = match f.align() {
+        Some(core::fmt::Alignment::Right) => (padding, 0),
+        Some(core::fmt::Alignment::Center) => (padding / 2, padding - padding / 2),
+        _ => (0, padding),
+    };
+
+    let fill = f.fill();
+    for _ in 0..before {
+        f.write_char(fill)?;
+    }
+    write_flagged(&mut Truncated { f, remaining: len }, value, alternate)?;
+    for _ in 0..after {
+        f.write_char(fill)?;
+    }
+    Ok(())
+}
+
+fn write_flagged(out: &mut dyn Write, value: &dyn Display, alternate: bool) -> core::fmt::Result {
+    if alternate {
+        write!(out, "{:#}", value)
+    } else {
+        write!(out, "{}", value)
+    }
+}
+
+struct CharCount(usize);
+
+impl Write for CharCount {
+    fn write_str(&mut self, s: &str) -> core::fmt::Result {
+        self.0 += s.chars().count();
+        Ok(())
+    }
+}
+
+struct Truncated<'a, 'b> {
+    f: &'a mut Formatter<'b>,
+    remaining: usize,
+}
+
+impl Write for Truncated<'_, '_> {
+    fn write_str(&mut self, s: &str) -> core::fmt::Result {
+        match s.char_indices().nth(self.remaining) {
+            Some((end, _)) => {
+                self.remaining = 0;
+                self.f.write_str(&s[..end])
+            }
+            None => {
+                self.remaining -= s.chars().count();
+                self.f.write_str(s)
+            }
+        }
+    }
+}
+
+/// A field of an error detail, rendered by the `detail_fields` method of
+/// the detail types defined by [`define_error!`](crate::define_error).
+///
+/// The field is rendered with `Display` if its type implements it, or with
+/// `Debug` otherwise, or as `<opaque>` if its type implements neither. The
+/// rendering is chosen with the [`RenderDisplayField`], [`RenderDebugField`]
+/// and [`RenderOpaqueField`] traits, which are implemented for increasingly
+/// fewer references to `DetailField`, so that the method call on
+/// `&&&DetailField` resolves to the first one that applies.
+#[doc(hidden)]
+#[cfg(feature = "alloc")]
+pub struct DetailField<'a, T: ?Sized>(pub &'a T);
+
+#[doc(hidden)]
+#[cfg(feature = "alloc")]
+pub trait RenderDisplayField {
+    fn render_field(&self) -> String;
+}
+
+#[doc(hidden)]
+#[cfg(feature = "alloc")]
+pub trait RenderDebugField {
+    fn render_field(&self) -> String;
+}
+
+#[doc(hidden)]
+#[cfg(feature = "alloc")]
+pub trait RenderOpaqueField {
+    fn render_field(&self) -> String;
+}
+
+#[cfg(feature = "alloc")]
+impl<T: Display + ?Sized> RenderDisplayField for &&DetailField<'_, T> {
+    fn render_field(&self) -> String {
+        self.0.to_string()
+    }
+}
+
+#[cfg(feature = "alloc")]
+impl<T: core::fmt::Debug + ?Sized> RenderDebugField for &DetailField<'_, T> {
+    fn render_field(&self) -> String {
+        alloc::format!("{:?}", self.0)
+    }
+}
+
+#[cfg(feature = "alloc")]
+impl<T: ?Sized> RenderOpaqueField for DetailField<'_, T> {
+    fn render_field(&self) -> String {
+        String::from("<opaque>")
+    }
+}
+
+/// A sub-detail formatted with `defmt` by the `defmt::Format` impls of the
+/// detail types defined by [`define_error!`](crate::define_error), in the
+/// form `MySubdetail { my_field: ..., source: ... }`.
+///
+/// Since `defmt::write!` refers to the `defmt` crate by name, the generated
+/// impls only call [`defmt::Format::format`] on these helpers, so that the
+/// crates defining the errors do not have to depend on `defmt` themselves.
+#[doc(hidden)]
+#[cfg(feature = "defmt")]
+pub struct DefmtStruct<Fields> {
+    pub name: &'static str,
+    pub fields: Fields,
+}
+
+/// A field of a [`DefmtStruct`], followed by the remaining fields.
+#[doc(hidden)]
+#[cfg(feature = "defmt")]
+pub struct DefmtField<'a, T: ?Sized, Rest> {
+    pub separator: &'static str,
+    pub name: &'static str,
+    pub value: &'a T,
+    pub rest: Rest,
+}
+
+/// The end of the fields of a [`DefmtStruct`].
+#[doc(hidden)]
+#[cfg(feature = "defmt")]
+pub struct DefmtFieldsEnd;
+
+/// The main error type formatted with `defmt`, as its error detail followed
+/// by its error trace.
+#[doc(hidden)]
+#[cfg(feature = "defmt")]
+pub struct DefmtError<'a, Detail, Trace> {
+    pub detail: &'a Detail,
+    pub trace: &'a Trace,
+}
+
+#[cfg(feature = "defmt")]
+impl<Fields: defmt::Format> defmt::Format for DefmtStruct<Fields> {
+    fn format(&self, f: defmt::Formatter<'_>) {
+        defmt::write!(f, "{=str} {{ {} }}", self.name, self.fields)
+    }
+}
+
+#[cfg(feature = "defmt")]
+impl<T, Rest> defmt::Format for DefmtField<'_, T, Rest>
+where
+    T: defmt::Format + ?Sized,
+    Rest: defmt::Format,
+{
+    fn format(&self, f: defmt::Formatter<'_>) {
+        defmt::write!(
+            f,
+            "{=str}{=str}: {}{}",
+            self.separator,
+            self.name,
+            self.value,
+            self.rest
+        )
+    }
+}
+
+#[cfg(feature = "defmt")]
+impl defmt::Format for DefmtFieldsEnd {
+    fn format(&self, f: defmt::Formatter<'_>) {
+        defmt::write!(f, "")
+    }
+}
+
+#[cfg(feature = "defmt")]
+impl<Detail, Trace> defmt::Format for DefmtError<'_, Detail, Trace>
+where
+    Detail: defmt::Format,
+    Trace: defmt::Format,
+{
+    fn format(&self, f: defmt::Formatter<'_>) {
+        defmt::write!(f, "{}: {}", self.detail, self.trace)
+    }
+}
@@ -0,0 +1,266 @@
+/*!
+  Export of error traces as `tracing`-compatible JSON records, for log replay.
+
+  When the `replay` feature is enabled, the built-in `eyre`, `anyhow`,
+  backtrace and `tracing` tracers record the time when each message is added
+  to the error trace. [`TracingJson`] then displays the error trace as newline-delimited
+  JSON records in the format of the JSON formatter of `tracing-subscriber`,
+  so that log replay tools can show when each wrap happened alongside the
+  normal spans:
+
+  ```
+  # use flex_error::replay::TracingJson;
+  # flex_error::define_error! {
+  #   RelayError {
+  #     Refused
+  #       | _ | { "connection refused" },
+  #     Relay
+  #       [ Self ]
+  #       | _ | { "failed to relay packet" },
+  #   }
+  # }
+  # let err = RelayError::relay(RelayError::refused());
+  eprintln!("{}", TracingJson::new(&err, "relay_packet"));
+  # assert_eq!(TracingJson::new(&err, "relay_packet").to_string().lines().count(), 4);
+  ```
+
+  The records consist of a synthetic span named after the given span name,
+  opened when the innermost message is traced and closed when the outermost
+  message is traced, and one event for each message in between, in the order
+  the messages have been traced:
+
+  ```text
+  {"timestamp":"2024-05-01T10:00:00.000100Z","level":"ERROR","fields":{"message":"new"},"target":"flex_error","span":{"name":"relay_packet"},"spans":[{"name":"relay_packet"}]}
+  {"timestamp":"2024-05-01T10:00:00.000100Z","level":"INFO","fields":{"message":"connection refused","error.depth":1},"target":"flex_error","span":{"name":"relay_packet"},"spans":[{"name":"relay_packet"}]}
+  {"timestamp":"2024-05-01T10:00:00.002300Z","level":"INFO","fields":{"message":"failed to relay packet","error.depth":0},"target":"flex_error","span":{"name":"relay_packet"},"spans":[{"name":"relay_packet"}]}
+  {"timestamp":"2024-05-01T10:00:00.002300Z","level":"ERROR","fields":{"message":"close"},"target":"flex_error","span":{"name":"relay_packet"},"spans":[{"name":"relay_packet"}]}
+  ```
+
+  The level of each event is the [`Level`] of its message. Messages traced
+  without a capture time, such as by `StringTracer`, take the capture time
+  of the previous message, or have no `timestamp` if there is none.
+*/
+
+use alloc::string::ToString;
+use alloc::vec::Vec;
+use core::fmt::{Debug, Display, Formatter, Write};
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
+use crate::locale::Rfc3339;
+use crate::render::{Level, TraceRender};
+
+/// The marker written by [`Stamped`] when formatted with the `-` flag.
+const TIME_MARKER: char = '\u{1}';
+
+/// A message traced together with the time when it has been traced.
+///
+/// `Stamped` displays as the message itself. When formatted with the `-`
+/// flag, it instead writes a marker with its capture time, which is how
+/// the time is recovered by [`message_time`]. The formatting flags are
+/// otherwise forwarded to the message.
+#[derive(Clone)]
+pub struct Stamped<E> {
+    pub time: SystemTime,
+    pub message: E,
+}
+
+impl<E> Stamped<E> {
+    /// Stamps the message with the current time.
+    pub fn now(message: E) -> Self {
+        Stamped {
+            time: SystemTime::now(),
+            message,
+        }
+    }
+}
+
+impl<E: Display> Display for Stamped<E> {
+    fn fmt(&self, f: &mut Formatter<'_>) -> core::fmt::Result {
+        if f.sign_minus() {
+            let nanos = self
+                .time
+                .duration_since(UNIX_EPOCH)
+                .unwrap_or_default()
+                .as_nanos();
+            write!(f, "{0}{1};", TIME_MARKER, nanos)
+        } else {
+            Display::fmt(&self.message, f)
+        }
+    }
+}
+
+impl<E: Debug> Debug for Stamped<E> {
+    fn fmt(&self, f: &mut Formatter<'_>) -> core::fmt::Result {
+        Debug::fmt(&self.message, f)
+    }
+}
+
+/// Returns the capture time of a message walked by [`TraceRender`], if the
+/// message has been traced as [`Stamped`].
+pub fn message_time(message: &dyn Display) -> Option<SystemTime> {
+    struct Probe {
+        buffer: [u8; 48],
+        len: usize,
+    }
+
+    impl Write for Probe {
+        fn write_str(&mut self, s: &str) -> core::fmt::Result {
+            for &byte in s.as_bytes() {
+                if self.len == self.buffer.len() {
+                    return Err(core::fmt::Error);
+                }
+                self.buffer[self.len] = byte;
+                self.len += 1;
+
+                // Stop formatting the message as soon as the time is known,
+                // or as soon as the message is known to have no time.
+                if byte == b';' || self.buffer[0] != TIME_MARKER as u8 {
+                    return Err(core::fmt::Error);
+                }
+            }
+            Ok(())
+        }
+    }
+
+    let mut probe = Probe {
+        buffer: [0; 48],
+        len: 0,
+    };
+    let _ = core::fmt::write(&mut probe, format_args!("{:-}", message));
+
+    let stamp = core::str::from_utf8(&probe.buffer[..probe.len]).ok()?;
+    let nanos = stamp
+        .strip_prefix(TIME_MARKER)?
+        .strip_suffix(';')?
+        .parse::<u64>()
+        .ok()?;
+    Some(UNIX_EPOCH + Duration::from_nanos(nanos))
+}
+
+/// A wrapper that displays an error trace as newline-delimited JSON records
+/// in the format of `tracing-subscriber`. See the [module documentation](self).
+pub struct TracingJson<'a, Trace: ?Sized> {
+    pub trace: &'a Trace,
+    pub span: &'a str,
+    pub target: &'a str,
+}
+
+impl<'a, Trace: ?Sized> TracingJson<'a, Trace> {
+    pub fn new(trace: &'a Trace, span: &'a str) -> Self {
+        TracingJson {
+            trace,
+            span,
+            target: "flex_error",
+        }
+    }
+
+    /// Sets the target of the records, which defaults to `flex_error`.
+    pub fn with_target(self, target: &'a str) -> Self {
+        TracingJson { target, ..self }
+    }
+}
+
+struct Record {
+    message: alloc::string::String,
+    level: Level,
+    time: Option<SystemTime>,
+}
+
+impl<'a, Trace> Display for TracingJson<'a, Trace>
+where
+    Trace: TraceRender + ?Sized,
+{
+    fn fmt(&self, f: &mut Formatter<'_>) -> core::fmt::Result {
+        let mut records = Vec::new();
+        self.trace.walk_leveled_messages(&mut |level, message| {
+            records.push(Record {
+                message: message.to_string(),
+                level,
+                time: message_time(message),
+            });
+            Ok(())
+        })?;
+
+        // Replay the messages from the innermost message, which is traced
+        // first, inheriting the time of the previous message if unknown.
+        let depths = records.len();
+        let mut time = None;
+        let mut events = Vec::with_capacity(depths);
+        for (depth, record) in records.iter().enumerate().rev() {
+            time = record.time.or(time);
+            events.push((depth, record, time));
+        }
+
+        let (opened, closed) = match (events.first(), events.last()) {
+            (Some(first), Some(last)) => (first.2, last.2),
+            _ => return Ok(()),
+        };
+
+        self.fmt_record(f, opened, Level::Error, "new", None)?;
+        for (depth, record, time) in events {
+            self.fmt_record(f, time, record.level, &record.message, Some(depth))?;
+        }
+        self.fmt_record(f, closed, Level::Error, "close", None)
+    }
+}
+
+impl<'a, Trace: ?Sized> TracingJson<'a, Trace> {
+    fn fmt_record(
+        &self,
+        f: &mut Formatter<'_>,
+        time: Option<SystemTime>,
+        level: Level,
+        message: &str,
+        depth: Option<usize>,
+    ) -> core::fmt::Result {
+        write!(f, "{{")?;
+        if let Some(time) = time {
+            write!(f, "\"timestamp\":\"{}\",", Rfc3339(time))?;
+        }
+        write!(
+            f,
+            "\"level\":\"{0}\",\"fields\":{{\"message\":\"{1}\"",
+            level_name(level),
+            JsonStr(message)
+        )?;
+        if let Some(depth) = depth {
+            write!(f, ",\"error.depth\":{}", depth)?;
+        }
+        writeln!(
+            f,
+            "}},\"target\":\"{0}\",\"span\":{{\"name\":\"{1}\"}},\"spans\":[{{\"name\":\"{1}\"}}]}}",
+            JsonStr(self.target),
+            JsonStr(self.span)
+        )
+    }
+}
+
+fn level_name(level: Level) -> &'static str {
+    match level {
+        Level::Trace => "TRACE",
+        Level::Debug => "DEBUG",
+        Level::Info => "INFO",
+        Level::Warn => "WARN",
+        Level::Error => "ERROR",
+    }
+}
+
+/// Displays a string escaped as the content of a JSON string.
+struct JsonStr<'a>(&'a str);
+
+impl<'a> Display for JsonStr<'a> {
+    fn fmt(&self, f: &mut Formatter<'_>) -> core::fmt::Result {
+        for c in self.0.chars() {
+            match c {
+                '"' => f.write_str("\\\"")?,
+                '\\' => f.write_str("\\\\")?,
+                '\n' => f.write_str("\\n")?,
+                '\r' => f.write_str("\\r")?,
+                '\t' => f.write_str("\\t")?,
+                c if c.is_control() => write!(f, "\\u{:04x}", c as u32)?,
+                c => f.write_char(c)?,
+            }
+        }
+        Ok(())
+    }
+}
@@ -0,0 +1,249 @@
+/*!
+  Composition of errors from multiple subsystems into a single report.
+
+  Applications made of several subsystems often end up with one error per
+  subsystem at shutdown, each of a different error type. [`ReportBuilder`]
+  collects them as titled sections, and builds a single [`Report`] that
+  renders all error traces with the same [`Theme`]:
+
+  ```
+  # use flex_error::define_error;
+  # use flex_error::report::ReportBuilder;
+  # define_error! {
+  #   SupervisorError {
+  #     Refused
+  #       | _ | { "connection refused" },
+  #     Spawn
+  #       [ Self ]
+  #       | _ | { "failed to spawn worker" },
+  #   }
+  # }
+  # define_error! {
+  #   TelemetryError {
+  #     Bind
+  #       { address: String }
+  #       | e | { format_args!("failed to bind to address {}", e.address) },
+  #   }
+  # }
+  # let supervisor_error = SupervisorError::spawn(SupervisorError::refused());
+  # let telemetry_error = TelemetryError::bind("0.0.0.0:3001".to_string());
+  let report = ReportBuilder::new()
+    .title("relayer stopped with errors")
+    .error("supervisor", &supervisor_error)
+    .error("telemetry", &telemetry_error)
+    .section("note", "some packets may not have been relayed")
+    .build();
+
+  eprintln!("{}", report);
+  # assert!(report.to_string().contains("failed to bind to address 0.0.0.0:3001"));
+  ```
+
+  This renders as:
+
+  ```text
+  relayer stopped with errors
+
+  supervisor:
+     0: failed to spawn worker
+     1: connection refused
+
+  telemetry:
+     0: failed to bind to address 0.0.0.0:3001
+
+  note:
+     some packets may not have been relayed
+  ```
+
+  The error types defined by [`define_error!`](crate::define_error)
+  implement [`TraceRender`], and can be added as error sections either by
+  reference or by value.
+
+  [`Report`] formats the same with `Debug`, so that it can be returned as
+  the error from `main`, and with the `std` feature it implements
+  [`std::error::Error`].
+
+  With the `backtrace` feature, the `Debug` output of the report also shows
+  the backtraces captured when the errors of its sections were created,
+  for the error types defined with the `@metadata` option, following the
+  `RUST_LIB_BACKTRACE` and `RUST_BACKTRACE` environment variables. The
+  sections of the errors defined without that option show no backtrace,
+  unless the `incidents` feature is enabled.
+*/
+
+use alloc::boxed::Box;
+use alloc::string::String;
+use alloc::vec::Vec;
+use core::fmt::{Debug, Display, Formatter};
+#[cfg(feature = "backtrace")]
+use std::backtrace::BacktraceStatus;
+
+use crate::render::{Level, Rendered, Styles, Theme, TraceRender};
+
+/// A builder for a [`Report`] composed of multiple sections.
+pub struct ReportBuilder<'a> {
+    report: Report<'a>,
+}
+
+/// A report composed of titled sections, each holding an error trace or
+/// a free-form text, built with [`ReportBuilder`].
+pub struct Report<'a> {
+    title: Option<String>,
+    sections: Vec<Section<'a>>,
+    theme: Theme,
+    min_level: Level,
+}
+
+struct Section<'a> {
+    title: String,
+    body: SectionBody<'a>,
+}
+
+enum SectionBody<'a> {
+    Trace(Box<dyn TraceRender + 'a>),
+    Text(Box<dyn Display + 'a>),
+}
+
+impl<'a> ReportBuilder<'a> {
+    pub fn new() -> Self {
+        ReportBuilder {
+            report: Report {
+                title: None,
+                sections: Vec::new(),
+                theme: Theme::Plain,
+                min_level: Level::Trace,
+            },
+        }
+    }
+
+    /// Sets the title rendered at the top of the report.
+    pub fn title(mut self, title: impl Into<String>) -> Self {
+        self.report.title = Some(title.into());
+        self
+    }
+
+    /// Sets the theme used to render all sections. Defaults to
+    /// [`Theme::Plain`].
+    pub fn theme(mut self, theme: Theme) -> Self {
+        self.report.theme = theme;
+        self
+    }
+
+    /// Renders only the trace messages with the given level or above.
+    pub fn min_level(mut self, min_level: Level) -> Self {
+        self.report.min_level = min_level;
+        self
+    }
+
+    /// Adds a section with the error trace of the given error.
+    pub fn error<E>(mut self, title: impl Into<String>, error: E) -> Self
+    where
+        E: TraceRender + 'a,
+    {
+        self.report.sections.push(Section {
+            title: title.into(),
+            body: SectionBody::Trace(Box::new(error)),
+        });
+        self
+    }
+
+    /// Adds a section with a free-form text, such as a note or a suggestion.
+    pub fn section<D>(mut self, title: impl Into<String>, text: D) -> Self
+    where
+        D: Display + 'a,
+    {
+        self.report.sections.push(Section {
+            title: title.into(),
+            body: SectionBody::Text(Box::new(text)),
+        });
+        self
+    }
+
+    /// Builds the report.
+    pub fn build(self) -> Report<'a> {
+        self.report
+    }
+}
+
+impl<'a> Default for ReportBuilder<'a> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<'a> Report<'a> {
+    /// Returns whether the report has at least one error section.
+    pub fn has_errors(&self) -> bool {
+        self.sections
+            .iter()
+            .any(|section| matches!(section.body, SectionBody::Trace(_)))
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.sections.is_empty()
+    }
+}
+
+impl<'a> Display for Report<'a> {
+    fn fmt(&self, f: &mut Formatter<'_>) -> core::fmt::Result {
+        let styles = Styles::new(self.theme);
+        let mut first = true;
+
+        if let Some(title) = &self.title {
+            write!(f, "{0}{1}{2}", styles.message, title, styles.reset)?;
+            first = false;
+        }
+
+        for section in &self.sections {
+            if !first {
+                write!(f, "\n\n")?;
+            }
+            first = false;
+
+            writeln!(f, "{0}{1}:{2}", styles.index, section.title, styles.reset)?;
+            match &section.body {
+                SectionBody::Trace(trace) => {
+                    let rendered = Rendered::new(&**trace, self.theme)
+                        .with_metadata(trace.metadata())
+                        .min_level(self.min_level);
+                    write!(f, "{}", rendered)?;
+                }
+                SectionBody::Text(text) => {
+                    write!(f, "     {}", text)?;
+                }
+            }
+        }
+
+        Ok(())
+    }
+}
+
+impl<'a> Debug for Report<'a> {
+    fn fmt(&self, f: &mut Formatter<'_>) -> core::fmt::Result {
+        Display::fmt(self, f)?;
+
+        #[cfg(feature = "backtrace")]
+        for section in &self.sections {
+            let backtrace = match &section.body {
+                SectionBody::Trace(trace) => trace
+                    .metadata()
+                    .and_then(|metadata| metadata.backtrace()),
+                SectionBody::Text(_) => None,
+            };
+
+            if let Some(backtrace) = backtrace {
+                if backtrace.status() == BacktraceStatus::Captured {
+                    write!(
+                        f,
+                        "\n\n{0} stack backtrace:\n{1}",
+                        section.title, backtrace
+                    )?;
+                }
+            }
+        }
+
+        Ok(())
+    }
+}
+
+#[cfg(feature = "std")]
+impl<'a> std::error::Error for Report<'a> {}
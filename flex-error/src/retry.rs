@@ -0,0 +1,239 @@
+/*!
+  Retry hints of the servers returning errors.
+
+  Servers under load hint how long clients should wait before retrying,
+  with the `Retry-After` header of HTTP responses, or with the
+  `grpc-retry-pushback-ms` metadata of gRPC responses. When the `http`
+  feature is enabled, errors derived from responses can be wrapped with the
+  [`RetryHinted`] error source, which extracts the hint into its detail:
+
+  ```
+  # use core::time::Duration;
+  # use flex_error::define_error;
+  # use flex_error::retry::{RetryHint, RetryHinted};
+  # use http::HeaderMap;
+  # #[derive(Debug)]
+  # struct ApiResponseError { headers: HeaderMap }
+  # impl std::fmt::Display for ApiResponseError {
+  #   fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+  #     write!(f, "too many requests")
+  #   }
+  # }
+  # async fn sleep(_delay: Duration) {}
+  impl RetryHint for ApiResponseError {
+    fn retry_hint(&self) -> Option<Duration> {
+      self.headers.retry_hint()
+    }
+  }
+
+  define_error! {
+    ClientError {
+      Api
+        [ RetryHinted<ApiResponseError> ]
+        | _ | { "API request failed" },
+    }
+  }
+
+  # async fn backoff(err: &ClientError) {
+  if let Some(delay) = err.retry_after() {
+    sleep(delay).await;
+  }
+  # }
+  # let mut headers = HeaderMap::new();
+  # headers.insert(http::header::RETRY_AFTER, "3".parse().unwrap());
+  # let err = ClientError::api(ApiResponseError { headers });
+  # assert_eq!(err.retry_after(), Some(Duration::from_secs(3)));
+  ```
+
+  The error types defined by [`define_error!`](crate::define_error) have the
+  generated method `retry_after`, which returns the hint of the innermost
+  error source that has one, walking through the nested error types defined
+  by `define_error!`, so that backoff logic can honor the server hints
+  without knowing which sub-error carries them.
+
+  With the `tonic` feature, [`RetryHint`] is also implemented for
+  `tonic::Status`, so that gRPC errors can be wrapped with
+  `RetryHinted<tonic::Status>`. The hint is read from the
+  `grpc-retry-pushback-ms` metadata of the statuses with the `Unavailable`
+  and `ResourceExhausted` codes, which are the codes of the servers pushing
+  back under load.
+*/
+
+use alloc::string::{String, ToString};
+use core::fmt::Display;
+use core::marker::PhantomData;
+use core::time::Duration;
+use std::time::{SystemTime, UNIX_EPOCH};
+
+use http::header::RETRY_AFTER;
+use http::HeaderMap;
+
+use crate::source::ErrorSource;
+use crate::tracer::ErrorMessageTracer;
+
+/// The name of the gRPC metadata holding the delay before a retry, in
+/// milliseconds.
+pub const GRPC_RETRY_PUSHBACK: &str = "grpc-retry-pushback-ms";
+
+/// A type implementing `RetryHint` provides the delay before a retry hinted
+/// by the server that returned it.
+pub trait RetryHint {
+    /// Returns the hinted delay before a retry, if any.
+    fn retry_hint(&self) -> Option<Duration>;
+}
+
+impl RetryHint for HeaderMap {
+    fn retry_hint(&self) -> Option<Duration> {
+        retry_after(self)
+    }
+}
+
+impl<B> RetryHint for http::Response<B> {
+    fn retry_hint(&self) -> Option<Duration> {
+        retry_after(self.headers())
+    }
+}
+
+impl RetryHint for http::response::Parts {
+    fn retry_hint(&self) -> Option<Duration> {
+        retry_after(&self.headers)
+    }
+}
+
+#[cfg(feature = "tonic")]
+impl RetryHint for tonic::Status {
+    fn retry_hint(&self) -> Option<Duration> {
+        match self.code() {
+            tonic::Code::Unavailable | tonic::Code::ResourceExhausted => self
+                .metadata()
+                .get(GRPC_RETRY_PUSHBACK)
+                .and_then(|value| value.to_str().ok())
+                .and_then(parse_grpc_pushback),
+            _ => None,
+        }
+    }
+}
+
+/// An [`ErrorSource`] for errors derived from responses, which provide
+/// the retry hint of the server with [`RetryHint`].
+///
+/// Its `Source` type is the error, and its `Detail` type is
+/// [`RetryHintedDetail`], holding the message of the error and the retry
+/// hint. When extracted, it also provides an error trace that is traced
+/// from the error message.
+pub struct RetryHinted<E>(PhantomData<E>);
+
+/// The message and the retry hint of an error derived from a response.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct RetryHintedDetail {
+    pub message: String,
+    pub retry_after: Option<Duration>,
+}
+
+impl<E, Tracer> ErrorSource<Tracer> for RetryHinted<E>
+where
+    E: RetryHint + Display,
+    Tracer: ErrorMessageTracer,
+{
+    type Detail = RetryHintedDetail;
+    type Source = E;
+
+    #[track_caller]
+    fn error_details(source: Self::Source) -> (Self::Detail, Option<Tracer>) {
+        let message = source.to_string();
+        let trace = Tracer::new_message(&message);
+        let retry_after = source.retry_hint();
+        (RetryHintedDetail { message, retry_after }, Some(trace))
+    }
+
+    fn retry_after(detail: &Self::Detail) -> Option<Duration> {
+        detail.retry_after
+    }
+
+    fn detail_display(detail: &Self::Detail) -> Option<&dyn Display> {
+        Some(&detail.message)
+    }
+}
+
+/// Returns the delay before a retry hinted in the headers of a response,
+/// from the `Retry-After` header, or from the `grpc-retry-pushback-ms`
+/// metadata otherwise.
+///
+/// A negative gRPC pushback, which tells the client not to retry, and the
+/// headers that cannot be parsed are ignored.
+pub fn retry_after(headers: &HeaderMap) -> Option<Duration> {
+    let retry_after = headers
+        .get(RETRY_AFTER)
+        .and_then(|value| value.to_str().ok())
+        .and_then(|value| parse_retry_after(value, SystemTime::now()));
+
+    retry_after.or_else(|| {
+        headers
+            .get(GRPC_RETRY_PUSHBACK)
+            .and_then(|value| value.to_str().ok())
+            .and_then(parse_grpc_pushback)
+    })
+}
+
+/// Parses the value of a `grpc-retry-pushback-ms` metadata, ignoring the
+/// negative values, which tell the client not to retry.
+fn parse_grpc_pushback(value: &str) -> Option<Duration> {
+    value.trim().parse::<u64>().ok().map(Duration::from_millis)
+}
+
+/// Parses the value of a `Retry-After` header, given either as a number of
+/// seconds, or as an HTTP date in the IMF-fixdate format, such as
+/// `Sun, 06 Nov 1994 08:49:37 GMT`, in which case the delay is counted from
+/// `now`. A date in the past gives a zero delay.
+pub fn parse_retry_after(value: &str, now: SystemTime) -> Option<Duration> {
+    let value = value.trim();
+    if let Ok(seconds) = value.parse::<u64>() {
+        return Some(Duration::from_secs(seconds));
+    }
+
+    let date = parse_http_date(value)?;
+    Some(date.duration_since(now).unwrap_or_default())
+}
+
+fn parse_http_date(value: &str) -> Option<SystemTime> {
+    const MONTHS: [&str; 12] = [
+        "Jan", "Feb", "Mar", "Apr", "May", "Jun", "Jul", "Aug", "Sep", "Oct", "Nov", "Dec",
+    ];
+
+    let mut parts = value.split_whitespace();
+    let _weekday = parts.next().filter(|weekday| weekday.ends_with(','))?;
+    let day: u64 = parts.next()?.parse().ok()?;
+    let month = parts.next()?;
+    let month = MONTHS.iter().position(|name| *name == month)? as u64 + 1;
+    let year: u64 = parts.next()?.parse().ok()?;
+    let mut time = parts.next()?.split(':').map(|part| part.parse::<u64>().ok());
+    let (hour, minute, second) = (time.next()??, time.next()??, time.next()??);
+
+    if parts.next()? != "GMT"
+        || parts.next().is_some()
+        || time.next().is_some()
+        || year < 1970
+        || !(1..=31).contains(&day)
+        || hour > 23
+        || minute > 59
+        || second > 60
+    {
+        return None;
+    }
+
+    // The number of days since the epoch of the civil date, counted from
+    // March so that the leap day is the last day of the year.
+    let (year, month) = if month <= 2 {
+        (year - 1, month + 9)
+    } else {
+        (year, month - 3)
+    };
+    let era = year / 400;
+    let year_of_era = year % 400;
+    let day_of_year = (153 * month + 2) / 5 + day - 1;
+    let day_of_era = year_of_era * 365 + year_of_era / 4 - year_of_era / 100 + day_of_year;
+    let days = (era * 146097 + day_of_era).checked_sub(719468)?;
+
+    let seconds = days * 86400 + hour * 3600 + minute * 60 + second;
+    Some(UNIX_EPOCH + Duration::from_secs(seconds))
+}
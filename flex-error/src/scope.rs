@@ -0,0 +1,163 @@
+/*!
+  Scoped recording of error traces, for asserting on trace contents in tests.
+
+  The error types defined by [`define_error!`](crate::define_error) have their
+  tracer type fixed to [`DefaultTracer`](crate::DefaultTracer), which may be
+  an opaque tracer such as [`eyre::Report`]. [`with_tracer_scope`] runs a
+  closure while recording all messages traced by the built-in error tracers
+  on the current thread, and replays the messages of each error trace into
+  its own tracer of type `T` chosen by the test:
+
+  ```
+  # use flex_error::tracer_impl::string::StringTracer;
+  # use flex_error::{define_error, with_tracer_scope};
+  # define_error! {
+  #   NetError {
+  #     Refused
+  #       | _ | { "connection refused" },
+  #     Connect
+  #       [ Self ]
+  #       | _ | { "failed to connect" },
+  #   }
+  # }
+  # fn connect(_address: &str) -> Result<(), NetError> {
+  #   Err(NetError::connect(NetError::refused()))
+  # }
+  # let address = "127.0.0.1:26657";
+  let (result, traces) = with_tracer_scope::<StringTracer, _>(|| connect(address));
+
+  assert!(result.is_err());
+  assert_eq!(traces.len(), 1);
+  assert_eq!(traces[0].0, "failed to connect: connection refused");
+  ```
+*/
+
+use alloc::string::{String, ToString};
+use alloc::vec::Vec;
+use core::cell::RefCell;
+
+use crate::render::{Joined, TraceRender};
+use crate::tracer::ErrorMessageTracer;
+
+/// The messages recorded for an error trace, with the joined messages
+/// of the trace as last seen, which identify the trace when messages are
+/// added to it.
+struct RecordedTrace {
+    key: String,
+    messages: Vec<String>,
+}
+
+std::thread_local! {
+    static SCOPES: RefCell<Vec<Vec<RecordedTrace>>> = const { RefCell::new(Vec::new()) };
+}
+
+/// Runs `f` while recording the messages traced on the current thread,
+/// and returns its result together with one tracer of type `T` for each
+/// error trace created or extended in `f`, in the order in which the
+/// traces were first traced. Each tracer has traced the recorded messages
+/// of its error trace in the same order.
+///
+/// Scopes can be nested, in which case the messages are only recorded by
+/// the innermost scope.
+pub fn with_tracer_scope<T, R>(f: impl FnOnce() -> R) -> (R, Vec<T>)
+where
+    T: ErrorMessageTracer,
+{
+    struct ScopeGuard;
+
+    impl Drop for ScopeGuard {
+        fn drop(&mut self) {
+            let _ = SCOPES.try_with(|scopes| scopes.borrow_mut().pop());
+        }
+    }
+
+    SCOPES.with(|scopes| scopes.borrow_mut().push(Vec::new()));
+    let guard = ScopeGuard;
+
+    let result = f();
+
+    let recorded = SCOPES.with(|scopes| {
+        scopes
+            .borrow_mut()
+            .last_mut()
+            .map(core::mem::take)
+            .unwrap_or_default()
+    });
+
+    // The messages traced while replaying are recorded into the current
+    // scope, and discarded when the guard pops it.
+    let traces = recorded
+        .into_iter()
+        .filter_map(|trace| {
+            let mut messages = trace.messages.into_iter();
+            let first = messages.next()?;
+            Some(messages.fold(T::new_message(&first), |trace, message| {
+                trace.add_message(&message)
+            }))
+        })
+        .collect();
+
+    drop(guard);
+
+    (result, traces)
+}
+
+/// Returns the key identifying an error trace in the innermost active
+/// scope, or `None` when no scope is active.
+pub(crate) fn trace_key<Trace: TraceRender + ?Sized>(trace: &Trace) -> Option<String> {
+    SCOPES
+        .try_with(|scopes| {
+            if scopes.borrow().is_empty() {
+                None
+            } else {
+                Some(Joined(trace).to_string())
+            }
+        })
+        .ok()
+        .flatten()
+}
+
+/// Records a newly created error trace in the innermost active scope,
+/// if any.
+pub(crate) fn record_new<Trace: TraceRender + ?Sized>(trace: &Trace) {
+    if let Some(key) = trace_key(trace) {
+        with_innermost(|scope| {
+            scope.push(RecordedTrace {
+                messages: alloc::vec![key.clone()],
+                key,
+            })
+        });
+    }
+}
+
+/// Records a message added to the error trace identified by `key`, as
+/// returned by [`trace_key`] before the message was added, which results
+/// in `trace`. Nothing is recorded when the tracer has not added the
+/// message, such as for a message repeating the outermost message.
+pub(crate) fn record_add<Trace: TraceRender + ?Sized>(key: String, message: String, trace: &Trace) {
+    let Some(new_key) = trace_key(trace) else { return };
+    if new_key == key {
+        return;
+    }
+
+    with_innermost(|scope| match scope.iter_mut().rev().find(|trace| trace.key == key) {
+        Some(recorded) => {
+            recorded.messages.push(message);
+            recorded.key = new_key;
+        }
+        // The error trace was created outside of the scope, and is
+        // recorded from its messages before the message was added.
+        None => scope.push(RecordedTrace {
+            messages: alloc::vec![key, message],
+            key: new_key,
+        }),
+    });
+}
+
+fn with_innermost(f: impl FnOnce(&mut Vec<RecordedTrace>)) {
+    let _ = SCOPES.try_with(|scopes| {
+        if let Some(scope) = scopes.borrow_mut().last_mut() {
+            f(scope);
+        }
+    });
+}
@@ -0,0 +1,295 @@
+/*!
+  Guidance for selecting the [`ErrorSource`](crate::ErrorSource) wrapper for
+  an external error type.
+
+  It is easy to pick a source wrapper that silently discards information,
+  such as using [`DisplayOnly`] for an error type that could have been kept
+  as detail. The [`best_source!`](crate::best_source) macro inspects the traits
+  implemented by an error type `E` with the [`DefaultTracer`](crate::DefaultTracer),
+  and returns the [`SourceKind`] of the most information-preserving wrapper:
+
+  - [`TraceClone`] if `E: Clone` and `E` can be traced by the tracer.
+  - [`DisplayError`] if `E: Display`.
+  - [`DetailOnly`] otherwise.
+
+  The [`assert_best_source!`](crate::assert_best_source) macro can be used in
+  tests to check that the wrappers used in `define_error!` are the best choices:
+
+  ```
+  # use flex_error::{assert_best_source, DisplayError};
+  # #[derive(Debug)]
+  # struct ExternalError;
+  # impl std::fmt::Display for ExternalError {
+  #   fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+  #     write!(f, "external error")
+  #   }
+  # }
+  assert_best_source!(ExternalError => DisplayError<ExternalError>);
+  ```
+
+  ## Type-Level Selection
+
+  As the selection done by [`best_source!`](crate::best_source) relies on
+  method resolution, it gives a value, and not a type that can be used in
+  the `[ ... ]` source positions of `define_error!`. For these, the
+  [`Best`] wrapper uses the source wrapper selected by the [`BestSource`]
+  trait of the error type:
+
+  ```
+  # use flex_error::define_error;
+  # use flex_error::select::Best;
+  # define_error! {
+  #   UpstreamError {
+  #     Unavailable
+  #       | _ | { "upstream unavailable" },
+  #   }
+  # }
+  define_error! {
+    MyError {
+      Upstream
+        [ Best<UpstreamError> ]
+        | _ | { "upstream error" },
+    }
+  }
+  ```
+
+  [`BestSource`] is implemented for the error types defined by
+  `define_error!`, which are their own error sources, and for common
+  error types of the standard library. It can be implemented for other
+  error types with the [`select_source!`](crate::select_source) macro,
+  together with a test that checks the selection:
+
+  ```
+  # use flex_error::{assert_best_source, select_source, DisplayError};
+  # #[derive(Debug)]
+  # struct ExternalError;
+  # impl std::fmt::Display for ExternalError {
+  #   fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+  #     write!(f, "external error")
+  #   }
+  # }
+  select_source!(ExternalError => DisplayError<ExternalError>);
+
+  // in a test of the crate
+  assert_best_source!(ExternalError => DisplayError<ExternalError>);
+  ```
+
+  As the trait solver cannot choose between overlapping implementations
+  without specialization, the selection cannot be made automatically for
+  all error types.
+*/
+
+use core::fmt::Display;
+use core::marker::PhantomData;
+use core::time::Duration;
+
+use crate::source::{DetailOnly, DisplayError, DisplayOnly, ErrorSource, TraceClone, TraceError};
+use crate::tracer::ErrorTracer;
+use crate::DefaultTracer;
+
+/// The kinds of [`ErrorSource`](crate::ErrorSource) wrappers for external error types.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SourceKind {
+    /// [`TraceClone`]
+    TraceClone,
+    /// [`TraceError`]
+    TraceError,
+    /// [`DisplayError`]
+    DisplayError,
+    /// [`DisplayOnly`]
+    DisplayOnly,
+    /// [`DetailOnly`]
+    DetailOnly,
+}
+
+mod sealed {
+    pub trait Sealed {}
+}
+
+/// A sealed trait implemented by the error source wrappers that
+/// have `E` as their `Source` type.
+pub trait SourceFor<E>: sealed::Sealed {
+    /// The kind of the error source wrapper.
+    const KIND: SourceKind;
+}
+
+impl<E> sealed::Sealed for TraceClone<E> {}
+impl<E> sealed::Sealed for TraceError<E> {}
+impl<E> sealed::Sealed for DisplayError<E> {}
+impl<E> sealed::Sealed for DisplayOnly<E> {}
+impl<E> sealed::Sealed for DetailOnly<E> {}
+
+impl<E> SourceFor<E> for TraceClone<E> {
+    const KIND: SourceKind = SourceKind::TraceClone;
+}
+
+impl<E> SourceFor<E> for TraceError<E> {
+    const KIND: SourceKind = SourceKind::TraceError;
+}
+
+impl<E> SourceFor<E> for DisplayError<E> {
+    const KIND: SourceKind = SourceKind::DisplayError;
+}
+
+impl<E> SourceFor<E> for DisplayOnly<E> {
+    const KIND: SourceKind = SourceKind::DisplayOnly;
+}
+
+impl<E> SourceFor<E> for DetailOnly<E> {
+    const KIND: SourceKind = SourceKind::DetailOnly;
+}
+
+/// Selects the error source wrapper used by [`Best`] for an error type.
+pub trait BestSource: Sized {
+    /// The most information-preserving error source wrapper for the type.
+    type Wrapper;
+}
+
+/// An error source that uses the wrapper selected by [`BestSource`] for `E`.
+pub struct Best<E>(PhantomData<E>);
+
+impl<E, Tracer> ErrorSource<Tracer> for Best<E>
+where
+    E: BestSource,
+    E::Wrapper: ErrorSource<Tracer, Source = E>,
+    <E::Wrapper as ErrorSource<Tracer>>::Detail: 'static,
+{
+    type Source = E;
+    type Detail = <E::Wrapper as ErrorSource<Tracer>>::Detail;
+
+    #[track_caller]
+    fn error_details(source: E) -> (Self::Detail, Option<Tracer>) {
+        E::Wrapper::error_details(source)
+    }
+
+    fn metadata(source: &E) -> Option<&crate::ErrorMetadata> {
+        E::Wrapper::metadata(source)
+    }
+
+    fn root_code(detail: &Self::Detail) -> Option<&'static str> {
+        E::Wrapper::root_code(detail)
+    }
+
+    fn retry_after(detail: &Self::Detail) -> Option<Duration> {
+        E::Wrapper::retry_after(detail)
+    }
+
+    fn exit_code(detail: &Self::Detail) -> Option<i32> {
+        E::Wrapper::exit_code(detail)
+    }
+
+    fn root_source<T: 'static>(detail: &Self::Detail) -> Option<&T>
+    where
+        Self::Detail: 'static,
+    {
+        E::Wrapper::root_source(detail)
+    }
+
+    fn detail_display(detail: &Self::Detail) -> Option<&dyn Display> {
+        E::Wrapper::detail_display(detail)
+    }
+}
+
+/// Implements [`BestSource`](crate::select::BestSource) for an error type,
+/// with the given error source wrapper.
+#[macro_export]
+macro_rules! select_source {
+    ( $error:ty => $source:ty ) => {
+        impl $crate::select::BestSource for $error {
+            type Wrapper = $source;
+        }
+    };
+}
+
+select_source!(core::fmt::Error => TraceClone<core::fmt::Error>);
+select_source!(core::num::ParseIntError => TraceClone<core::num::ParseIntError>);
+select_source!(core::num::ParseFloatError => TraceClone<core::num::ParseFloatError>);
+select_source!(core::num::TryFromIntError => TraceClone<core::num::TryFromIntError>);
+select_source!(core::str::ParseBoolError => TraceClone<core::str::ParseBoolError>);
+select_source!(core::str::Utf8Error => TraceClone<core::str::Utf8Error>);
+select_source!(core::char::ParseCharError => TraceClone<core::char::ParseCharError>);
+
+#[cfg(feature = "alloc")]
+select_source!(alloc::string::FromUtf8Error => TraceClone<alloc::string::FromUtf8Error>);
+
+#[cfg(feature = "std")]
+select_source!(std::io::Error => DisplayError<std::io::Error>);
+
+/// Probe type used by [`best_source!`](crate::best_source) to select the
+/// source kind through auto-ref method resolution.
+#[doc(hidden)]
+pub struct Probe<E>(PhantomData<E>);
+
+impl<E> Probe<E> {
+    #[doc(hidden)]
+    pub fn new() -> Self {
+        Probe(PhantomData)
+    }
+}
+
+impl<E> Default for Probe<E> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[doc(hidden)]
+pub trait ProbeTraceClone {
+    fn best_source_kind(&self) -> SourceKind {
+        SourceKind::TraceClone
+    }
+}
+
+#[doc(hidden)]
+pub trait ProbeDisplayError {
+    fn best_source_kind(&self) -> SourceKind {
+        SourceKind::DisplayError
+    }
+}
+
+#[doc(hidden)]
+pub trait ProbeDetailOnly {
+    fn best_source_kind(&self) -> SourceKind {
+        SourceKind::DetailOnly
+    }
+}
+
+impl<E> ProbeTraceClone for &&Probe<E>
+where
+    E: Clone,
+    DefaultTracer: ErrorTracer<E>,
+{
+}
+
+impl<E: Display> ProbeDisplayError for &Probe<E> {}
+
+impl<E> ProbeDetailOnly for Probe<E> {}
+
+/// Returns the [`SourceKind`](crate::select::SourceKind) of the most
+/// information-preserving error source wrapper for the given error type,
+/// when used with the [`DefaultTracer`](crate::DefaultTracer).
+#[macro_export]
+macro_rules! best_source {
+    ( $error:ty ) => {{
+        #[allow(unused_imports)]
+        use $crate::select::{ProbeDetailOnly, ProbeDisplayError, ProbeTraceClone};
+
+        (&&&$crate::select::Probe::<$error>::new()).best_source_kind()
+    }};
+}
+
+/// Asserts that the given error source wrapper is the most
+/// information-preserving wrapper for the given error type,
+/// as selected by [`best_source!`](crate::best_source).
+#[macro_export]
+macro_rules! assert_best_source {
+    ( $error:ty => $source:ty ) => {
+        ::core::assert_eq!(
+            <$source as $crate::select::SourceFor<$error>>::KIND,
+            $crate::best_source!($error),
+            "{} is not the most information-preserving error source for {}",
+            ::core::stringify!($source),
+            ::core::stringify!($error),
+        )
+    };
+}
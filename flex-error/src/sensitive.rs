@@ -0,0 +1,51 @@
+/*!
+  Compile-time enforcement that sensitive types are never stored in error details.
+
+  Error details are commonly logged, serialized and sent across process
+  boundaries, so types such as private keys must not be kept in them. Such
+  types can be marked once with [`NotForErrorDetail`]:
+
+  ```
+  # struct PrivateKey([u8; 32]);
+  impl flex_error::NotForErrorDetail for PrivateKey {}
+  ```
+
+  [`define_error!`](crate::define_error) then fails to compile if a marked type
+  is used as the type of a sub-error field, or as the error detail of a sub-error
+  source. The compile error is reported as an ambiguous type annotation on the
+  offending `define_error!` call.
+
+  Only the field types themselves are checked. Marked types nested inside other
+  types, such as `Vec<PrivateKey>`, are not detected unless those types are
+  marked as well.
+*/
+
+/// Marker trait for types that must never be stored in an error detail.
+pub trait NotForErrorDetail {}
+
+/// Helper trait that is implemented twice for types implementing
+/// [`NotForErrorDetail`], making the method lookup in
+/// [`assert_error_detail_field!`](crate::assert_error_detail_field) ambiguous.
+#[doc(hidden)]
+pub trait AmbiguousIfNotForErrorDetail<A> {
+    fn check() {}
+}
+
+#[doc(hidden)]
+pub struct Sensitive;
+
+impl<T: ?Sized> AmbiguousIfNotForErrorDetail<()> for T {}
+
+impl<T: ?Sized + NotForErrorDetail> AmbiguousIfNotForErrorDetail<Sensitive> for T {}
+
+/// Asserts at compile time that the given type is allowed to be stored in an
+/// error detail, i.e. it does not implement [`NotForErrorDetail`].
+#[macro_export]
+#[doc(hidden)]
+macro_rules! assert_error_detail_field {
+  ( $field:ty ) => {
+    const _: fn() = || {
+      let _ = <$field as $crate::sensitive::AmbiguousIfNotForErrorDetail<_>>::check;
+    };
+  };
+}
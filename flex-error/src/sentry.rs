@@ -0,0 +1,146 @@
+/*!
+  Export of errors as [Sentry](https://sentry.io) events.
+
+  When the `sentry` feature is enabled, the error types defined by
+  [`define_error!`](crate::define_error) have the generated method
+  `to_sentry_event`, which returns the error as an event of the
+  [`sentry_types`] protocol, ready to be sent with a Sentry client:
+
+  ```
+  # mod sentry {
+  #   pub fn capture_event(event: sentry_types::protocol::v7::Event<'static>) {
+  #     assert_eq!(event.exception.values.len(), 1);
+  #   }
+  # }
+  # flex_error::define_error! {
+  #   MyError {
+  #     Timeout
+  #       | _ | { "timed out" },
+  #   }
+  # }
+  # let err = MyError::timeout();
+  sentry::capture_event(err.to_sentry_event());
+  ```
+
+  The messages in the error trace are mapped to the exception chain of the
+  event, with the innermost message as the first exception, as expected by
+  Sentry. The outermost exception has the type `MyError::Variant`, so that
+  the events are grouped by sub-error, and the source location recorded by
+  the tracer, if any, is attached as the stack frame of the innermost
+  exception. The code returned by `root_code`, if any, is set as the
+  `error.code` tag.
+
+  If the `@serde` option is given, the error detail is attached as the
+  `detail` context of the event, serialized with the `@serde` representation.
+  The fields annotated with `#[detail(skip_serde)]` are left out, so that
+  sensitive fields are never sent to Sentry. The messages in the error trace
+  are filtered by the trace filter set with
+  [`set_trace_filter`](crate::set_trace_filter) before they are stored, as
+  with the other exports.
+*/
+
+use alloc::format;
+use alloc::string::{String, ToString};
+use alloc::vec::Vec;
+
+use sentry_types::protocol::v7::{
+    Context, Event, Exception, Frame, Level, Map, Stacktrace, Values,
+};
+
+use crate::render::{trace_entries, TraceRender};
+
+/// The exception type of the inner messages in the exception chain, which
+/// are only known by their messages.
+const TRACE_EXCEPTION_TYPE: &str = "flex_error::TraceMessage";
+
+/// Builds a Sentry event from the messages in an error trace. The error
+/// type names the outermost exception, and `detail` is attached as the
+/// `detail` context, if any. See the [module documentation](self).
+pub fn trace_event<Trace>(
+    error_type: String,
+    trace: &Trace,
+    root_code: Option<&str>,
+    detail: Option<serde_json::Value>,
+) -> Event<'static>
+where
+    Trace: TraceRender + ?Sized,
+{
+    let entries = trace_entries(trace);
+    let outermost = entries.len().saturating_sub(1);
+
+    let exceptions: Vec<Exception> = entries
+        .into_iter()
+        .rev()
+        .enumerate()
+        .map(|(index, entry)| Exception {
+            ty: if index == outermost {
+                error_type.clone()
+            } else {
+                TRACE_EXCEPTION_TYPE.to_string()
+            },
+            value: Some(entry.message),
+            stacktrace: entry.location.map(location_stacktrace),
+            ..Default::default()
+        })
+        .collect();
+
+    let mut event = Event::new();
+    event.level = Level::Error;
+    event.logger = Some("flex_error".to_string());
+    event.exception = Values::from(exceptions);
+
+    if let Some(code) = root_code {
+        event
+            .tags
+            .insert("error.code".to_string(), code.to_string());
+    }
+
+    if let Some(detail) = detail {
+        let detail = match detail {
+            serde_json::Value::Object(fields) => fields.into_iter().collect(),
+            value => {
+                let mut fields = Map::new();
+                fields.insert("value".to_string(), value);
+                fields
+            }
+        };
+        event
+            .contexts
+            .insert("detail".to_string(), Context::Other(detail));
+    }
+
+    event
+}
+
+/// Parses a source location in the `file:line:column` form into a
+/// stack trace with a single frame.
+fn location_stacktrace(location: &str) -> Stacktrace {
+    let mut parts = location.rsplitn(3, ':');
+    let column = parts.next().and_then(|part| part.parse().ok());
+    let line = parts.next().and_then(|part| part.parse().ok());
+
+    let frame = match (parts.next(), line, column) {
+        (Some(file), Some(line), Some(column)) => Frame {
+            filename: Some(file.to_string()),
+            lineno: Some(line),
+            colno: Some(column),
+            ..Default::default()
+        },
+        _ => Frame {
+            filename: Some(location.to_string()),
+            ..Default::default()
+        },
+    };
+
+    Stacktrace {
+        frames: alloc::vec![frame],
+        ..Default::default()
+    }
+}
+
+/// Returns the exception type of the outermost exception, in the form
+/// `MyError::Variant`.
+#[doc(hidden)]
+pub fn error_type(name: &str, kind: &dyn core::fmt::Debug) -> String {
+    format!("{}::{:?}", name, kind)
+}
@@ -1,6 +1,10 @@
+use core::any::Any;
 use core::fmt::Display;
 use core::marker::PhantomData;
+use core::time::Duration;
 
+#[cfg(feature = "alloc")]
+use crate::render::{trace_entries, TraceRender};
 use crate::tracer::{ErrorMessageTracer, ErrorTracer};
 
 /**
@@ -19,13 +23,22 @@ use crate::tracer::{ErrorMessageTracer, ErrorTracer};
      to be used for tracing, and also be stored as detail.
    - [`DisplayOnly`] - An error source that implements [`Display`](std::fmt::Display)
      to be used for tracing, and discarded instead of being stored as detail.
+   - [`DisplayChain`] - An error source that implements [`Error`](std::error::Error),
+     with the messages of its chain of sources used for tracing, and also be
+     stored as detail.
    - [`DetailOnly`] - An error source that is used as detail and do not contain any error trace.
    - [`TraceError`] - An error source that implements [`Error`](std::error::Error)
      and used only for tracing.
    - [`TraceClone`] - An error source that implements [`Error`](std::error::Error) and
      have a cloned copy as detail.
+   - [`BoxedTraceError`] - An error source that is a boxed [`Error`](std::error::Error)
+     and used only for tracing, which can also be captured from errors that are
+     not `'static`.
+   - [`Both`] - A pair of error sources, such as a primary error together with
+     a cleanup error, with both details kept and both traces chained.
+   - [`BoxSource`] - An error source wrapping another error source, with its
+     detail boxed, such as for mutually recursive error types.
 **/
-
 pub trait ErrorSource<Trace> {
     /// The type of the error source.
     type Source;
@@ -36,6 +49,167 @@ pub trait ErrorSource<Trace> {
     /// Extracts the error details out from the error source, together with
     /// an optional error trace.
     fn error_details(source: Self::Source) -> (Self::Detail, Option<Trace>);
+
+    /// Returns the metadata kept by the error source, which is carried to
+    /// the errors wrapping it. Defaults to `None` for external error sources,
+    /// and for the error types defined without the `@metadata` option.
+    fn metadata(_source: &Self::Source) -> Option<&ErrorMetadata> {
+        None
+    }
+
+    /// Returns the code of the innermost error in the error detail that has
+    /// an error code assigned with `@codes`. Defaults to `None` for external
+    /// error sources.
+    fn root_code(_detail: &Self::Detail) -> Option<&'static str> {
+        None
+    }
+
+    /// Returns the delay before a retry hinted by the server that returned
+    /// the error, if any. Defaults to `None` for external error sources.
+    fn retry_after(_detail: &Self::Detail) -> Option<Duration> {
+        None
+    }
+
+    /// Returns the process exit code given with `@exit_code` to the
+    /// outermost error in the error detail that has one. Defaults to `None`
+    /// for external error sources.
+    fn exit_code(_detail: &Self::Detail) -> Option<i32> {
+        None
+    }
+
+    /// Returns the innermost external error of type `T` kept in the error
+    /// detail, such as the error of a [`TraceClone`] or [`DetailOnly`]
+    /// source. Defaults to `None` for the error sources that do not keep
+    /// the error in their detail.
+    fn root_source<T: 'static>(_detail: &Self::Detail) -> Option<&T>
+    where
+        Self::Detail: 'static,
+    {
+        None
+    }
+
+    /// Returns the error detail as [`Display`], if it can be displayed, so
+    /// that the formatters of the sub-errors can include it in their
+    /// messages with the generated `source_display` method. Defaults to
+    /// `None` for the error sources whose detail is `()` or may not
+    /// implement `Display`.
+    fn detail_display(_detail: &Self::Detail) -> Option<&dyn Display> {
+        None
+    }
+}
+
+/// Tracks whether an error defined by [`define_error!`](crate::define_error)
+/// has already been reported, e.g. logged at the point where it is wrapped
+/// by another error.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum ReportStatus {
+    /// Neither the error nor any error wrapped by it has been reported.
+    #[default]
+    Unreported,
+
+    /// An error wrapped by the error has been reported, but not the error itself.
+    SourceReported,
+
+    /// The error itself has been reported.
+    Reported,
+}
+
+impl ReportStatus {
+    /// Returns the status of an error wrapping a source with this status.
+    pub fn wrapped(self) -> Self {
+        match self {
+            ReportStatus::Unreported => ReportStatus::Unreported,
+            _ => ReportStatus::SourceReported,
+        }
+    }
+}
+
+/// The metadata kept next to the error detail and the error trace by the
+/// error types defined by [`define_error!`](crate::define_error) with the
+/// `@metadata` option, and carried to the errors wrapping them.
+#[derive(Debug, Clone, Default)]
+pub struct ErrorMetadata {
+    status: ReportStatus,
+    #[cfg(feature = "incidents")]
+    incident: Option<(crate::incident::IncidentId, std::time::SystemTime)>,
+    #[cfg(feature = "backtrace")]
+    backtrace: Option<alloc::sync::Arc<std::backtrace::Backtrace>>,
+}
+
+impl ErrorMetadata {
+    /// Creates the metadata of a new error wrapping error sources with the
+    /// given metadata.
+    #[doc(hidden)]
+    #[track_caller]
+    pub fn capture(sources: &[Option<&ErrorMetadata>]) -> Self {
+        let status = sources
+            .iter()
+            .flatten()
+            .map(|source| source.status.wrapped())
+            .max_by_key(|status| *status != ReportStatus::Unreported)
+            .unwrap_or_default();
+
+        ErrorMetadata {
+            status,
+            #[cfg(feature = "incidents")]
+            incident: sources
+                .iter()
+                .flatten()
+                .find_map(|source| source.incident)
+                .or_else(|| {
+                    Some((
+                        crate::incident::IncidentId::next(),
+                        std::time::SystemTime::now(),
+                    ))
+                }),
+            #[cfg(feature = "backtrace")]
+            backtrace: sources
+                .iter()
+                .flatten()
+                .find_map(|source| source.backtrace.clone())
+                .or_else(|| {
+                    Some(alloc::sync::Arc::new(
+                        std::backtrace::Backtrace::capture(),
+                    ))
+                }),
+        }
+    }
+
+    #[doc(hidden)]
+    pub fn with_status(mut self, status: ReportStatus) -> Self {
+        self.status = status;
+        self
+    }
+
+    /// Returns whether the error, or an error wrapped by it, has already
+    /// been reported.
+    pub fn report_status(&self) -> ReportStatus {
+        self.status
+    }
+
+    /// Returns the incident ID assigned to the innermost error with metadata
+    /// wrapped by the error, or to the error itself.
+    #[cfg(feature = "incidents")]
+    pub fn incident_id(&self) -> Option<crate::incident::IncidentId> {
+        self.incident.map(|(id, _)| id)
+    }
+
+    /// Returns the backtrace captured when the innermost error with metadata
+    /// wrapped by the error, or the error itself, was created.
+    ///
+    /// The backtrace is captured with
+    /// [`Backtrace::capture`](std::backtrace::Backtrace::capture), which
+    /// follows the `RUST_LIB_BACKTRACE` and `RUST_BACKTRACE` environment
+    /// variables.
+    #[cfg(feature = "backtrace")]
+    pub fn backtrace(&self) -> Option<&std::backtrace::Backtrace> {
+        self.backtrace.as_deref()
+    }
+
+    #[cfg(feature = "incidents")]
+    pub(crate) fn incident(&self) -> Option<(crate::incident::IncidentId, std::time::SystemTime)> {
+        self.incident
+    }
 }
 
 /// Type alias to `<Error as ErrorSource<Trace>>::Detail`
@@ -58,10 +232,26 @@ pub struct NoSource;
 ///
 /// Both its `Source` and `Detail` types are `E`. When extraced, it also provides
 /// an error trace that is traced from its string representation.
+///
+/// The sources of `E` are not traced, even if `E` implements
+/// [`Error`](std::error::Error). [`DisplayChain`] can be used instead for such
+/// errors, so that the messages of the inner errors are kept.
 pub struct DisplayError<E>(PhantomData<E>);
 
 pub struct DisplayOnly<E>(PhantomData<E>);
 
+/// An [`ErrorSource`] like [`DisplayError`], for errors implementing
+/// [`Error`](std::error::Error) that are traced together with their chain
+/// of sources.
+///
+/// Both its `Source` and `Detail` types are `E`. When extracted, the error
+/// trace is built from the messages of the innermost source up to the error
+/// itself, so that the messages of the inner errors are not lost, unlike
+/// with `DisplayError`, and the error is not required to be `Send`, `Sync`
+/// and `'static`, unlike with [`TraceClone`].
+#[cfg(feature = "std")]
+pub struct DisplayChain<E>(PhantomData<E>);
+
 /// An [`ErrorSource`] that should implement [`Error`](std::error::Error) and
 /// other constraints such as `Send`, `Sync`, `'static`, so that it can be traced
 /// by error tracing libraries such as [`eyre`] and [`anyhow`]. Because these libraries
@@ -71,6 +261,88 @@ pub struct TraceError<E>(PhantomData<E>);
 
 pub struct TraceClone<E>(PhantomData<E>);
 
+/// An [`ErrorSource`] for errors that are boxed and type-erased as
+/// `Box<dyn Error + Send + Sync>`, and used only for tracing.
+///
+/// [`TraceError`] requires the error to be `Send`, `Sync` and `'static` for
+/// most tracers, which rules out errors borrowing data, such as parse errors
+/// borrowing the input. Such errors can instead be captured with
+/// [`BoxedTraceError::capture`], which copies the messages of the error and
+/// its sources into an owned error before boxing it:
+///
+/// ```
+/// # use flex_error::{define_error, BoxedTraceError};
+/// # #[derive(Debug)]
+/// # struct ParseIntError<'a>(&'a str);
+/// # impl std::fmt::Display for ParseIntError<'_> {
+/// #   fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+/// #     write!(f, "invalid number {:?}", self.0)
+/// #   }
+/// # }
+/// # impl std::error::Error for ParseIntError<'_> {}
+/// # fn parse(input: &str) -> Result<u64, ParseIntError<'_>> {
+/// #   input.parse().map_err(|_| ParseIntError(input))
+/// # }
+/// # define_error! {
+/// #   MyError {
+/// #     Parse
+/// #       [ BoxedTraceError ]
+/// #       | _ | { "failed to parse the input" },
+/// #   }
+/// # }
+/// # fn run(input: String) -> Result<u64, MyError> {
+/// let value = parse(&input)
+///     .map_err(|e| MyError::parse(BoxedTraceError::capture(&e)))?;
+/// # Ok(value)
+/// # }
+/// # assert!(run("42x".to_string()).is_err());
+/// ```
+///
+/// Errors that are already `Send + Sync + 'static` can be boxed with `into()`.
+#[cfg(feature = "std")]
+pub struct BoxedTraceError;
+
+/// An [`ErrorSource`] for errors that are boxed and type-erased as
+/// `Box<dyn Error + Send + Sync>`, as returned by many libraries, which are
+/// traced from their string representation and kept as the detail.
+///
+/// Both its `Source` and `Detail` types are the boxed error. Unlike
+/// [`BoxedTraceError`], the error is traced with
+/// [`ErrorMessageTracer::new_message`](crate::tracer::ErrorMessageTracer::new_message),
+/// so it can be used with any error tracer, and the error is still
+/// available from the detail, e.g. to be downcast:
+///
+/// ```
+/// # use flex_error::{define_error, DynError};
+/// define_error! {
+///   PluginError {
+///     Plugin
+///       { name: String }
+///       [ DynError ]
+///       | e | { format_args!("plugin {} failed", e.name) },
+///   }
+/// }
+///
+/// # let io_error = std::io::Error::new(std::io::ErrorKind::NotFound, "no such file");
+/// # let err = PluginError::plugin("loader".to_string(), Box::new(io_error));
+/// if let PluginErrorDetail::Plugin(e) = err.detail() {
+///   if let Some(io) = e.source.downcast_ref::<std::io::Error>() {
+///     # assert_eq!(io.kind(), std::io::ErrorKind::NotFound);
+///     eprintln!("plugin {} failed with an I/O error: {}", e.name, io);
+///   }
+/// }
+/// ```
+///
+/// [`DynErrorOnly`] can be used instead to only trace the error, without
+/// keeping it as the detail.
+#[cfg(feature = "std")]
+pub struct DynError;
+
+/// An [`ErrorSource`] like [`DynError`], for boxed errors that are only
+/// traced from their string representation. Its `Detail` type is `()`.
+#[cfg(feature = "std")]
+pub struct DynErrorOnly;
+
 /// An [`ErrorSource`] that contains only the error trace with no detail.
 /// This can for example be used for upstream functions that return tracers like
 /// [`eyre::Report`] directly.
@@ -89,6 +361,75 @@ pub struct DetailOnly<Detail>(PhantomData<Detail>);
 
 pub struct BoxDetail<Detail: ?Sized>(PhantomData<Detail>);
 
+/// An [`ErrorSource`] for a pair of errors returned together, such as a
+/// primary error and a secondary error raised while cleaning up after it,
+/// so that neither of them is dropped when they are wrapped.
+///
+/// Its `Source` type is the pair of the sources of `A` and `B`, and its
+/// `Detail` type is the pair of their details. When extracted, the messages
+/// in the error trace of `A` are added on top of the error trace of `B`, so
+/// that the primary error is right below the message of the wrapping error:
+///
+/// ```
+/// # use flex_error::{define_error, Both, DisplayError, TraceError};
+/// # use std::io;
+/// # struct Transaction;
+/// # impl Transaction {
+/// #   fn commit(self) -> Result<(), (io::Error, String)> {
+/// #     Err((io::Error::new(io::ErrorKind::Other, "disk full"), "rollback failed".to_string()))
+/// #   }
+/// # }
+/// # let txn = Transaction;
+/// define_error! {
+///   StoreError {
+///     Commit
+///       [ Both<TraceError<io::Error>, DisplayError<String>> ]
+///       | _ | { "failed to commit" },
+///   }
+/// }
+///
+/// let result = txn.commit().map_err(StoreError::commit);
+/// # assert!(result.is_err());
+/// ```
+#[cfg(feature = "alloc")]
+pub struct Both<A, B>(PhantomData<(A, B)>);
+
+/// An [`ErrorSource`] that wraps the error source `E`, with its detail
+/// boxed. Its `Source` type is the `Source` type of `E`, and its `Detail`
+/// type is `Box<E::Detail>`.
+///
+/// The `[ Self ]` source boxes the detail of the error type being defined,
+/// but error types defined in different modules that wrap each other, such
+/// as an error `A` wrapping an error `B` wrapping `A`, would otherwise have
+/// details of infinite size. Boxing the source detail on one side of the
+/// cycle breaks it:
+///
+/// ```
+/// # use flex_error::{define_error, BoxSource};
+/// define_error! {
+///   QueryError {
+///     Plan
+///       [ BoxSource<PlanError> ]
+///       | _ | { "failed to plan the query" },
+///   }
+/// }
+///
+/// define_error! {
+///   PlanError {
+///     Subquery
+///       [ QueryError ]
+///       | _ | { "failed to run a subquery" },
+///   }
+/// }
+/// ```
+///
+/// As for `[ Self ]`, the boxed detail is left out of the `where` bounds of
+/// the `Arbitrary` and `Valuable` implementations of the error detail, so
+/// that the cycle is not followed by the trait solver, and is then required
+/// to implement these traits when the features are enabled.
+#[cfg(feature = "alloc")]
+pub struct BoxSource<E>(PhantomData<E>);
+
 impl<Detail, Trace> ErrorSource<Trace> for DetailOnly<Detail> {
     type Detail = Detail;
     type Source = Detail;
@@ -96,6 +437,13 @@ impl<Detail, Trace> ErrorSource<Trace> for DetailOnly<Detail> {
     fn error_details(source: Self::Source) -> (Self::Detail, Option<Trace>) {
         (source, None)
     }
+
+    fn root_source<T: 'static>(detail: &Self::Detail) -> Option<&T>
+    where
+        Self::Detail: 'static,
+    {
+        (detail as &dyn Any).downcast_ref()
+    }
 }
 
 impl<Trace> ErrorSource<Trace> for NoSource {
@@ -124,10 +472,22 @@ where
     type Detail = E;
     type Source = E;
 
+    #[track_caller]
     fn error_details(source: Self::Source) -> (Self::Detail, Option<Tracer>) {
         let trace = Tracer::new_message(&source);
         (source, Some(trace))
     }
+
+    fn detail_display(detail: &Self::Detail) -> Option<&dyn Display> {
+        Some(detail)
+    }
+
+    fn root_source<T: 'static>(detail: &Self::Detail) -> Option<&T>
+    where
+        Self::Detail: 'static,
+    {
+        (detail as &dyn Any).downcast_ref()
+    }
 }
 
 impl<E, Tracer> ErrorSource<Tracer> for DisplayOnly<E>
@@ -138,12 +498,52 @@ where
     type Detail = ();
     type Source = E;
 
+    #[track_caller]
     fn error_details(source: Self::Source) -> (Self::Detail, Option<Tracer>) {
         let trace = Tracer::new_message(&source);
         ((), Some(trace))
     }
 }
 
+#[cfg(feature = "std")]
+impl<E, Tracer> ErrorSource<Tracer> for DisplayChain<E>
+where
+    E: std::error::Error,
+    Tracer: ErrorMessageTracer,
+{
+    type Detail = E;
+    type Source = E;
+
+    #[track_caller]
+    fn error_details(source: Self::Source) -> (Self::Detail, Option<Tracer>) {
+        let trace = trace_chain(&source);
+        (source, Some(trace))
+    }
+
+    fn detail_display(detail: &Self::Detail) -> Option<&dyn Display> {
+        Some(detail)
+    }
+
+    fn root_source<T: 'static>(detail: &Self::Detail) -> Option<&T>
+    where
+        Self::Detail: 'static,
+    {
+        (detail as &dyn Any).downcast_ref()
+    }
+}
+
+/// Traces the messages of an error and its chain of sources, starting
+/// from the innermost source.
+#[cfg(feature = "std")]
+#[doc(hidden)]
+#[track_caller]
+pub fn trace_chain<Tracer: ErrorMessageTracer>(err: &dyn std::error::Error) -> Tracer {
+    match err.source() {
+        Some(source) => trace_chain::<Tracer>(source).add_message(&err),
+        None => Tracer::new_message(&err),
+    }
+}
+
 impl<E, Tracer> ErrorSource<Tracer> for TraceClone<E>
 where
     E: Clone,
@@ -152,11 +552,19 @@ where
     type Detail = E;
     type Source = E;
 
+    #[track_caller]
     fn error_details(source: Self::Source) -> (Self::Detail, Option<Tracer>) {
         let detail = source.clone();
         let trace = Tracer::new_trace(source);
         (detail, Some(trace))
     }
+
+    fn root_source<T: 'static>(detail: &Self::Detail) -> Option<&T>
+    where
+        Self::Detail: 'static,
+    {
+        (detail as &dyn Any).downcast_ref()
+    }
 }
 
 impl<E, Tracer> ErrorSource<Tracer> for TraceError<E>
@@ -166,8 +574,217 @@ where
     type Detail = ();
     type Source = E;
 
+    #[track_caller]
     fn error_details(source: Self::Source) -> (Self::Detail, Option<Tracer>) {
         let trace = Tracer::new_trace(source);
         ((), Some(trace))
     }
 }
+
+#[cfg(feature = "std")]
+impl<Tracer> ErrorSource<Tracer> for DynError
+where
+    Tracer: ErrorMessageTracer,
+{
+    type Detail = alloc::boxed::Box<dyn std::error::Error + Send + Sync>;
+    type Source = alloc::boxed::Box<dyn std::error::Error + Send + Sync>;
+
+    #[track_caller]
+    fn error_details(source: Self::Source) -> (Self::Detail, Option<Tracer>) {
+        let trace = Tracer::new_message(&source);
+        (source, Some(trace))
+    }
+
+    fn detail_display(detail: &Self::Detail) -> Option<&dyn Display> {
+        Some(detail)
+    }
+}
+
+#[cfg(feature = "std")]
+impl<Tracer> ErrorSource<Tracer> for DynErrorOnly
+where
+    Tracer: ErrorMessageTracer,
+{
+    type Detail = ();
+    type Source = alloc::boxed::Box<dyn std::error::Error + Send + Sync>;
+
+    #[track_caller]
+    fn error_details(source: Self::Source) -> (Self::Detail, Option<Tracer>) {
+        let trace = Tracer::new_message(&source);
+        ((), Some(trace))
+    }
+}
+
+#[cfg(feature = "std")]
+impl BoxedTraceError {
+    /// Captures the messages of an error and its chain of sources into an
+    /// owned error, which can be used as the source of `BoxedTraceError`
+    /// regardless of the lifetime of the error.
+    pub fn capture<E>(err: &E) -> alloc::boxed::Box<dyn std::error::Error + Send + Sync>
+    where
+        E: std::error::Error + ?Sized,
+    {
+        alloc::boxed::Box::new(CapturedError::new(err))
+    }
+}
+
+#[cfg(feature = "std")]
+impl<Tracer> ErrorSource<Tracer> for BoxedTraceError
+where
+    Tracer: ErrorTracer<alloc::boxed::Box<dyn std::error::Error + Send + Sync>>,
+{
+    type Detail = ();
+    type Source = alloc::boxed::Box<dyn std::error::Error + Send + Sync>;
+
+    #[track_caller]
+    fn error_details(source: Self::Source) -> (Self::Detail, Option<Tracer>) {
+        let trace = Tracer::new_trace(source);
+        ((), Some(trace))
+    }
+}
+
+/// The messages of an error and its sources, captured by
+/// [`BoxedTraceError::capture`].
+#[cfg(feature = "std")]
+#[derive(Debug)]
+struct CapturedError {
+    message: alloc::string::String,
+    source: Option<alloc::boxed::Box<CapturedError>>,
+}
+
+#[cfg(feature = "std")]
+impl CapturedError {
+    fn new<E: std::error::Error + ?Sized>(err: &E) -> Self {
+        use alloc::string::ToString;
+
+        CapturedError {
+            message: err.to_string(),
+            source: err
+                .source()
+                .map(|source| alloc::boxed::Box::new(CapturedError::new(source))),
+        }
+    }
+}
+
+#[cfg(feature = "std")]
+impl Display for CapturedError {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        f.write_str(&self.message)
+    }
+}
+
+#[cfg(feature = "std")]
+impl std::error::Error for CapturedError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        self.source
+            .as_deref()
+            .map(|source| source as &(dyn std::error::Error + 'static))
+    }
+}
+
+#[cfg(feature = "alloc")]
+impl<E, Tracer> ErrorSource<Tracer> for BoxSource<E>
+where
+    E: ErrorSource<Tracer>,
+    E::Detail: 'static,
+{
+    type Detail = alloc::boxed::Box<E::Detail>;
+    type Source = E::Source;
+
+    #[track_caller]
+    fn error_details(source: Self::Source) -> (Self::Detail, Option<Tracer>) {
+        let (detail, trace) = E::error_details(source);
+        (alloc::boxed::Box::new(detail), trace)
+    }
+
+    fn metadata(source: &Self::Source) -> Option<&ErrorMetadata> {
+        E::metadata(source)
+    }
+
+    fn root_code(detail: &Self::Detail) -> Option<&'static str> {
+        E::root_code(detail)
+    }
+
+    fn retry_after(detail: &Self::Detail) -> Option<Duration> {
+        E::retry_after(detail)
+    }
+
+    fn exit_code(detail: &Self::Detail) -> Option<i32> {
+        E::exit_code(detail)
+    }
+
+    fn root_source<T: 'static>(detail: &Self::Detail) -> Option<&T>
+    where
+        Self::Detail: 'static,
+    {
+        E::root_source(detail)
+    }
+
+    fn detail_display(detail: &Self::Detail) -> Option<&dyn Display> {
+        E::detail_display(detail)
+    }
+}
+
+#[cfg(feature = "alloc")]
+impl<A, B, Tracer> ErrorSource<Tracer> for Both<A, B>
+where
+    A: ErrorSource<Tracer>,
+    B: ErrorSource<Tracer>,
+    A::Detail: 'static,
+    B::Detail: 'static,
+    Tracer: ErrorMessageTracer + TraceRender,
+{
+    type Detail = (A::Detail, B::Detail);
+    type Source = (A::Source, B::Source);
+
+    #[track_caller]
+    fn error_details((source_a, source_b): Self::Source) -> (Self::Detail, Option<Tracer>) {
+        let (detail_a, trace_a) = A::error_details(source_a);
+        let (detail_b, trace_b) = B::error_details(source_b);
+
+        let trace = match (trace_a, trace_b) {
+            (Some(trace_a), Some(trace_b)) => Some(
+                trace_entries(&trace_a)
+                    .into_iter()
+                    .rev()
+                    .fold(trace_b, |trace, entry| {
+                        trace.add_message_with_level(&entry.message, entry.level)
+                    }),
+            ),
+            (trace_a, trace_b) => trace_a.or(trace_b),
+        };
+
+        ((detail_a, detail_b), trace)
+    }
+
+    fn metadata((source_a, source_b): &Self::Source) -> Option<&ErrorMetadata> {
+        // prefer the metadata of a source that has been reported
+        match (A::metadata(source_a), B::metadata(source_b)) {
+            (Some(metadata_a), Some(metadata_b))
+                if metadata_a.report_status() == ReportStatus::Unreported =>
+            {
+                Some(metadata_b)
+            }
+            (metadata_a, metadata_b) => metadata_a.or(metadata_b),
+        }
+    }
+
+    fn root_code((detail_a, detail_b): &Self::Detail) -> Option<&'static str> {
+        A::root_code(detail_a).or_else(|| B::root_code(detail_b))
+    }
+
+    fn retry_after((detail_a, detail_b): &Self::Detail) -> Option<Duration> {
+        A::retry_after(detail_a).or_else(|| B::retry_after(detail_b))
+    }
+
+    fn exit_code((detail_a, detail_b): &Self::Detail) -> Option<i32> {
+        A::exit_code(detail_a).or_else(|| B::exit_code(detail_b))
+    }
+
+    fn root_source<T: 'static>((detail_a, detail_b): &Self::Detail) -> Option<&T>
+    where
+        Self::Detail: 'static,
+    {
+        A::root_source(detail_a).or_else(|| B::root_source(detail_b))
+    }
+}
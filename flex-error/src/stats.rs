@@ -0,0 +1,103 @@
+/*!
+  Process-wide statistics of the errors constructed since startup.
+
+  When the `error_stats` feature is enabled, each constructor generated by
+  [`define_error!`](crate::define_error) increments a counter for its error
+  type and sub-error. A snapshot of the counters can be taken with [`stats`],
+  for example to expose error rate summaries in health endpoints, and the
+  counters can be reset with [`reset_stats`].
+*/
+
+use alloc::vec::Vec;
+use core::sync::atomic::{AtomicBool, AtomicU64, Ordering};
+use std::sync::Mutex;
+
+/// The number of errors constructed for a sub-error of an error type.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct ErrorStat {
+    /// The type name of the error.
+    pub error: &'static str,
+
+    /// The name of the sub-error.
+    pub variant: &'static str,
+
+    /// The number of errors constructed since startup or the last reset.
+    pub count: u64,
+}
+
+/// A counter for the constructed errors of a sub-error. A static counter
+/// is defined inside each error constructor generated by
+/// [`define_error!`](crate::define_error).
+#[doc(hidden)]
+pub struct ErrorCounter {
+    error: fn() -> &'static str,
+    variant: &'static str,
+    count: AtomicU64,
+    registered: AtomicBool,
+}
+
+static COUNTERS: Mutex<Vec<&'static ErrorCounter>> = Mutex::new(Vec::new());
+
+impl ErrorCounter {
+    pub const fn new(error: fn() -> &'static str, variant: &'static str) -> Self {
+        ErrorCounter {
+            error,
+            variant,
+            count: AtomicU64::new(0),
+            registered: AtomicBool::new(false),
+        }
+    }
+
+    pub fn increment(&'static self) {
+        self.count.fetch_add(1, Ordering::Relaxed);
+
+        if !self.registered.swap(true, Ordering::Relaxed) {
+            if let Ok(mut counters) = COUNTERS.lock() {
+                counters.push(self);
+            }
+        }
+    }
+}
+
+/// Returns a snapshot of the number of errors constructed for each error
+/// type and sub-error, sorted by the error type names. Sub-errors that
+/// have never been constructed are not included.
+pub fn stats() -> Vec<ErrorStat> {
+    let mut stats: Vec<ErrorStat> = match COUNTERS.lock() {
+        Ok(counters) => counters
+            .iter()
+            .map(|counter| ErrorStat {
+                error: (counter.error)(),
+                variant: counter.variant,
+                count: counter.count.load(Ordering::Relaxed),
+            })
+            .collect(),
+        Err(_) => Vec::new(),
+    };
+
+    stats.sort();
+    stats
+}
+
+/// Resets the counters of all error types and sub-errors to zero.
+pub fn reset_stats() {
+    if let Ok(counters) = COUNTERS.lock() {
+        for counter in counters.iter() {
+            counter.count.store(0, Ordering::Relaxed);
+        }
+    }
+}
+
+/// Internal macro used to count the constructed errors in error constructors.
+#[macro_export]
+#[doc(hidden)]
+macro_rules! record_error_stat {
+  ( @name( $name:ty ),
+    @variant( $variant:expr ) $(,)?
+  ) => {{
+    static COUNTER: $crate::stats::ErrorCounter =
+      $crate::stats::ErrorCounter::new(::core::any::type_name::<$name>, $variant);
+
+    COUNTER.increment();
+  }};
+}
@@ -1,4 +1,5 @@
-use core::fmt::Display;
+use crate::render::Level;
+use core::fmt::{Display, Formatter};
 
 /// An `ErrorMessageTracer` can be used to generically trace
 /// any error detail that implements [`Display`](std::fmt::Display).
@@ -13,14 +14,61 @@ pub trait ErrorMessageTracer {
     /// detail that implements [`Display`](std::fmt::Display).
     fn new_message<E: Display>(message: &E) -> Self;
 
+    /// Creates a new error trace from a static message, such as the message
+    /// of a sub-error with the `@static_msg` option.
+    ///
+    /// By default the message is traced with [`new_message`](Self::new_message).
+    /// Error tracers that can keep a `&'static str` as is override this method,
+    /// so that the message is not formatted into a newly allocated string.
+    #[track_caller]
+    fn new_static(message: &'static str) -> Self
+    where
+        Self: Sized,
+    {
+        Self::new_message(&message)
+    }
+
     /// Adds new error detail to an existing trace.
+    ///
+    /// The built-in error tracers skip a message that is the same as the
+    /// outermost message of the trace, so that the errors converted at
+    /// several layers do not repeat their messages. The `StringTracer` and
+    /// the `DefmtTracer` check whether their joined messages start with it,
+    /// followed by `": "` or by nothing.
     fn add_message<E: Display>(self, message: &E) -> Self;
 
-    /// If the `std` feature is enabled, the error tracer
+    /// Adds new error detail to an existing trace, with the given [`Level`].
+    ///
+    /// By default the level is dropped and the message is added with
+    /// [`add_message`](Self::add_message). Error tracers that keep the level
+    /// override this method, together with
+    /// [`TraceRender::walk_leveled_messages`](crate::TraceRender::walk_leveled_messages).
+    fn add_message_with_level<E: Display>(self, message: &E, _level: Level) -> Self
+    where
+        Self: Sized,
+    {
+        self.add_message(message)
+    }
+
+    /// Adds structured key-value fields to an existing trace, such as the
+    /// fields of a sub-detail marked with `#[detail(trace)]`.
+    ///
+    /// By default the fields are added as a message of their own on top of
+    /// the message they describe, displayed as [`TraceFields`] in the form
+    /// `height=10, hash=5f2c1e9`. Error tracers that can keep structured
+    /// fields override this method to retain them for machine processing.
+    fn add_fields(self, fields: &[(&str, &dyn Display)]) -> Self
+    where
+        Self: Sized,
+    {
+        self.add_message(&TraceFields(fields))
+    }
+
+    /// If the `std` or `error_in_core` feature is enabled, the error tracer
     /// also provides method to optionally converts itself
-    /// to a `dyn` [`Error`](std::error::Error).
-    #[cfg(feature = "std")]
-    fn as_error(&self) -> Option<&(dyn std::error::Error + 'static)>;
+    /// to a `dyn` [`Error`](crate::StdError).
+    #[cfg(any(feature = "std", feature = "error_in_core"))]
+    fn as_error(&self) -> Option<&(dyn crate::StdError + 'static)>;
 }
 
 /// An error tracer implements `ErrorTracer<E>` if it supports
@@ -52,3 +100,100 @@ pub trait ErrorTracer<E>: ErrorMessageTracer {
     /// one backtrace coming from the original error source.
     fn add_trace(self, err: E) -> Self;
 }
+
+/// Displays structured key-value fields added to an error trace with
+/// [`ErrorMessageTracer::add_fields`], in the form `key=value, key=value`.
+pub struct TraceFields<'a>(pub &'a [(&'a str, &'a dyn Display)]);
+
+impl<'a> Display for TraceFields<'a> {
+    fn fmt(&self, f: &mut Formatter<'_>) -> core::fmt::Result {
+        for (index, (key, value)) in self.0.iter().enumerate() {
+            let separator = if index > 0 { ", " } else { "" };
+            write!(f, "{0}{1}={2}", separator, key, value)?;
+        }
+        Ok(())
+    }
+}
+
+/// A type implementing `TraceDisplay` provides the representation of itself
+/// to be traced by the error tracers, which may differ from its `Display`
+/// representation.
+///
+/// This is implemented for the error details defined by
+/// [`define_error!`](crate::define_error), using the `@trace` formatter of
+/// each sub-error, or falling back to `Display` when it is not given.
+pub trait TraceDisplay {
+    /// Formats the value for the error trace.
+    fn fmt_trace(&self, f: &mut Formatter<'_>) -> core::fmt::Result;
+}
+
+/// A wrapper that displays a value using its [`TraceDisplay`] implementation,
+/// so that it can be passed to the methods of [`ErrorMessageTracer`].
+pub struct TraceDisplayed<'a, T: ?Sized>(pub &'a T);
+
+impl<'a, T> Display for TraceDisplayed<'a, T>
+where
+    T: TraceDisplay + ?Sized,
+{
+    fn fmt(&self, f: &mut Formatter<'_>) -> core::fmt::Result {
+        self.0.fmt_trace(f)
+    }
+}
+
+/// A type implementing `UserMessage<Catalog>` provides a user-facing message
+/// for itself from a message catalog, which is maintained separately from
+/// the formatters used for the error trace.
+///
+/// [`define_error!`](crate::define_error) generates a trait
+/// `MyErrorUserMessage` for each error type `MyError`, with one method for
+/// each sub-error, and implements `UserMessage<Catalog>` for `MyErrorDetail`
+/// for all catalogs implementing that trait. Since the trait has no default
+/// methods, adding a sub-error fails the build until a message is provided
+/// for it in every catalog:
+///
+/// ```
+/// # use flex_error::define_error;
+/// # use std::fmt::{self, Formatter};
+/// # define_error! {
+/// #   MyError {
+/// #     NotFound
+/// #       { id: u64 }
+/// #       | e | { format_args!("item {} not found", e.id) },
+/// #     Internal
+/// #       | _ | { "internal error" },
+/// #   }
+/// # }
+/// # let err = MyError::not_found(42);
+/// struct English;
+///
+/// impl MyErrorUserMessage for English {
+///   fn not_found(&self, detail: &NotFoundSubdetail, f: &mut Formatter<'_>) -> fmt::Result {
+///     write!(f, "We could not find item {}.", detail.id)
+///   }
+///
+///   fn internal(&self, _: &InternalSubdetail, f: &mut Formatter<'_>) -> fmt::Result {
+///     write!(f, "Something went wrong, please try again later.")
+///   }
+/// }
+///
+/// println!("{}", err.user_message(&English));
+/// # assert_eq!(err.user_message(&English).to_string(), "We could not find item 42.");
+/// ```
+pub trait UserMessage<Catalog: ?Sized> {
+    /// Formats the user-facing message from the catalog.
+    fn fmt_user_message(&self, catalog: &Catalog, f: &mut Formatter<'_>) -> core::fmt::Result;
+}
+
+/// A wrapper that displays a value using its [`UserMessage`] implementation
+/// with the given catalog.
+pub struct UserMessageDisplayed<'a, T: ?Sized, Catalog: ?Sized>(pub &'a T, pub &'a Catalog);
+
+impl<'a, T, Catalog> Display for UserMessageDisplayed<'a, T, Catalog>
+where
+    T: UserMessage<Catalog> + ?Sized,
+    Catalog: ?Sized,
+{
+    fn fmt(&self, f: &mut Formatter<'_>) -> core::fmt::Result {
+        self.0.fmt_user_message(self.1, f)
+    }
+}
@@ -0,0 +1,208 @@
+use crate::render::TraceRender;
+use crate::tracer::{ErrorMessageTracer, ErrorTracer};
+use alloc::string::String;
+use alloc::sync::Arc;
+use alloc::vec::Vec;
+use core::fmt::{Debug, Display, Formatter};
+
+/// The maximum number of messages kept by an [`AggregatingTracer`].
+pub const MAX_AGGREGATED_MESSAGES: usize = 16;
+
+/// The maximum number of child traces kept by an [`AggregatingTracer`].
+pub const MAX_AGGREGATED_CHILDREN: usize = 32;
+
+/// A bounded error tracer for supervisors, which aggregate the errors of
+/// their child tasks into long-lived structures.
+///
+/// The messages are rendered into compact strings when they are traced, so
+/// that no backtrace or source error is kept alive, and the traces of the
+/// child tasks can be merged into the trace of the supervisor with
+/// [`merge`](AggregatingTracer::merge) without being rendered again:
+///
+/// ```
+/// # use flex_error::define_error;
+/// # use flex_error::tracer_impl::aggregating::AggregatingTracer;
+/// # define_error! {
+/// #   ChildError: tracer = AggregatingTracer {
+/// #     Crashed
+/// #       | _ | { "child task crashed" },
+/// #   }
+/// # }
+/// # let name = "relay".to_string();
+/// # let child_err = ChildError::crashed();
+/// define_error! {
+///   SupervisorError: tracer = AggregatingTracer {
+///     ChildFailed
+///       { name: String }
+///       | e | { format_args!("child task {} failed", e.name) },
+///   }
+/// }
+///
+/// let err = SupervisorError::child_failed(name)
+///     .map_trace(|trace| trace.merge(child_err.into_trace()));
+/// # assert_eq!(err.trace().children().len(), 1);
+/// ```
+///
+/// The messages and the children are shared with [`Arc`], so a trace is
+/// cloned without copying them, and copied on write only when a clone is
+/// extended. At most [`MAX_AGGREGATED_MESSAGES`] messages are kept for each
+/// trace, the innermost message and the outermost ones, and at most
+/// [`MAX_AGGREGATED_CHILDREN`] children are kept, the most recently merged
+/// ones. The numbers of elided messages and children are displayed in
+/// their place. The levels of the messages are not kept.
+#[derive(Clone)]
+pub struct AggregatingTracer {
+    // From the innermost to the outermost message
+    messages: Arc<Vec<Arc<str>>>,
+    children: Arc<Vec<AggregatingTracer>>,
+    elided_messages: usize,
+    elided_children: usize,
+}
+
+impl AggregatingTracer {
+    fn capture(message: String) -> Self {
+        AggregatingTracer {
+            messages: Arc::new(alloc::vec![Arc::from(message)]),
+            children: Arc::new(Vec::new()),
+            elided_messages: 0,
+            elided_children: 0,
+        }
+    }
+
+    fn push(mut self, message: String) -> Self {
+        let messages = Arc::make_mut(&mut self.messages);
+        if messages.len() >= MAX_AGGREGATED_MESSAGES {
+            // keep the innermost message, which is usually the root cause
+            messages.remove(1);
+            self.elided_messages += 1;
+        }
+        messages.push(Arc::from(message));
+        self
+    }
+
+    /// Merges the trace of a child into this trace, sharing its rendered
+    /// messages and children.
+    pub fn merge(mut self, child: AggregatingTracer) -> Self {
+        let children = Arc::make_mut(&mut self.children);
+        if children.len() >= MAX_AGGREGATED_CHILDREN {
+            children.remove(0);
+            self.elided_children += 1;
+        }
+        children.push(child);
+        self
+    }
+
+    /// Returns the messages of the trace, from the outermost to the
+    /// innermost message.
+    pub fn messages(&self) -> impl Iterator<Item = &str> {
+        self.messages.iter().rev().map(|message| &**message)
+    }
+
+    /// Returns the traces of the children merged into this trace, from the
+    /// earliest to the most recently merged.
+    pub fn children(&self) -> &[AggregatingTracer] {
+        &self.children
+    }
+
+    /// Returns the numbers of messages and children elided to keep the
+    /// trace bounded.
+    pub fn elided(&self) -> (usize, usize) {
+        (self.elided_messages, self.elided_children)
+    }
+
+    fn fmt_tree(&self, f: &mut Formatter<'_>, depth: usize) -> core::fmt::Result {
+        for child in self.children.iter() {
+            write!(f, "\n{0:1$}- {2}", "", depth * 2, child)?;
+            child.fmt_tree(f, depth + 1)?;
+        }
+
+        if self.elided_children > 0 {
+            write!(
+                f,
+                "\n{0:1$}- ({2} earlier children elided)",
+                "",
+                depth * 2,
+                self.elided_children
+            )?;
+        }
+        Ok(())
+    }
+}
+
+impl ErrorMessageTracer for AggregatingTracer {
+    #[track_caller]
+    fn new_message<E: Display>(err: &E) -> Self {
+        super::start_trace(AggregatingTracer::capture(super::prepare_message(err)))
+    }
+
+    fn add_message<E: Display>(self, err: &E) -> Self {
+        super::extend_trace(self, super::prepare_message(err), |trace, message| {
+            if trace.messages.last().map(|last| **last == *message) == Some(true) {
+                return trace;
+            }
+            trace.push(message)
+        })
+    }
+
+    #[cfg(any(feature = "std", feature = "error_in_core"))]
+    fn as_error(&self) -> Option<&(dyn crate::StdError + 'static)> {
+        None
+    }
+}
+
+impl<E: Display> ErrorTracer<E> for AggregatingTracer {
+    #[track_caller]
+    fn new_trace(err: E) -> Self {
+        <AggregatingTracer as ErrorMessageTracer>::new_message(&err)
+    }
+
+    fn add_trace(self, err: E) -> Self {
+        self.add_message(&err)
+    }
+}
+
+impl TraceRender for AggregatingTracer {
+    fn walk_messages(
+        &self,
+        f: &mut dyn FnMut(&dyn Display) -> core::fmt::Result,
+    ) -> core::fmt::Result {
+        let mut messages = self.messages.iter().rev();
+        let innermost = messages.next_back();
+        for message in messages {
+            f(message)?;
+        }
+
+        if self.elided_messages > 0 {
+            f(&format_args!("({0} messages elided)", self.elided_messages))?;
+        }
+
+        match innermost {
+            Some(message) => f(message),
+            None => Ok(()),
+        }
+    }
+}
+
+impl Debug for AggregatingTracer {
+    fn fmt(&self, f: &mut Formatter<'_>) -> core::fmt::Result {
+        write!(f, "AggregatingTracer: {0:#}", self)
+    }
+}
+
+impl Display for AggregatingTracer {
+    fn fmt(&self, f: &mut Formatter<'_>) -> core::fmt::Result {
+        let mut separator = "";
+        self.walk_messages(&mut |message| {
+            write!(f, "{0}{1}", separator, message)?;
+            separator = ": ";
+            Ok(())
+        })?;
+
+        // The alternate mode `{:#}` also displays the merged children,
+        // one per line.
+        if f.alternate() {
+            self.fmt_tree(f, 0)?;
+        }
+        Ok(())
+    }
+}
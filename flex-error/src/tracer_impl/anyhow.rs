@@ -1,3 +1,5 @@
+use crate::render::{Level, TraceRender};
+use crate::source::ErrorSource;
 use crate::tracer::{ErrorMessageTracer, ErrorTracer};
 use core::fmt::{Debug, Display};
 
@@ -6,13 +8,38 @@ pub type AnyhowTracer = anyhow::Error;
 
 impl ErrorMessageTracer for AnyhowTracer {
     fn new_message<E: Display>(err: &E) -> Self {
-        let message = alloc::format!("{}", err);
-        AnyhowTracer::msg(message)
+        let message = super::report_message(super::prepare_message(err));
+        super::start_trace(AnyhowTracer::msg(message))
+    }
+
+    fn new_static(message: &'static str) -> Self {
+        // The interned and stamped messages, and the filtered messages,
+        // are owned strings, so these go through `new_message`.
+        if cfg!(any(feature = "interning", feature = "replay")) || crate::redact::has_filter() {
+            return Self::new_message(&message);
+        }
+
+        super::start_trace(AnyhowTracer::msg(message))
     }
 
     fn add_message<E: Display>(self, err: &E) -> Self {
-        let message = alloc::format!("{}", err);
-        self.context(message)
+        super::extend_trace(self, super::prepare_message(err), |trace, message| {
+            if super::repeats_last(&trace, &message) {
+                return trace;
+            }
+            let message = super::report_message(message);
+            trace.context(message)
+        })
+    }
+
+    fn add_message_with_level<E: Display>(self, err: &E, level: Level) -> Self {
+        super::extend_trace(self, super::prepare_message(err), |trace, message| {
+            if super::repeats_last(&trace, &message) {
+                return trace;
+            }
+            let message = super::report_message(message);
+            AnyhowTracer::new(super::LeveledMessage::new(level, message, trace))
+        })
     }
 
     #[cfg(feature = "std")]
@@ -27,11 +54,90 @@ where
     E: Display + Debug + Send + Sync + 'static,
 {
     fn new_trace(err: E) -> Self {
-        AnyhowTracer::msg(err)
+        if crate::redact::has_filter() {
+            return <AnyhowTracer as ErrorMessageTracer>::new_message(&err);
+        }
+
+        #[cfg(feature = "replay")]
+        let err = crate::replay::Stamped::now(err);
+        super::start_trace(AnyhowTracer::msg(err))
     }
 
     fn add_trace(self, err: E) -> Self {
-        let message = alloc::format!("{}", err);
-        self.context(message)
+        super::extend_trace(self, super::prepare_message(&err), |trace, message| {
+            if super::repeats_last(&trace, &message) {
+                return trace;
+            }
+            let message = super::report_message(message);
+            trace.context(message)
+        })
+    }
+}
+
+impl TraceRender for AnyhowTracer {
+    fn walk_messages(
+        &self,
+        f: &mut dyn FnMut(&dyn Display) -> core::fmt::Result,
+    ) -> core::fmt::Result {
+        for err in self.chain() {
+            f(&err)?;
+        }
+        Ok(())
+    }
+
+    fn walk_leveled_messages(
+        &self,
+        f: &mut dyn FnMut(Level, &dyn Display) -> core::fmt::Result,
+    ) -> core::fmt::Result {
+        for err in self.chain() {
+            f(super::message_level::<AnyhowTracer>(err), &err)?;
+        }
+        Ok(())
+    }
+}
+
+/// Converts the error types defined by [`define_error!`](crate::define_error)
+/// with the [`AnyhowTracer`] into an [`anyhow::Error`], keeping their error
+/// trace:
+///
+/// ```
+/// use flex_error::tracer_impl::anyhow::IntoAnyhow;
+/// # use flex_error::define_error;
+/// # use flex_error::tracer_impl::anyhow::AnyhowTracer;
+/// # define_error! {
+/// #   MyError: tracer = AnyhowTracer {
+/// #     Timeout
+/// #       | _ | { "timed out" },
+/// #   }
+/// # }
+/// # fn run() -> Result<(), MyError> {
+/// #   Ok(())
+/// # }
+///
+/// fn main() -> anyhow::Result<()> {
+///     run().map_err(MyError::into_anyhow)?;
+///     Ok(())
+/// }
+/// ```
+///
+/// Unlike the conversion with the `?` operator, which wraps the error as a
+/// source error of a new `anyhow::Error`, the converted error is the error
+/// trace itself, with the message of the error detail added on top of it,
+/// unless it is already the outermost message.
+pub trait IntoAnyhow {
+    fn into_anyhow(self) -> AnyhowTracer;
+}
+
+impl<E> IntoAnyhow for E
+where
+    E: ErrorSource<AnyhowTracer, Source = E>,
+    E::Detail: Display,
+{
+    fn into_anyhow(self) -> AnyhowTracer {
+        let (detail, trace) = E::error_details(self);
+        match trace {
+            Some(trace) => trace.add_message(&detail),
+            None => AnyhowTracer::new_message(&detail),
+        }
     }
 }
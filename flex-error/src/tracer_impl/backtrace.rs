@@ -0,0 +1,198 @@
+use crate::render::{Level, TraceRender};
+use crate::tracer::{ErrorMessageTracer, ErrorTracer};
+use alloc::boxed::Box;
+use alloc::string::String;
+use core::fmt::{Debug, Display, Formatter};
+use std::backtrace::{Backtrace, BacktraceStatus};
+
+/// A lightweight error tracer that keeps the chain of error messages,
+/// together with a single [`Backtrace`] captured when the trace is created.
+///
+/// The backtrace is captured with [`Backtrace::capture`], which follows the
+/// `RUST_LIB_BACKTRACE` and `RUST_BACKTRACE` environment variables. When
+/// backtraces are disabled, the capture is cheap and nothing is printed.
+pub struct BacktraceTracer {
+    /// The outermost error message, linked to the inner messages
+    /// as its error sources.
+    head: TraceMessage,
+    backtrace: Backtrace,
+}
+
+#[cfg(feature = "interning")]
+type MessageText = crate::intern::SharedMessage;
+
+#[cfg(not(feature = "interning"))]
+type MessageText = String;
+
+/// A message in a [`BacktraceTracer`], which implements
+/// [`Error`](std::error::Error) with the next inner message as its source.
+#[derive(Debug)]
+struct TraceMessage {
+    message: MessageText,
+    level: Level,
+    #[cfg(feature = "replay")]
+    time: std::time::SystemTime,
+    source: Option<Box<TraceMessage>>,
+}
+
+impl Display for TraceMessage {
+    fn fmt(&self, f: &mut Formatter<'_>) -> core::fmt::Result {
+        #[cfg(feature = "replay")]
+        let message = crate::replay::Stamped {
+            time: self.time,
+            message: &self.message,
+        };
+        #[cfg(not(feature = "replay"))]
+        let message = &self.message;
+
+        Display::fmt(&message, f)
+    }
+}
+
+impl std::error::Error for TraceMessage {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        self.source
+            .as_deref()
+            .map(|source| source as &(dyn std::error::Error + 'static))
+    }
+}
+
+impl BacktraceTracer {
+    fn capture(message: String) -> Self {
+        BacktraceTracer {
+            head: TraceMessage {
+                message: MessageText::from(message),
+                level: Level::Info,
+                #[cfg(feature = "replay")]
+                time: std::time::SystemTime::now(),
+                source: None,
+            },
+            backtrace: Backtrace::capture(),
+        }
+    }
+
+    fn push(self, message: String, level: Level) -> Self {
+        // A message repeating the outermost message is not added again.
+        if message == self.head.message.as_str() {
+            return self;
+        }
+
+        BacktraceTracer {
+            head: TraceMessage {
+                message: MessageText::from(message),
+                level,
+                #[cfg(feature = "replay")]
+                time: std::time::SystemTime::now(),
+                source: Some(Box::new(self.head)),
+            },
+            backtrace: self.backtrace,
+        }
+    }
+
+    /// Returns the backtrace captured when the trace was created.
+    pub fn backtrace(&self) -> &Backtrace {
+        &self.backtrace
+    }
+
+    /// Returns an iterator over the error messages, from the outermost
+    /// to the innermost message.
+    pub fn messages(&self) -> impl Iterator<Item = &str> {
+        core::iter::successors(Some(&self.head), |message| message.source.as_deref())
+            .map(|message| message.message.as_str())
+    }
+}
+
+impl ErrorMessageTracer for BacktraceTracer {
+    fn new_message<E: Display>(err: &E) -> Self {
+        super::start_trace(Self::capture(super::prepare_message(err)))
+    }
+
+    fn add_message<E: Display>(self, err: &E) -> Self {
+        super::extend_trace(self, super::prepare_message(err), |trace, message| {
+            trace.push(message, Level::Info)
+        })
+    }
+
+    fn add_message_with_level<E: Display>(self, err: &E, level: Level) -> Self {
+        super::extend_trace(self, super::prepare_message(err), |trace, message| {
+            trace.push(message, level)
+        })
+    }
+
+    fn as_error(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        Some(&self.head)
+    }
+}
+
+impl<E: Display> ErrorTracer<E> for BacktraceTracer {
+    fn new_trace(err: E) -> Self {
+        super::start_trace(Self::capture(super::prepare_message(&err)))
+    }
+
+    fn add_trace(self, err: E) -> Self {
+        super::extend_trace(self, super::prepare_message(&err), |trace, message| {
+            trace.push(message, Level::Info)
+        })
+    }
+}
+
+impl TraceRender for BacktraceTracer {
+    fn walk_messages(
+        &self,
+        f: &mut dyn FnMut(&dyn Display) -> core::fmt::Result,
+    ) -> core::fmt::Result {
+        let mut message = Some(&self.head);
+        while let Some(current) = message {
+            f(current)?;
+            message = current.source.as_deref();
+        }
+        Ok(())
+    }
+
+    fn walk_leveled_messages(
+        &self,
+        f: &mut dyn FnMut(Level, &dyn Display) -> core::fmt::Result,
+    ) -> core::fmt::Result {
+        let mut message = Some(&self.head);
+        while let Some(current) = message {
+            f(current.level, current)?;
+            message = current.source.as_deref();
+        }
+        Ok(())
+    }
+}
+
+impl Debug for BacktraceTracer {
+    fn fmt(&self, f: &mut Formatter<'_>) -> core::fmt::Result {
+        for (index, message) in self.messages().enumerate() {
+            if index > 0 {
+                writeln!(f)?;
+            }
+            write!(f, "{0:>4}: {1}", index, message)?;
+        }
+
+        if self.backtrace.status() == BacktraceStatus::Captured {
+            write!(f, "\n\nStack backtrace:\n{0}", self.backtrace)?;
+        }
+
+        Ok(())
+    }
+}
+
+impl Display for BacktraceTracer {
+    fn fmt(&self, f: &mut Formatter<'_>) -> core::fmt::Result {
+        // As with eyre and anyhow, only the outermost message is displayed,
+        // and the alternate mode `{:#}` displays the chain of messages.
+        if !f.alternate() {
+            return write!(f, "{0}", self.head.message);
+        }
+
+        for (index, message) in self.messages().enumerate() {
+            if index > 0 {
+                write!(f, ": ")?;
+            }
+            write!(f, "{0}", message)?;
+        }
+        Ok(())
+    }
+}
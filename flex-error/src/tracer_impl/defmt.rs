@@ -0,0 +1,144 @@
+use crate::render::TraceRender;
+use crate::tracer::{ErrorMessageTracer, ErrorTracer};
+use core::fmt::{Debug, Display, Formatter, Write};
+
+/// A compact error tracer for firmware logging with [`defmt`], which does
+/// not require an allocator.
+///
+/// Like the [`StringTracer`](crate::tracer_impl::string::StringTracer), the
+/// messages are joined into a single message, from the outermost message to
+/// the innermost error source, but into an inline buffer of `N` bytes. The
+/// messages that do not fit in the buffer are truncated, keeping the
+/// outermost messages, and the truncation is shown with a trailing `...`.
+///
+/// The tracer implements [`defmt::Format`], so that the traces can be
+/// logged with `defmt` as well as displayed with `core::fmt`. A smaller or
+/// larger buffer can be chosen with the const parameter:
+///
+/// ```
+/// # use flex_error::tracer_impl::defmt::DefmtTracer;
+/// type SmallTracer = DefmtTracer<64>;
+/// ```
+pub struct DefmtTracer<const N: usize = 128>(InlineBuffer<N>);
+
+struct InlineBuffer<const N: usize> {
+    bytes: [u8; N],
+    len: usize,
+    truncated: bool,
+}
+
+impl<const N: usize> DefmtTracer<N> {
+    fn capture(message: &dyn Display) -> Self {
+        let mut buffer = InlineBuffer {
+            bytes: [0; N],
+            len: 0,
+            truncated: false,
+        };
+
+        #[cfg(feature = "alloc")]
+        let _ = buffer.write_str(&super::prepare_message(message));
+        #[cfg(not(feature = "alloc"))]
+        let _ = write!(buffer, "{}", message);
+
+        DefmtTracer(buffer)
+    }
+
+    fn push(self, message: &dyn Display) -> Self {
+        if super::repeats_last_joined(self.as_str(), message) {
+            return self;
+        }
+
+        let mut trace = Self::capture(message);
+        let _ = trace.0.write_str(": ");
+        let _ = trace.0.write_str(self.as_str());
+        trace.0.truncated |= self.0.truncated;
+        trace
+    }
+
+    /// Returns the joined messages kept in the buffer.
+    pub fn as_str(&self) -> &str {
+        core::str::from_utf8(&self.0.bytes[..self.0.len]).unwrap_or_default()
+    }
+
+    /// Returns whether the messages have been truncated to fit in the
+    /// buffer.
+    pub fn is_truncated(&self) -> bool {
+        self.0.truncated
+    }
+
+    fn ellipsis(&self) -> &'static str {
+        if self.0.truncated {
+            "..."
+        } else {
+            ""
+        }
+    }
+}
+
+impl<const N: usize> Write for InlineBuffer<N> {
+    fn write_str(&mut self, s: &str) -> core::fmt::Result {
+        let mut end = s.len().min(N - self.len);
+        while !s.is_char_boundary(end) {
+            end -= 1;
+        }
+
+        self.bytes[self.len..self.len + end].copy_from_slice(&s.as_bytes()[..end]);
+        self.len += end;
+        self.truncated |= end < s.len();
+        Ok(())
+    }
+}
+
+impl<const N: usize> ErrorMessageTracer for DefmtTracer<N> {
+    #[track_caller]
+    fn new_message<E: Display>(err: &E) -> Self {
+        super::start_trace(DefmtTracer::capture(err))
+    }
+
+    fn add_message<E: Display>(self, err: &E) -> Self {
+        super::extend_trace(self, err, |trace, message| trace.push(message))
+    }
+
+    #[cfg(any(feature = "std", feature = "error_in_core"))]
+    fn as_error(&self) -> Option<&(dyn crate::StdError + 'static)> {
+        None
+    }
+}
+
+impl<E: Display, const N: usize> ErrorTracer<E> for DefmtTracer<N> {
+    #[track_caller]
+    fn new_trace(err: E) -> Self {
+        super::start_trace(DefmtTracer::capture(&err))
+    }
+
+    fn add_trace(self, err: E) -> Self {
+        super::extend_trace(self, &err, |trace, message| trace.push(message))
+    }
+}
+
+impl<const N: usize> TraceRender for DefmtTracer<N> {
+    fn walk_messages(
+        &self,
+        f: &mut dyn FnMut(&dyn Display) -> core::fmt::Result,
+    ) -> core::fmt::Result {
+        f(self)
+    }
+}
+
+impl<const N: usize> Debug for DefmtTracer<N> {
+    fn fmt(&self, f: &mut Formatter<'_>) -> core::fmt::Result {
+        write!(f, "DefmtTracer: {0}{1}", self.as_str(), self.ellipsis())
+    }
+}
+
+impl<const N: usize> Display for DefmtTracer<N> {
+    fn fmt(&self, f: &mut Formatter<'_>) -> core::fmt::Result {
+        write!(f, "{0}{1}", self.as_str(), self.ellipsis())
+    }
+}
+
+impl<const N: usize> ::defmt::Format for DefmtTracer<N> {
+    fn format(&self, f: ::defmt::Formatter<'_>) {
+        ::defmt::write!(f, "{=str}{=str}", self.as_str(), self.ellipsis())
+    }
+}
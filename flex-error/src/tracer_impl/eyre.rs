@@ -1,18 +1,130 @@
+use crate::render::{Level, TraceRender};
+use crate::source::ErrorSource;
 use crate::tracer::{ErrorMessageTracer, ErrorTracer};
+use alloc::string::{String, ToString};
 use core::fmt::{Debug, Display};
 
 /// Type alias to [`eyre::Report`].
 pub type EyreTracer = eyre::Report;
 
+/// A lightweight [`eyre::EyreHandler`] that only formats the chain of
+/// error messages, without capturing any backtrace.
+///
+/// When only the `eyre_tracer_minimal` feature is enabled, the
+/// `auto-install` feature of `eyre` is disabled, and this handler is
+/// installed when the first [`EyreTracer`] is created, unless another
+/// handler has already been installed. To use a different handler, it
+/// has to be installed with [`eyre::set_hook`] before any error is created.
+///
+/// The handler records the location where the error trace was created,
+/// which is returned by [`TraceRender::location`].
+#[derive(Debug, Default)]
+pub struct MinimalHandler {
+    location: Option<String>,
+}
+
+impl eyre::EyreHandler for MinimalHandler {
+    fn debug(
+        &self,
+        error: &(dyn std::error::Error + 'static),
+        f: &mut core::fmt::Formatter<'_>,
+    ) -> core::fmt::Result {
+        if f.alternate() {
+            return core::fmt::Debug::fmt(error, f);
+        }
+
+        write!(f, "{}", error)?;
+
+        let mut source = error.source();
+        if source.is_some() {
+            write!(f, "\n\nCaused by:")?;
+        }
+
+        let mut index = 0;
+        while let Some(err) = source {
+            write!(f, "\n   {}: {}", index, err)?;
+            source = err.source();
+            index += 1;
+        }
+
+        Ok(())
+    }
+
+    fn track_caller(&mut self, location: &'static core::panic::Location<'static>) {
+        self.location = Some(crate::redact::filter_message(location.to_string()));
+    }
+}
+
+/// Installs [`MinimalHandler`] as the global handler for [`eyre::Report`].
+pub fn install_minimal_handler() -> Result<(), eyre::InstallError> {
+    eyre::set_hook(alloc::boxed::Box::new(|_| {
+        alloc::boxed::Box::new(MinimalHandler::default())
+    }))
+}
+
+/// Creates a new [`EyreTracer`] from the given message, installing the
+/// [`MinimalHandler`] first if `eyre` cannot install its default handler.
+#[track_caller]
+fn report<M>(message: M) -> EyreTracer
+where
+    M: Display + Debug + Send + Sync + 'static,
+{
+    #[cfg(not(feature = "eyre_tracer"))]
+    {
+        static INSTALL: std::sync::Once = std::sync::Once::new();
+
+        // An `InstallError` means that a handler is already installed,
+        // which is then used instead.
+        INSTALL.call_once(|| {
+            let _ = install_minimal_handler();
+        });
+    }
+
+    EyreTracer::msg(message)
+}
+
 impl ErrorMessageTracer for EyreTracer {
+    #[track_caller]
     fn new_message<E: Display>(err: &E) -> Self {
-        let message = alloc::format!("{}", err);
-        EyreTracer::msg(message)
+        let message = super::report_message(super::prepare_message(err));
+        super::start_trace(report(message))
+    }
+
+    #[track_caller]
+    fn new_static(message: &'static str) -> Self {
+        // The interned and stamped messages, and the filtered messages,
+        // are owned strings, so these go through `new_message`.
+        if cfg!(any(feature = "interning", feature = "replay")) || crate::redact::has_filter() {
+            return Self::new_message(&message);
+        }
+
+        super::start_trace(report(message))
     }
 
     fn add_message<E: Display>(self, err: &E) -> Self {
-        let message = alloc::format!("{}", err);
-        self.wrap_err(message)
+        super::extend_trace(self, super::prepare_message(err), |trace, message| {
+            if super::repeats_last(&trace, &message) {
+                return trace;
+            }
+            let message = super::report_message(message);
+            trace.wrap_err(message)
+        })
+    }
+
+    fn add_message_with_level<E: Display>(self, err: &E, level: Level) -> Self {
+        super::extend_trace(self, super::prepare_message(err), |trace, message| {
+            if super::repeats_last(&trace, &message) {
+                return trace;
+            }
+            // The new report keeps the location where the trace was created.
+            let location = trace.location().map(String::from);
+            let message = super::report_message(message);
+            let mut trace = EyreTracer::new(super::LeveledMessage::new(level, message, trace));
+            if let Some(handler) = trace.handler_mut().downcast_mut::<MinimalHandler>() {
+                handler.location = location;
+            }
+            trace
+        })
     }
 
     #[cfg(feature = "std")]
@@ -26,12 +138,101 @@ impl<E> ErrorTracer<E> for EyreTracer
 where
     E: Display + Debug + Send + Sync + 'static,
 {
+    #[track_caller]
     fn new_trace(err: E) -> Self {
-        EyreTracer::msg(err)
+        if crate::redact::has_filter() {
+            return <EyreTracer as ErrorMessageTracer>::new_message(&err);
+        }
+
+        #[cfg(feature = "replay")]
+        let err = crate::replay::Stamped::now(err);
+        super::start_trace(report(err))
     }
 
     fn add_trace(self, err: E) -> Self {
-        let message = alloc::format!("{}", err);
-        self.wrap_err(message)
+        super::extend_trace(self, super::prepare_message(&err), |trace, message| {
+            if super::repeats_last(&trace, &message) {
+                return trace;
+            }
+            let message = super::report_message(message);
+            trace.wrap_err(message)
+        })
+    }
+}
+
+impl TraceRender for EyreTracer {
+    fn walk_messages(
+        &self,
+        f: &mut dyn FnMut(&dyn Display) -> core::fmt::Result,
+    ) -> core::fmt::Result {
+        for err in self.chain() {
+            f(&err)?;
+        }
+        Ok(())
+    }
+
+    fn walk_leveled_messages(
+        &self,
+        f: &mut dyn FnMut(Level, &dyn Display) -> core::fmt::Result,
+    ) -> core::fmt::Result {
+        for err in self.chain() {
+            f(super::message_level::<EyreTracer>(err), &err)?;
+        }
+        Ok(())
+    }
+
+    fn location(&self) -> Option<&str> {
+        self.handler()
+            .downcast_ref::<MinimalHandler>()
+            .and_then(|handler| handler.location.as_deref())
+    }
+}
+
+/// Converts the error types defined by [`define_error!`](crate::define_error)
+/// with the [`EyreTracer`] into an [`eyre::Report`], keeping their error
+/// trace:
+///
+/// ```
+/// use flex_error::tracer_impl::eyre::IntoReport;
+/// # use flex_error::define_error;
+/// # use flex_error::tracer_impl::eyre::EyreTracer;
+/// # define_error! {
+/// #   MyError: tracer = EyreTracer {
+/// #     Timeout
+/// #       | _ | { "timed out" },
+/// #   }
+/// # }
+/// # fn run() -> Result<(), MyError> {
+/// #   Ok(())
+/// # }
+///
+/// fn main() -> eyre::Result<()> {
+///     run().map_err(MyError::into_report)?;
+///     Ok(())
+/// }
+/// ```
+///
+/// The `?` operator can also convert the error types into an `eyre::Report`,
+/// through the `From` implementation of `eyre` for all error types. As that
+/// implementation already covers the error types, it cannot be specialized,
+/// and the resulting report wraps the error as a source error, with a new
+/// backtrace. With `into_report`, the report is the error trace itself, with
+/// the message of the error detail added on top of it, unless it is already
+/// the outermost message.
+pub trait IntoReport {
+    fn into_report(self) -> EyreTracer;
+}
+
+impl<E> IntoReport for E
+where
+    E: ErrorSource<EyreTracer, Source = E>,
+    E::Detail: Display,
+{
+    fn into_report(self) -> EyreTracer {
+        let (detail, trace) = E::error_details(self);
+        match trace {
+            Some(trace) => trace.add_message(&detail),
+            None => EyreTracer::new_message(&detail),
+        }
     }
 }
@@ -0,0 +1,234 @@
+use crate::render::{Level, TraceRender};
+use crate::tracer::{ErrorMessageTracer, ErrorTracer};
+use alloc::boxed::Box;
+use alloc::string::{String, ToString};
+use alloc::vec::Vec;
+use core::fmt::{Debug, Display, Formatter};
+use core::panic::Location;
+use std::sync::{Mutex, OnceLock, PoisonError};
+
+/// A tracer that defers the creation of an error tracer `T` until the
+/// trace is actually used, for the hot paths where errors are frequently
+/// created and discarded, such as in retry loops:
+///
+/// ```
+/// # use flex_error::define_error;
+/// # use flex_error::tracer_impl::eyre::EyreTracer;
+/// # use flex_error::tracer_impl::lazy::LazyTracer;
+/// type HotTracer = LazyTracer<EyreTracer>;
+///
+/// define_error! {
+///   LookupError: tracer = HotTracer {
+///     NotFound
+///       { key: u64 }
+///       | e | { format_args!("key {} not found", e.key) },
+///   }
+/// }
+/// # let err = LookupError::not_found(7);
+/// # assert_eq!(format!("{:#}", err), "key 7 not found");
+/// ```
+///
+/// The traced messages are only formatted into strings, and the source
+/// errors traced with [`ErrorTracer::new_trace`] or
+/// [`ErrorTracer::add_trace`] are kept as is. The trace `T` is materialized
+/// from them when the trace is displayed, rendered or converted with
+/// [`ErrorMessageTracer::as_error`], so that the backtrace of an
+/// [`EyreTracer`](crate::tracer_impl::eyre::EyreTracer) is only captured
+/// for the errors that are reported.
+///
+/// As a consequence, the backtrace and the context captured by `T` are the
+/// ones of the place where the trace is materialized. The location where
+/// the trace was created is still recorded, and is returned by
+/// [`TraceRender::location`].
+///
+/// With the `incidents` feature, the trace is materialized as soon as the
+/// error is constructed, to store the snapshot of its messages in the
+/// incident registry.
+pub struct LazyTracer<T> {
+    entries: Mutex<Vec<Entry<T>>>,
+    trace: OnceLock<T>,
+    caller: &'static Location<'static>,
+    location: OnceLock<String>,
+}
+
+enum Entry<T> {
+    Static(&'static str),
+    Message(String, Option<Level>),
+    Fields(Vec<(String, String)>),
+    Trace(Box<dyn FnOnce(Option<T>) -> T + Send>),
+}
+
+impl<T: ErrorMessageTracer> Entry<T> {
+    fn apply(self, trace: Option<T>) -> T {
+        match (self, trace) {
+            (Entry::Static(message), None) => T::new_static(message),
+            (Entry::Static(message), Some(trace)) => trace.add_message(&message),
+            (Entry::Message(message, _), None) => T::new_message(&message),
+            (Entry::Message(message, None), Some(trace)) => trace.add_message(&message),
+            (Entry::Message(message, Some(level)), Some(trace)) => {
+                trace.add_message_with_level(&message, level)
+            }
+            (Entry::Fields(fields), trace) => {
+                let fields: Vec<(&str, &dyn Display)> = fields
+                    .iter()
+                    .map(|(key, value)| (key.as_str(), value as &dyn Display))
+                    .collect();
+                match trace {
+                    Some(trace) => trace.add_fields(&fields),
+                    None => T::new_message(&crate::tracer::TraceFields(&fields)),
+                }
+            }
+            (Entry::Trace(trace_fn), trace) => trace_fn(trace),
+        }
+    }
+
+    fn trace<E>(err: E) -> Self
+    where
+        E: Send + 'static,
+        T: ErrorTracer<E>,
+    {
+        Entry::Trace(Box::new(move |trace: Option<T>| match trace {
+            Some(trace) => trace.add_trace(err),
+            None => T::new_trace(err),
+        }))
+    }
+}
+
+impl<T: ErrorMessageTracer> LazyTracer<T> {
+    #[track_caller]
+    fn new(entry: Entry<T>) -> Self {
+        LazyTracer {
+            entries: Mutex::new(alloc::vec![entry]),
+            trace: OnceLock::new(),
+            caller: Location::caller(),
+            location: OnceLock::new(),
+        }
+    }
+
+    fn push(mut self, entry: Entry<T>) -> Self {
+        match self.trace.take() {
+            Some(trace) => {
+                let _ = self.trace.set(entry.apply(Some(trace)));
+            }
+            None => self
+                .entries
+                .get_mut()
+                .unwrap_or_else(PoisonError::into_inner)
+                .push(entry),
+        }
+        self
+    }
+
+    /// Returns the materialized error tracer, creating it from the traced
+    /// messages and source errors on the first call.
+    pub fn materialize(&self) -> &T {
+        self.trace.get_or_init(|| {
+            let entries = core::mem::take(
+                &mut *self.entries.lock().unwrap_or_else(PoisonError::into_inner),
+            );
+
+            entries
+                .into_iter()
+                .fold(None, |trace, entry| Some(entry.apply(trace)))
+                // the entries are only missing if a previous materialization
+                // has panicked
+                .unwrap_or_else(|| T::new_static(""))
+        })
+    }
+
+    /// Returns whether the error tracer has been materialized.
+    pub fn is_materialized(&self) -> bool {
+        self.trace.get().is_some()
+    }
+
+    /// Materializes the error tracer and returns it.
+    pub fn into_inner(self) -> T {
+        self.materialize();
+        match self.trace.into_inner() {
+            Some(trace) => trace,
+            None => unreachable!("the trace is materialized"),
+        }
+    }
+}
+
+impl<T: ErrorMessageTracer> ErrorMessageTracer for LazyTracer<T> {
+    #[track_caller]
+    fn new_message<E: Display>(err: &E) -> Self {
+        LazyTracer::new(Entry::Message(err.to_string(), None))
+    }
+
+    #[track_caller]
+    fn new_static(message: &'static str) -> Self {
+        LazyTracer::new(Entry::Static(message))
+    }
+
+    fn add_message<E: Display>(self, err: &E) -> Self {
+        self.push(Entry::Message(err.to_string(), None))
+    }
+
+    fn add_message_with_level<E: Display>(self, err: &E, level: Level) -> Self {
+        self.push(Entry::Message(err.to_string(), Some(level)))
+    }
+
+    fn add_fields(self, fields: &[(&str, &dyn Display)]) -> Self {
+        let fields = fields
+            .iter()
+            .map(|(key, value)| (key.to_string(), value.to_string()))
+            .collect();
+        self.push(Entry::Fields(fields))
+    }
+
+    fn as_error(&self) -> Option<&(dyn crate::StdError + 'static)> {
+        self.materialize().as_error()
+    }
+}
+
+impl<E, T> ErrorTracer<E> for LazyTracer<T>
+where
+    E: Send + 'static,
+    T: ErrorTracer<E>,
+{
+    #[track_caller]
+    fn new_trace(err: E) -> Self {
+        LazyTracer::new(Entry::trace(err))
+    }
+
+    fn add_trace(self, err: E) -> Self {
+        self.push(Entry::trace(err))
+    }
+}
+
+impl<T: ErrorMessageTracer + TraceRender> TraceRender for LazyTracer<T> {
+    fn walk_messages(
+        &self,
+        f: &mut dyn FnMut(&dyn Display) -> core::fmt::Result,
+    ) -> core::fmt::Result {
+        self.materialize().walk_messages(f)
+    }
+
+    fn walk_leveled_messages(
+        &self,
+        f: &mut dyn FnMut(Level, &dyn Display) -> core::fmt::Result,
+    ) -> core::fmt::Result {
+        self.materialize().walk_leveled_messages(f)
+    }
+
+    fn location(&self) -> Option<&str> {
+        let location = self
+            .location
+            .get_or_init(|| crate::redact::filter_message(self.caller.to_string()));
+        Some(location.as_str())
+    }
+}
+
+impl<T: ErrorMessageTracer + Debug> Debug for LazyTracer<T> {
+    fn fmt(&self, f: &mut Formatter<'_>) -> core::fmt::Result {
+        Debug::fmt(self.materialize(), f)
+    }
+}
+
+impl<T: ErrorMessageTracer + Display> Display for LazyTracer<T> {
+    fn fmt(&self, f: &mut Formatter<'_>) -> core::fmt::Result {
+        Display::fmt(self.materialize(), f)
+    }
+}
@@ -1,7 +1,182 @@
+pub mod no_trace;
+
+pub mod pair;
+
+#[cfg(feature = "std")]
+pub mod lazy;
+
+#[cfg(feature = "alloc")]
 pub mod string;
 
+#[cfg(feature = "alloc")]
+pub mod aggregating;
+
 #[cfg(feature = "anyhow_tracer")]
 pub mod anyhow;
 
-#[cfg(feature = "eyre_tracer")]
+#[cfg(feature = "eyre_tracer_minimal")]
 pub mod eyre;
+
+#[cfg(feature = "backtrace_tracer")]
+pub mod backtrace;
+
+#[cfg(feature = "tracing_tracer")]
+pub mod tracing;
+
+#[cfg(feature = "defmt_tracer")]
+pub mod defmt;
+
+/// A writer that strips what is written from the start of a string, and
+/// fails as soon as it is not a prefix of the string.
+#[cfg(any(feature = "alloc", feature = "defmt_tracer"))]
+struct Remaining<'a>(&'a str);
+
+#[cfg(any(feature = "alloc", feature = "defmt_tracer"))]
+impl core::fmt::Write for Remaining<'_> {
+    fn write_str(&mut self, s: &str) -> core::fmt::Result {
+        self.0 = self.0.strip_prefix(s).ok_or(core::fmt::Error)?;
+        Ok(())
+    }
+}
+
+/// Returns whether a message is the same as the outermost message of an
+/// error trace, so that it is not added again when an error is converted
+/// at several layers with the same message.
+#[cfg(any(feature = "anyhow_tracer", feature = "eyre_tracer_minimal"))]
+fn repeats_last(trace: &dyn core::fmt::Display, message: &str) -> bool {
+    use core::fmt::Write;
+
+    let mut remaining = Remaining(message);
+    write!(remaining, "{}", trace).is_ok() && remaining.0.is_empty()
+}
+
+/// Returns whether a message is the same as the outermost message of the
+/// messages joined by `": "`, as kept by the `StringTracer` and the
+/// `DefmtTracer`, so that it is not added again as with [`repeats_last`].
+#[cfg(any(feature = "alloc", feature = "defmt_tracer"))]
+fn repeats_last_joined(joined: &str, message: &dyn core::fmt::Display) -> bool {
+    use core::fmt::Write;
+
+    let mut remaining = Remaining(joined);
+    write!(remaining, "{}", message).is_ok()
+        && (remaining.0.is_empty() || remaining.0.starts_with(": "))
+}
+
+/// A message added with a [`Level`](crate::Level) to the error tracers
+/// that keep the traced messages as error objects. It is traced as an error
+/// object of its own, with the previous trace as its source, so that its
+/// level can be recovered by downcasting the messages walked in the trace.
+#[cfg(any(feature = "anyhow_tracer", feature = "eyre_tracer_minimal"))]
+struct LeveledMessage<T> {
+    level: crate::Level,
+    message: alloc::boxed::Box<dyn core::fmt::Display + Send + Sync>,
+    source: T,
+}
+
+#[cfg(any(feature = "anyhow_tracer", feature = "eyre_tracer_minimal"))]
+impl<T> LeveledMessage<T> {
+    fn new<M>(level: crate::Level, message: M, source: T) -> Self
+    where
+        M: core::fmt::Display + Send + Sync + 'static,
+    {
+        LeveledMessage {
+            level,
+            message: alloc::boxed::Box::new(message),
+            source,
+        }
+    }
+}
+
+#[cfg(any(feature = "anyhow_tracer", feature = "eyre_tracer_minimal"))]
+impl<T> core::fmt::Display for LeveledMessage<T> {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        core::fmt::Display::fmt(&self.message, f)
+    }
+}
+
+#[cfg(any(feature = "anyhow_tracer", feature = "eyre_tracer_minimal"))]
+impl<T> core::fmt::Debug for LeveledMessage<T> {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        core::fmt::Display::fmt(&self.message, f)
+    }
+}
+
+#[cfg(any(feature = "anyhow_tracer", feature = "eyre_tracer_minimal"))]
+impl<T> std::error::Error for LeveledMessage<T>
+where
+    T: AsRef<dyn std::error::Error + Send + Sync>,
+{
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        Some(self.source.as_ref())
+    }
+}
+
+/// Returns the level of a message in the chain of an error tracer `T`,
+/// which is [`Level::Info`](crate::Level::Info) unless the message has
+/// been added as a [`LeveledMessage`].
+#[cfg(any(feature = "anyhow_tracer", feature = "eyre_tracer_minimal"))]
+fn message_level<T>(message: &(dyn std::error::Error + 'static)) -> crate::Level
+where
+    T: AsRef<dyn std::error::Error + Send + Sync> + 'static,
+{
+    message
+        .downcast_ref::<LeveledMessage<T>>()
+        .map_or(crate::Level::Info, |message| message.level)
+}
+
+/// Prepares a message traced by the built-in error tracers, by formatting
+/// it with the trace filter applied.
+#[cfg(feature = "alloc")]
+pub(crate) fn prepare_message<E: core::fmt::Display + ?Sized>(err: &E) -> alloc::string::String {
+    let message = alloc::format!("{}", err);
+    #[cfg(feature = "std")]
+    let message = crate::redact::filter_message(message);
+    message
+}
+
+/// Wraps a prepared message into the message type kept by the tracers
+/// that trace messages as error objects, which is interned with the
+/// `interning` feature and stamped with the `replay` feature.
+#[cfg(any(feature = "anyhow_tracer", feature = "eyre_tracer_minimal"))]
+fn report_message(
+    message: alloc::string::String,
+) -> impl core::fmt::Display + core::fmt::Debug + Send + Sync + 'static {
+    #[cfg(feature = "interning")]
+    let message = crate::intern::SharedMessage::from(message);
+    #[cfg(feature = "replay")]
+    let message = crate::replay::Stamped::now(message);
+    message
+}
+
+/// Finishes an error trace newly created by the built-in error tracers,
+/// by recording its message in the current tracer scope and attaching the
+/// context of the current task.
+#[cfg(any(feature = "alloc", feature = "defmt_tracer"))]
+pub(crate) fn start_trace<T>(trace: T) -> T
+where
+    T: crate::tracer::ErrorMessageTracer + crate::render::TraceRender,
+{
+    #[cfg(feature = "std")]
+    crate::scope::record_new(&trace);
+    #[cfg(feature = "task_context")]
+    let trace = crate::context::attach(trace);
+    trace
+}
+
+/// Adds a message to an error trace of the built-in error tracers with
+/// `add`, and records the message in the current tracer scope.
+#[cfg(any(feature = "alloc", feature = "defmt_tracer"))]
+pub(crate) fn extend_trace<T, M>(trace: T, message: M, add: impl FnOnce(T, M) -> T) -> T
+where
+    T: crate::render::TraceRender,
+    M: core::fmt::Display,
+{
+    #[cfg(feature = "std")]
+    let recorded = crate::scope::trace_key(&trace).map(|key| (key, alloc::format!("{}", message)));
+    let trace = add(trace, message);
+    #[cfg(feature = "std")]
+    if let Some((key, message)) = recorded {
+        crate::scope::record_add(key, message, &trace);
+    }
+    trace
+}
@@ -0,0 +1,50 @@
+use crate::render::TraceRender;
+use crate::tracer::{ErrorMessageTracer, ErrorTracer};
+use core::fmt::{Display, Formatter};
+
+/// A tracer that discards all error messages and traces.
+/// This is used by error types defined in the detail-only mode
+/// of [`define_error!`](crate::define_error), which do not carry
+/// any error trace.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct NoTracer;
+
+impl ErrorMessageTracer for NoTracer {
+    fn new_message<E: Display>(_: &E) -> Self {
+        NoTracer
+    }
+
+    fn add_message<E: Display>(self, _: &E) -> Self {
+        self
+    }
+
+    #[cfg(any(feature = "std", feature = "error_in_core"))]
+    fn as_error(&self) -> Option<&(dyn crate::StdError + 'static)> {
+        None
+    }
+}
+
+impl<E> ErrorTracer<E> for NoTracer {
+    fn new_trace(_: E) -> Self {
+        NoTracer
+    }
+
+    fn add_trace(self, _: E) -> Self {
+        self
+    }
+}
+
+impl Display for NoTracer {
+    fn fmt(&self, _: &mut Formatter<'_>) -> core::fmt::Result {
+        Ok(())
+    }
+}
+
+impl TraceRender for NoTracer {
+    fn walk_messages(
+        &self,
+        _: &mut dyn FnMut(&dyn Display) -> core::fmt::Result,
+    ) -> core::fmt::Result {
+        Ok(())
+    }
+}
@@ -0,0 +1,123 @@
+use crate::render::{Level, TraceRender};
+use crate::tracer::{ErrorMessageTracer, ErrorTracer};
+use core::fmt::{Debug, Display, Formatter};
+
+/// A tracer that drives two error tracers at once, such as an
+/// [`EyreTracer`](crate::tracer_impl::eyre::EyreTracer) for the
+/// human-readable reports and a
+/// [`StringTracer`](crate::tracer_impl::string::StringTracer) for the
+/// telemetry:
+///
+/// ```
+/// # use flex_error::define_error;
+/// # use flex_error::tracer_impl::eyre::EyreTracer;
+/// # use flex_error::tracer_impl::pair::PairTracer;
+/// # use flex_error::tracer_impl::string::StringTracer;
+/// # fn send_telemetry(message: &str) {
+/// #   assert_eq!(message, "disk full");
+/// # }
+/// type ReportTracer = PairTracer<EyreTracer, StringTracer>;
+///
+/// define_error! {
+///   MyError: tracer = ReportTracer {
+///     DiskFull
+///       | _ | { "disk full" },
+///   }
+/// }
+///
+/// let err = MyError::disk_full();
+/// send_telemetry(&err.trace().1.0);
+/// ```
+///
+/// Every message is added to both tracers. The ownership of a source error
+/// traced with [`ErrorTracer::new_trace`] or [`ErrorTracer::add_trace`] is
+/// given to the first tracer, and the second tracer only traces its
+/// message. The first tracer is also used to display and render the trace.
+#[derive(Clone)]
+pub struct PairTracer<A, B>(pub A, pub B);
+
+impl<A, B> ErrorMessageTracer for PairTracer<A, B>
+where
+    A: ErrorMessageTracer,
+    B: ErrorMessageTracer,
+{
+    #[track_caller]
+    fn new_message<E: Display>(err: &E) -> Self {
+        PairTracer(A::new_message(err), B::new_message(err))
+    }
+
+    #[track_caller]
+    fn new_static(message: &'static str) -> Self {
+        PairTracer(A::new_static(message), B::new_static(message))
+    }
+
+    fn add_message<E: Display>(self, err: &E) -> Self {
+        PairTracer(self.0.add_message(err), self.1.add_message(err))
+    }
+
+    fn add_message_with_level<E: Display>(self, err: &E, level: Level) -> Self {
+        PairTracer(
+            self.0.add_message_with_level(err, level),
+            self.1.add_message_with_level(err, level),
+        )
+    }
+
+    fn add_fields(self, fields: &[(&str, &dyn Display)]) -> Self {
+        PairTracer(self.0.add_fields(fields), self.1.add_fields(fields))
+    }
+
+    #[cfg(any(feature = "std", feature = "error_in_core"))]
+    fn as_error(&self) -> Option<&(dyn crate::StdError + 'static)> {
+        self.0.as_error()
+    }
+}
+
+impl<E, A, B> ErrorTracer<E> for PairTracer<A, B>
+where
+    E: Display,
+    A: ErrorTracer<E>,
+    B: ErrorMessageTracer,
+{
+    #[track_caller]
+    fn new_trace(err: E) -> Self {
+        let second = B::new_message(&err);
+        PairTracer(A::new_trace(err), second)
+    }
+
+    fn add_trace(self, err: E) -> Self {
+        let second = self.1.add_message(&err);
+        PairTracer(self.0.add_trace(err), second)
+    }
+}
+
+impl<A: TraceRender, B: TraceRender> TraceRender for PairTracer<A, B> {
+    fn walk_messages(
+        &self,
+        f: &mut dyn FnMut(&dyn Display) -> core::fmt::Result,
+    ) -> core::fmt::Result {
+        self.0.walk_messages(f)
+    }
+
+    fn walk_leveled_messages(
+        &self,
+        f: &mut dyn FnMut(Level, &dyn Display) -> core::fmt::Result,
+    ) -> core::fmt::Result {
+        self.0.walk_leveled_messages(f)
+    }
+
+    fn location(&self) -> Option<&str> {
+        self.0.location().or_else(|| self.1.location())
+    }
+}
+
+impl<A: Debug, B> Debug for PairTracer<A, B> {
+    fn fmt(&self, f: &mut Formatter<'_>) -> core::fmt::Result {
+        Debug::fmt(&self.0, f)
+    }
+}
+
+impl<A: Display, B> Display for PairTracer<A, B> {
+    fn fmt(&self, f: &mut Formatter<'_>) -> core::fmt::Result {
+        Display::fmt(&self.0, f)
+    }
+}
@@ -1,3 +1,4 @@
+use crate::render::TraceRender;
 use crate::tracer::{ErrorMessageTracer, ErrorTracer};
 use alloc::string::String;
 use core::fmt::{Debug, Display, Formatter};
@@ -6,36 +7,112 @@ use core::fmt::{Debug, Display, Formatter};
 /// string and simply concatenate them together.
 /// This can be used for example in `no_std` environment,
 /// which may not support more complex error tracers.
-pub struct StringTracer(pub String);
+///
+/// Since the messages are joined into a single string, the levels of the
+/// messages are not kept, and all messages are always rendered.
+///
+/// With the `string_tracer_location` feature, the tracer also records the
+/// source location where the trace was created, which is returned by
+/// [`location`](StringTracer::location), using
+/// [`Location::caller`](core::panic::Location::caller) through the
+/// `#[track_caller]` error constructors. This gives file and line context
+/// to the traces in `no_std` environments, where backtraces are not
+/// available. The location is kept in a second private field, which only
+/// exists with the feature, so that a `StringTracer` can still be created
+/// with `StringTracer(messages)` without it.
+pub struct StringTracer(
+    pub String,
+    #[cfg(feature = "string_tracer_location")] Option<String>,
+);
+
+impl StringTracer {
+    /// Creates a trace with the given joined messages, without a location.
+    pub fn new(messages: String) -> Self {
+        StringTracer(
+            messages,
+            #[cfg(feature = "string_tracer_location")]
+            None,
+        )
+    }
+
+    /// Returns the source location where the trace was created, which is
+    /// only recorded with the `string_tracer_location` feature.
+    pub fn location(&self) -> Option<&str> {
+        #[cfg(feature = "string_tracer_location")]
+        return self.1.as_deref();
+        #[cfg(not(feature = "string_tracer_location"))]
+        return None;
+    }
+
+    #[track_caller]
+    fn capture(message: String) -> Self {
+        StringTracer(
+            message,
+            #[cfg(feature = "string_tracer_location")]
+            Some(super::prepare_message(core::panic::Location::caller())),
+        )
+    }
+
+    fn push(self, message: String) -> Self {
+        if super::repeats_last_joined(&self.0, &message) {
+            return self;
+        }
+
+        StringTracer(
+            alloc::format!("{0}: {1}", message, self.0),
+            #[cfg(feature = "string_tracer_location")]
+            self.1,
+        )
+    }
+}
 
 impl ErrorMessageTracer for StringTracer {
+    #[track_caller]
     fn new_message<E: Display>(err: &E) -> Self {
-        StringTracer(alloc::format!("{}", err))
+        super::start_trace(StringTracer::capture(super::prepare_message(err)))
     }
 
     fn add_message<E: Display>(self, err: &E) -> Self {
-        StringTracer(alloc::format!("{0}: {1}", err, self.0))
+        super::extend_trace(self, super::prepare_message(err), StringTracer::push)
     }
 
-    #[cfg(feature = "std")]
-    fn as_error(&self) -> Option<&(dyn std::error::Error + 'static)> {
+    #[cfg(any(feature = "std", feature = "error_in_core"))]
+    fn as_error(&self) -> Option<&(dyn crate::StdError + 'static)> {
         None
     }
 }
 
 impl<E: Display> ErrorTracer<E> for StringTracer {
+    #[track_caller]
     fn new_trace(err: E) -> Self {
-        StringTracer(alloc::format!("{}", err))
+        super::start_trace(StringTracer::capture(super::prepare_message(&err)))
     }
 
     fn add_trace(self, err: E) -> Self {
-        StringTracer(alloc::format!("{0}: {1}", err, self.0))
+        super::extend_trace(self, super::prepare_message(&err), StringTracer::push)
+    }
+}
+
+impl TraceRender for StringTracer {
+    fn walk_messages(
+        &self,
+        f: &mut dyn FnMut(&dyn Display) -> core::fmt::Result,
+    ) -> core::fmt::Result {
+        f(&self.0)
+    }
+
+    fn location(&self) -> Option<&str> {
+        StringTracer::location(self)
     }
 }
 
 impl Debug for StringTracer {
     fn fmt(&self, f: &mut Formatter<'_>) -> core::fmt::Result {
-        write!(f, "StringTracer: {0}", self.0)
+        write!(f, "StringTracer: {0}", self.0)?;
+        if let Some(location) = self.location() {
+            write!(f, "\n    at {0}", location)?;
+        }
+        Ok(())
     }
 }
 
@@ -0,0 +1,223 @@
+use crate::render::{Level, TraceRender};
+use crate::tracer::{ErrorMessageTracer, ErrorTracer};
+use alloc::boxed::Box;
+use alloc::string::String;
+use core::fmt::{Debug, Display, Formatter};
+use tracing::Span;
+
+/// An error tracer integrated with [`tracing`](https://docs.rs/tracing),
+/// which keeps the chain of error messages together with the span that was
+/// current when each message was traced.
+///
+/// Each message is also recorded as a `tracing` event with the target
+/// `flex_error`, at the [`Level`] of the message, so that the error trace
+/// shows up in the logs within the span where the error happened. Messages
+/// added without a level, such as the error sources, are recorded at the
+/// `INFO` level.
+///
+/// The spans are captured with [`Span::current`], and are disabled if no
+/// `tracing` subscriber is installed.
+pub struct TracingTracer {
+    /// The outermost error message, linked to the inner messages
+    /// as its error sources.
+    head: TraceMessage,
+}
+
+#[cfg(feature = "interning")]
+type MessageText = crate::intern::SharedMessage;
+
+#[cfg(not(feature = "interning"))]
+type MessageText = String;
+
+/// A message in a [`TracingTracer`], which implements
+/// [`Error`](std::error::Error) with the next inner message as its source.
+#[derive(Debug)]
+struct TraceMessage {
+    message: MessageText,
+    level: Level,
+    span: Span,
+    #[cfg(feature = "replay")]
+    time: std::time::SystemTime,
+    source: Option<Box<TraceMessage>>,
+}
+
+impl Display for TraceMessage {
+    fn fmt(&self, f: &mut Formatter<'_>) -> core::fmt::Result {
+        #[cfg(feature = "replay")]
+        let message = crate::replay::Stamped {
+            time: self.time,
+            message: &self.message,
+        };
+        #[cfg(not(feature = "replay"))]
+        let message = &self.message;
+
+        Display::fmt(&message, f)
+    }
+}
+
+impl std::error::Error for TraceMessage {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        self.source
+            .as_deref()
+            .map(|source| source as &(dyn std::error::Error + 'static))
+    }
+}
+
+impl TraceMessage {
+    fn new(message: String, level: Level, source: Option<Box<TraceMessage>>) -> Self {
+        record_event(&message, level);
+
+        TraceMessage {
+            message: MessageText::from(message),
+            level,
+            span: Span::current(),
+            #[cfg(feature = "replay")]
+            time: std::time::SystemTime::now(),
+            source,
+        }
+    }
+}
+
+/// Records an error message as a `tracing` event at the given level.
+fn record_event(message: &str, level: Level) {
+    match level {
+        Level::Trace => tracing::trace!(target: "flex_error", "{}", message),
+        Level::Debug => tracing::debug!(target: "flex_error", "{}", message),
+        Level::Info => tracing::info!(target: "flex_error", "{}", message),
+        Level::Warn => tracing::warn!(target: "flex_error", "{}", message),
+        Level::Error => tracing::error!(target: "flex_error", "{}", message),
+    }
+}
+
+impl TracingTracer {
+    fn capture(message: String) -> Self {
+        TracingTracer {
+            head: TraceMessage::new(message, Level::Info, None),
+        }
+    }
+
+    fn push(self, message: String, level: Level) -> Self {
+        // A message repeating the outermost message is not added again.
+        if message == self.head.message.as_str() {
+            return self;
+        }
+
+        TracingTracer {
+            head: TraceMessage::new(message, level, Some(Box::new(self.head))),
+        }
+    }
+
+    fn entries(&self) -> impl Iterator<Item = &TraceMessage> {
+        core::iter::successors(Some(&self.head), |message| message.source.as_deref())
+    }
+
+    /// Returns the span that was current when the trace was created.
+    pub fn span(&self) -> &Span {
+        let mut message = &self.head;
+        while let Some(source) = message.source.as_deref() {
+            message = source;
+        }
+        &message.span
+    }
+
+    /// Returns an iterator over the error messages, from the outermost
+    /// to the innermost message.
+    pub fn messages(&self) -> impl Iterator<Item = &str> {
+        self.entries().map(|message| message.message.as_str())
+    }
+
+    /// Returns an iterator over the spans that were current when each
+    /// error message was traced, from the outermost to the innermost message.
+    pub fn spans(&self) -> impl Iterator<Item = &Span> {
+        self.entries().map(|message| &message.span)
+    }
+}
+
+impl ErrorMessageTracer for TracingTracer {
+    fn new_message<E: Display>(err: &E) -> Self {
+        super::start_trace(Self::capture(super::prepare_message(err)))
+    }
+
+    fn add_message<E: Display>(self, err: &E) -> Self {
+        super::extend_trace(self, super::prepare_message(err), |trace, message| {
+            trace.push(message, Level::Info)
+        })
+    }
+
+    fn add_message_with_level<E: Display>(self, err: &E, level: Level) -> Self {
+        super::extend_trace(self, super::prepare_message(err), |trace, message| {
+            trace.push(message, level)
+        })
+    }
+
+    fn as_error(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        Some(&self.head)
+    }
+}
+
+impl<E: Display> ErrorTracer<E> for TracingTracer {
+    fn new_trace(err: E) -> Self {
+        super::start_trace(Self::capture(super::prepare_message(&err)))
+    }
+
+    fn add_trace(self, err: E) -> Self {
+        super::extend_trace(self, super::prepare_message(&err), |trace, message| {
+            trace.push(message, Level::Info)
+        })
+    }
+}
+
+impl TraceRender for TracingTracer {
+    fn walk_messages(
+        &self,
+        f: &mut dyn FnMut(&dyn Display) -> core::fmt::Result,
+    ) -> core::fmt::Result {
+        for message in self.entries() {
+            f(message)?;
+        }
+        Ok(())
+    }
+
+    fn walk_leveled_messages(
+        &self,
+        f: &mut dyn FnMut(Level, &dyn Display) -> core::fmt::Result,
+    ) -> core::fmt::Result {
+        for message in self.entries() {
+            f(message.level, message)?;
+        }
+        Ok(())
+    }
+}
+
+impl Debug for TracingTracer {
+    fn fmt(&self, f: &mut Formatter<'_>) -> core::fmt::Result {
+        for (index, message) in self.entries().enumerate() {
+            if index > 0 {
+                writeln!(f)?;
+            }
+            write!(f, "{0:>4}: {1}", index, message.message.as_str())?;
+            if let Some(metadata) = message.span.metadata() {
+                write!(f, "\n      in {0}::{1}", metadata.target(), metadata.name())?;
+            }
+        }
+        Ok(())
+    }
+}
+
+impl Display for TracingTracer {
+    fn fmt(&self, f: &mut Formatter<'_>) -> core::fmt::Result {
+        // As with eyre and anyhow, only the outermost message is displayed,
+        // and the alternate mode `{:#}` displays the chain of messages.
+        if !f.alternate() {
+            return write!(f, "{0}", self.head.message);
+        }
+
+        for (index, message) in self.messages().enumerate() {
+            if index > 0 {
+                write!(f, ": ")?;
+            }
+            write!(f, "{0}", message)?;
+        }
+        Ok(())
+    }
+}
@@ -0,0 +1,110 @@
+/*!
+  Conversion of errors to JavaScript values, for browser-targeted builds.
+
+  When the `wasm` feature is enabled, the error types defined by
+  [`define_error!`](crate::define_error) implement
+  `From<MyError> for JsValue`, so that they can be returned from
+  `#[wasm_bindgen]` functions:
+
+  ```no_run
+  # use wasm_bindgen::prelude::*;
+  # mod client {
+  #   flex_error::define_error! {
+  #     ClientError {
+  #       InvalidUrl
+  #         { url: String }
+  #         | e | { format_args!("invalid url {}", e.url) },
+  #     }
+  #   }
+  #   pub fn connect(url: &str) -> Result<(), ClientError> {
+  #     Err(ClientError::invalid_url(url.to_string()))
+  #   }
+  # }
+  #[wasm_bindgen]
+  pub fn connect(url: &str) -> Result<(), JsValue> {
+    client::connect(url)?;
+    Ok(())
+  }
+  ```
+
+  The error is converted to a JavaScript `Error`, with the outermost message
+  as its `message`, and with the following additional properties:
+
+  - `name`: the name of the main error type, e.g. `"MyError"`.
+  - `variant`: the name of the sub-error, e.g. `"Timeout"`.
+  - `messages`: an array of the messages in the error trace, from the
+    outermost to the innermost message.
+
+  The generated `log_to_console` method logs an error to `console.error`,
+  with the messages in the error trace in a collapsed group.
+  [`install_hook`] installs a panic hook that logs panics in the same way,
+  including the errors that are formatted in panic messages by `unwrap`
+  or `expect`.
+*/
+
+use alloc::boxed::Box;
+use alloc::format;
+use alloc::string::{String, ToString};
+use js_sys::{Array, Reflect};
+use wasm_bindgen::prelude::*;
+
+#[wasm_bindgen]
+extern "C" {
+    #[wasm_bindgen(js_namespace = console, js_name = error)]
+    fn console_error(message: &str);
+
+    #[wasm_bindgen(js_namespace = console, js_name = groupCollapsed)]
+    fn console_group_collapsed(label: &str);
+
+    #[wasm_bindgen(js_namespace = console, js_name = groupEnd)]
+    fn console_group_end();
+}
+
+/// Converts the messages of an error to a JavaScript `Error`, with the
+/// `name`, `variant` and `messages` properties. This is called by the
+/// `From<MyError> for JsValue` implementations of the error types.
+pub fn to_js_error(name: &str, variant: &str, messages: &[String]) -> JsValue {
+    let message = messages.first().map(String::as_str).unwrap_or(variant);
+    let error = js_sys::Error::new(message);
+    error.set_name(name);
+
+    let messages: Array = messages
+        .iter()
+        .map(|message| JsValue::from_str(message))
+        .collect();
+
+    // Setting properties of a newly created `Error` object cannot fail.
+    let _ = Reflect::set(&error, &JsValue::from_str("variant"), &JsValue::from_str(variant));
+    let _ = Reflect::set(&error, &JsValue::from_str("messages"), &messages);
+
+    error.into()
+}
+
+/// Logs the messages of an error to `console.error`, with the outermost
+/// message as the label of a collapsed group containing all messages.
+pub fn log_error(name: &str, variant: &str, messages: &[String]) {
+    let message = messages.first().map(String::as_str).unwrap_or_default();
+    log_group(&format!("{0}::{1}: {2}", name, variant, message), messages);
+}
+
+/// Installs a panic hook that logs panics to `console.error`, with the
+/// first line of the panic message as the label of a collapsed group
+/// containing the remaining lines, such as the causes of an error
+/// formatted with `unwrap`.
+pub fn install_hook() {
+    std::panic::set_hook(Box::new(|info| {
+        let message = info.to_string();
+        let mut lines = message.lines().filter(|line| !line.trim().is_empty());
+        let label = lines.next().unwrap_or("panicked");
+        let lines: alloc::vec::Vec<&str> = lines.collect();
+        log_group(label, &lines);
+    }));
+}
+
+fn log_group<S: AsRef<str>>(label: &str, lines: &[S]) {
+    console_group_collapsed(label);
+    for line in lines {
+        console_error(line.as_ref());
+    }
+    console_group_end();
+}
@@ -0,0 +1,102 @@
+/*!
+  Integration with the [`axum`](https://docs.rs/axum) web framework.
+
+  When the `axum` feature is enabled, the error types defined by
+  [`define_error!`](crate::define_error) implement
+  `axum::response::IntoResponse`, so that they can be returned from
+  handlers. The response has the HTTP status given to the sub-error with
+  the `@problem` option, and the problem details of the error as an
+  `application/problem+json` body. See the [`problem`](crate::problem)
+  module. The `trace` extension member is omitted from the response, so
+  that the error trace is not exposed to clients.
+
+  Rejections of axum extractors can be used as error sources with
+  [`Rejection`], so that they are wrapped into the error types with a single
+  `map_err`:
+
+  ```
+  # use axum::extract::rejection::JsonRejection;
+  # use axum::Json;
+  # use flex_error::define_error;
+  # use flex_error::web::Rejection;
+  # type Item = serde_json::Value;
+  define_error! {
+    ApiError {
+      InvalidBody
+        [ Rejection<JsonRejection> ]
+        @problem(status = 400, title = "Invalid request body")
+        | _ | { "invalid request body" },
+      Conflict
+        @problem(status = 409, title = "Item already exists")
+        | _ | { "item already exists" },
+    }
+  }
+
+  async fn create(payload: Result<Json<Item>, JsonRejection>) -> Result<Json<Item>, ApiError> {
+    let Json(item) = payload.map_err(ApiError::invalid_body)?;
+    # if item.is_null() { return Err(ApiError::conflict()); }
+    Ok(Json(item))
+  }
+  ```
+*/
+
+use alloc::string::{String, ToString};
+use core::marker::PhantomData;
+
+use axum::http::{header, HeaderValue, StatusCode};
+use axum::response::{IntoResponse, Response};
+
+use crate::problem::{ProblemDetails, CONTENT_TYPE};
+use crate::source::ErrorSource;
+use crate::tracer::ErrorMessageTracer;
+
+/// An [`ErrorSource`] for the rejections of axum extractors, such as
+/// `JsonRejection` or `PathRejection`.
+///
+/// Its `Source` type is the rejection, and its `Detail` type is
+/// [`RejectionDetail`], holding the status and message of the rejection.
+/// When extracted, it also provides an error trace that is traced from the
+/// rejection message.
+pub struct Rejection<R>(PhantomData<R>);
+
+/// The status and message of a rejection of an axum extractor.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct RejectionDetail {
+    pub status: u16,
+    pub message: String,
+}
+
+impl<R, Tracer> ErrorSource<Tracer> for Rejection<R>
+where
+    R: IntoResponse + core::fmt::Display,
+    Tracer: ErrorMessageTracer,
+{
+    type Detail = RejectionDetail;
+    type Source = R;
+
+    #[track_caller]
+    fn error_details(source: Self::Source) -> (Self::Detail, Option<Tracer>) {
+        let message = source.to_string();
+        let trace = Tracer::new_message(&message);
+        let status = source.into_response().status().as_u16();
+        (RejectionDetail { status, message }, Some(trace))
+    }
+
+    fn detail_display(detail: &Self::Detail) -> Option<&dyn core::fmt::Display> {
+        Some(&detail.message)
+    }
+}
+
+/// Builds the `application/problem+json` response of the problem details,
+/// without its `trace` extension member. This is called by the
+/// `IntoResponse` implementations of the error types.
+pub fn problem_response(mut problem: ProblemDetails) -> Response {
+    problem.trace.clear();
+
+    let status = StatusCode::from_u16(problem.status).unwrap_or(StatusCode::INTERNAL_SERVER_ERROR);
+    let mut response = (status, axum::Json(problem)).into_response();
+    response
+        .headers_mut()
+        .insert(header::CONTENT_TYPE, HeaderValue::from_static(CONTENT_TYPE));
+    response
+}
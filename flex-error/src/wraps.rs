@@ -0,0 +1,164 @@
+/*!
+  Compile-time checks of the error types wrapped by other error types.
+
+  Each error type defined by [`define_error!`](crate::define_error)
+  implements [`Wraps`] for the error sources of its sub-errors, so that
+  architecture rules such as "all subsystem errors must be wrappable into
+  `RelayerError`" can be enforced by the type system with
+  [`assert_wrappable!`](crate::assert_wrappable):
+
+  ```
+  # mod chain {
+  #   flex_error::define_error! {
+  #     ChainError {
+  #       Halted
+  #         | _ | { "chain halted" },
+  #     }
+  #   }
+  # }
+  # use chain::ChainError;
+  # flex_error::define_error! {
+  #   RelayerError {
+  #     Chain
+  #       [ ChainError ]
+  #       | _ | { "chain error" },
+  #     Io
+  #       [ flex_error::TraceError<std::io::Error> ]
+  #       | _ | { "I/O error" },
+  #   }
+  # }
+  flex_error::assert_wrappable!(RelayerError from ChainError);
+  flex_error::assert_wrappable!(RelayerError from std::io::Error);
+  ```
+
+  The build fails if `RelayerError` has no sub-error with a constructor
+  taking a `ChainError`, or a `std::io::Error` such as with a
+  `[ TraceError<std::io::Error> ]` source. The sub-errors with two error
+  sources or with the `@no_constructor` option are not taken into account.
+
+  If several sub-errors wrap the same error type, the check is ambiguous,
+  and the sub-error to check is given with the type of its sub-detail:
+
+  ```
+  # mod relayer {
+  #   pub mod error {
+  #     flex_error::define_error! {
+  #       RelayerError {
+  #         Io
+  #           [ flex_error::TraceError<std::io::Error> ]
+  #           | _ | { "I/O error" },
+  #         Config
+  #           [ flex_error::TraceError<std::io::Error> ]
+  #           | _ | { "failed to read the configuration" },
+  #       }
+  #     }
+  #   }
+  # }
+  # use relayer::error::RelayerError;
+  flex_error::assert_wrappable!(RelayerError from std::io::Error via relayer::error::IoSubdetail);
+  ```
+*/
+
+/// Implemented by an error type defined by [`define_error!`](crate::define_error)
+/// for the error source `E` of the sub-error with the sub-detail `Via`, whose
+/// constructor wraps an `E` into the error type.
+pub trait Wraps<E, Via> {}
+
+/// Asserts at compile time that an error type defined by
+/// [`define_error!`](crate::define_error) has a sub-error wrapping the given
+/// error type. See the [`wraps`](crate::wraps) module.
+#[macro_export]
+macro_rules! assert_wrappable {
+  ( $( $outer:ident )::+ from $( $inner:ident )::+ $(,)? ) => {
+    const _: fn() = || {
+      fn assert_wrappable<Outer, Inner, Via>()
+      where
+        Outer: $crate::wraps::Wraps<Inner, Via>,
+      {
+      }
+
+      assert_wrappable::< $( $outer )::+, $( $inner )::+, _ >();
+    };
+  };
+  ( $( $outer:ident )::+ from $( $inner:ident )::+ via $( $via:ident )::+ $(,)? ) => {
+    const _: fn() = || {
+      fn assert_wrappable<Outer, Inner, Via>()
+      where
+        Outer: $crate::wraps::Wraps<Inner, Via>,
+      {
+      }
+
+      assert_wrappable::< $( $outer )::+, $( $inner )::+, $( $via )::+ >();
+    };
+  };
+}
+
+/// Internal macro used to implement [`Wraps`] for the error sources of the
+/// sub-errors of an error type.
+#[macro_export]
+#[doc(hidden)]
+macro_rules! define_error_wraps {
+  ( @tracer( $tracer:ty ),
+    @name( $name:ident ),
+    @suberrors{
+      $(
+        $( #[$sub_attr:meta] )*
+        $suberror:ident $( as $alias:ident )* $( = $numeric_code:literal )?
+        $( { $( $( #[detail $field_opts:tt] )* $arg_name:ident : $( @ $modifier:ident )? $arg_type:ty ),* $(,)? } )?
+        $( [ $( $source:tt )+ ] )?
+        $( @ $option:ident $( ( $( $option_arg:tt )* ) )? )*
+        | $formatter_arg:pat | $formatter:expr
+      ),* $(,)?
+    } $(,)?
+  ) => {
+    $(
+      $crate::define_error_wraps! {
+        @options[ $( @ $option ( $( $( $option_arg )* )? ) )* ],
+        @tracer( $tracer ),
+        @name( $name ),
+        @suberror( $suberror )
+        $( @source[ $( $source )+ ] )?
+      }
+    )*
+  };
+  ( @options[ @no_constructor() $( $options:tt )* ],
+    $( $rest:tt )*
+  ) => {};
+  ( @options[ @ $option:ident $option_args:tt $( $options:tt )* ],
+    $( $rest:tt )*
+  ) => {
+    $crate::define_error_wraps! {
+      @options[ $( $options )* ],
+      $( $rest )*
+    }
+  };
+  ( @options[],
+    @tracer( $tracer:ty ),
+    @name( $name:ident ),
+    @suberror( $suberror:ident )
+    @source[ Self ]
+  ) => {
+    $crate::macros::paste! [
+      impl $crate::wraps::Wraps< $name, [< $suberror Subdetail >] > for $name {}
+    ];
+  };
+  ( @options[],
+    @tracer( $tracer:ty ),
+    @name( $name:ident ),
+    @suberror( $suberror:ident )
+    @source[ $source:ty ]
+  ) => {
+    $crate::macros::paste! [
+      impl $crate::wraps::Wraps<
+        $crate::AsErrorSource< $source, $tracer >,
+        [< $suberror Subdetail >],
+      > for $name {}
+    ];
+  };
+  ( @options[],
+    @tracer( $tracer:ty ),
+    @name( $name:ident ),
+    @suberror( $suberror:ident )
+    $( @source[ $source:ty, $source2:ty ] )?
+  ) => {};
+}
@@ -0,0 +1,27 @@
+#![cfg(feature = "interning")]
+
+use flex_error::define_error;
+use flex_error::intern::{aggregate, Interned};
+
+define_error! {
+    RelayerError {
+        ChainTimeout
+            { chain_id: Interned<str> }
+            | e | { format_args!("timed out waiting for chain {}", e.chain_id) },
+    }
+}
+
+#[test]
+fn aggregates_the_traced_messages() {
+    let errors: Vec<RelayerError> = aggregate(|| {
+        (0..3)
+            .map(|_| RelayerError::chain_timeout("ibc-2".into()).add_trace(&"while relaying"))
+            .collect()
+    });
+    for err in &errors {
+        assert_eq!(
+            format!("{:#}", err),
+            "while relaying: timed out waiting for chain ibc-2"
+        );
+    }
+}
@@ -0,0 +1,53 @@
+#![cfg(feature = "alloc")]
+
+use flex_error::define_error;
+use flex_error::tracer_impl::aggregating::{AggregatingTracer, MAX_AGGREGATED_MESSAGES};
+
+define_error! {
+    ChildError: tracer = AggregatingTracer {
+        Crashed
+            { name: String }
+            | e | { format_args!("child task {} crashed", e.name) },
+    }
+}
+
+define_error! {
+    SupervisorError: tracer = AggregatingTracer {
+        ChildFailed
+            | _ | { "child task failed" },
+    }
+}
+
+#[test]
+fn merges_the_child_traces() {
+    let err = SupervisorError::child_failed()
+        .map_trace(|trace| trace.merge(ChildError::crashed("relay".into()).into_trace()))
+        .map_trace(|trace| trace.merge(ChildError::crashed("monitor".into()).into_trace()));
+
+    let trace = err.trace();
+    assert_eq!(trace.messages().collect::<Vec<_>>(), ["child task failed"]);
+    assert_eq!(
+        trace
+            .children()
+            .iter()
+            .map(|child| child.to_string())
+            .collect::<Vec<_>>(),
+        ["child task relay crashed", "child task monitor crashed"]
+    );
+    assert_eq!(trace.elided(), (0, 0));
+}
+
+#[test]
+fn bounds_the_aggregated_messages() {
+    let mut err = ChildError::crashed("relay".into());
+    for attempt in 0..MAX_AGGREGATED_MESSAGES + 4 {
+        err = err.add_trace(&format_args!("attempt {}", attempt));
+    }
+
+    let trace = err.trace();
+    assert_eq!(trace.messages().count(), MAX_AGGREGATED_MESSAGES);
+    assert_eq!(trace.elided(), (5, 0));
+    assert_eq!(trace.messages().next(), Some("attempt 19"));
+    assert_eq!(trace.messages().last(), Some("child task relay crashed"));
+    assert!(trace.to_string().contains(": (5 messages elided): child task relay crashed"));
+}
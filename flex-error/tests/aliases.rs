@@ -0,0 +1,20 @@
+use flex_error::define_error;
+
+define_error! {
+    NetError {
+        ConnectTimeout as ReadTimeout as WriteTimeout
+            { timeout: u64 }
+            | e | { format_args!("timed out after {}ms", e.timeout) },
+    }
+}
+
+#[test]
+fn constructs_the_variant_aliases_with_the_shared_sub_detail() {
+    let err = NetError::read_timeout(5);
+    assert!(matches!(
+        err.detail(),
+        NetErrorDetail::ReadTimeout(ConnectTimeoutSubdetail { timeout: 5 })
+    ));
+    assert_eq!(err.kind(), NetErrorKind::ReadTimeout);
+    assert_ne!(err.kind(), NetErrorKind::WriteTimeout);
+}
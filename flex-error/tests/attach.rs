@@ -0,0 +1,50 @@
+#![cfg(feature = "attachments")]
+
+use flex_error::attach::{capture, clear_providers, register_provider, Attachment};
+use flex_error::define_error;
+
+define_error! {
+    NodeError {
+        Corrupted
+            { height: u64 }
+            @attach(build, node)
+            | e | { format_args!("corrupted state at height {}", e.height) },
+        Unattached
+            { height: u64 }
+            @attach(missing)
+            | e | { format_args!("invalid state at height {}", e.height) },
+    }
+}
+
+#[test]
+fn attaches_the_snapshots_to_the_trace() {
+    register_provider("build", "version", || "1.2.0".into());
+    register_provider("build", "git_hash", || "5f2c1e9".into());
+    register_provider("node", "chain_id", || "cosmoshub-4".into());
+
+    let err = NodeError::corrupted(42);
+    assert_eq!(
+        format!("{:#}", err),
+        "build: version=1.2.0, git_hash=5f2c1e9: node: chain_id=cosmoshub-4: \
+         corrupted state at height 42"
+    );
+    assert_eq!(err.detail().to_string(), "corrupted state at height 42");
+
+    let err = NodeError::unattached(42);
+    assert_eq!(format!("{:#}", err), "invalid state at height 42");
+}
+
+#[test]
+fn captures_the_registered_providers() {
+    register_provider("capture", "config", || "9a3b".into());
+    assert_eq!(
+        capture("capture"),
+        Some(Attachment {
+            kind: "capture",
+            values: vec![("config", "9a3b".to_string())],
+        })
+    );
+
+    clear_providers("capture");
+    assert_eq!(capture("capture"), None);
+}
@@ -0,0 +1,80 @@
+#![cfg(feature = "audit-tool")]
+
+use flex_error::audit::{audited_errors, report};
+use flex_error::{define_error, TraceError};
+
+define_error! {
+    @codes(include_str!("error_codes.toml"))
+    MyError {
+        ConnectTimeout
+            { timeout: u64 }
+            | e | { format_args!("timed out after {}ms", e.timeout) },
+        Refused
+            | _ | { "connection refused" },
+    }
+}
+
+define_error! {
+    StoreError {
+        Io
+            [ TraceError<std::io::Error> ]
+            | _ | { "I/O error" },
+        Net
+            [ MyError ]
+            | _ | { "network error" },
+        Retry
+            [ Self ]
+            | _ | { "retry failed" },
+    }
+}
+
+#[test]
+fn registers_the_error_types() {
+    let errors = audited_errors();
+    let names = errors
+        .iter()
+        .filter(|error| error.module == "audit")
+        .map(|error| error.error)
+        .collect::<Vec<_>>();
+    assert_eq!(names, ["MyError", "StoreError"]);
+
+    let codes = errors[0]
+        .variants
+        .iter()
+        .map(|v| (v.name, v.code.map(|code| code.code)))
+        .collect::<Vec<_>>();
+    assert_eq!(
+        codes,
+        [
+            ("ConnectTimeout", Some("NET-0001")),
+            ("Refused", Some("NET-0002"))
+        ]
+    );
+
+    let sources = errors[1]
+        .variants
+        .iter()
+        .map(|v| (v.name, v.code.is_some(), v.sources))
+        .collect::<Vec<_>>();
+    assert_eq!(
+        sources,
+        [
+            ("Io", false, &["TraceError<std::io::Error>"][..]),
+            ("Net", false, &["MyError"][..]),
+            ("Retry", false, &["StoreError"][..]),
+        ]
+    );
+}
+
+#[test]
+fn reports_the_error_types_in_json() {
+    let report = report();
+    assert!(report.starts_with("{\"errors\":[{\"module\":\"audit\",\"error\":\"MyError\",\"variants\":["));
+    assert!(report.contains(
+        "{\"name\":\"ConnectTimeout\",\"code\":\"NET-0001\",\"severity\":\"warning\",\"sources\":[]}"
+    ));
+    assert!(report.contains(
+        "{\"name\":\"Io\",\"code\":null,\"severity\":null,\"sources\":[\"TraceError<std::io::Error>\"]}"
+    ));
+    assert!(report.ends_with("]}]}"));
+}
@@ -0,0 +1,58 @@
+#![cfg(feature = "backtrace")]
+
+use std::backtrace::BacktraceStatus;
+
+use flex_error::define_error;
+use flex_error::report::ReportBuilder;
+
+define_error! {
+    @metadata
+    NetError {
+        Timeout
+            | _ | { "timed out" },
+    }
+}
+
+define_error! {
+    @metadata
+    AppError {
+        Net
+            [ NetError ]
+            | _ | { "network error" },
+    }
+}
+
+#[test]
+fn captures_the_backtrace_when_the_error_is_constructed() {
+    // The environment variable is read at the first capture, which is
+    // the one below in this test binary.
+    std::env::set_var("RUST_LIB_BACKTRACE", "1");
+
+    let err = NetError::timeout();
+    let backtrace = err.backtrace().unwrap();
+    assert_eq!(backtrace.status(), BacktraceStatus::Captured);
+
+    let backtrace: *const _ = backtrace;
+    let err = AppError::net(err);
+    assert!(std::ptr::eq(err.backtrace().unwrap(), backtrace));
+
+    let report = ReportBuilder::new().error("app", &err).build();
+    assert!(format!("{:?}", report).contains("\n\napp stack backtrace:\n"));
+    assert!(!format!("{}", report).contains("stack backtrace"));
+}
+
+#[cfg(feature = "incidents")]
+define_error! {
+    DbError {
+        Locked
+            | _ | { "database locked" },
+    }
+}
+
+#[cfg(feature = "incidents")]
+#[test]
+fn captures_the_backtrace_without_the_metadata_option_with_incidents() {
+    std::env::set_var("RUST_LIB_BACKTRACE", "1");
+    let err = DbError::locked();
+    assert!(err.backtrace().is_some());
+}
@@ -0,0 +1,25 @@
+#![cfg(feature = "alloc")]
+
+use std::io;
+
+use flex_error::{define_error, Both, DisplayError, TraceError};
+
+define_error! {
+    StoreError {
+        Commit
+            [ Both<TraceError<io::Error>, DisplayError<String>> ]
+            | _ | { "failed to commit" },
+    }
+}
+
+#[test]
+fn traces_a_pair_of_errors() {
+    let err = StoreError::commit((io::Error::other("disk full"), "rollback failed".to_string()));
+    assert_eq!(
+        format!("{:#}", err),
+        "failed to commit: disk full: rollback failed"
+    );
+    match err.detail() {
+        StoreErrorDetail::Commit(e) => assert_eq!(e.source.1, "rollback failed"),
+    }
+}
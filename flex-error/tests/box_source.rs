@@ -0,0 +1,33 @@
+#![cfg(feature = "alloc")]
+
+use flex_error::{define_error, BoxSource};
+
+define_error! {
+    QueryError {
+        Plan
+            [ BoxSource<PlanError> ]
+            | _ | { "failed to plan the query" },
+    }
+}
+
+define_error! {
+    PlanError {
+        Subquery
+            [ QueryError ]
+            | _ | { "failed to run a subquery" },
+        Empty
+            | _ | { "empty plan" },
+    }
+}
+
+#[test]
+fn boxes_the_mutually_recursive_sources() {
+    let err = QueryError::plan(PlanError::subquery(QueryError::plan(PlanError::empty())));
+    assert_eq!(
+        format!("{:#}", err),
+        "failed to plan the query: failed to run a subquery: failed to plan the query: empty plan"
+    );
+    match err.detail() {
+        QueryErrorDetail::Plan(e) => assert!(matches!(*e.source, PlanErrorDetail::Subquery(_))),
+    }
+}
@@ -0,0 +1,34 @@
+#![cfg(any(feature = "eyre_tracer_minimal", feature = "anyhow_tracer"))]
+
+use std::error::Error;
+use std::io;
+
+use flex_error::{define_error, TraceError};
+
+define_error! {
+    StoreError {
+        Io
+            [ TraceError<io::Error> ]
+            | _ | { "I/O error" },
+    }
+}
+
+fn run() -> Result<(), Box<dyn Error + Send + Sync>> {
+    Err(StoreError::io(io::Error::other("disk full")))?;
+    Ok(())
+}
+
+#[test]
+fn keeps_the_error_chain_in_boxed_errors() {
+    let err = run().unwrap_err();
+    assert!(err.downcast_ref::<StoreError>().is_some());
+
+    let mut messages = vec![err.to_string()];
+    let mut source = err.source();
+    while let Some(err) = source {
+        messages.push(err.to_string());
+        source = err.source();
+    }
+    assert!(messages.len() > 1);
+    assert!(messages.last().unwrap().ends_with("disk full"));
+}
@@ -0,0 +1,49 @@
+#![cfg(feature = "std")]
+
+use std::error::Error;
+use std::fmt;
+use std::io;
+
+use flex_error::{define_error, BoxedTraceError};
+
+define_error! {
+    StoreError {
+        Captured
+            [ BoxedTraceError ]
+            | _ | { "failed to parse the input" },
+    }
+}
+
+#[derive(Debug)]
+pub struct ParseError<'a> {
+    input: &'a str,
+    source: io::Error,
+}
+
+impl fmt::Display for ParseError<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "unexpected input {:?}", self.input)
+    }
+}
+
+impl Error for ParseError<'_> {
+    fn source(&self) -> Option<&(dyn Error + 'static)> {
+        Some(&self.source)
+    }
+}
+
+#[test]
+fn captures_the_borrowed_errors() {
+    let input = String::from("0x1g");
+    let err = {
+        let source = ParseError {
+            input: &input,
+            source: io::Error::other("invalid digit"),
+        };
+        StoreError::captured(BoxedTraceError::capture(&source))
+    };
+    drop(input);
+
+    assert!(format!("{:#}", err)
+        .starts_with("failed to parse the input: unexpected input \"0x1g\""));
+}
@@ -0,0 +1,70 @@
+#![cfg(feature = "alloc")]
+
+use flex_error::breadcrumbs::TraceBuilder;
+use flex_error::define_error;
+
+define_error! {
+    DecodeError {
+        Truncated
+            | _ | { "input is truncated" },
+    }
+}
+
+fn decode_header(bytes: &[u8]) -> Result<u8, DecodeError> {
+    bytes.first().copied().ok_or_else(DecodeError::truncated)
+}
+
+fn decode_body(header: u8, bytes: &[u8]) -> Result<Vec<u8>, DecodeError> {
+    bytes
+        .get(1..=usize::from(header))
+        .map(<[u8]>::to_vec)
+        .ok_or_else(DecodeError::truncated)
+}
+
+fn decode(stages: &mut TraceBuilder, bytes: &[u8]) -> Result<Vec<u8>, DecodeError> {
+    stages.stage("decoding header");
+    let header = decode_header(bytes).map_err(|e| e.map_trace(|trace| stages.fold(trace)))?;
+    stages.pop();
+
+    stages.scoped("decoding body", |stages| {
+        decode_body(header, bytes).map_err(|e| e.map_trace(|trace| stages.fold(trace)))
+    })
+}
+
+#[test]
+fn folds_the_current_stages_into_the_trace() {
+    let mut stages = TraceBuilder::new();
+    stages.stage("decoding packet");
+
+    let err = decode(&mut stages, &[]).unwrap_err();
+    assert_eq!(
+        format!("{:#}", err),
+        "decoding packet: decoding header: input is truncated"
+    );
+
+    let mut stages = TraceBuilder::new();
+    stages.stage("decoding packet");
+
+    let err = decode(&mut stages, &[4, 1, 2]).unwrap_err();
+    assert_eq!(
+        format!("{:#}", err),
+        "decoding packet: decoding body: input is truncated"
+    );
+}
+
+#[test]
+fn pops_the_stages_of_the_successful_steps() {
+    let mut stages = TraceBuilder::new();
+    assert!(stages.is_empty());
+
+    assert_eq!(decode(&mut stages, &[2, 1, 2]).unwrap(), [1, 2]);
+    assert!(stages.is_empty());
+
+    stages.stage("decoding packet").stage(format!("decoding field {}", 3));
+    assert_eq!(
+        stages.stages().collect::<Vec<_>>(),
+        ["decoding packet", "decoding field 3"]
+    );
+    assert_eq!(stages.pop().as_deref(), Some("decoding field 3"));
+    assert_eq!(stages.stages().count(), 1);
+}
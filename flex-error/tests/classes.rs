@@ -0,0 +1,31 @@
+use core::time::Duration;
+
+use flex_error::{define_error, ErrorClass};
+
+define_error! {
+    RelayError {
+        Timeout
+            { duration: Duration }
+            @transient
+            | e | { format_args!("timed out after {:?}", e.duration) },
+        Rejected
+            { height: u64, message: String }
+            @fatal
+            | e | { format_args!("packet at height {} rejected: {}", e.height, e.message) },
+        Aborted
+            | _ | { "relaying aborted" },
+    }
+}
+
+#[test]
+fn classifies_the_sub_errors() {
+    let err = RelayError::timeout(Duration::from_secs(1));
+    assert_eq!(err.class(), ErrorClass::Transient);
+    assert!(err.is_transient() && !err.is_fatal());
+
+    let err = RelayError::rejected(10, "invalid proof".into());
+    assert_eq!(err.detail().class(), ErrorClass::Fatal);
+    assert!(err.is_fatal() && !err.is_transient());
+
+    assert_eq!(RelayError::aborted().class(), ErrorClass::Unclassified);
+}
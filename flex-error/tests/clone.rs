@@ -0,0 +1,22 @@
+#![cfg(feature = "alloc")]
+
+use flex_error::define_error;
+
+define_error! {
+    @derive_main(Clone)
+    #[derive(Debug, Clone)]
+    CartError {
+        NotFound
+            { id: u64 }
+            | e | { format_args!("item {} not found", e.id) },
+    }
+}
+
+#[test]
+fn clones_the_error_with_a_new_trace() {
+    let err = CartError::not_found(42).add_trace(&"while loading the cart");
+    let cloned = err.clone();
+    assert!(matches!(cloned.detail(), CartErrorDetail::NotFound(e) if e.id == 42));
+    assert_eq!(format!("{:#}", cloned), format!("{:#}", err));
+    assert_eq!(format!("{:#}", cloned), "while loading the cart: item 42 not found");
+}
@@ -0,0 +1,80 @@
+#![cfg(feature = "code_registry")]
+
+use flex_error::registry::{code_collisions, registered_codes};
+
+const CODES: &str = r#"
+[NetError.Timeout]
+code = "NET-0001"
+
+[NetError.Refused]
+code = "NET-0002"
+
+[StoreError.Timeout]
+code = "NET-0001"
+
+[StoreError.Full]
+code = "STO-0001"
+"#;
+
+mod net {
+    flex_error::define_error! {
+        @codes(super::CODES)
+        NetError {
+            Timeout
+                | _ | { "timed out" },
+            Refused
+                | _ | { "connection refused" },
+        }
+    }
+}
+
+mod store {
+    flex_error::define_error! {
+        @codes(super::CODES)
+        StoreError {
+            Timeout
+                | _ | { "timed out" },
+            Full
+                | _ | { "disk full" },
+        }
+    }
+}
+
+#[test]
+fn registers_the_codes_of_the_sub_errors() {
+    let codes = registered_codes()
+        .into_iter()
+        .map(|code| (code.code, code.error, code.variant))
+        .collect::<Vec<_>>();
+    assert_eq!(
+        codes,
+        [
+            ("NET-0001", "NetError", "Timeout"),
+            ("NET-0001", "StoreError", "Timeout"),
+            ("NET-0002", "NetError", "Refused"),
+            ("STO-0001", "StoreError", "Full"),
+        ]
+    );
+}
+
+#[test]
+fn detects_the_colliding_codes() {
+    let collisions = code_collisions();
+    assert_eq!(collisions.len(), 1);
+    assert_eq!(
+        collisions[0]
+            .iter()
+            .map(|code| (code.module, code.error, code.variant))
+            .collect::<Vec<_>>(),
+        [
+            ("code_registry::net", "NetError", "Timeout"),
+            ("code_registry::store", "StoreError", "Timeout")
+        ]
+    );
+}
+
+#[test]
+#[should_panic(expected = "error codes are not unique:\n  NET-0001:\n    code_registry::net::NetError.Timeout\n    code_registry::store::StoreError.Timeout")]
+fn panics_on_the_colliding_codes() {
+    flex_error::assert_unique_codes!();
+}
@@ -0,0 +1,68 @@
+use flex_error::codes::{self, ErrorCode, Severity};
+use flex_error::define_error;
+
+const CODES: &str = r#"
+# Error codes of the relayer
+
+[NetError.ConnectTimeout]
+code = "NET-0001"
+severity = "warning"
+
+[NetError.Refused]
+code = NET-0002
+
+[RelayError.Net]
+code = "RLY-0001"
+severity = "fatal"
+"#;
+
+define_error! {
+    @codes(CODES)
+    NetError {
+        ConnectTimeout
+            { timeout: u64 }
+            | e | { format_args!("timed out after {}ms", e.timeout) },
+        Refused
+            | _ | { "connection refused" },
+    }
+}
+
+define_error! {
+    @codes(CODES)
+    RelayError {
+        Net
+            [ NetError ]
+            | _ | { "network error" },
+    }
+}
+
+#[test]
+fn looks_up_the_codes_in_the_mapping_file() {
+    assert_eq!(
+        codes::lookup(CODES, "NetError", "ConnectTimeout"),
+        Some(ErrorCode {
+            code: "NET-0001",
+            severity: Severity::Warning
+        })
+    );
+    assert_eq!(
+        codes::lookup(CODES, "NetError", "Refused"),
+        Some(ErrorCode {
+            code: "NET-0002",
+            severity: Severity::Error
+        })
+    );
+    assert_eq!(codes::lookup(CODES, "NetError", "Closed"), None);
+    assert_eq!(codes::lookup(CODES, "Net", "Refused"), None);
+}
+
+#[test]
+fn returns_the_codes_of_the_sub_errors() {
+    let err = NetError::connect_timeout(100);
+    assert_eq!(err.detail().error_code().code, "NET-0001");
+    assert_eq!(err.detail().error_code().severity, Severity::Warning);
+
+    let err = RelayError::net(NetError::refused());
+    assert_eq!(err.detail().error_code().to_string(), "RLY-0001");
+    assert_eq!(err.detail().error_code().severity.to_string(), "fatal");
+}
@@ -0,0 +1,45 @@
+#![cfg(all(feature = "compat-0-4", feature = "eyre_tracer"))]
+
+use flex_error::tracer_impl::string::StringTracer;
+use flex_error::{define_compat_source, define_error};
+
+#[derive(Debug)]
+pub struct OldErrorDetail;
+
+pub struct OldError(pub OldErrorDetail, pub eyre::Report);
+
+define_compat_source! {
+    pub OldSource(OldError, OldErrorDetail, eyre::Report)
+}
+
+pub struct OldStringError(pub OldErrorDetail, pub String);
+
+define_compat_source! {
+    pub OldStringSource(OldStringError, OldErrorDetail, String) @display
+}
+
+define_error! {
+    NewError: tracer = StringTracer {
+        Old
+            [ OldSource ]
+            | _ | { "from old" },
+        OldString
+            [ OldStringSource ]
+            | _ | { "from old string" },
+    }
+}
+
+#[test]
+fn keeps_the_chain_of_the_old_trace() {
+    let report = eyre::eyre!("root cause").wrap_err("middle").wrap_err("old outer");
+    let err = NewError::old(OldError(OldErrorDetail, report));
+
+    assert_eq!(err.trace().0, "from old: old outer: middle: root cause");
+}
+
+#[test]
+fn traces_display_only_old_traces() {
+    let err = NewError::old_string(OldStringError(OldErrorDetail, "old: cause".into()));
+
+    assert_eq!(err.trace().0, "from old string: old: cause");
+}
@@ -0,0 +1,21 @@
+use flex_error::define_error;
+
+define_error! {
+    @detail_only
+    StateError {
+        Uninitialized
+            @const
+            | _ | { "state not initialized" },
+        Corrupted
+            { height: u64 }
+            | e | { format_args!("state corrupted at height {}", e.height) },
+    }
+}
+
+const UNINITIALIZED: StateError = StateError::uninitialized();
+
+#[test]
+fn constructs_the_errors_in_constants() {
+    assert!(matches!(UNINITIALIZED, StateErrorDetail::Uninitialized(_)));
+    assert_eq!(UNINITIALIZED.to_string(), "state not initialized");
+}
@@ -0,0 +1,45 @@
+use std::io;
+
+use flex_error::{define_error, TraceError};
+
+define_error! {
+    NetError {
+        ConnectTimeout
+            { timeout: u64 }
+            | e | { format_args!("timed out after {}ms", e.timeout) },
+        NotInitialized
+            | _ | { "not initialized" },
+        Io
+            [ TraceError<io::Error> ]
+            | _ | { "I/O error" },
+    }
+}
+
+struct Recorded(Vec<&'static str>);
+
+impl NetErrorCtor for Recorded {
+    fn connect_timeout(_timeout: u64) -> Self {
+        Recorded(vec!["connect_timeout"])
+    }
+
+    fn not_initialized() -> Self {
+        Recorded(vec!["not_initialized"])
+    }
+
+    fn io(_source: io::Error) -> Self {
+        Recorded(vec!["io"])
+    }
+}
+
+fn connect<E: NetErrorCtor>(timeout: u64) -> Result<(), E> {
+    Err(E::connect_timeout(timeout))
+}
+
+#[test]
+fn mocks_the_constructor_trait() {
+    assert_eq!(connect::<Recorded>(5).unwrap_err().0, ["connect_timeout"]);
+    assert!(matches!(
+        connect::<NetError>(5).unwrap_err().detail(),
+        NetErrorDetail::ConnectTimeout(_)
+    ));
+}
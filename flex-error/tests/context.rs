@@ -0,0 +1,56 @@
+#![cfg(feature = "task_context")]
+
+use flex_error::context::{capture_keys, current, scope, sync_scope};
+use flex_error::define_error;
+
+define_error! {
+    RelayError {
+        Timeout
+            | _ | { "timed out" },
+    }
+}
+
+async fn relay_packets() -> Result<(), RelayError> {
+    Err(RelayError::timeout())
+}
+
+fn block_on<F: std::future::Future>(future: F) -> F::Output {
+    tokio::runtime::Builder::new_current_thread()
+        .build()
+        .unwrap()
+        .block_on(future)
+}
+
+// The captured keys are set process-wide, so the task context is only
+// checked in one test.
+#[test]
+fn attaches_the_task_context_to_the_errors() {
+    assert_eq!(current(), None);
+    assert_eq!(format!("{:#}", RelayError::timeout()), "timed out");
+
+    let err = block_on(scope(
+        [("chain_id", "ibc-0".to_string()), ("connection_id", "connection-1".to_string())],
+        async {
+            scope([("chain_id", "ibc-1".to_string())], relay_packets()).await
+        },
+    ))
+    .unwrap_err();
+    assert_eq!(
+        format!("{:#}", err),
+        "[connection_id=connection-1, chain_id=ibc-1]: timed out"
+    );
+
+    let context = sync_scope([("chain_id", "ibc-0".to_string())], current).unwrap();
+    assert_eq!(context.get("chain_id"), Some("ibc-0"));
+    assert_eq!(context.iter().collect::<Vec<_>>(), [("chain_id", "ibc-0")]);
+
+    capture_keys(&["connection_id"]);
+    let err = sync_scope(
+        [("chain_id", "ibc-0".to_string()), ("connection_id", "connection-1".to_string())],
+        RelayError::timeout,
+    );
+    assert_eq!(format!("{:#}", err), "[connection_id=connection-1]: timed out");
+
+    let err = sync_scope([("chain_id", "ibc-0".to_string())], RelayError::timeout);
+    assert_eq!(format!("{:#}", err), "timed out");
+}
@@ -0,0 +1,21 @@
+use std::borrow::Cow;
+
+use flex_error::define_error;
+
+define_error! {
+    NetError {
+        Rejected
+            { reason: @into Cow<'static, str> }
+            | e | { format_args!("request rejected: {}", e.reason) },
+    }
+}
+
+#[test]
+fn converts_the_into_fields() {
+    let err = NetError::rejected("quota exceeded");
+    assert!(matches!(err.detail(), NetErrorDetail::Rejected(e) if matches!(e.reason, Cow::Borrowed(_))));
+    assert_eq!(err.detail().to_string(), "request rejected: quota exceeded");
+
+    let err = NetError::rejected(format!("quota of {} exceeded", 10));
+    assert_eq!(err.detail().to_string(), "request rejected: quota of 10 exceeded");
+}
@@ -0,0 +1,20 @@
+#![cfg(feature = "alloc")]
+
+use flex_error::tracer_impl::string::StringTracer;
+use flex_error::{define_error, DisplayError};
+
+define_error! {
+    StringError: tracer = StringTracer {
+        Read
+            { path: String }
+            [ DisplayError<String> ]
+            | e | { format_args!("failed to read {}", e.path) },
+    }
+}
+
+#[test]
+fn uses_the_custom_tracer() {
+    let err = StringError::read("config.toml".into(), "not found".into());
+    let trace: &StringTracer = err.trace();
+    assert_eq!(trace.0, "failed to read config.toml: not found");
+}
@@ -0,0 +1,44 @@
+#![cfg(feature = "alloc")]
+
+use flex_error::{define_error, DisplayError};
+
+define_error! {
+    @debug(full)
+    RelayError {
+        Io
+            [ DisplayError<String> ]
+            | _ | { "I/O error" },
+    }
+}
+
+define_error! {
+    @debug(detail)
+    DetailError {
+        Timeout
+            { seconds: u64 }
+            | e | { format_args!("timed out after {} seconds", e.seconds) },
+    }
+}
+
+define_error! {
+    CartError {
+        NotFound
+            { id: u64 }
+            | e | { format_args!("item {} not found", e.id) },
+    }
+}
+
+#[test]
+fn selects_the_debug_output() {
+    let debug = format!("{:?}", RelayError::io("disk full".into()));
+    assert!(debug.starts_with("RelayError { detail: Io(IoSubdetail { source: \"disk full\" }), trace: "));
+
+    assert_eq!(
+        format!("{:?}", DetailError::timeout(3)),
+        "DetailError(Timeout(TimeoutSubdetail { seconds: 3 }))"
+    );
+
+    let debug = format!("{:?}", CartError::not_found(42));
+    assert!(!debug.contains("NotFoundSubdetail"));
+    assert!(debug.contains("item 42 not found"));
+}
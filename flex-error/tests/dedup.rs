@@ -0,0 +1,30 @@
+#![cfg(any(feature = "alloc", feature = "defmt_tracer"))]
+
+use flex_error::define_error;
+
+define_error! {
+    CartError {
+        Internal
+            | _ | { "internal error" },
+    }
+}
+
+#[test]
+fn skips_the_repeated_messages() {
+    let err = CartError::internal()
+        .add_trace(&"retrying")
+        .add_trace(&"retrying")
+        .add_trace(&"retrying");
+    assert_eq!(format!("{:#}", err), "retrying: internal error");
+}
+
+#[test]
+fn keeps_the_messages_only_starting_with_the_outermost_message() {
+    let err = CartError::internal()
+        .add_trace(&"retrying the request")
+        .add_trace(&"retrying");
+    assert_eq!(
+        format!("{:#}", err),
+        "retrying: retrying the request: internal error"
+    );
+}
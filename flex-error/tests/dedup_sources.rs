@@ -0,0 +1,38 @@
+#![cfg(any(feature = "eyre_tracer_minimal", feature = "anyhow_tracer"))]
+
+use flex_error::render::Rendered;
+use flex_error::{define_error, DisplayError, Theme};
+
+define_error! {
+    ConfigError {
+        Read
+            [ DisplayError<String> ]
+            | e | { format_args!("failed to read config: {}", e.source) },
+        Status
+            { status: u16 }
+            [ DisplayError<String> ]
+            | e | { format_args!("request failed with status {}", e.status) },
+    }
+}
+
+#[test]
+fn skips_the_sources_printed_by_the_previous_message() {
+    let err = ConfigError::read("disk full".into());
+    assert_eq!(
+        Rendered::new(err.trace(), Theme::Plain)
+            .dedup_sources()
+            .to_string(),
+        "   0: failed to read config: disk full"
+    );
+}
+
+#[test]
+fn keeps_the_sources_only_contained_in_the_previous_message() {
+    let err = ConfigError::status(404, "404".into());
+    assert_eq!(
+        Rendered::new(err.trace(), Theme::Plain)
+            .dedup_sources()
+            .to_string(),
+        "   0: request failed with status 404\n   1: 404"
+    );
+}
@@ -0,0 +1,48 @@
+use flex_error::{define_error, DefaultTracer};
+
+define_error! {
+    TimeoutError {
+        Timeout
+            | _ | { "timed out" },
+    }
+}
+
+define_error! {
+    NetError {
+        Connect
+            [ TimeoutError ]
+            | _ | { "failed to connect" },
+    }
+}
+
+#[test]
+fn uses_the_default_tracer() {
+    let err = NetError::connect(TimeoutError::timeout());
+    let _: &DefaultTracer = err.trace();
+}
+
+#[cfg(all(
+    feature = "alloc",
+    not(feature = "eyre_tracer_minimal"),
+    not(feature = "anyhow_tracer"),
+    not(feature = "backtrace_tracer"),
+    not(feature = "tracing_tracer")
+))]
+#[test]
+fn uses_the_string_tracer_with_the_alloc_feature() {
+    use flex_error::tracer_impl::string::StringTracer;
+
+    let err = NetError::connect(TimeoutError::timeout());
+    let trace: &StringTracer = err.trace();
+    assert_eq!(trace.0, "failed to connect: timed out");
+}
+
+#[cfg(all(feature = "defmt_tracer", not(feature = "alloc")))]
+#[test]
+fn uses_the_defmt_tracer_without_the_alloc_feature() {
+    use flex_error::tracer_impl::defmt::DefmtTracer;
+
+    let err = NetError::connect(TimeoutError::timeout());
+    let trace: &DefmtTracer = err.trace();
+    assert_eq!(trace.as_str(), "failed to connect: timed out");
+}
@@ -0,0 +1,104 @@
+#![cfg(all(feature = "derive", feature = "alloc"))]
+
+use std::io;
+
+use flex_error::tracer_impl::string::StringTracer;
+use flex_error::{DisplayError, FlexError, TraceError};
+
+#[derive(FlexError)]
+#[flex_error(name = FooError)]
+pub enum FooErrorDef {
+    /// The file could not be read.
+    #[error("failed to read {path}")]
+    Read {
+        path: String,
+        #[source(TraceError<io::Error>)]
+        source: io::Error,
+    },
+
+    #[error("invalid size {}, expected at most {}", size, max)]
+    InvalidSize { size: u64, max: u64 },
+
+    #[error("failed to parse {input}: {source}")]
+    Parse {
+        input: String,
+        #[source(DisplayError<String>)]
+        source: String,
+    },
+
+    #[error("failed to copy: {source1}, {source2}")]
+    Copy {
+        #[source(DisplayError<String>)]
+        from: String,
+        #[source(DisplayError<String>)]
+        to: String,
+    },
+
+    #[error("nested foo error")]
+    Nested {
+        #[source(Self)]
+        source: FooError,
+    },
+}
+
+#[derive(FlexError)]
+#[flex_error(name = BarError, tracer = StringTracer)]
+pub enum BarErrorDef {
+    #[error("bar error with code {code}")]
+    Bar { code: u32 },
+}
+
+#[test]
+fn defines_the_error_type_from_the_enum() {
+    let err = FooError::read("/etc/foo".into(), io::ErrorKind::NotFound.into());
+    assert!(err.detail().is_read());
+    assert_eq!(format!("{:#}", err), "failed to read /etc/foo: entity not found");
+
+    let err = FooError::invalid_size(100, 64);
+    assert_eq!(
+        err.detail().to_string(),
+        "invalid size 100, expected at most 64"
+    );
+
+    let err = FooError::nested(FooError::invalid_size(100, 64));
+    assert_eq!(
+        format!("{:#}", err),
+        "nested foo error: invalid size 100, expected at most 64"
+    );
+}
+
+#[test]
+fn refers_to_the_source_details_in_the_message() {
+    let err = FooError::parse("x".into(), "invalid digit".into());
+    assert_eq!(err.detail().to_string(), "failed to parse x: invalid digit");
+
+    let err = FooError::copy("disk full".into(), "permission denied".into());
+    assert_eq!(
+        err.detail().to_string(),
+        "failed to copy: disk full, permission denied"
+    );
+}
+
+#[test]
+fn converts_the_enum_into_the_error_type() {
+    fn check_size(size: u64) -> Result<(), FooError> {
+        if size > 64 {
+            return Err(FooErrorDef::InvalidSize { size, max: 64 }.into());
+        }
+        Ok(())
+    }
+
+    assert!(check_size(10).is_ok());
+    let err = check_size(100).unwrap_err();
+    assert!(matches!(
+        err.detail(),
+        FooErrorDetail::InvalidSize(InvalidSizeSubdetail { size: 100, max: 64 })
+    ));
+}
+
+#[test]
+fn uses_the_given_tracer() {
+    let err = BarError::bar(3);
+    let trace: &StringTracer = err.trace();
+    assert_eq!(trace.0, "bar error with code 3");
+}
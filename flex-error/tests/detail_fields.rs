@@ -0,0 +1,29 @@
+#![cfg(feature = "alloc")]
+
+use std::time::Duration;
+
+use flex_error::{define_error, DisplayError};
+
+define_error! {
+    RelayError {
+        Unreachable
+            { endpoint: String, elapsed: Duration }
+            | e | { format_args!("{} unreachable after {:?}", e.endpoint, e.elapsed) },
+        Io
+            [ DisplayError<String> ]
+            | _ | { "I/O error" },
+    }
+}
+
+#[test]
+fn lists_the_detail_fields() {
+    let err = RelayError::unreachable("10.0.0.1".into(), Duration::from_secs(1));
+    assert_eq!(
+        err.detail_fields().collect::<Vec<_>>(),
+        [
+            ("endpoint", "10.0.0.1".to_string()),
+            ("elapsed", "1s".to_string())
+        ]
+    );
+    assert_eq!(RelayError::io("disk full".into()).detail_fields().count(), 0);
+}
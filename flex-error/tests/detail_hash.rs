@@ -0,0 +1,36 @@
+use std::collections::hash_map::DefaultHasher;
+use std::hash::Hasher;
+use std::time::Duration;
+
+use flex_error::{define_error, DisplayError};
+
+define_error! {
+    RelayError {
+        Unreachable
+            {
+                #[detail(hash)]
+                endpoint: String,
+                elapsed: Duration,
+            }
+            | e | { format_args!("{} unreachable after {:?}", e.endpoint, e.elapsed) },
+        Io
+            [ DisplayError<String> ]
+            | _ | { "I/O error" },
+    }
+}
+
+fn key(err: &RelayError) -> u64 {
+    let mut hasher = DefaultHasher::new();
+    err.detail_hash(&mut hasher);
+    hasher.finish()
+}
+
+#[test]
+fn hashes_the_marked_fields() {
+    let a = RelayError::unreachable("10.0.0.1".into(), Duration::from_secs(1));
+    let b = RelayError::unreachable("10.0.0.1".into(), Duration::from_secs(2));
+    let c = RelayError::unreachable("10.0.0.2".into(), Duration::from_secs(1));
+    assert_eq!(key(&a), key(&b));
+    assert_ne!(key(&a), key(&c));
+    assert_ne!(key(&a), key(&RelayError::io("disk full".into())));
+}
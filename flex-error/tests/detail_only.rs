@@ -0,0 +1,49 @@
+use flex_error::define_error;
+
+define_error! {
+    @detail_only
+    StateError {
+        Uninitialized
+            | _ | { "state not initialized" },
+        Corrupted
+            { height: u64 }
+            | e | { format_args!("state corrupted at height {}", e.height) },
+    }
+}
+
+define_error! {
+    NodeError {
+        State
+            [ StateError ]
+            | _ | { "invalid node state" },
+    }
+}
+
+#[test]
+fn constructs_the_detail_only_errors() {
+    let err: StateError = StateError::corrupted(10);
+    assert!(matches!(err, StateErrorDetail::Corrupted(CorruptedSubdetail { height: 10 })));
+    assert_eq!(err.to_string(), "state corrupted at height 10");
+}
+
+#[test]
+fn wraps_the_detail_only_errors() {
+    let err = NodeError::state(StateError::corrupted(10));
+    assert!(matches!(
+        err.detail(),
+        NodeErrorDetail::State(StateSubdetail {
+            source: StateErrorDetail::Corrupted(CorruptedSubdetail { height: 10 })
+        })
+    ));
+    assert_eq!(err.detail().to_string(), "invalid node state");
+}
+
+#[cfg(feature = "alloc")]
+#[test]
+fn traces_the_detail_only_sources() {
+    let err = NodeError::state(StateError::uninitialized());
+    assert_eq!(
+        format!("{:#}", err),
+        "invalid node state: state not initialized"
+    );
+}
@@ -0,0 +1,39 @@
+#![cfg(feature = "std")]
+
+use std::error::Error;
+use std::fmt;
+use std::io;
+
+use flex_error::{define_error, DisplayChain};
+
+define_error! {
+    StoreError {
+        Chained
+            [ DisplayChain<ChainedError> ]
+            | _ | { "chained error" },
+    }
+}
+
+#[derive(Debug)]
+pub struct ChainedError(io::Error);
+
+impl fmt::Display for ChainedError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "outer")
+    }
+}
+
+impl Error for ChainedError {
+    fn source(&self) -> Option<&(dyn Error + 'static)> {
+        Some(&self.0)
+    }
+}
+
+#[test]
+fn traces_the_chain_of_sources() {
+    let err = StoreError::chained(ChainedError(io::Error::other("disk full")));
+    assert_eq!(format!("{:#}", err), "chained error: outer: disk full");
+    match err.detail() {
+        StoreErrorDetail::Chained(e) => assert_eq!(e.source.0.to_string(), "disk full"),
+    }
+}
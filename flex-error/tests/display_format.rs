@@ -0,0 +1,18 @@
+use flex_error::define_error;
+
+define_error! {
+    @display(detail)
+    DetailError {
+        Timeout
+            { seconds: u64 }
+            | e | { format_args!("timed out after {} seconds", e.seconds) },
+    }
+}
+
+#[test]
+fn pads_and_truncates_the_displayed_message() {
+    let err = DetailError::timeout(3);
+    assert_eq!(format!("{:>30}", err), "     timed out after 3 seconds");
+    assert_eq!(format!("{:.9}", err), "timed out");
+    assert_eq!(format!("{:-<12.9}", err.detail()), "timed out---");
+}
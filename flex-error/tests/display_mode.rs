@@ -0,0 +1,31 @@
+#![cfg(feature = "alloc")]
+
+use flex_error::{define_error, DisplayError};
+
+define_error! {
+    @display(chain)
+    RelayError {
+        Io
+            [ DisplayError<String> ]
+            | _ | { "I/O error" },
+    }
+}
+
+define_error! {
+    @display(detail)
+    DetailError {
+        Relay
+            [ RelayError ]
+            | _ | { "failed to relay" },
+    }
+}
+
+#[test]
+fn selects_the_display_output() {
+    let err = RelayError::io("disk full".into());
+    assert_eq!(err.to_string(), "I/O error: disk full");
+
+    let err = DetailError::relay(err);
+    assert_eq!(err.to_string(), "failed to relay");
+    assert_eq!(format!("{:#}", err), "failed to relay: I/O error: disk full");
+}
@@ -0,0 +1,46 @@
+//! The error types in this file generate their own tests with the `@tests`
+//! option, which are run together with the tests written by hand.
+
+#![cfg(feature = "alloc")]
+
+use std::num::ParseIntError;
+
+use flex_error::{define_error, DisplayError};
+
+define_error! {
+    @tests(display)
+    ConnectError {
+        Connect
+            { address: String, attempts: Vec<u32> }
+            | e | {
+                format_args!("failed to connect to {} after {} attempts",
+                    e.address, e.attempts.len())
+            },
+        Parse
+            { input: String }
+            [ DisplayError<ParseIntError> ]
+            @fixture(ParseSubdetail {
+                input: "x".into(),
+                source: "x".parse::<u32>().unwrap_err(),
+            })
+            | e | { format_args!("failed to parse {}", e.input) },
+        Retry
+            [ Self ]
+            @fixture(RetrySubdetail {
+                source: Box::new(ConnectErrorDetail::Connect(ConnectSubdetail {
+                    address: "10.0.0.1".into(),
+                    attempts: vec![1, 2],
+                })),
+            })
+            | _ | { "retry failed" },
+    }
+}
+
+#[test]
+fn defines_the_display_tests() {
+    let err = ConnectError::retry(ConnectError::connect("10.0.0.1".into(), vec![1, 2]));
+    assert_eq!(
+        format!("{:#}", err),
+        "retry failed: failed to connect to 10.0.0.1 after 2 attempts"
+    );
+}
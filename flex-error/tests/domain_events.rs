@@ -0,0 +1,57 @@
+use core::time::Duration;
+use std::io;
+
+use flex_error::{define_error, TraceError};
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum RelayEvent {
+    Timeout(Duration),
+    Rejected { height: u64, reason: String },
+    Aborted,
+}
+
+define_error! {
+    @events(RelayEvent)
+    RelayError {
+        Timeout
+            { duration: Duration }
+            @event( RelayEvent::Timeout(duration) )
+            | e | { format_args!("timed out after {:?}", e.duration) },
+        Rejected
+            { height: u64, message: String }
+            @event( RelayEvent::Rejected { height, reason: message } )
+            | e | { format_args!("packet at height {} rejected: {}", e.height, e.message) },
+        Aborted
+            @event( RelayEvent::Aborted )
+            | _ | { "relaying aborted" },
+        Io
+            [ TraceError<io::Error> ]
+            | _ | { "I/O error" },
+    }
+}
+
+#[test]
+fn maps_the_sub_errors_to_domain_events() {
+    let err = RelayError::timeout(Duration::from_secs(1));
+    assert_eq!(
+        err.to_domain_event(),
+        Some(RelayEvent::Timeout(Duration::from_secs(1)))
+    );
+
+    let err = RelayError::rejected(10, "invalid proof".into());
+    assert_eq!(
+        err.detail().to_domain_event(),
+        Some(RelayEvent::Rejected {
+            height: 10,
+            reason: "invalid proof".into()
+        })
+    );
+
+    assert_eq!(
+        RelayError::aborted().to_domain_event(),
+        Some(RelayEvent::Aborted)
+    );
+
+    let err = RelayError::io(io::ErrorKind::Other.into());
+    assert_eq!(err.to_domain_event(), None);
+}
@@ -0,0 +1,25 @@
+#![cfg(feature = "std")]
+
+use std::io;
+
+use flex_error::{define_error, DynError};
+
+define_error! {
+    StoreError {
+        Plugin
+            [ DynError ]
+            | _ | { "plugin failed" },
+    }
+}
+
+#[test]
+fn keeps_the_boxed_errors_as_detail() {
+    let err = StoreError::plugin(Box::new(io::Error::other("disk full")));
+    assert_eq!(format!("{:#}", err), "plugin failed: disk full");
+    match err.detail() {
+        StoreErrorDetail::Plugin(e) => {
+            let io = e.source.downcast_ref::<io::Error>().unwrap();
+            assert_eq!(io.kind(), io::ErrorKind::Other);
+        }
+    }
+}
@@ -0,0 +1,38 @@
+use flex_error::define_error;
+
+define_error! {
+    ConfigError {
+        Missing
+            { key: String }
+            @exit_code(78)
+            | e | { format_args!("missing configuration key {}", e.key) },
+    }
+}
+
+define_error! {
+    RelayError {
+        Rejected
+            { height: u64 }
+            @exit_code(65)
+            | e | { format_args!("packet at height {} rejected", e.height) },
+        Aborted
+            | _ | { "relaying aborted" },
+        Config
+            [ ConfigError ]
+            | _ | { "invalid configuration" },
+    }
+}
+
+#[test]
+fn returns_the_exit_code_of_the_outermost_error_that_has_one() {
+    let err = RelayError::rejected(10);
+    assert_eq!(err.exit_code(), 65);
+
+    let err = RelayError::config(ConfigError::missing("rpc_addr".into()));
+    assert_eq!(err.exit_code(), 78);
+    assert_eq!(err.detail().exit_code(), Some(78));
+
+    let err = RelayError::aborted();
+    assert_eq!(err.exit_code(), 1);
+    assert_eq!(err.detail().exit_code(), None);
+}
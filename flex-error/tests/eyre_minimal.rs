@@ -0,0 +1,25 @@
+#![cfg(all(feature = "eyre_tracer_minimal", not(feature = "eyre_tracer")))]
+
+use flex_error::define_error;
+use flex_error::tracer_impl::eyre::MinimalHandler;
+
+define_error! {
+    AppError {
+        Read
+            { path: String }
+            | e | { format_args!("failed to read {}", e.path) },
+        Nested
+            [ Self ]
+            | _ | { "nested" },
+    }
+}
+
+#[test]
+fn installs_the_minimal_handler_on_first_use() {
+    let err = AppError::nested(AppError::read("config.toml".to_string()));
+
+    assert!(err.trace().handler().downcast_ref::<MinimalHandler>().is_some());
+
+    let messages: Vec<String> = err.trace().chain().map(|e| e.to_string()).collect();
+    assert_eq!(messages, ["nested", "failed to read config.toml"]);
+}
@@ -0,0 +1,24 @@
+use flex_error::define_error;
+
+define_error! {
+    @free_fns(error)
+    NetError {
+        ConnectTimeout as ReadTimeout
+            { timeout: u64 }
+            | e | { format_args!("timed out after {}ms", e.timeout) },
+        Refused
+            | _ | { "connection refused" },
+    }
+}
+
+#[test]
+fn defines_the_free_functions() {
+    let err: NetError = connect_timeout_error(5);
+    assert!(matches!(err.detail(), NetErrorDetail::ConnectTimeout(_)));
+
+    let err: NetError = read_timeout_error(5);
+    assert!(matches!(err.detail(), NetErrorDetail::ReadTimeout(_)));
+
+    let err: NetError = refused_error();
+    assert_eq!(err.detail().to_string(), "connection refused");
+}
@@ -0,0 +1,29 @@
+use std::io;
+
+use flex_error::{define_error, TraceError};
+
+define_error! {
+    StoreError {
+        Io
+            [ TraceError<io::Error> ]
+            @from
+            | _ | { "I/O error" },
+    }
+}
+
+fn read(path: &str) -> Result<String, StoreError> {
+    Ok(std::fs::read_to_string(path)?)
+}
+
+#[test]
+fn converts_the_source_with_the_question_mark_operator() {
+    let err = read("/nonexistent/config.toml").unwrap_err();
+    assert!(matches!(err.detail(), StoreErrorDetail::Io(_)));
+}
+
+#[cfg(feature = "alloc")]
+#[test]
+fn traces_the_converted_source() {
+    let err: StoreError = io::Error::other("disk full").into();
+    assert_eq!(format!("{:#}", err), "I/O error: disk full");
+}
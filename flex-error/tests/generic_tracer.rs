@@ -0,0 +1,36 @@
+#![cfg(feature = "alloc")]
+
+use flex_error::tracer_impl::string::StringTracer;
+use flex_error::{define_error, DefaultTracer, ErrorMessageTracer};
+
+define_error! {
+    @generic_tracer(Tracer)
+    QuuxError {
+        Timeout
+            | _ | { "timed out" },
+    }
+}
+
+define_error! {
+    @generic_tracer(Tracer)
+    FooError {
+        Bar
+            { code: u32 }
+            [ QuuxError ]
+            | e | { format_args!("bar error with code {}", e.code) },
+    }
+}
+
+fn bar<Tracer: ErrorMessageTracer>(source: QuuxError<Tracer>) -> Result<(), FooError<Tracer>> {
+    Err(FooError::bar(3, source))
+}
+
+#[test]
+fn chooses_the_generic_tracer_from_the_context() {
+    let err = bar::<StringTracer>(QuuxError::timeout()).unwrap_err();
+    assert_eq!(err.trace().0, "bar error with code 3: timed out");
+
+    let err: FooError = bar(QuuxError::timeout()).unwrap_err();
+    let _: &DefaultTracer = err.trace();
+    assert_eq!(format!("{:#}", err), "bar error with code 3: timed out");
+}
@@ -0,0 +1,48 @@
+#![cfg(feature = "error_graph")]
+
+use flex_error::define_error;
+use flex_error::graph::{error_graph, error_graph_dot, ErrorEdge};
+use flex_error::DisplayError;
+
+define_error! {
+    StoreError {
+        Io
+            [ DisplayError<String> ]
+            | _ | { "I/O error" },
+    }
+}
+
+define_error! {
+    RelayError {
+        Store
+            [ StoreError ]
+            | _ | { "store error" },
+        Uncalled
+            [ StoreError ]
+            | _ | { "never constructed" },
+    }
+}
+
+#[test]
+fn records_the_wrapping_constructors() {
+    let _ = RelayError::store(StoreError::io("disk full".into()));
+    let _ = RelayError::store(StoreError::io("disk full".into()));
+
+    let graph = error_graph();
+    assert!(graph.contains(&ErrorEdge {
+        error: "graph::RelayError",
+        constructor: "store",
+        source: "graph::StoreError",
+    }));
+    assert!(graph.contains(&ErrorEdge {
+        error: "graph::StoreError",
+        constructor: "io",
+        source: "flex_error::source::DisplayError<alloc::string::String>",
+    }));
+    assert!(!graph.iter().any(|edge| edge.constructor == "uncalled"));
+
+    let dot = error_graph_dot();
+    assert!(dot.starts_with("digraph errors {\n"));
+    assert!(dot.contains("  \"graph::RelayError\" -> \"graph::StoreError\" [label=\"store\"];\n"));
+    assert!(dot.ends_with('}'));
+}
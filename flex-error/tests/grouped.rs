@@ -0,0 +1,55 @@
+#![cfg(feature = "alloc")]
+
+use core::fmt::Display;
+
+use flex_error::render::Rendered;
+use flex_error::{define_error, Theme, TraceRender};
+
+define_error! {
+    CartError {
+        Internal
+            | _ | { "internal error" },
+    }
+}
+
+struct Messages(&'static [&'static str]);
+
+impl TraceRender for Messages {
+    fn walk_messages(
+        &self,
+        f: &mut dyn FnMut(&dyn Display) -> core::fmt::Result,
+    ) -> core::fmt::Result {
+        self.0.iter().try_for_each(|message| f(message))
+    }
+}
+
+#[test]
+fn groups_the_consecutive_repeated_messages() {
+    let trace = Messages(&["retrying", "retrying", "giving up", "internal error"]);
+    assert_eq!(
+        Rendered::new(&trace, Theme::Plain).grouped().to_string(),
+        "   0: retrying (x2)\n   1: giving up\n   2: internal error"
+    );
+}
+
+#[test]
+fn keeps_the_messages_repeated_apart() {
+    let trace = Messages(&["retrying", "giving up", "retrying", "internal error"]);
+    assert_eq!(
+        Rendered::new(&trace, Theme::Plain).grouped().to_string(),
+        "   0: retrying\n   1: giving up\n   2: retrying\n   3: internal error"
+    );
+}
+
+#[cfg(any(feature = "eyre_tracer_minimal", feature = "anyhow_tracer"))]
+#[test]
+fn renders_the_error_with_the_repeated_messages_apart() {
+    let err = CartError::internal()
+        .add_trace(&"retrying")
+        .add_trace(&"giving up")
+        .add_trace(&"retrying");
+    assert!(err
+        .render_grouped()
+        .to_string()
+        .starts_with("   0: retrying\n   1: giving up\n   2: retrying\n   3: internal error"));
+}
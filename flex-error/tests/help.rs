@@ -0,0 +1,29 @@
+#![cfg(feature = "alloc")]
+
+use flex_error::{define_error, Theme};
+
+define_error! {
+    ConfigError {
+        Missing
+            { key: String }
+            @help("set the key in the configuration file")
+            | e | { format_args!("missing configuration key {}", e.key) },
+        Invalid
+            | _ | { "invalid configuration" },
+    }
+}
+
+#[test]
+fn appends_the_help_text_to_the_rendered_trace() {
+    let err = ConfigError::missing("rpc_addr".into());
+    assert_eq!(
+        err.detail().help(),
+        Some("set the key in the configuration file")
+    );
+
+    let rendered = err.render(Theme::Plain).to_string();
+    assert!(rendered.starts_with("   0: missing configuration key rpc_addr"));
+    assert!(rendered.contains("\n\nhelp: set the key in the configuration file"));
+
+    assert_eq!(ConfigError::invalid().detail().help(), None);
+}
@@ -0,0 +1,87 @@
+#![cfg(feature = "incidents")]
+
+use flex_error::tracer_impl::string::StringTracer;
+use flex_error::{define_error, lookup, Theme};
+
+define_error! {
+    @metadata
+    RelayError: tracer = StringTracer {
+        Refused
+            | _ | { "connection refused" },
+        Message
+            { message: String }
+            | e | { format_args!("{}", e.message) },
+        Relay
+            [ Self ]
+            | _ | { "failed to relay" },
+    }
+}
+
+#[test]
+fn keeps_the_incident_id_out_of_the_trace() {
+    let err = RelayError::relay(RelayError::refused()).add_trace(&"while starting");
+
+    assert_eq!(
+        err.trace().0,
+        "while starting: failed to relay: connection refused"
+    );
+    assert_eq!(err.trace_entries().count(), 1);
+}
+
+#[test]
+fn shares_the_incident_id_with_the_wrapped_error() {
+    let inner = RelayError::refused();
+    let id = inner.metadata().incident_id();
+    assert!(id.is_some());
+
+    let outer = RelayError::relay(inner);
+    assert_eq!(outer.metadata().incident_id(), id);
+}
+
+#[test]
+fn stores_the_snapshot_when_the_error_is_constructed() {
+    let inner = RelayError::refused();
+    let id = inner.incident_id().unwrap().to_string();
+    assert_eq!(
+        lookup(&id).unwrap().messages,
+        ["connection refused".to_string()]
+    );
+
+    let err = RelayError::relay(inner);
+    let incident = lookup(&id).unwrap();
+    assert_eq!(
+        incident.messages,
+        ["failed to relay: connection refused".to_string()]
+    );
+
+    let rendered = err.render(Theme::Plain).to_string();
+    assert!(rendered.ends_with(&format!("incident: {}", id)));
+    assert_eq!(lookup(&id), Some(incident));
+}
+
+#[test]
+fn assigns_incident_ids_without_the_metadata_option() {
+    define_error! {
+        PlainError: tracer = StringTracer {
+            Closed
+                | _ | { "connection closed" },
+        }
+    }
+
+    let err = PlainError::closed();
+    let id = err.incident_id().unwrap().to_string();
+    assert_eq!(
+        lookup(&id).unwrap().messages,
+        ["connection closed".to_string()]
+    );
+}
+
+#[test]
+fn does_not_parse_incident_ids_from_messages() {
+    let err = RelayError::message("incident ERR-000000".to_string());
+
+    assert_ne!(
+        err.incident_id().map(|id| id.to_string()).as_deref(),
+        Some("ERR-000000")
+    );
+}
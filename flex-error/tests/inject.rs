@@ -0,0 +1,65 @@
+#![cfg(feature = "error_injection")]
+
+use flex_error::{define_error, inject, inject_point};
+
+pub mod client {
+    use flex_error::define_error;
+
+    define_error! {
+        ClientError {
+            Timeout
+                { timeout: u64 }
+                | e | { format_args!("timed out after {}ms", e.timeout) },
+            Parse
+                { input: String }
+                | e | { format_args!("failed to parse {}", e.input) },
+        }
+    }
+}
+
+define_error! {
+    QueryError {
+        Timeout
+            | _ | { "query timed out" },
+        Unknown
+            | _ | { "unknown error" },
+    }
+}
+
+use client::{ClientError, ClientErrorDetail};
+
+#[test]
+fn constructors_raise_the_forced_detail() {
+    inject::force_next_in("other", ClientError::timeout(1).into_detail());
+    let err = ClientError::parse("input".to_string());
+    assert!(matches!(err.detail(), ClientErrorDetail::Parse(_)));
+
+    inject::force_next_in("inject::client", ClientError::timeout(5).into_detail());
+    let err = ClientError::parse("input".to_string());
+    assert!(matches!(err.detail(), ClientErrorDetail::Timeout(_)));
+    assert_eq!(err.detail().to_string(), "timed out after 5ms");
+
+    // The forced detail is only raised once.
+    let err = ClientError::parse("input".to_string());
+    assert!(matches!(err.detail(), ClientErrorDetail::Parse(_)));
+
+    inject::clear();
+}
+
+fn query() -> Result<(), QueryError> {
+    inject_point!(QueryErrorKind, |kind| match kind {
+        QueryErrorKind::Timeout => QueryError::timeout(),
+        _ => QueryError::unknown(),
+    })
+}
+
+#[test]
+fn injection_points_raise_the_forced_kind() {
+    assert!(query().is_ok());
+
+    inject::force_next(QueryErrorKind::Timeout);
+    let err = query().unwrap_err();
+    assert!(matches!(err.detail(), QueryErrorDetail::Timeout(_)));
+
+    assert!(query().is_ok());
+}
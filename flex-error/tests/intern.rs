@@ -0,0 +1,46 @@
+#![cfg(feature = "interning")]
+
+use flex_error::define_error;
+use flex_error::intern::{intern_stats, purge_interned, Interned};
+
+define_error! {
+    RelayerError {
+        ChainTimeout
+            { chain_id: Interned<str> }
+            | e | { format_args!("timed out waiting for chain {}", e.chain_id) },
+    }
+}
+
+#[test]
+fn shares_the_storage_of_equal_strings() {
+    let a = RelayerError::chain_timeout("ibc-0".into());
+    let b = RelayerError::chain_timeout(String::from("ibc-0").into());
+    let c = RelayerError::chain_timeout("ibc-1".into());
+
+    let chain_id = |err: &RelayerError| match err.detail() {
+        RelayerErrorDetail::ChainTimeout(e) => e.chain_id.clone(),
+    };
+    assert_eq!(chain_id(&a), chain_id(&b));
+    assert_eq!(chain_id(&a).as_ptr(), chain_id(&b).as_ptr());
+    assert_ne!(chain_id(&a), chain_id(&c));
+    assert_eq!(a.detail().to_string(), "timed out waiting for chain ibc-0");
+}
+
+// The interning table is global, so the statistics and purging are only
+// checked in one test.
+#[test]
+fn reports_the_statistics_and_purges_the_unused_values() {
+    let before = intern_stats();
+    let first = Interned::new("purged value");
+    let second = Interned::new("purged value");
+    let after = intern_stats();
+    assert!(after.hits > before.hits && after.misses > before.misses);
+    assert!(after.hit_rate() > 0.0);
+
+    drop((first, second));
+    assert!(purge_interned() >= 1);
+
+    let kept = Interned::new("kept value");
+    purge_interned();
+    assert_eq!(Interned::new("kept value").as_ptr(), kept.as_ptr());
+}
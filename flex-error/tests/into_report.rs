@@ -0,0 +1,68 @@
+#![cfg(any(feature = "eyre_tracer_minimal", feature = "anyhow_tracer"))]
+
+#[cfg(feature = "eyre_tracer_minimal")]
+mod to_eyre {
+    use flex_error::define_error;
+    use flex_error::tracer_impl::eyre::{EyreTracer, IntoReport};
+
+    define_error! {
+        AppError: tracer = EyreTracer {
+            Config
+                { path: String }
+                | e | { format_args!("invalid configuration {}", e.path) },
+        }
+    }
+
+    #[test]
+    fn converts_into_eyre() {
+        let report: EyreTracer = AppError::config("relayer.toml".into())
+            .add_trace(&"while starting")
+            .into_report();
+        assert_eq!(
+            report.chain().map(|e| e.to_string()).collect::<Vec<_>>(),
+            [
+                "invalid configuration relayer.toml",
+                "while starting",
+                "invalid configuration relayer.toml"
+            ]
+        );
+
+        let report: EyreTracer = AppError::with_new_trace(
+            AppError::config("relayer.toml".into()).into_detail(),
+        )
+        .into_report();
+        assert_eq!(report.chain().count(), 1);
+    }
+}
+
+#[cfg(feature = "anyhow_tracer")]
+mod to_anyhow {
+    use flex_error::define_error;
+    use flex_error::tracer_impl::anyhow::{AnyhowTracer, IntoAnyhow};
+
+    define_error! {
+        AppError: tracer = AnyhowTracer {
+            Startup
+                { path: String }
+                | e | { format_args!("invalid configuration {}", e.path) },
+        }
+    }
+
+    #[test]
+    fn converts_into_anyhow() {
+        let err: AnyhowTracer = AppError::startup("relayer.toml".into())
+            .add_trace(&"while starting")
+            .into_anyhow();
+        assert_eq!(
+            err.chain().map(|e| e.to_string()).collect::<Vec<_>>(),
+            [
+                "invalid configuration relayer.toml",
+                "while starting",
+                "invalid configuration relayer.toml"
+            ]
+        );
+
+        let err: AnyhowTracer = AppError::startup("relayer.toml".into()).into_anyhow();
+        assert_eq!(err.chain().count(), 1);
+    }
+}
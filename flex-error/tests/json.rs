@@ -0,0 +1,29 @@
+#![cfg(feature = "serde_json")]
+
+use flex_error::{define_error, DisplayError};
+use serde_json::json;
+
+define_error! {
+    @serde(tag = "kind")
+    RelayError {
+        Io
+            [ DisplayError<String> ]
+            | _ | { "I/O error" },
+    }
+}
+
+#[test]
+fn exports_the_error_trace() {
+    let err = RelayError::io("disk full".into()).add_trace(&"while relaying");
+    let json = err.to_json().unwrap();
+    assert_eq!(json["message"], "I/O error");
+    assert_eq!(json["detail"], json!({ "kind": "Io", "source": "disk full" }));
+
+    let trace = json["trace"].as_array().unwrap();
+    let messages = trace
+        .iter()
+        .map(|entry| entry["message"].as_str().unwrap())
+        .collect::<Vec<_>>();
+    assert_eq!(messages.join(": "), "while relaying: I/O error: disk full");
+    assert!(trace.iter().all(|entry| entry["level"] == "info"));
+}
@@ -0,0 +1,27 @@
+#![cfg(all(feature = "serde_json", feature = "std"))]
+
+use flex_error::define_error;
+
+define_error! {
+    @serde(tag = "kind")
+    RelayError {
+        Closed
+            { address: String }
+            | e | { format_args!("connection to {} closed", e.address) },
+    }
+}
+
+#[test]
+fn writes_the_error_trace_into_a_writer() {
+    let err = RelayError::closed("10.0.0.1".into()).add_trace(&"while relaying");
+    let mut body = Vec::new();
+    err.serialize_chain_into(&mut body).unwrap();
+
+    let json: serde_json::Value = serde_json::from_slice(&body).unwrap();
+    assert_eq!(json, err.to_json().unwrap());
+    let trace = json["trace"].as_array().unwrap();
+    assert!(trace.last().unwrap()["message"]
+        .as_str()
+        .unwrap()
+        .ends_with("connection to 10.0.0.1 closed"));
+}
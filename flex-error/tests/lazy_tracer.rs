@@ -0,0 +1,27 @@
+#![cfg(feature = "std")]
+
+use flex_error::define_error;
+use flex_error::tracer_impl::lazy::LazyTracer;
+use flex_error::tracer_impl::string::StringTracer;
+
+define_error! {
+    LazyError: tracer = LazyTracer<StringTracer> {
+        NotFound
+            { key: u64 }
+            | e | { format_args!("key {} not found", e.key) },
+    }
+}
+
+#[test]
+fn materializes_the_lazy_trace_when_displayed() {
+    let err = LazyError::not_found(7).add_trace(&"while loading");
+    // The snapshot of the trace is stored when the error is constructed.
+    #[cfg(not(feature = "incidents"))]
+    assert!(!err.trace().is_materialized());
+
+    assert_eq!(format!("{:#}", err), "while loading: key 7 not found");
+    assert!(err.trace().is_materialized());
+
+    let trace = err.into_trace().into_inner();
+    assert_eq!(trace.0, "while loading: key 7 not found");
+}
@@ -0,0 +1,44 @@
+#![cfg(any(feature = "eyre_tracer_minimal", feature = "anyhow_tracer"))]
+
+use flex_error::render::{trace_entries, Joined};
+use flex_error::{define_error, Level, Theme};
+
+define_error! {
+    CartError {
+        NotFound
+            { id: u64 }
+            | e | { format_args!("item {} not found", e.id) },
+    }
+}
+
+#[test]
+fn filters_the_trace_by_level() {
+    let err = CartError::not_found(42)
+        .add_trace_with_level(&"cache miss", Level::Debug)
+        .add_trace(&"while loading the cart");
+
+    assert!(err
+        .render(Theme::Plain)
+        .to_string()
+        .starts_with("   0: while loading the cart\n   1: cache miss\n   2: item 42 not found"));
+    assert!(err
+        .render(Theme::Plain)
+        .min_level(Level::Info)
+        .to_string()
+        .starts_with("   0: while loading the cart\n   1: item 42 not found"));
+}
+
+#[test]
+fn keeps_the_levels_as_trace_entries() {
+    let err = CartError::not_found(42).add_trace_with_level(&"cache miss", Level::Debug);
+
+    let levels = trace_entries(err.trace())
+        .iter()
+        .map(|entry| entry.level)
+        .collect::<Vec<_>>();
+    assert_eq!(levels, [Level::Debug, Level::Info]);
+    assert_eq!(
+        format!("{:+}", Joined(err.trace())),
+        "cache miss: item 42 not found"
+    );
+}
@@ -0,0 +1,84 @@
+#![cfg(feature = "std")]
+
+use std::fmt::{self, Formatter};
+use std::time::{Duration, SystemTime};
+
+use flex_error::define_error;
+use flex_error::locale::{with_locale, LocaleFormatter, Localized, Number};
+
+define_error! {
+    TransferError {
+        InsufficientFunds
+            { balance: f64, count: u32 }
+            | e | {
+                format_args!("insufficient funds: {} available for {} transfers",
+                    Localized(&e.balance), Localized(&e.count))
+            },
+        Expired
+            { deadline: SystemTime, elapsed: Duration }
+            | e | {
+                format_args!("expired at {} after {}",
+                    Localized(&e.deadline), Localized(&e.elapsed))
+            },
+    }
+}
+
+struct German;
+
+impl LocaleFormatter for German {
+    fn fmt_number(&self, number: Number, f: &mut Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", number.to_string().replace('.', ","))
+    }
+}
+
+struct Seconds;
+
+impl LocaleFormatter for Seconds {
+    fn fmt_duration(&self, duration: Duration, f: &mut Formatter<'_>) -> fmt::Result {
+        write!(f, "{} seconds", duration.as_secs())
+    }
+}
+
+fn expired() -> TransferError {
+    let deadline = SystemTime::UNIX_EPOCH + Duration::from_secs(86_400 + 3_661);
+    TransferError::expired(deadline, Duration::from_secs(90))
+}
+
+#[test]
+fn formats_the_localized_values_by_default() {
+    let err = TransferError::insufficient_funds(1234.5, 3);
+    assert_eq!(
+        err.detail().to_string(),
+        "insufficient funds: 1234.5 available for 3 transfers"
+    );
+
+    let message = expired().detail().to_string();
+    assert!(message.starts_with("expired at 1970-01-02T01:01:01"));
+    assert!(message.ends_with(" after 90s"));
+}
+
+#[test]
+fn formats_the_localized_values_with_the_locale() {
+    let err = TransferError::insufficient_funds(1234.5, 3);
+    let message = with_locale(German, || err.detail().to_string());
+    assert_eq!(message, "insufficient funds: 1234,5 available for 3 transfers");
+
+    let message = with_locale(Seconds, || expired().detail().to_string());
+    assert!(message.ends_with(" after 90 seconds"));
+
+    assert_eq!(
+        err.detail().to_string(),
+        "insufficient funds: 1234.5 available for 3 transfers"
+    );
+}
+
+#[test]
+fn uses_the_innermost_locale() {
+    let err = TransferError::insufficient_funds(1234.5, 3);
+    let (inner, outer) = with_locale(German, || {
+        let inner = with_locale(Seconds, || err.detail().to_string());
+        (inner, err.detail().to_string())
+    });
+    assert_eq!(inner, "insufficient funds: 1234.5 available for 3 transfers");
+    assert_eq!(outer, "insufficient funds: 1234,5 available for 3 transfers");
+}
@@ -0,0 +1,26 @@
+#![cfg(feature = "alloc")]
+
+use std::io;
+
+use flex_error::{define_error, TraceError};
+
+define_error! {
+    StoreError {
+        Write
+            { path: String }
+            [ TraceError<io::Error>, TraceError<io::Error> ]
+            | e | { format_args!("failed to write {}, and to remove the partial file", e.path) },
+    }
+}
+
+#[test]
+fn traces_both_sources() {
+    let write_error = io::Error::other("disk full");
+    let remove_error = io::Error::new(io::ErrorKind::PermissionDenied, "permission denied");
+    let err = StoreError::write("/data/block.tmp".into(), write_error, remove_error);
+    assert_eq!(
+        format!("{:#}", err),
+        "failed to write /data/block.tmp, and to remove the partial file: \
+         permission denied: disk full"
+    );
+}
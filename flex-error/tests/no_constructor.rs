@@ -0,0 +1,28 @@
+#![cfg(feature = "alloc")]
+
+use flex_error::{define_error, NoSource};
+
+define_error! {
+    NetError {
+        InvalidRange
+            { start: u64, end: u64 }
+            @no_constructor
+            | e | { format_args!("invalid range {}..{}", e.start, e.end) },
+    }
+}
+
+impl NetError {
+    pub fn invalid_range(start: u64, end: u64) -> NetError {
+        let (start, end) = (start.min(end), start.max(end));
+        NetError::trace_from::<NoSource, _>((), |_| {
+            NetErrorDetail::InvalidRange(InvalidRangeSubdetail { start, end })
+        })
+    }
+}
+
+#[test]
+fn uses_the_hand_written_constructor() {
+    let err = NetError::invalid_range(5, 2);
+    assert_eq!(err.detail().to_string(), "invalid range 2..5");
+    assert_eq!(format!("{:#}", err), "invalid range 2..5");
+}
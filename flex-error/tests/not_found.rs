@@ -0,0 +1,44 @@
+use std::collections::HashMap;
+use std::io;
+
+use flex_error::{define_error, TraceError};
+
+define_error! {
+    StoreError {
+        Io
+            [ TraceError<io::Error> ]
+            | _ | { "I/O error" },
+        NotFound
+            { key: String }
+            @from_none
+            | e | { format_args!("entry {} not found", e.key) },
+    }
+}
+
+fn lookup(entries: &HashMap<String, u64>, key: &str) -> Result<Option<u64>, StoreError> {
+    Ok(entries.get(key).copied())
+}
+
+#[test]
+fn converts_the_missing_values() {
+    let mut entries = HashMap::new();
+    entries.insert("a".to_string(), 1);
+
+    let value = lookup(&entries, "a").ok_or_not_found(|| NotFoundSubdetail { key: "a".into() });
+    assert_eq!(value.unwrap(), 1);
+
+    let err = lookup(&entries, "b")
+        .ok_or_not_found(|| NotFoundSubdetail { key: "b".into() })
+        .unwrap_err();
+    assert_eq!(err.detail().to_string(), "entry b not found");
+
+    let err = None::<u64>
+        .ok_or_not_found(|| NotFoundSubdetail { key: "c".into() })
+        .unwrap_err();
+    assert_eq!(err.detail().to_string(), "entry c not found");
+
+    let err = Err::<Option<u64>, _>(StoreError::io(io::Error::other("disk full")))
+        .ok_or_not_found(|| unreachable!())
+        .unwrap_err();
+    assert!(matches!(err.detail(), StoreErrorDetail::Io(_)));
+}
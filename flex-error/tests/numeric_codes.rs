@@ -0,0 +1,23 @@
+use flex_error::define_error;
+
+define_error! {
+    AbciError {
+        InsufficientFunds = 5
+            { required: u64 }
+            | e | { format_args!("insufficient funds, {} required", e.required) },
+        InvalidSignature = 17
+            | _ | { "invalid signature" },
+    }
+}
+
+#[test]
+fn returns_the_numeric_codes() {
+    let err = AbciError::insufficient_funds(10);
+    assert_eq!(err.code(), 5);
+    assert_eq!(err.detail().code(), 5);
+    assert_eq!(AbciError::invalid_signature().code(), 17);
+
+    assert_eq!(AbciError::from_code(17), Some("InvalidSignature"));
+    assert_eq!(AbciErrorDetail::from_code(5), Some("InsufficientFunds"));
+    assert_eq!(AbciError::from_code(0), None);
+}
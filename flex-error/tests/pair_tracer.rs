@@ -0,0 +1,28 @@
+#![cfg(feature = "eyre_tracer_minimal")]
+
+use flex_error::tracer_impl::eyre::EyreTracer;
+use flex_error::tracer_impl::pair::PairTracer;
+use flex_error::tracer_impl::string::StringTracer;
+use flex_error::{define_error, DisplayError};
+
+type ReportTracer = PairTracer<EyreTracer, StringTracer>;
+
+define_error! {
+    PairError: tracer = ReportTracer {
+        DiskFull
+            [ DisplayError<String> ]
+            | _ | { "disk full" },
+    }
+}
+
+#[test]
+fn drives_both_tracers() {
+    let err = PairError::disk_full("no space left on device".into());
+    let PairTracer(report, string) = err.trace();
+    assert_eq!(
+        report.chain().map(|e| e.to_string()).collect::<Vec<_>>(),
+        ["disk full", "no space left on device"]
+    );
+    assert_eq!(string.0, "disk full: no space left on device");
+    assert_eq!(format!("{:#}", err), "disk full: no space left on device");
+}
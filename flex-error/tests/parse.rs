@@ -0,0 +1,61 @@
+#![cfg(feature = "alloc")]
+
+use flex_error::define_error;
+use flex_error::parse::{ParseError, ParseSource, SourceSpan};
+
+define_error! {
+    ConfigError {
+        Parse
+            [ ParseSource<&'static str> ]
+            | _ | { "failed to parse the configuration" },
+    }
+}
+
+const INPUT: &str = "[relayer]\nport = 80a\n";
+
+#[test]
+fn locates_the_parse_failure() {
+    let err = ConfigError::parse(ParseError::new("invalid digit", INPUT, 19));
+    match err.detail() {
+        ConfigErrorDetail::Parse(e) => {
+            assert_eq!((e.source.span.line, e.source.span.column), (2, 10));
+            assert_eq!(e.source.span.snippet, "port = 80a");
+            assert_eq!(e.source.error, "invalid digit");
+        }
+    }
+    assert_eq!(
+        format!("{:#}", err),
+        "failed to parse the configuration: invalid digit at line 2, column 10"
+    );
+
+    let remaining = &INPUT[19..];
+    let from_remaining = ParseError::from_remaining("invalid digit", INPUT, remaining);
+    assert_eq!(from_remaining, ParseError::new("invalid digit", INPUT, 19));
+}
+
+#[test]
+fn underlines_the_span() {
+    let span = SourceSpan::locate(INPUT, 17, 3);
+    assert_eq!(span.underline().to_string(), "port = 80a\n       ^^^");
+
+    let span = SourceSpan::locate(INPUT, 0, 1);
+    assert_eq!(span.underline().to_string(), "[relayer]\n^");
+}
+
+#[test]
+fn underlines_at_least_one_character() {
+    let span = SourceSpan::locate(INPUT, 17, 0);
+    assert_eq!(span.underline().to_string(), "port = 80a\n       ^");
+
+    let span = SourceSpan {
+        len: 0,
+        ..SourceSpan::locate(INPUT, 17, 1)
+    };
+    assert_eq!(span.underline().to_string(), "port = 80a\n       ^");
+
+    let span = SourceSpan {
+        column: 0,
+        ..SourceSpan::locate(INPUT, 10, 3)
+    };
+    assert_eq!(span.underline().to_string(), "port = 80a\n^^^");
+}
@@ -0,0 +1,36 @@
+#![cfg(feature = "alloc")]
+
+use std::io;
+
+use flex_error::{define_error, Decision, Policy, TraceError};
+
+define_error! {
+    RelayError {
+        Timeout
+            | _ | { "timed out" },
+        Rejected
+            | _ | { "packet rejected" },
+        Aborted
+            | _ | { "relaying aborted" },
+        Io
+            [ TraceError<io::Error> ]
+            | _ | { "I/O error" },
+    }
+}
+
+#[test]
+fn applies_a_policy_to_the_error_kinds() {
+    let policy = Policy::new()
+        .retry(RelayErrorKind::Timeout)
+        .ignore(RelayErrorKind::Aborted);
+
+    assert_eq!(policy.apply(&RelayError::timeout()), Decision::Retry);
+    assert_eq!(policy.apply(&RelayError::aborted()), Decision::Ignore);
+    assert_eq!(policy.apply(&RelayError::rejected()), Decision::Escalate);
+
+    let policy = policy
+        .otherwise(Decision::Ignore)
+        .escalate(RelayErrorKind::Aborted);
+    assert_eq!(policy.apply(&RelayError::aborted()), Decision::Escalate);
+    assert_eq!(policy.decision(RelayErrorKind::Io), Decision::Ignore);
+}
@@ -0,0 +1,23 @@
+use flex_error::define_error;
+
+define_error! {
+    NetError {
+        ConnectTimeout
+            { timeout: u64 }
+            | e | { format_args!("timed out after {}ms", e.timeout) },
+        Refused
+            | _ | { "connection refused" },
+    }
+}
+
+#[test]
+fn matches_the_sub_errors() {
+    let err = NetError::connect_timeout(5);
+    assert!(err.detail().is_connect_timeout());
+    assert!(!err.detail().is_refused());
+    assert_eq!(err.detail().as_connect_timeout().unwrap().timeout, 5);
+
+    let err = NetError::refused();
+    assert!(err.detail().is_refused());
+    assert!(err.detail().as_connect_timeout().is_none());
+}
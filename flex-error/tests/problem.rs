@@ -0,0 +1,71 @@
+#![cfg(feature = "problem_details")]
+
+use flex_error::define_error;
+use flex_error::problem::{reason_phrase, ProblemDetails};
+
+define_error! {
+    ApiError {
+        NotFound
+            { id: u64 }
+            @problem(status = 404, title = "Resource not found", type = "/problems/not-found")
+            | e | { format_args!("no resource with id {}", e.id) },
+        Conflict
+            @problem(status = 409)
+            | _ | { "item already exists" },
+        Internal
+            | _ | { "internal error" },
+    }
+}
+
+#[test]
+fn renders_the_problem_details() {
+    let problem = ApiError::not_found(7).to_problem_details();
+    assert_eq!(
+        problem,
+        ProblemDetails {
+            type_uri: "/problems/not-found".into(),
+            title: "Resource not found".into(),
+            status: 404,
+            detail: "no resource with id 7".into(),
+            trace: vec!["no resource with id 7".into()],
+        }
+    );
+}
+
+#[test]
+fn fills_in_the_default_type_and_title() {
+    let problem = ApiError::conflict().to_problem_details();
+    assert_eq!(problem.status, 409);
+    assert_eq!(problem.title, reason_phrase(409));
+    assert_eq!(problem.type_uri, "about:blank");
+
+    let problem = ApiError::internal()
+        .add_trace(&"while handling the request")
+        .to_problem_details();
+    assert_eq!(problem.status, 500);
+    assert_eq!(problem.title, "Internal Server Error");
+    assert_eq!(problem.detail, "internal error");
+    assert_eq!(
+        problem.trace.join(": "),
+        "while handling the request: internal error"
+    );
+}
+
+#[cfg(feature = "serde_json")]
+#[test]
+fn serializes_the_problem_details() {
+    let mut problem = ApiError::not_found(7).to_problem_details();
+    assert_eq!(
+        serde_json::to_value(&problem).unwrap(),
+        serde_json::json!({
+            "type": "/problems/not-found",
+            "title": "Resource not found",
+            "status": 404,
+            "detail": "no resource with id 7",
+            "trace": ["no resource with id 7"],
+        })
+    );
+
+    problem.trace.clear();
+    assert!(serde_json::to_value(&problem).unwrap().get("trace").is_none());
+}
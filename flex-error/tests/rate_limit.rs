@@ -0,0 +1,85 @@
+#![cfg(feature = "log")]
+
+use std::sync::Mutex;
+use std::time::Duration;
+
+use flex_error::define_error;
+use flex_error::rate_limit::{throttle, Throttle};
+
+define_error! {
+    RelayError {
+        Timeout
+            | _ | { "timed out" },
+    }
+}
+
+struct Captured(Mutex<Vec<String>>);
+
+impl log::Log for Captured {
+    fn enabled(&self, metadata: &log::Metadata<'_>) -> bool {
+        metadata.target() == "flex_error"
+    }
+
+    fn log(&self, record: &log::Record<'_>) {
+        if self.enabled(record.metadata()) {
+            self.0.lock().unwrap().push(record.args().to_string());
+        }
+    }
+
+    fn flush(&self) {}
+}
+
+static LOGGER: Captured = Captured(Mutex::new(Vec::new()));
+
+#[test]
+fn throttles_the_repeated_occurrences() {
+    let interval = Duration::from_secs(60);
+    assert_eq!(
+        throttle("throttle", "RelayError::Timeout", interval),
+        Throttle::Full { suppressed: 0 }
+    );
+    assert_eq!(
+        throttle("throttle", "RelayError::Timeout", interval),
+        Throttle::Repeated { count: 1 }
+    );
+    assert_eq!(
+        throttle("throttle", "RelayError::Timeout", interval),
+        Throttle::Repeated { count: 2 }
+    );
+    assert_eq!(
+        throttle("throttle", "RelayError::Timeout", Duration::ZERO),
+        Throttle::Full { suppressed: 2 }
+    );
+
+    assert_eq!(
+        throttle("other_key", "RelayError::Timeout", interval),
+        Throttle::Full { suppressed: 0 }
+    );
+}
+
+#[test]
+fn logs_the_full_trace_at_most_once_per_interval() {
+    log::set_logger(&LOGGER).unwrap();
+    log::set_max_level(log::LevelFilter::Error);
+
+    let err = RelayError::timeout().add_trace(&"while relaying");
+    for _ in 0..3 {
+        err.log_rate_limited("relay_packet", Duration::from_secs(60));
+    }
+    err.log_rate_limited("relay_packet", Duration::ZERO);
+
+    let logs = LOGGER
+        .0
+        .lock()
+        .unwrap()
+        .iter()
+        .filter(|message| message.starts_with("[relay_packet]"))
+        .cloned()
+        .collect::<Vec<_>>();
+    assert_eq!(logs.len(), 4);
+    assert!(logs[0].starts_with("[relay_packet] RelayError::Timeout: "));
+    assert!(logs[0].contains("while relaying"));
+    assert!(logs[1].ends_with(" (1 occurrences since the last full trace)"));
+    assert!(logs[2].ends_with(" (2 occurrences since the last full trace)"));
+    assert!(logs[3].ends_with(" (2 similar errors suppressed)"));
+}
@@ -0,0 +1,31 @@
+#![cfg(feature = "std")]
+
+use flex_error::define_error;
+use flex_error::redact::{clear_trace_filter, set_trace_filter};
+use flex_error::render::Joined;
+
+define_error! {
+    FileError {
+        NotFound
+            { path: String }
+            | e | { format_args!("file not found: {}", e.path) },
+    }
+}
+
+fn hide_secrets(message: &str) -> String {
+    message.replace("secret", "***")
+}
+
+#[test]
+fn filters_the_messages_while_a_filter_is_set() {
+    let err = FileError::not_found("/secret/a".to_string());
+    assert_eq!(Joined(err.trace()).to_string(), "file not found: /secret/a");
+
+    set_trace_filter(hide_secrets);
+    let err = FileError::not_found("/secret/b".to_string());
+    assert_eq!(Joined(err.trace()).to_string(), "file not found: /***/b");
+
+    clear_trace_filter();
+    let err = FileError::not_found("/secret/c".to_string());
+    assert_eq!(Joined(err.trace()).to_string(), "file not found: /secret/c");
+}
@@ -0,0 +1,76 @@
+#![cfg(all(feature = "replay", feature = "serde_json"))]
+
+use std::time::{Duration, UNIX_EPOCH};
+
+use flex_error::define_error;
+use flex_error::replay::{message_time, Stamped, TracingJson};
+use flex_error::Level;
+use serde_json::Value;
+
+define_error! {
+    RelayError {
+        Refused
+            | _ | { "connection \"refused\"" },
+        Relay
+            [ Self ]
+            | _ | { "failed to relay packet" },
+    }
+}
+
+fn parse_records(json: &str) -> Vec<Value> {
+    json.lines()
+        .map(|line| serde_json::from_str(line).unwrap())
+        .collect()
+}
+
+#[test]
+fn exports_the_trace_as_tracing_records() {
+    let err = RelayError::relay(RelayError::refused())
+        .add_trace_with_level(&"retrying", Level::Warn);
+    let records = parse_records(&TracingJson::new(&err, "relay_packet").to_string());
+
+    let fields = records
+        .iter()
+        .map(|record| {
+            (
+                record["level"].as_str().unwrap(),
+                record["fields"]["message"].as_str().unwrap(),
+                record["fields"]["error.depth"].as_u64(),
+            )
+        })
+        .collect::<Vec<_>>();
+    assert_eq!(
+        fields,
+        [
+            ("ERROR", "new", None),
+            ("INFO", "connection \"refused\"", Some(2)),
+            ("INFO", "failed to relay packet", Some(1)),
+            ("WARN", "retrying", Some(0)),
+            ("ERROR", "close", None),
+        ]
+    );
+
+    for record in &records {
+        assert!(record["timestamp"].as_str().unwrap().ends_with('Z'));
+        assert_eq!(record["target"], "flex_error");
+        assert_eq!(record["span"]["name"], "relay_packet");
+        assert_eq!(record["spans"][0]["name"], "relay_packet");
+    }
+
+    let json = TracingJson::new(&err, "relay_packet")
+        .with_target("relayer")
+        .to_string();
+    assert!(parse_records(&json).iter().all(|record| record["target"] == "relayer"));
+}
+
+#[test]
+fn recovers_the_capture_time_of_the_messages() {
+    let stamped = Stamped {
+        time: UNIX_EPOCH + Duration::from_nanos(1_714_557_600_000_100_000),
+        message: "connection refused",
+    };
+    assert_eq!(stamped.to_string(), "connection refused");
+    assert_eq!(message_time(&stamped), Some(stamped.time));
+
+    assert_eq!(message_time(&"connection refused"), None);
+}
@@ -0,0 +1,68 @@
+#![cfg(feature = "alloc")]
+
+use flex_error::{define_error, ReportStatus};
+
+define_error! {
+    PlainError {
+        Failed
+            | _ | { "failed" },
+    }
+}
+
+define_error! {
+    @metadata
+    NetError {
+        Timeout
+            { timeout: u64 }
+            | e | { format_args!("timed out after {}ms", e.timeout) },
+        Nested
+            [ Self ]
+            | _ | { "nested" },
+    }
+}
+
+define_error! {
+    @metadata
+    AppError {
+        Net
+            [ NetError ]
+            | _ | { "network error" },
+        Plain
+            [ PlainError ]
+            | _ | { "plain error" },
+    }
+}
+
+// With the `incidents` feature, all error types keep their metadata.
+#[cfg(not(feature = "incidents"))]
+#[test]
+fn keeps_two_fields_without_the_metadata_option() {
+    let PlainError(detail, trace) = PlainError::failed();
+    let err: PlainError = PlainError(detail, trace);
+
+    assert!(matches!(err.detail(), PlainErrorDetail::Failed(_)));
+    let _: &flex_error::DefaultTracer = err.trace();
+}
+
+#[test]
+fn carries_the_report_status_through_wrapping() {
+    let inner = NetError::timeout(5).mark_reported();
+    assert!(inner.is_reported());
+    assert!(!inner.is_source_reported());
+
+    let nested = NetError::nested(inner);
+    assert!(!nested.is_reported());
+    assert!(nested.is_source_reported());
+
+    let outer = AppError::net(nested);
+    assert_eq!(outer.report_status(), ReportStatus::SourceReported);
+}
+
+#[test]
+fn starts_unreported_from_sources_without_metadata() {
+    let err = AppError::plain(PlainError::failed());
+    assert_eq!(err.report_status(), ReportStatus::Unreported);
+
+    let err = NetError::timeout(5).add_trace(&"while connecting");
+    assert_eq!(err.report_status(), ReportStatus::Unreported);
+}
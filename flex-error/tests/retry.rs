@@ -0,0 +1,72 @@
+#![cfg(feature = "http")]
+
+use core::time::Duration;
+
+use flex_error::define_error;
+use flex_error::retry::{retry_after, RetryHint, RetryHinted, GRPC_RETRY_PUSHBACK};
+use http::header::RETRY_AFTER;
+use http::HeaderMap;
+
+#[test]
+fn reads_the_retry_after_header_before_the_grpc_pushback() {
+    let mut headers = HeaderMap::new();
+    headers.insert(GRPC_RETRY_PUSHBACK, "250".parse().unwrap());
+    assert_eq!(retry_after(&headers), Some(Duration::from_millis(250)));
+
+    headers.insert(RETRY_AFTER, "3".parse().unwrap());
+    assert_eq!(retry_after(&headers), Some(Duration::from_secs(3)));
+}
+
+#[test]
+fn ignores_a_negative_grpc_pushback() {
+    let mut headers = HeaderMap::new();
+    headers.insert(GRPC_RETRY_PUSHBACK, "-1".parse().unwrap());
+    assert_eq!(retry_after(&headers), None);
+}
+
+#[cfg(feature = "tonic")]
+mod tonic_status {
+    use super::*;
+
+    fn with_pushback(mut status: tonic::Status, pushback: &str) -> tonic::Status {
+        status
+            .metadata_mut()
+            .insert(GRPC_RETRY_PUSHBACK, pushback.parse().unwrap());
+        status
+    }
+
+    define_error! {
+        ClientError {
+            Grpc
+                [ RetryHinted<tonic::Status> ]
+                | _ | { "gRPC request failed" },
+        }
+    }
+
+    #[test]
+    fn reads_the_pushback_of_the_unavailable_and_resource_exhausted_statuses() {
+        let status = with_pushback(tonic::Status::unavailable("overloaded"), "150");
+        assert_eq!(status.retry_hint(), Some(Duration::from_millis(150)));
+
+        let status = with_pushback(tonic::Status::resource_exhausted("quota"), "20");
+        assert_eq!(status.retry_hint(), Some(Duration::from_millis(20)));
+
+        let status = with_pushback(tonic::Status::unavailable("overloaded"), "-1");
+        assert_eq!(status.retry_hint(), None);
+
+        assert_eq!(tonic::Status::unavailable("overloaded").retry_hint(), None);
+    }
+
+    #[test]
+    fn ignores_the_pushback_of_the_other_statuses() {
+        let status = with_pushback(tonic::Status::invalid_argument("bad request"), "150");
+        assert_eq!(status.retry_hint(), None);
+    }
+
+    #[test]
+    fn surfaces_the_pushback_through_the_error() {
+        let status = with_pushback(tonic::Status::unavailable("overloaded"), "150");
+        let err = ClientError::grpc(status);
+        assert_eq!(err.retry_after(), Some(Duration::from_millis(150)));
+    }
+}
@@ -0,0 +1,63 @@
+use std::io;
+
+use flex_error::{define_error, TraceError};
+
+const CODES: &str = r#"
+[NetError.ConnectTimeout]
+code = "NET-0001"
+
+[NetError.Refused]
+code = "NET-0002"
+
+[RelayError.Net]
+code = "RLY-0001"
+
+[RelayError.Io]
+code = "RLY-0002"
+"#;
+
+define_error! {
+    @codes(CODES)
+    NetError {
+        ConnectTimeout
+            | _ | { "timed out" },
+        Refused
+            | _ | { "connection refused" },
+    }
+}
+
+define_error! {
+    @codes(CODES)
+    RelayError {
+        Net
+            [ NetError ]
+            | _ | { "network error" },
+        Io
+            [ TraceError<io::Error> ]
+            | _ | { "I/O error" },
+    }
+}
+
+define_error! {
+    PacketError {
+        Relay
+            [ RelayError ]
+            | _ | { "failed to relay the packet" },
+        Unknown
+            | _ | { "unknown packet" },
+    }
+}
+
+#[test]
+fn returns_the_code_of_the_root_cause() {
+    let err = RelayError::net(NetError::refused());
+    assert_eq!(err.root_code(), Some("NET-0002"));
+
+    let err = RelayError::io(io::ErrorKind::Other.into());
+    assert_eq!(err.root_code(), Some("RLY-0002"));
+
+    let err = PacketError::relay(RelayError::net(NetError::connect_timeout()));
+    assert_eq!(err.root_code(), Some("NET-0001"));
+
+    assert_eq!(PacketError::unknown().root_code(), None);
+}
@@ -0,0 +1,32 @@
+use std::io;
+
+use flex_error::{define_error, TraceClone};
+
+define_error! {
+    StoreError {
+        Cloned
+            [ TraceClone<io::ErrorKind> ]
+            | _ | { "cloned error" },
+        Empty
+            | _ | { "empty store" },
+    }
+}
+
+define_error! {
+    QueryError {
+        Store
+            [ StoreError ]
+            | _ | { "store error" },
+    }
+}
+
+#[test]
+fn returns_the_root_source() {
+    let err = QueryError::store(StoreError::cloned(io::ErrorKind::TimedOut));
+    assert_eq!(err.root_source::<io::ErrorKind>(), Some(&io::ErrorKind::TimedOut));
+    assert_eq!(err.detail().root_source::<io::ErrorKind>(), Some(&io::ErrorKind::TimedOut));
+    assert_eq!(err.root_source::<String>(), None);
+
+    let err = QueryError::store(StoreError::empty());
+    assert_eq!(err.root_source::<io::ErrorKind>(), None);
+}
@@ -0,0 +1,57 @@
+#![cfg(feature = "std")]
+
+use flex_error::tracer_impl::string::StringTracer;
+use flex_error::{define_error, with_tracer_scope};
+
+define_error! {
+    NetError {
+        Timeout
+            { timeout: u64 }
+            | e | { format_args!("timed out after {}ms", e.timeout) },
+        Connect
+            [ Self ]
+            | _ | { "failed to connect" },
+    }
+}
+
+#[test]
+fn replays_each_error_into_its_own_tracer() {
+    let (errors, traces) = with_tracer_scope::<StringTracer, _>(|| {
+        let first = NetError::connect(NetError::timeout(1));
+        let second = NetError::timeout(2);
+        (first, second)
+    });
+
+    assert!(matches!(errors.0.detail(), NetErrorDetail::Connect(_)));
+    assert!(matches!(errors.1.detail(), NetErrorDetail::Timeout(_)));
+
+    let traces: Vec<_> = traces.into_iter().map(|trace| trace.0).collect();
+    assert_eq!(
+        traces,
+        ["failed to connect: timed out after 1ms", "timed out after 2ms"]
+    );
+}
+
+#[test]
+fn records_the_error_traces_of_the_innermost_scope() {
+    let (_, outer) = with_tracer_scope::<StringTracer, _>(|| {
+        let (inner_error, inner) =
+            with_tracer_scope::<StringTracer, _>(|| NetError::timeout(1));
+
+        assert_eq!(inner.len(), 1);
+        assert_eq!(inner[0].0, "timed out after 1ms");
+
+        NetError::connect(inner_error)
+    });
+
+    assert_eq!(outer.len(), 1);
+    assert_eq!(outer[0].0, "failed to connect: timed out after 1ms");
+}
+
+#[test]
+fn returns_no_tracer_without_errors() {
+    let (result, traces) = with_tracer_scope::<StringTracer, _>(|| 1);
+
+    assert_eq!(result, 1);
+    assert!(traces.is_empty());
+}
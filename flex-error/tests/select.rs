@@ -0,0 +1,68 @@
+#![cfg(feature = "std")]
+
+use flex_error::select::{Best, SourceKind};
+use flex_error::{assert_best_source, best_source, define_error, select_source, DisplayError};
+
+#[derive(Debug)]
+pub struct ExternalError;
+
+impl core::fmt::Display for ExternalError {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        write!(f, "external error")
+    }
+}
+
+select_source!(ExternalError => DisplayError<ExternalError>);
+
+define_error! {
+    InnerError {
+        Failed
+            | _ | { "inner failure" },
+    }
+}
+
+define_error! {
+    OuterError {
+        Inner
+            [ Best<InnerError> ]
+            | _ | { "from inner" },
+        External
+            [ Best<ExternalError> ]
+            | _ | { "from external" },
+        Parse
+            [ Best<core::num::ParseIntError> ]
+            | _ | { "from parse" },
+        Io
+            [ Best<std::io::Error> ]
+            | _ | { "from io" },
+    }
+}
+
+#[test]
+fn selects_the_error_types_themselves_for_define_error_types() {
+    let err = OuterError::inner(InnerError::failed());
+
+    match err.detail() {
+        OuterErrorDetail::Inner(sub) => {
+            assert!(matches!(sub.source, InnerErrorDetail::Failed(_)))
+        }
+        _ => panic!("unexpected detail"),
+    }
+    assert!(format!("{:?}", err).contains("inner failure"));
+}
+
+#[test]
+fn selects_the_declared_wrappers_for_external_types() {
+    assert_best_source!(ExternalError => DisplayError<ExternalError>);
+
+    let err = OuterError::external(ExternalError);
+    assert!(format!("{:?}", err).contains("external error"));
+
+    let parse_err = "x".parse::<u8>().unwrap_err();
+    assert_eq!(best_source!(core::num::ParseIntError), SourceKind::TraceClone);
+    let err = OuterError::parse(parse_err);
+    assert!(format!("{:?}", err).contains("invalid digit"));
+
+    let err = OuterError::io(std::io::Error::other("disk full"));
+    assert!(format!("{:?}", err).contains("disk full"));
+}
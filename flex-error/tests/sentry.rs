@@ -0,0 +1,73 @@
+#![cfg(feature = "sentry")]
+
+use flex_error::define_error;
+use sentry_types::protocol::v7::{Context, Level};
+
+const CODES: &str = r#"
+[UploadError.Rejected]
+code = "UPL-0001"
+"#;
+
+define_error! {
+    @codes(CODES)
+    @serde(tag = "kind")
+    UploadError {
+        Rejected
+            {
+                file: String,
+                #[detail(skip_serde)]
+                token: String,
+            }
+            | e | { format_args!("upload of {} rejected", e.file) },
+    }
+}
+
+#[test]
+fn exports_the_error_as_a_sentry_event() {
+    let err = UploadError::rejected("report.pdf".into(), "secret".into())
+        .add_trace(&"while syncing the reports");
+    let event = err.to_sentry_event();
+
+    assert_eq!(event.level, Level::Error);
+    assert_eq!(event.logger.as_deref(), Some("flex_error"));
+    assert_eq!(
+        event.exception.values.last().map(|e| e.ty.as_str()),
+        Some("UploadError::Rejected")
+    );
+
+    assert_eq!(
+        event.tags.get("error.code").map(String::as_str),
+        Some("UPL-0001")
+    );
+
+    match event.contexts.get("detail") {
+        Some(Context::Other(detail)) => {
+            assert_eq!(detail["kind"], "Rejected");
+            assert_eq!(detail["file"], "report.pdf");
+            assert!(!detail.contains_key("token"));
+        }
+        _ => panic!("expected the detail context"),
+    }
+}
+
+#[cfg(any(feature = "eyre_tracer_minimal", feature = "anyhow_tracer"))]
+#[test]
+fn exports_one_exception_per_trace_message() {
+    let err = UploadError::rejected("report.pdf".into(), "secret".into())
+        .add_trace(&"while syncing the reports");
+    let event = err.to_sentry_event();
+
+    let exceptions = event
+        .exception
+        .values
+        .iter()
+        .map(|e| (e.ty.as_str(), e.value.as_deref().unwrap()))
+        .collect::<Vec<_>>();
+    assert_eq!(
+        exceptions,
+        [
+            ("flex_error::TraceMessage", "upload of report.pdf rejected"),
+            ("UploadError::Rejected", "while syncing the reports"),
+        ]
+    );
+}
@@ -0,0 +1,74 @@
+#![cfg(feature = "serde_json")]
+
+use flex_error::define_error;
+use serde_json::json;
+
+define_error! {
+    @serde(external)
+    ExternalError {
+        Timeout
+            { seconds: u64 }
+            | e | { format_args!("timed out after {} seconds", e.seconds) },
+    }
+}
+
+define_error! {
+    @serde(tag = "kind")
+    RelayError {
+        Relay
+            { height: u64 }
+            [ ExternalError ]
+            | e | { format_args!("failed to relay at height {}", e.height) },
+    }
+}
+
+define_error! {
+    @codes(include_str!("error_codes.toml"))
+    @serde(code_message)
+    MyError {
+        ConnectTimeout
+            { timeout: u64 }
+            | e | { format_args!("timed out after {}ms", e.timeout) },
+        Refused
+            | _ | { "connection refused" },
+    }
+}
+
+define_error! {
+    @serde(code_message)
+    PlainError {
+        NotFound
+            { id: u64 }
+            | e | { format_args!("item {} not found", e.id) },
+    }
+}
+
+#[test]
+fn serializes_the_externally_tagged_details() {
+    let err = ExternalError::timeout(3);
+    assert_eq!(
+        serde_json::to_value(err.detail()).unwrap(),
+        json!({ "Timeout": { "seconds": 3 } })
+    );
+}
+
+#[test]
+fn serializes_the_internally_tagged_details() {
+    let err = RelayError::relay(10, ExternalError::timeout(3));
+    assert_eq!(
+        serde_json::to_value(err.detail()).unwrap(),
+        json!({ "kind": "Relay", "height": 10, "source": { "Timeout": { "seconds": 3 } } })
+    );
+}
+
+#[test]
+fn serializes_the_codes_and_messages() {
+    assert_eq!(
+        serde_json::to_value(MyError::connect_timeout(100).detail()).unwrap(),
+        json!({ "code": "NET-0001", "message": "timed out after 100ms" })
+    );
+    assert_eq!(
+        serde_json::to_value(PlainError::not_found(7).detail()).unwrap(),
+        json!({ "code": "NotFound", "message": "item 7 not found" })
+    );
+}
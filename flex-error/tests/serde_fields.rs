@@ -0,0 +1,29 @@
+#![cfg(feature = "serde_json")]
+
+use std::net::SocketAddr;
+
+use flex_error::define_error;
+use serde_json::json;
+
+define_error! {
+    @serde(tag = "kind")
+    RelayError {
+        Closed
+            {
+                #[detail(skip_serde)]
+                socket: SocketAddr,
+                #[detail(rename = "addr")]
+                address: String,
+            }
+            | e | { format_args!("connection to {} closed", e.address) },
+    }
+}
+
+#[test]
+fn skips_and_renames_the_serialized_fields() {
+    let err = RelayError::closed("10.0.0.1:26657".parse().unwrap(), "10.0.0.1".into());
+    assert_eq!(
+        serde_json::to_value(err.detail()).unwrap(),
+        json!({ "kind": "Closed", "addr": "10.0.0.1" })
+    );
+}
@@ -0,0 +1,25 @@
+use std::num::ParseIntError;
+
+use flex_error::{define_error, DisplayError};
+
+define_error! {
+    StoreError {
+        Parse
+            { input: String }
+            [ DisplayError<ParseIntError> ]
+            | e | {
+                format_args!("failed to parse {}: {}", e.input,
+                    e.source_display().unwrap_or(&"unknown source"))
+            },
+    }
+}
+
+#[test]
+fn displays_the_source_detail_in_the_formatter() {
+    let source = "x".parse::<u32>().unwrap_err();
+    let err = StoreError::parse("x".into(), source);
+    assert_eq!(
+        err.detail().to_string(),
+        "failed to parse x: invalid digit found in string"
+    );
+}
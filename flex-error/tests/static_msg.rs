@@ -0,0 +1,19 @@
+use flex_error::define_error;
+
+define_error! {
+    NetError {
+        NotInitialized
+            @static_msg
+            | _ | { "not initialized" },
+    }
+}
+
+#[test]
+fn keeps_the_static_message() {
+    let err = NetError::not_initialized();
+    assert_eq!(
+        err.detail().as_not_initialized().unwrap().static_message(),
+        "not initialized"
+    );
+    assert_eq!(err.detail().to_string(), "not initialized");
+}
@@ -0,0 +1,56 @@
+#![cfg(feature = "error_stats")]
+
+use flex_error::define_error;
+use flex_error::stats::{reset_stats, stats, ErrorStat};
+
+define_error! {
+    FetchError {
+        Timeout
+            | _ | { "timed out" },
+        NotFound
+            { path: String }
+            | e | { format_args!("{} not found", e.path) },
+        Unused
+            | _ | { "never constructed" },
+    }
+}
+
+fn fetch_stats() -> Vec<ErrorStat> {
+    stats()
+        .into_iter()
+        .filter(|stat| stat.error == "stats::FetchError")
+        .collect()
+}
+
+// The counters are global, so they are only checked and reset in one test.
+#[test]
+fn counts_and_resets_the_constructed_errors() {
+    let _ = FetchError::timeout();
+    let _ = FetchError::timeout();
+    let _ = FetchError::not_found("/blocks/10".into());
+
+    assert_eq!(
+        fetch_stats(),
+        [
+            ErrorStat {
+                error: "stats::FetchError",
+                variant: "NotFound",
+                count: 1
+            },
+            ErrorStat {
+                error: "stats::FetchError",
+                variant: "Timeout",
+                count: 2
+            },
+        ]
+    );
+
+    reset_stats();
+    let _ = FetchError::timeout();
+
+    let counts = fetch_stats()
+        .into_iter()
+        .map(|stat| (stat.variant, stat.count))
+        .collect::<Vec<_>>();
+    assert_eq!(counts, [("NotFound", 0), ("Timeout", 1)]);
+}
@@ -0,0 +1,38 @@
+#![cfg(feature = "alloc")]
+
+use flex_error::tracer_impl::string::StringTracer;
+use flex_error::ErrorMessageTracer;
+
+#[test]
+fn joins_the_messages_into_the_public_field() {
+    let trace = StringTracer::new_message(&"timed out").add_message(&"failed to connect");
+    assert_eq!(trace.0, "failed to connect: timed out");
+}
+
+#[test]
+fn creates_traces_without_location() {
+    let trace = StringTracer::new("failed".to_string());
+    assert_eq!(trace.0, "failed");
+    assert_eq!(trace.location(), None);
+}
+
+#[cfg(feature = "string_tracer_location")]
+#[test]
+fn records_the_location_with_the_location_feature() {
+    let trace = StringTracer::new_message(&"timed out");
+    let location = trace.location().unwrap().to_string();
+    assert!(location.starts_with("flex-error/tests/string_tracer.rs:"), "{}", location);
+
+    let trace = trace.add_message(&"failed to connect");
+    assert_eq!(trace.location(), Some(location.as_str()));
+}
+
+#[cfg(not(feature = "string_tracer_location"))]
+#[test]
+fn records_no_location_without_the_location_feature() {
+    let trace = StringTracer::new_message(&"timed out");
+    assert_eq!(trace.location(), None);
+
+    let StringTracer(messages) = StringTracer("timed out".to_string());
+    assert_eq!(messages, "timed out");
+}
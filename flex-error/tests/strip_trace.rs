@@ -0,0 +1,39 @@
+#![cfg(feature = "alloc")]
+
+use flex_error::define_error;
+use flex_error::render::Joined;
+
+define_error! {
+    NetError {
+        Timeout
+            { timeout: u64 }
+            | e | { format_args!("timed out after {}ms", e.timeout) },
+        Connect
+            [ Self ]
+            | _ | { "failed to connect" },
+    }
+}
+
+#[test]
+fn strip_trace_returns_the_detail() {
+    let err = NetError::connect(NetError::timeout(5));
+    assert_eq!(
+        Joined(err.trace()).to_string(),
+        "failed to connect: timed out after 5ms"
+    );
+
+    let detail = err.strip_trace();
+    assert!(matches!(detail, NetErrorDetail::Connect(_)));
+
+    let err = NetError::with_new_trace(detail);
+    assert_eq!(Joined(err.trace()).to_string(), "failed to connect");
+}
+
+#[test]
+fn with_new_trace_restores_an_error_from_its_detail() {
+    let detail = NetError::connect(NetError::timeout(5)).into_detail();
+
+    let err = NetError::with_new_trace(detail);
+    assert!(matches!(err.detail(), NetErrorDetail::Connect(_)));
+    assert_eq!(Joined(err.trace()).to_string(), "failed to connect");
+}
@@ -0,0 +1,30 @@
+#![cfg(any(feature = "eyre_tracer_minimal", feature = "anyhow_tracer"))]
+
+use flex_error::define_error;
+
+define_error! {
+    CartError {
+        NotFound
+            { id: u64 }
+            | e | { format_args!("item {} not found", e.id) },
+    }
+}
+
+#[test]
+fn summarizes_the_trace() {
+    let err = CartError::not_found(42)
+        .add_trace(&"while loading the cart")
+        .add_trace(&"while rendering the page")
+        .add_trace(&"request failed");
+
+    assert_eq!(
+        err.summarize(200),
+        "request failed: while rendering the page: while loading the cart: item 42 not found"
+    );
+
+    let summary = err.summarize(60);
+    assert!(summary.chars().count() <= 60);
+    assert!(summary.starts_with("request failed: "));
+    assert!(summary.ends_with(": item 42 not found"));
+    assert!(summary.contains("more ...]"));
+}
@@ -0,0 +1,32 @@
+#![cfg(any(feature = "eyre_tracer_minimal", feature = "anyhow_tracer"))]
+
+use flex_error::{define_error, set_color_mode, ColorMode, Theme};
+
+define_error! {
+    CartError {
+        NotFound
+            { id: u64 }
+            | e | { format_args!("item {} not found", e.id) },
+    }
+}
+
+#[test]
+fn renders_the_trace_with_the_theme() {
+    let err = CartError::not_found(42).add_trace(&"while loading the cart");
+
+    set_color_mode(ColorMode::Never);
+    assert!(err
+        .render(Theme::Ansi)
+        .to_string()
+        .starts_with("   0: while loading the cart\n   1: item 42 not found"));
+
+    set_color_mode(ColorMode::Always);
+    assert!(err.render(Theme::Ansi).to_string().starts_with(
+        "\x1b[31m   0:\x1b[0m while loading the cart\x1b[0m\n\x1b[31m   1:\x1b[0m item 42 not found\x1b[0m"
+    ));
+    assert!(err
+        .render(Theme::Plain)
+        .to_string()
+        .starts_with("   0: while loading the cart\n   1: item 42 not found"));
+    set_color_mode(ColorMode::Auto);
+}
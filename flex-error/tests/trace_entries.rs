@@ -0,0 +1,26 @@
+#![cfg(any(feature = "eyre_tracer_minimal", feature = "anyhow_tracer"))]
+
+use flex_error::{define_error, Level};
+
+define_error! {
+    CartError {
+        NotFound
+            { id: u64 }
+            | e | { format_args!("item {} not found", e.id) },
+    }
+}
+
+#[test]
+fn collects_the_trace_entries() {
+    let err = CartError::not_found(42).add_trace_with_level(&"cache miss", Level::Debug);
+    let entries = err.trace_entries().collect::<Vec<_>>();
+    assert_eq!(entries.len(), 2);
+    assert_eq!(
+        (entries[0].message.as_str(), entries[0].level),
+        ("cache miss", Level::Debug)
+    );
+    assert_eq!(
+        (entries[1].message.as_str(), entries[1].level),
+        ("item 42 not found", Level::Info)
+    );
+}
@@ -0,0 +1,24 @@
+#![cfg(feature = "alloc")]
+
+use flex_error::define_error;
+
+define_error! {
+    @display(chain)
+    RelayError {
+        Corrupted
+            {
+                #[detail(trace)]
+                height: u64,
+                #[detail(trace)]
+                hash: String,
+            }
+            | _ | { "corrupted state" },
+    }
+}
+
+#[test]
+fn adds_the_trace_fields() {
+    let err = RelayError::corrupted(10, "5f2c1e9".into());
+    assert_eq!(err.detail().to_string(), "corrupted state");
+    assert_eq!(err.to_string(), "height=10, hash=5f2c1e9: corrupted state");
+}
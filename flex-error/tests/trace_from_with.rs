@@ -0,0 +1,27 @@
+#![cfg(feature = "alloc")]
+
+use std::io;
+
+use flex_error::{define_error, TraceError};
+
+define_error! {
+    NetError {
+        Io
+            [ TraceError<io::Error> ]
+            | _ | { "I/O error" },
+    }
+}
+
+#[test]
+fn adds_the_context_message_of_trace_from_with() {
+    let eof = io::Error::new(io::ErrorKind::UnexpectedEof, "unexpected end of file");
+    let err = NetError::trace_from_with::<TraceError<io::Error>, _>(
+        eof,
+        format_args!("while reading block {}", 42),
+        |source| NetErrorDetail::Io(IoSubdetail { source }),
+    );
+    assert_eq!(
+        format!("{:#}", err),
+        "I/O error: while reading block 42: unexpected end of file"
+    );
+}
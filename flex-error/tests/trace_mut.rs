@@ -0,0 +1,25 @@
+#![cfg(feature = "alloc")]
+
+use flex_error::{define_error, DefaultTracer, ErrorMessageTracer};
+
+define_error! {
+    NetError {
+        ConnectTimeout
+            { timeout: u64 }
+            | e | { format_args!("timed out after {}ms", e.timeout) },
+    }
+}
+
+#[test]
+fn modifies_the_trace_in_place() {
+    let mut err = NetError::connect_timeout(5);
+    let trace = core::mem::replace(err.trace_mut(), DefaultTracer::new_message(&"replaced"));
+    assert_eq!(trace.to_string(), "timed out after 5ms");
+    assert_eq!(format!("{:#}", err), "replaced");
+
+    let err = err.map_trace(|trace| trace.add_message(&"wrapped"));
+    assert_eq!(format!("{:#}", err), "wrapped: replaced");
+
+    let err = err.add_trace(&"while relaying");
+    assert_eq!(format!("{:#}", err), "while relaying: wrapped: replaced");
+}
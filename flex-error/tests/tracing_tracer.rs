@@ -0,0 +1,94 @@
+#![cfg(feature = "tracing_tracer")]
+
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::{Arc, Mutex};
+
+use flex_error::tracer_impl::tracing::TracingTracer;
+use flex_error::{define_error, Level};
+use tracing::field::{Field, Visit};
+use tracing::span::{Attributes, Id, Record};
+use tracing::{Event, Metadata, Subscriber};
+
+define_error! {
+    RelayError: tracer = TracingTracer {
+        Timeout
+            | _ | { "timed out" },
+    }
+}
+
+/// A subscriber recording the events with the `flex_error` target.
+#[derive(Default)]
+struct Recorder {
+    next_id: AtomicU64,
+    events: Arc<Mutex<Vec<(tracing::Level, String)>>>,
+}
+
+struct MessageVisitor<'a>(&'a mut String);
+
+impl Visit for MessageVisitor<'_> {
+    fn record_debug(&mut self, field: &Field, value: &dyn std::fmt::Debug) {
+        if field.name() == "message" {
+            *self.0 = format!("{:?}", value);
+        }
+    }
+}
+
+impl Subscriber for Recorder {
+    fn enabled(&self, _: &Metadata<'_>) -> bool {
+        true
+    }
+
+    fn new_span(&self, _: &Attributes<'_>) -> Id {
+        Id::from_u64(self.next_id.fetch_add(1, Ordering::Relaxed) + 1)
+    }
+
+    fn record(&self, _: &Id, _: &Record<'_>) {}
+
+    fn record_follows_from(&self, _: &Id, _: &Id) {}
+
+    fn event(&self, event: &Event<'_>) {
+        if event.metadata().target() == "flex_error" {
+            let mut message = String::new();
+            event.record(&mut MessageVisitor(&mut message));
+            self.events
+                .lock()
+                .unwrap()
+                .push((*event.metadata().level(), message));
+        }
+    }
+
+    fn enter(&self, _: &Id) {}
+
+    fn exit(&self, _: &Id) {}
+}
+
+#[test]
+fn records_the_messages_as_events() {
+    let recorder = Recorder::default();
+    let events = recorder.events.clone();
+
+    let err = tracing::subscriber::with_default(recorder, || {
+        RelayError::timeout().add_trace_with_level(&"giving up", Level::Warn)
+    });
+
+    assert_eq!(format!("{:#}", err), "giving up: timed out");
+    assert_eq!(
+        err.trace().messages().collect::<Vec<_>>(),
+        ["giving up", "timed out"]
+    );
+
+    assert_eq!(
+        *events.lock().unwrap(),
+        [
+            (tracing::Level::INFO, "timed out".to_string()),
+            (tracing::Level::WARN, "giving up".to_string()),
+        ]
+    );
+}
+
+#[test]
+fn does_not_capture_the_spans_without_a_subscriber() {
+    let err = RelayError::timeout();
+    assert!(err.trace().span().is_disabled());
+    assert_eq!(format!("{:?}", err.trace()), "   0: timed out");
+}
@@ -0,0 +1,23 @@
+use flex_error::define_error;
+
+define_error! {
+    @type_url("/relayer.error.RelayError")
+    RelayError {
+        Timeout
+            | _ | { "timed out" },
+    }
+}
+
+define_error! {
+    DetailError {
+        Closed
+            | _ | { "connection closed" },
+    }
+}
+
+#[test]
+fn returns_the_type_url() {
+    assert_eq!(RelayError::type_url(), "/relayer.error.RelayError");
+    assert_eq!(RelayError::TYPE_URL, "/relayer.error.RelayError");
+    assert_eq!(DetailError::type_url(), "/type_url::DetailError");
+}
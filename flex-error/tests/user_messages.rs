@@ -0,0 +1,38 @@
+use std::fmt::{self, Formatter};
+
+use flex_error::define_error;
+
+define_error! {
+    CartError {
+        NotFound
+            { id: u64 }
+            | e | { format_args!("item {} not found", e.id) },
+        Internal
+            | _ | { "internal error" },
+    }
+}
+
+struct English;
+
+impl CartErrorUserMessage for English {
+    fn not_found(&self, detail: &NotFoundSubdetail, f: &mut Formatter<'_>) -> fmt::Result {
+        write!(f, "We could not find item {}.", detail.id)
+    }
+
+    fn internal(&self, _: &InternalSubdetail, f: &mut Formatter<'_>) -> fmt::Result {
+        write!(f, "Something went wrong, please try again later.")
+    }
+}
+
+#[test]
+fn displays_the_user_messages_from_the_catalog() {
+    let err = CartError::not_found(42);
+    assert_eq!(
+        err.user_message(&English).to_string(),
+        "We could not find item 42."
+    );
+    assert_eq!(
+        CartError::internal().user_message(&English).to_string(),
+        "Something went wrong, please try again later."
+    );
+}
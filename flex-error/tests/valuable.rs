@@ -0,0 +1,45 @@
+#![cfg(feature = "valuable")]
+
+use flex_error::define_error;
+use flex_error::valuable::{Enumerable, Structable, Valuable, Value};
+
+define_error! {
+    RelayError {
+        Timeout
+            { height: u64, chain_id: String }
+            | e | { format_args!("timed out at height {} on {}", e.height, e.chain_id) },
+        Refused
+            | _ | { "connection refused" },
+    }
+}
+
+#[test]
+fn records_the_error_as_a_structured_value() {
+    let err = RelayError::timeout(10, "ibc-0".into()).add_trace(&"while relaying");
+    assert!(matches!(err.as_value(), Value::Structable(_)));
+    assert_eq!(err.definition().name(), "RelayError");
+    assert_eq!(err.detail().variant().name(), "Timeout");
+    assert_eq!(RelayError::refused().detail().variant().name(), "Refused");
+
+    assert_eq!(
+        format!("{:?}", err.as_value()),
+        "RelayError { message: \"while relaying: timed out at height 10 on ibc-0\", \
+         detail: RelayErrorDetail::Timeout { height: 10, chain_id: \"ibc-0\" } }"
+    );
+}
+
+// The implementations are omitted for the error types with fields that do
+// not implement `Valuable`, which still compile.
+define_error! {
+    ClockError {
+        Skewed
+            { at: std::time::Instant }
+            | _ | { "clock skewed" },
+    }
+}
+
+#[test]
+fn defines_the_error_types_with_fields_that_are_not_valuable() {
+    let err = ClockError::skewed(std::time::Instant::now());
+    assert_eq!(err.detail().to_string(), "clock skewed");
+}
@@ -0,0 +1,28 @@
+mod net {
+    flex_error::define_error! {
+        #[derive(Debug)]
+        #[non_exhaustive]
+        NetError {
+            Internal
+                { code: u32 }
+                @vis(pub(crate))
+                | e | { format_args!("internal error {}", e.code) },
+            Private
+                @vis()
+                | _ | { "private error" },
+        }
+    }
+
+    pub fn private() -> NetError {
+        NetError::private()
+    }
+}
+
+#[test]
+fn restricts_the_visibility_of_the_constructors() {
+    let err = net::NetError::internal(3);
+    assert_eq!(err.detail().to_string(), "internal error 3");
+
+    let err = net::private();
+    assert_eq!(err.detail().to_string(), "private error");
+}
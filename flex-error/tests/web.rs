@@ -0,0 +1,72 @@
+#![cfg(feature = "axum")]
+
+use std::fmt;
+
+use axum::http::header::CONTENT_TYPE;
+use axum::http::StatusCode;
+use axum::response::{IntoResponse, Response};
+use flex_error::define_error;
+use flex_error::web::{Rejection, RejectionDetail};
+
+#[derive(Debug)]
+pub struct MissingHeader(&'static str);
+
+impl fmt::Display for MissingHeader {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "missing header {}", self.0)
+    }
+}
+
+impl IntoResponse for MissingHeader {
+    fn into_response(self) -> Response {
+        (StatusCode::BAD_REQUEST, self.to_string()).into_response()
+    }
+}
+
+define_error! {
+    ApiError {
+        NotFound
+            { id: u64 }
+            @problem(status = 404, title = "Resource not found")
+            | e | { format_args!("no resource with id {}", e.id) },
+        InvalidRequest
+            [ Rejection<MissingHeader> ]
+            @problem(status = 400)
+            | _ | { "invalid request" },
+        Internal
+            | _ | { "internal error" },
+    }
+}
+
+#[test]
+fn responds_with_the_problem_details() {
+    let response = ApiError::not_found(7).into_response();
+    assert_eq!(response.status(), 404);
+    assert_eq!(
+        response.headers()[CONTENT_TYPE],
+        flex_error::problem::CONTENT_TYPE
+    );
+
+    let response = ApiError::internal().into_response();
+    assert_eq!(response.status(), 500);
+}
+
+#[test]
+fn wraps_the_rejections() {
+    let err = ApiError::invalid_request(MissingHeader("authorization"));
+    match err.detail() {
+        ApiErrorDetail::InvalidRequest(e) => assert_eq!(
+            e.source,
+            RejectionDetail {
+                status: 400,
+                message: "missing header authorization".into(),
+            }
+        ),
+        _ => panic!("expected an invalid request error"),
+    }
+    assert_eq!(
+        format!("{:#}", err),
+        "invalid request: missing header authorization"
+    );
+    assert_eq!(err.into_response().status(), 400);
+}
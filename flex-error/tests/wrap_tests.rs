@@ -0,0 +1,27 @@
+//! The error types in this file generate the tests of their conversions
+//! with the `@tests(wrap)` option.
+
+#![cfg(all(feature = "arbitrary", feature = "alloc"))]
+
+use flex_error::{define_error, BoxSource};
+
+define_error! {
+    InnerError {
+        Timeout
+            { height: u64 }
+            | e | { format_args!("timed out at height {}", e.height) },
+    }
+}
+
+define_error! {
+    @tests(wrap)
+    RelayError {
+        Inner
+            { attempt: u32 }
+            [ InnerError ]
+            | e | { format_args!("inner error on attempt {}", e.attempt) },
+        Boxed
+            [ BoxSource<InnerError> ]
+            | _ | { "boxed inner error" },
+    }
+}